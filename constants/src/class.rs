@@ -1,6 +1,30 @@
 /// Road classification codes for heightmap generation
 pub const ROAD_CLASSIFICATIONS: &[u8] = &[2, 10, 11, 12];
 
+/// Building classification codes that block first-person walking
+pub const BUILDING_CLASSIFICATIONS: &[u8] = &[6];
+
+/// Carriageway surface codes; kerbs are found where these meet the sidewalk
+pub const CARRIAGEWAY_CLASSIFICATIONS: &[u8] = &[11];
+
+/// Sidewalk surface codes for kerb detection and sidewalk width analysis
+pub const SIDEWALK_CLASSIFICATIONS: &[u8] = &[2];
+
+/// Divisor packing a connectivity object ID into the position texture's alpha channel.
+/// `modified_classification.wgsl` repeats it.
+pub const OBJECT_ID_SCALE: f32 = 121.0;
+
+/// Position texel alpha for a connectivity object ID.
+pub fn encode_object_id(object_number: f32) -> f32 {
+    object_number / OBJECT_ID_SCALE
+}
+
+/// Object ID from a position texel alpha. Rounds, as most IDs don't survive the
+/// division exactly.
+pub fn decode_object_id(alpha: f32) -> u32 {
+    (alpha * OBJECT_ID_SCALE).round() as u32
+}
+
 pub struct ClassInfo {
     pub id: u8,
     pub name: &'static str,
@@ -31,6 +55,10 @@ pub const CLASS_MAP: &[ClassInfo] = &[
         id: 6,
         name: "buildings",
     },
+    ClassInfo {
+        id: 7,
+        name: "noise",
+    },
     ClassInfo {
         id: 8,
         name: "street furniture",
@@ -1,8 +1,11 @@
-/// The maximum number of points the compute shader expects to perform re-classification and hide operations
-pub const MAXIMUM_POLYGON_POINTS: usize = 2048;
+/// The maximum number of polygon vertices across all polygons, stored in the compute shader's point storage buffer
+pub const MAXIMUM_POLYGON_POINTS: usize = 262_144;
 
 /// The maximum number of polygons the compute shader expects to perform re-classification and hide operations
-pub const MAXIMUM_POLYGONS: usize = 512;
+pub const MAXIMUM_POLYGONS: usize = 4096;
 
-/// The maximum number of polygons the compute shader expects to perform re-classification and hide operations
-pub const MAX_IGNORE_MASK_LENGTH: usize = 512;
+/// The maximum number of class/object mask entries across all polygons, stored in the compute shader's mask storage buffer
+pub const MAX_IGNORE_MASK_LENGTH: usize = 65_536;
+
+/// The maximum number of selected point indices across all screen-space selections, stored in the compute shader's selection storage buffer
+pub const MAXIMUM_SELECTION_POINTS: usize = 4_194_304;
@@ -7,5 +7,12 @@ pub const MAX_POINTS: usize = TEXTURE_SIZE * TEXTURE_SIZE;
 /// Heightmap blend radius for road surface smoothing (pixels)
 pub const HEIGHTMAP_BLEND_RADIUS: f32 = 64.0;
 
+/// Default Gaussian sigma for heightmap smoothing (pixels)
+pub const HEIGHTMAP_GAUSSIAN_SIGMA: f32 = 3.0;
+
+/// Default grid resolution per axis for Z-order spatial cells, overridden by `sampling.grid_resolution`
+/// in the pipeline config. Only the preprocessor's Morton sort uses it.
+pub const SPATIAL_GRID_RESOLUTION: u32 = 1024;
+
 /// Sample size for colour detection
 pub const COLOUR_DETECTION_SAMPLE_SIZE: usize = 100;
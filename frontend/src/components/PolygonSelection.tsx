@@ -34,6 +34,15 @@ const PolygonToolPanel: React.FC<PolygonToolPanelProps> = ({
     hidePointsInPolygon,
     reclassifyPointsInPolygon,
     setOnMouseEnterObjectID,
+    polygons,
+    polygonEditMode,
+    polygonLimits,
+    polygonError,
+    listPolygons,
+    updatePolygon,
+    deletePolygon,
+    reorderPolygon,
+    setPolygonEditMode,
   } = useWebRpc(canvasRef);
 
   const [operation, setOperation] = useState<Operation>("hide");
@@ -170,6 +179,7 @@ const PolygonToolPanel: React.FC<PolygonToolPanelProps> = ({
     if (!isVisible) return;
     selectTool("polygon").catch(console.error);
     getClassificationCategories();
+    listPolygons().catch(console.error);
   }, [isVisible]);
 
   // Handle double-click
@@ -558,6 +568,141 @@ const PolygonToolPanel: React.FC<PolygonToolPanelProps> = ({
         )}
       </div>
 
+      {/* Completed polygons; later entries override earlier ones */}
+      <div
+        style={{
+          padding: `${theme.spacing[3]} ${theme.spacing[5]}`,
+          borderTop: `1px solid ${theme.colors.border.default}`,
+        }}
+      >
+        <div
+          style={{
+            display: "flex",
+            alignItems: "center",
+            justifyContent: "space-between",
+            marginBottom: theme.spacing[2],
+          }}
+        >
+          <span
+            style={{
+              fontSize: theme.fontSizes.sm,
+              fontWeight: theme.fontWeights.semibold,
+              color: theme.colors.gray[300],
+            }}
+          >
+            Polygons ({polygons.length}
+            {polygonLimits && ` / ${polygonLimits.max_polygons}`})
+          </span>
+          <button
+            onClick={() => {
+              setPolygonEditMode(!polygonEditMode).catch(console.error);
+              returnFocusToCanvas();
+            }}
+            title="Drag vertices of completed polygons"
+            style={{
+              ...styleUtils.toolItem(polygonEditMode),
+              padding: `${theme.spacing[1]} ${theme.spacing[2]}`,
+              fontSize: theme.fontSizes.xs,
+            }}
+          >
+            {polygonEditMode ? "editing vertices" : "edit vertices"}
+          </button>
+        </div>
+        {polygonLimits && (
+          <div
+            style={{
+              fontSize: theme.fontSizes.xs,
+              color:
+                polygonLimits.points > polygonLimits.max_points * 0.9
+                  ? theme.colors.primary.orangeLight
+                  : theme.colors.gray[500],
+              marginBottom: theme.spacing[2],
+            }}
+          >
+            {polygonLimits.points.toLocaleString()} /{" "}
+            {polygonLimits.max_points.toLocaleString()} vertices,{" "}
+            {polygonLimits.masks.toLocaleString()} /{" "}
+            {polygonLimits.max_masks.toLocaleString()} masks
+          </div>
+        )}
+        {polygonError && (
+          <div
+            style={{
+              fontSize: theme.fontSizes.xs,
+              color: theme.colors.error,
+              marginBottom: theme.spacing[2],
+            }}
+          >
+            {polygonError}
+          </div>
+        )}
+        <div style={{ maxHeight: "160px", overflowY: "auto" }}>
+          {polygons.map((polygon, i) => (
+            <div
+              key={polygon.id}
+              style={{
+                display: "flex",
+                alignItems: "center",
+                gap: theme.spacing[1],
+                fontSize: theme.fontSizes.xs,
+                color: theme.colors.gray[400],
+                marginBottom: theme.spacing[1],
+              }}
+            >
+              <span style={{ flex: 1 }}>
+                #{polygon.id}{" "}
+                {polygon.mode === "hide"
+                  ? "hide"
+                  : `class ${polygon.target_class_id}`}
+                {polygon.masked_classes.length > 0 &&
+                  ` (${polygon.masked_classes.length} masks)`}
+              </span>
+              {operation === "reclassify" && targetClassId >= 0 && (
+                <button
+                  onClick={() =>
+                    updatePolygon(polygon.id, {
+                      mode: "reclassify",
+                      target_class_id: targetClassId,
+                    }).catch(console.error)
+                  }
+                  title="Reclassify this polygon to the selected target class"
+                  style={{ ...styleUtils.toolItem(false), padding: "2px 6px" }}
+                >
+                  set
+                </button>
+              )}
+              <button
+                disabled={i === 0}
+                onClick={() =>
+                  reorderPolygon(polygon.id, i - 1).catch(console.error)
+                }
+                title="Move earlier (overridden by later polygons)"
+                style={{ ...styleUtils.toolItem(false), padding: "2px 6px" }}
+              >
+                ↑
+              </button>
+              <button
+                disabled={i === polygons.length - 1}
+                onClick={() =>
+                  reorderPolygon(polygon.id, i + 1).catch(console.error)
+                }
+                title="Move later (overrides earlier polygons)"
+                style={{ ...styleUtils.toolItem(false), padding: "2px 6px" }}
+              >
+                ↓
+              </button>
+              <button
+                onClick={() => deletePolygon(polygon.id).catch(console.error)}
+                title="Delete polygon"
+                style={{ ...styleUtils.toolItem(false), padding: "2px 6px" }}
+              >
+                ✕
+              </button>
+            </div>
+          ))}
+        </div>
+      </div>
+
       {/* Footer */}
       <div
         style={{
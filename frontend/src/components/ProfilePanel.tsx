@@ -0,0 +1,228 @@
+import type React from "react";
+import { useState, useEffect, useMemo, type RefObject } from "react";
+import { useWebRpc, type Profile } from "../hooks/useWebRpc";
+import { theme, styleUtils } from "../theme";
+
+interface ProfilePanelProps {
+  isVisible: boolean;
+  canvasRef: RefObject<HTMLIFrameElement | null>;
+}
+
+const CHART_WIDTH = 640;
+const CHART_HEIGHT = 220;
+const CHART_MARGIN = 40;
+
+// Ground and road classes share a colour so the surface line reads clearly
+const GROUND_CLASSES = [2, 10, 11, 12];
+
+const colourForClass = (classId: number): string => {
+  if (GROUND_CLASSES.includes(classId)) return theme.colors.primary.orange;
+  if (classId >= 3 && classId <= 5) return theme.colors.success;
+  if (classId === 6) return theme.colors.gray[500];
+  return theme.colors.primary.blueLight;
+};
+
+const ProfileChart: React.FC<{ profile: Profile }> = ({ profile }) => {
+  const [low, high] = useMemo(() => {
+    const [min, max] = profile.elevation_range ?? [0, 1];
+    const pad = Math.max((max - min) * 0.05, 0.1);
+    return [min - pad, max + pad];
+  }, [profile]);
+
+  const length = Math.max(profile.length, 1e-6);
+  const plotWidth = CHART_WIDTH - 2 * CHART_MARGIN;
+  const plotHeight = CHART_HEIGHT - 2 * CHART_MARGIN;
+  const x = (chainage: number) =>
+    CHART_MARGIN + (chainage / length) * plotWidth;
+  const y = (elevation: number) =>
+    CHART_MARGIN + ((high - elevation) / (high - low)) * plotHeight;
+  const ticks = [0, 0.25, 0.5, 0.75, 1];
+
+  return (
+    <svg
+      viewBox={`0 0 ${CHART_WIDTH} ${CHART_HEIGHT}`}
+      style={{ width: "100%", height: "auto", display: "block" }}
+    >
+      <rect
+        x={CHART_MARGIN}
+        y={CHART_MARGIN}
+        width={plotWidth}
+        height={plotHeight}
+        fill="none"
+        stroke={theme.colors.border.medium}
+      />
+      {ticks.map((t) => (
+        <g key={t} fontSize="10" fill={theme.colors.gray[400]}>
+          <text
+            x={x(t * profile.length)}
+            y={CHART_HEIGHT - CHART_MARGIN + 14}
+            textAnchor="middle"
+          >
+            {(t * profile.length).toFixed(1)}
+          </text>
+          <text
+            x={CHART_MARGIN - 4}
+            y={y(low + t * (high - low)) + 3}
+            textAnchor="end"
+          >
+            {(low + t * (high - low)).toFixed(2)}
+          </text>
+        </g>
+      ))}
+      {profile.samples.map(([chainage, elevation, , classId], i) => (
+        <circle
+          key={i}
+          cx={x(chainage)}
+          cy={y(elevation)}
+          r={1.2}
+          fill={colourForClass(classId)}
+        />
+      ))}
+    </svg>
+  );
+};
+
+const ProfilePanel: React.FC<ProfilePanelProps> = ({
+  isVisible,
+  canvasRef,
+}) => {
+  const {
+    profile,
+    profileVertexCount,
+    profileAction,
+    setProfileCorridor,
+    onNotification,
+  } = useWebRpc(canvasRef);
+
+  const [halfWidth, setHalfWidth] = useState<number>(1.0);
+  const [groundOnly, setGroundOnly] = useState<boolean>(false);
+
+  // Exports arrive as a notification carrying the file contents
+  useEffect(() => {
+    onNotification("profile_exported", (params) => {
+      if (!params?.content) return;
+      const type = params.format === "svg" ? "image/svg+xml" : "text/csv";
+      const url = URL.createObjectURL(new Blob([params.content], { type }));
+      const link = document.createElement("a");
+      link.href = url;
+      link.download = params.filename ?? `profile.${params.format}`;
+      link.click();
+      URL.revokeObjectURL(url);
+    });
+  }, [onNotification]);
+
+  const applyCorridor = (width: number, ground: boolean): void => {
+    if (!(width > 0)) return;
+    setProfileCorridor(width, ground).catch(console.error);
+  };
+
+  if (!isVisible) return null;
+
+  const buttonStyle: React.CSSProperties = {
+    ...styleUtils.buttonGhost(),
+    padding: `${theme.spacing[2]} ${theme.spacing[4]}`,
+    fontSize: theme.fontSizes.sm,
+  };
+
+  return (
+    <div
+      style={{
+        position: "fixed",
+        left: "50%",
+        bottom: theme.spacing[6],
+        transform: "translateX(-50%)",
+        width: "680px",
+        padding: theme.spacing[5],
+        ...styleUtils.glassPanel("medium"),
+        zIndex: theme.zIndex.modal,
+        fontFamily: theme.fonts.mono,
+        fontSize: theme.fontSizes.sm,
+        color: theme.colors.gray[200],
+      }}
+    >
+      <div
+        style={{
+          display: "flex",
+          alignItems: "center",
+          gap: theme.spacing[4],
+          marginBottom: theme.spacing[4],
+        }}
+      >
+        <span style={styleUtils.text.subtitle()}>Profile</span>
+        <label>
+          Half-width (m){" "}
+          <input
+            type="number"
+            min={0.1}
+            step={0.1}
+            value={halfWidth}
+            onChange={(e) => setHalfWidth(parseFloat(e.target.value))}
+            onBlur={() => applyCorridor(halfWidth, groundOnly)}
+            style={{
+              ...styleUtils.inputField(),
+              width: "64px",
+              padding: theme.spacing[1],
+            }}
+          />
+        </label>
+        <label>
+          <input
+            type="checkbox"
+            checked={groundOnly}
+            onChange={(e) => {
+              setGroundOnly(e.target.checked);
+              applyCorridor(halfWidth, e.target.checked);
+            }}
+          />{" "}
+          Ground only
+        </label>
+        <div style={{ marginLeft: "auto", display: "flex", gap: "6px" }}>
+          <button
+            style={buttonStyle}
+            disabled={profileVertexCount < 2}
+            onClick={() => profileAction("complete").catch(console.error)}
+          >
+            Extract
+          </button>
+          <button
+            style={buttonStyle}
+            onClick={() => profileAction("clear").catch(console.error)}
+          >
+            Clear
+          </button>
+          <button
+            style={buttonStyle}
+            disabled={!profile}
+            onClick={() => profileAction("export", "csv").catch(console.error)}
+          >
+            CSV
+          </button>
+          <button
+            style={buttonStyle}
+            disabled={!profile}
+            onClick={() => profileAction("export", "svg").catch(console.error)}
+          >
+            SVG
+          </button>
+        </div>
+      </div>
+
+      {profile ? (
+        <>
+          <div style={{ marginBottom: theme.spacing[3] }}>
+            {profile.length.toFixed(2)} m, {profile.point_count} points within
+            ±{profile.half_width.toFixed(2)} m
+            {profile.ground_only ? " (ground only)" : ""}
+          </div>
+          <ProfileChart profile={profile} />
+        </>
+      ) : (
+        <div>
+          Click to add vertices ({profileVertexCount} placed), then Extract
+        </div>
+      )}
+    </div>
+  );
+};
+
+export default ProfilePanel;
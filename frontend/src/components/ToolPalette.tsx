@@ -22,6 +22,8 @@ const ToolPalette: React.FC<ToolPaletteProps> = ({
   const tools: Tool[] = [
     { id: "polygon" },
     { id: "measure" },
+    { id: "profile" },
+    { id: "selection" },
     { id: "assets" },
   ];
 
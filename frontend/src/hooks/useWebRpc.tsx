@@ -96,11 +96,195 @@ interface PolygonOperationResult {
 }
 
 // Measure tool interfaces
-interface Measurement {
-  id?: number;
-  start?: [number, number, number];
-  end?: [number, number, number];
-  distance?: number;
+export type MeasureMode =
+  | "distance"
+  | "polyline"
+  | "area"
+  | "height"
+  | "slope"
+  | "angle";
+
+// Points are render-space; values are keyed by quantity (e.g. distance, horizontal, vertical)
+// and `label` is the headline value as shown in the viewport. `snap` is the snap kind of
+// the last point, null when it followed the ground plane (live and completed only)
+export interface Measurement {
+  id: number;
+  name: string;
+  mode: MeasureMode;
+  points: [number, number, number][];
+  values: Record<string, number>;
+  label: string;
+  snap?: MeasureSnap["mode"] | null;
+}
+
+// Measure tool snapping; holding Alt in the viewport inverts `enabled`
+export interface MeasureSnap {
+  mode: "point" | "ground" | "vertex" | "midpoint";
+  enabled: boolean;
+}
+
+// Profile tool interfaces
+// Samples are [chainage, elevation, offset, class_id], thinned for charting
+export interface Profile {
+  id: number;
+  length: number;
+  half_width: number;
+  ground_only: boolean;
+  point_count: number;
+  elevation_range: [number, number] | null;
+  vertices: Array<[number, number, number]>;
+  samples: Array<[number, number, number, number]>;
+}
+
+// Completed polygon in evaluation order; later polygons override earlier ones
+export interface ClassificationPolygon {
+  id: number;
+  index: number;
+  mode: "hide" | "reclassify";
+  target_class_id: number;
+  masked_classes: Array<{ class_id: number; object_id: number }>;
+  vertices: Array<[number, number, number]>;
+  point_count: number;
+  vertical_range: VerticalRange | null;
+}
+
+// Optional height band turning a polygon into a prism volume
+export interface VerticalRange {
+  reference: "absolute" | "ground";
+  min: number;
+  max: number;
+}
+
+// Storage buffer capacity for polygons and current usage
+export interface PolygonLimits {
+  max_polygons: number;
+  max_points: number;
+  max_masks: number;
+  max_selected_points: number;
+  polygons: number;
+  points: number;
+  masks: number;
+  selected_points: number;
+}
+
+export interface ClassificationStatistics {
+  point_count: number;
+  hidden: number;
+  classes: { class_id: number; count: number }[];
+}
+
+// Rendered point under the cursor; position is render space, world_position absolute
+export interface PickedPoint {
+  index: number;
+  position: [number, number, number];
+  world_position: [number, number, number];
+  class_id: number;
+  original_class_id: number;
+  object_id: number;
+  colour: [number, number, number];
+}
+
+// Fields left undefined keep their current value
+export interface PolygonUpdate {
+  mode?: "hide" | "reclassify";
+  target_class_id?: number;
+  masked_classes?: Array<{ class_id: number; object_id: number }>;
+  vertices?: Array<[number, number, number]>;
+  vertical_range?: VerticalRange | null;
+}
+
+// Screen-space selection tool settings and the pending selection size
+export interface SelectionState {
+  shape: "rectangle" | "lasso" | "brush";
+  depth: "through" | "front";
+  operation: "replace" | "add" | "subtract";
+  brush_radius: number;
+  count: number;
+}
+
+// Fields left undefined keep their current value
+export interface SelectionMode {
+  shape?: SelectionState["shape"];
+  depth?: SelectionState["depth"];
+  operation?: SelectionState["operation"];
+  brush_radius?: number;
+}
+
+// Undo/redo stacks; undo is oldest first, redo is next-to-redo first
+export interface EditHistory {
+  undo: string[];
+  redo: string[];
+  can_undo: boolean;
+  can_redo: boolean;
+}
+
+// Saved camera view; focus_point is in absolute world coordinates
+export interface CameraBookmark {
+  name: string;
+  focus_point: [number, number, number];
+  height: number;
+  yaw: number;
+  pitch: number;
+  top_view: boolean;
+}
+
+// Give either a world position or a connectivity object ID
+export interface FlyToTarget {
+  position?: [number, number, number];
+  object_id?: number;
+  height?: number;
+  duration?: number;
+}
+
+export type EyeHeightPreset = "pedestrian" | "wheelchair" | "custom";
+
+export interface WalkMode {
+  enabled: boolean;
+  preset: EyeHeightPreset;
+  eye_height: number;
+  speed: number;
+}
+
+// Fields left out keep their current value; eye_height overrides preset
+export interface WalkModeSettings {
+  enabled?: boolean;
+  preset?: Exclude<EyeHeightPreset, "custom">;
+  eye_height?: number;
+  speed?: number;
+}
+
+export interface CameraPathKeyframe {
+  time: number;
+  focus_point: [number, number, number];
+  height: number;
+  yaw: number;
+  pitch: number;
+  top_view: boolean;
+}
+
+export interface CameraPath {
+  keyframes: CameraPathKeyframe[];
+  duration: number;
+  state: "idle" | "recording" | "playing";
+}
+
+export interface FrameExportOptions {
+  directory?: string;
+  fps?: number;
+  width?: number;
+  height?: number;
+}
+
+// Frames written so far while a native frame export runs
+export interface FrameExportProgress {
+  frame: number;
+  frames: number;
+}
+
+export interface ScreenshotOptions {
+  scale?: number;
+  overlays?: { polygons?: boolean; measurements?: boolean; grid?: boolean };
+  path?: string;
 }
 
 export const useWebRpc = (canvasRef: RefObject<HTMLIFrameElement | null>) => {
@@ -112,6 +296,12 @@ export const useWebRpc = (canvasRef: RefObject<HTMLIFrameElement | null>) => {
   const [classificationCategories, setClassificationCategories] = useState<
     ClassificationCategory[]
   >([]); // Categories state array for polygon tool
+  const [polygons, setPolygons] = useState<ClassificationPolygon[]>([]);
+  const [polygonEditMode, setPolygonEditModeState] = useState<boolean>(false);
+  const [polygonLimits, setPolygonLimits] = useState<PolygonLimits | null>(
+    null,
+  );
+  const [polygonError, setPolygonError] = useState<string | null>(null);
 
   // Measure state
   const [currentMeasurement, setCurrentMeasurement] =
@@ -119,6 +309,31 @@ export const useWebRpc = (canvasRef: RefObject<HTMLIFrameElement | null>) => {
   const [completedMeasurements, setCompletedMeasurements] = useState<
     Measurement[]
   >([]);
+  const [measureMode, setMeasureModeState] = useState<MeasureMode>("distance");
+  const [measureSnap, setMeasureSnapState] = useState<MeasureSnap | null>(
+    null,
+  );
+
+  // Profile state
+  const [profile, setProfile] = useState<Profile | null>(null);
+  const [profileVertexCount, setProfileVertexCount] = useState<number>(0);
+
+  // Selection state
+  const [selection, setSelection] = useState<SelectionState | null>(null);
+
+  // History state
+  const [history, setHistory] = useState<EditHistory | null>(null);
+
+  // Camera state
+  const [cameraBookmarks, setCameraBookmarks] = useState<CameraBookmark[]>(
+    [],
+  );
+  const [topView, setTopViewState] = useState<boolean>(false);
+  const [walkMode, setWalkModeState] = useState<WalkMode | null>(null);
+  const [cameraPath, setCameraPath] = useState<CameraPath | null>(null);
+  const [frameExport, setFrameExport] = useState<FrameExportProgress | null>(
+    null,
+  );
 
   const requestIdCounter = useRef<number>(1);
   const pendingRequests = useRef<Map<number, PendingRequest>>(new Map());
@@ -255,6 +470,12 @@ export const useWebRpc = (canvasRef: RefObject<HTMLIFrameElement | null>) => {
 
           if (message.method === "tool_state_changed") {
             console.log("Tool state changed:", message.params);
+            if (message.params?.snap) {
+              setMeasureSnapState(message.params.snap as MeasureSnap);
+            }
+            if (message.params?.tool === "measure" && message.params?.mode) {
+              setMeasureModeState(message.params.mode as MeasureMode);
+            }
           }
 
           // Polygon category-related notification handler
@@ -268,33 +489,99 @@ export const useWebRpc = (canvasRef: RefObject<HTMLIFrameElement | null>) => {
 
           // Measure notifications
           if (message.method === "measure_started") {
-            setCurrentMeasurement({ start: message.params?.position });
-            setCompletedMeasurements([]);
             console.log("Measure started:", message.params);
           }
           if (message.method === "measure_updated") {
-            setCurrentMeasurement({
-              start: message.params?.start,
-              end: message.params?.end,
-              distance: message.params?.distance,
-            });
-            console.log("Measure updated:", message.params);
+            setCurrentMeasurement(message.params as Measurement);
           }
           if (message.method === "measure_completed") {
-            const measurement: Measurement = {
-              id: message.params?.id,
-              start: message.params?.start,
-              end: message.params?.end,
-              distance: message.params?.distance,
-            };
-            setCompletedMeasurements([measurement]);
             setCurrentMeasurement(null);
             console.log("Measure completed:", message.params);
           }
-          if (message.method === "measure_cleared") {
+          if (message.method === "measure_clear") {
+            setCurrentMeasurement(null);
+          }
+          if (message.method === "measure_mode_changed") {
+            setMeasureModeState(message.params?.mode as MeasureMode);
             setCurrentMeasurement(null);
-            setCompletedMeasurements([]);
-            console.log("Measure cleared:", message.params);
+          }
+          // Full list after every add, rename, delete, undo or session load
+          if (message.method === "measurements_changed") {
+            setCompletedMeasurements(
+              (message.params?.measurements || []) as Measurement[],
+            );
+          }
+          if (message.method === "measure_snap_changed") {
+            setMeasureSnapState(message.params as MeasureSnap);
+          }
+
+          // Profile notifications
+          if (message.method === "profile_vertex_added") {
+            setProfileVertexCount(message.params?.vertex_count || 0);
+          }
+          if (message.method === "profile_completed") {
+            setProfile(message.params as Profile);
+            console.log("Profile completed:", message.params?.point_count);
+          }
+          if (message.method === "profile_cleared") {
+            setProfile(null);
+            setProfileVertexCount(0);
+          }
+
+          // Selection notifications
+          if (message.method === "selection_changed") {
+            setSelection(message.params as SelectionState);
+          }
+
+          // Polygon management notifications
+          if (message.method === "polygons_changed") {
+            setPolygons(message.params?.polygons || []);
+            if (message.params?.limits) {
+              setPolygonLimits(message.params.limits as PolygonLimits);
+            }
+            setPolygonError(null);
+          }
+          if (message.method === "polygon_error") {
+            setPolygonError(message.params?.error ?? "Polygon rejected");
+            if (message.params?.limits) {
+              setPolygonLimits(message.params.limits as PolygonLimits);
+            }
+          }
+          if (message.method === "polygon_edit_mode_changed") {
+            setPolygonEditModeState(!!message.params?.enabled);
+          }
+
+          if (message.method === "history_changed") {
+            setHistory(message.params as EditHistory);
+          }
+
+          // Camera notifications
+          if (message.method === "camera_bookmarks_changed") {
+            setCameraBookmarks(
+              (message.params?.bookmarks || []) as CameraBookmark[],
+            );
+          }
+          if (message.method === "camera_top_view_changed") {
+            setTopViewState(!!message.params?.enabled);
+          }
+          if (message.method === "walk_mode_changed") {
+            setWalkModeState(message.params as WalkMode);
+          }
+          if (message.method === "camera_path_changed") {
+            setCameraPath(message.params as CameraPath);
+          }
+          if (message.method === "camera_path_export_started") {
+            setFrameExport({ frame: 0, frames: message.params?.frames ?? 0 });
+          }
+          if (message.method === "camera_path_export_progress") {
+            setFrameExport(message.params as FrameExportProgress);
+          }
+          if (message.method === "camera_path_export_finished") {
+            setFrameExport(null);
+            console.log("Frame export finished:", message.params);
+          }
+          if (message.method === "camera_error") {
+            console.error("Camera error:", message.params?.error);
           }
 
           // Custom handlers
@@ -494,6 +781,7 @@ export const useWebRpc = (canvasRef: RefObject<HTMLIFrameElement | null>) => {
   const hidePointsInPolygon = useCallback(
     async (
       masked_classes: Array<{ class_id: number; object_id: number }>,
+      verticalRange?: VerticalRange,
     ): Promise<PolygonOperationResult> => {
       try {
         console.log("Hide Data: ", masked_classes);
@@ -501,6 +789,7 @@ export const useWebRpc = (canvasRef: RefObject<HTMLIFrameElement | null>) => {
           "hide_points_in_polygon",
           {
             masked_classes: masked_classes,
+            vertical_range: verticalRange,
           },
         );
         return result;
@@ -518,6 +807,7 @@ export const useWebRpc = (canvasRef: RefObject<HTMLIFrameElement | null>) => {
       maskedClasses: Array<{ class_id: number; object_id: number }>,
       targetClassId: number,
       targetObjectId: number,
+      verticalRange?: VerticalRange,
     ): Promise<PolygonOperationResult> => {
       try {
         const result = await sendRequest<PolygonOperationResult>(
@@ -526,6 +816,7 @@ export const useWebRpc = (canvasRef: RefObject<HTMLIFrameElement | null>) => {
             masked_classes: maskedClasses,
             target_class_id: targetClassId,
             target_object_id: targetObjectId,
+            vertical_range: verticalRange,
           },
         );
 
@@ -538,6 +829,461 @@ export const useWebRpc = (canvasRef: RefObject<HTMLIFrameElement | null>) => {
     [sendRequest],
   );
 
+  // Polygon management; the updated list arrives via "polygons_changed"
+  const listPolygons = useCallback(async (): Promise<
+    ClassificationPolygon[]
+  > => {
+    const result = await sendRequest<{
+      polygons: ClassificationPolygon[];
+      limits: PolygonLimits;
+    }>("list_polygons", {});
+    setPolygons(result.polygons);
+    setPolygonLimits(result.limits);
+    return result.polygons;
+  }, [sendRequest]);
+
+  const getPolygonLimits = useCallback(async (): Promise<PolygonLimits> => {
+    const result = await sendRequest<PolygonLimits>("get_polygon_limits", {});
+    setPolygonLimits(result);
+    return result;
+  }, [sendRequest]);
+
+  const getClassificationStatistics =
+    useCallback(async (): Promise<ClassificationStatistics> => {
+      return await sendRequest<ClassificationStatistics>(
+        "get_classification_statistics",
+        {},
+      );
+    }, [sendRequest]);
+
+  // Picks under the cursor when x and y are omitted; null when nothing is there
+  const pickPoint = useCallback(
+    async (x?: number, y?: number): Promise<PickedPoint | null> => {
+      const result = await sendRequest<{ point: PickedPoint | null }>(
+        "pick_point",
+        x !== undefined && y !== undefined ? { x, y } : {},
+      );
+      return result.point;
+    },
+    [sendRequest],
+  );
+
+  const updatePolygon = useCallback(
+    async (id: number, update: PolygonUpdate): Promise<any> => {
+      try {
+        return await sendRequest("update_polygon", { id, ...update });
+      } catch (error) {
+        console.error(`Failed to update polygon ${id}:`, error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const deletePolygon = useCallback(
+    async (id: number): Promise<any> => {
+      try {
+        return await sendRequest("delete_polygon", { id });
+      } catch (error) {
+        console.error(`Failed to delete polygon ${id}:`, error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const reorderPolygon = useCallback(
+    async (id: number, index: number): Promise<any> => {
+      try {
+        return await sendRequest("reorder_polygon", { id, index });
+      } catch (error) {
+        console.error(`Failed to reorder polygon ${id}:`, error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const setPolygonEditMode = useCallback(
+    async (enabled: boolean): Promise<any> => {
+      try {
+        return await sendRequest("set_polygon_edit_mode", { enabled });
+      } catch (error) {
+        console.error("Failed to set polygon edit mode:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  /// Measure methods
+  // Fields left undefined keep their current value
+  const setMeasureSnap = useCallback(
+    async (snap: Partial<MeasureSnap>): Promise<any> => {
+      try {
+        return await sendRequest("set_measure_snap", snap);
+      } catch (error) {
+        console.error("Failed to set measure snap:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const setMeasureMode = useCallback(
+    async (mode: MeasureMode): Promise<any> => {
+      try {
+        return await sendRequest("set_measure_mode", { mode });
+      } catch (error) {
+        console.error("Failed to set measure mode:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  // Complete (polyline/area), remove the last point of, or cancel the measurement being drawn
+  const measureAction = useCallback(
+    async (
+      action: "complete" | "remove_last_point" | "cancel",
+    ): Promise<any> => {
+      try {
+        return await sendRequest("measure_action", { action });
+      } catch (error) {
+        console.error("Failed to perform measure action:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const listMeasurements = useCallback(async (): Promise<Measurement[]> => {
+    try {
+      const result = await sendRequest("list_measurements", {});
+      const measurements = (result?.measurements || []) as Measurement[];
+      setCompletedMeasurements(measurements);
+      return measurements;
+    } catch (error) {
+      console.error("Failed to list measurements:", error);
+      throw error;
+    }
+  }, [sendRequest]);
+
+  const deleteMeasurement = useCallback(
+    async (id: number): Promise<any> => {
+      try {
+        return await sendRequest("delete_measurement", { id });
+      } catch (error) {
+        console.error("Failed to delete measurement:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const renameMeasurement = useCallback(
+    async (id: number, name: string): Promise<any> => {
+      try {
+        return await sendRequest("rename_measurement", { id, name });
+      } catch (error) {
+        console.error("Failed to rename measurement:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  // The file arrives via the "measurements_exported" notification
+  const exportMeasurements = useCallback(
+    async (format: "csv" | "json" = "csv"): Promise<any> => {
+      try {
+        return await sendRequest("export_measurements", { format });
+      } catch (error) {
+        console.error("Failed to export measurements:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  /// Profile methods
+  // Complete, clear or export ("csv" | "svg") the current profile
+  const profileAction = useCallback(
+    async (action: string, format?: string): Promise<any> => {
+      try {
+        return await sendRequest("profile_action", { action, format });
+      } catch (error) {
+        console.error(`Profile ${action} failed:`, error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const setProfileCorridor = useCallback(
+    async (halfWidth: number, groundOnly: boolean): Promise<any> => {
+      try {
+        return await sendRequest("set_profile_corridor", {
+          half_width: halfWidth,
+          ground_only: groundOnly,
+        });
+      } catch (error) {
+        console.error("Failed to set profile corridor:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  /// Selection methods
+  // The selected points feed hidePointsInPolygon / reclassifyPointsInPolygon
+  const setSelectionMode = useCallback(
+    async (mode: SelectionMode): Promise<any> => {
+      try {
+        return await sendRequest("set_selection_mode", mode);
+      } catch (error) {
+        console.error("Failed to set selection mode:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const clearSelection = useCallback(async (): Promise<any> => {
+    try {
+      return await sendRequest("clear_selection", {});
+    } catch (error) {
+      console.error("Failed to clear selection:", error);
+      throw error;
+    }
+  }, [sendRequest]);
+
+  /// Camera methods
+  // Without a name the engine picks the next free "View N"
+  const saveCameraBookmark = useCallback(
+    async (name?: string): Promise<any> => {
+      try {
+        return await sendRequest("save_camera_bookmark", { name });
+      } catch (error) {
+        console.error("Failed to save camera bookmark:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const goToCameraBookmark = useCallback(
+    async (name: string, duration?: number): Promise<any> => {
+      try {
+        return await sendRequest("go_to_camera_bookmark", { name, duration });
+      } catch (error) {
+        console.error("Failed to go to camera bookmark:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const deleteCameraBookmark = useCallback(
+    async (name: string): Promise<any> => {
+      try {
+        return await sendRequest("delete_camera_bookmark", { name });
+      } catch (error) {
+        console.error("Failed to delete camera bookmark:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const listCameraBookmarks = useCallback(async (): Promise<
+    CameraBookmark[]
+  > => {
+    try {
+      const result = await sendRequest("list_camera_bookmarks", {});
+      const bookmarks = (result?.bookmarks || []) as CameraBookmark[];
+      setCameraBookmarks(bookmarks);
+      return bookmarks;
+    } catch (error) {
+      console.error("Failed to list camera bookmarks:", error);
+      throw error;
+    }
+  }, [sendRequest]);
+
+  const flyTo = useCallback(
+    async (target: FlyToTarget): Promise<any> => {
+      try {
+        return await sendRequest("fly_to", target);
+      } catch (error) {
+        console.error("Failed to fly to target:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  // Leaving enabled undefined toggles the top view
+  const setTopView = useCallback(
+    async (enabled?: boolean): Promise<any> => {
+      try {
+        return await sendRequest("set_top_view", { enabled });
+      } catch (error) {
+        console.error("Failed to set top view:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  // The applied settings arrive via the "walk_mode_changed" notification
+  const setWalkMode = useCallback(
+    async (settings: WalkModeSettings): Promise<any> => {
+      try {
+        return await sendRequest("set_walk_mode", settings);
+      } catch (error) {
+        console.error("Failed to set walk mode:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  /// Camera path methods
+  // Leaving enabled undefined toggles recording
+  const recordCameraPath = useCallback(
+    async (enabled?: boolean): Promise<any> => {
+      try {
+        return await sendRequest("record_camera_path", { enabled });
+      } catch (error) {
+        console.error("Failed to record camera path:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  // Without names every bookmark is visited in order
+  const cameraPathFromBookmarks = useCallback(
+    async (names?: string[], secondsPerView?: number): Promise<any> => {
+      try {
+        return await sendRequest("camera_path_from_bookmarks", {
+          names,
+          seconds_per_view: secondsPerView,
+        });
+      } catch (error) {
+        console.error("Failed to build camera path from bookmarks:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const addCameraPathKeyframe = useCallback(
+    async (time?: number): Promise<any> => {
+      try {
+        return await sendRequest("add_camera_path_keyframe", { time });
+      } catch (error) {
+        console.error("Failed to add camera path keyframe:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const setCameraPathDuration = useCallback(
+    async (duration: number): Promise<any> => {
+      try {
+        return await sendRequest("set_camera_path_duration", { duration });
+      } catch (error) {
+        console.error("Failed to set camera path duration:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const cameraPathAction = useCallback(
+    async (action: "play" | "stop" | "clear"): Promise<any> => {
+      try {
+        return await sendRequest(`${action}_camera_path`, {});
+      } catch (error) {
+        console.error(`Failed to ${action} camera path:`, error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  // Native builds only; progress arrives via "camera_path_export_progress"
+  const exportCameraPathFrames = useCallback(
+    async (options: FrameExportOptions = {}): Promise<any> => {
+      try {
+        return await sendRequest("export_camera_path_frames", options);
+      } catch (error) {
+        console.error("Failed to export camera path frames:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  /// Screenshot methods
+  // The PNG arrives via the "screenshot_captured" notification
+  const captureScreenshot = useCallback(
+    async (options: ScreenshotOptions = {}): Promise<any> => {
+      try {
+        return await sendRequest("capture_screenshot", options);
+      } catch (error) {
+        console.error("Failed to capture screenshot:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  /// Session methods
+  // The project file arrives via the "session_saved" notification
+  const saveSession = useCallback(async (): Promise<any> => {
+    try {
+      return await sendRequest("save_session", {});
+    } catch (error) {
+      console.error("Failed to save session:", error);
+      throw error;
+    }
+  }, [sendRequest]);
+
+  const loadSession = useCallback(
+    async (content: string): Promise<any> => {
+      try {
+        return await sendRequest("load_session", { content });
+      } catch (error) {
+        console.error("Failed to load session:", error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  /// History methods
+  // Undo/redo results arrive via the "history_changed" notification
+  const historyAction = useCallback(
+    async (action: "undo" | "redo"): Promise<any> => {
+      try {
+        return await sendRequest(action, {});
+      } catch (error) {
+        console.error(`Failed to ${action}:`, error);
+        throw error;
+      }
+    },
+    [sendRequest],
+  );
+
+  const getHistory = useCallback(async (): Promise<EditHistory> => {
+    const result = await sendRequest<EditHistory>("get_history", {});
+    setHistory(result);
+    return result;
+  }, [sendRequest]);
+
   return {
     // State
     fps,
@@ -546,10 +1292,33 @@ export const useWebRpc = (canvasRef: RefObject<HTMLIFrameElement | null>) => {
     selectedAsset,
     placedAssets,
     classificationCategories,
+    polygons,
+    polygonEditMode,
+    polygonLimits,
+    polygonError,
 
     // Measure state
     currentMeasurement,
     completedMeasurements,
+    measureMode,
+    measureSnap,
+
+    // Profile state
+    profile,
+    profileVertexCount,
+
+    // Selection state
+    selection,
+
+    // History state
+    history,
+
+    // Camera state
+    cameraBookmarks,
+    topView,
+    walkMode,
+    cameraPath,
+    frameExport,
 
     // Generic RPC methods
     sendRequest,
@@ -572,5 +1341,58 @@ export const useWebRpc = (canvasRef: RefObject<HTMLIFrameElement | null>) => {
     getClassificationCategories,
     hidePointsInPolygon,
     reclassifyPointsInPolygon,
+    listPolygons,
+    getPolygonLimits,
+    getClassificationStatistics,
+    pickPoint,
+    updatePolygon,
+    deletePolygon,
+    reorderPolygon,
+    setPolygonEditMode,
+
+    // Measure
+    setMeasureSnap,
+    setMeasureMode,
+    measureAction,
+    listMeasurements,
+    deleteMeasurement,
+    renameMeasurement,
+    exportMeasurements,
+
+    // Profile
+    profileAction,
+    setProfileCorridor,
+
+    // Selection
+    setSelectionMode,
+    clearSelection,
+
+    // Camera
+    saveCameraBookmark,
+    goToCameraBookmark,
+    deleteCameraBookmark,
+    listCameraBookmarks,
+    flyTo,
+    setTopView,
+    setWalkMode,
+
+    // Camera path
+    recordCameraPath,
+    cameraPathFromBookmarks,
+    addCameraPathKeyframe,
+    setCameraPathDuration,
+    cameraPathAction,
+    exportCameraPathFrames,
+
+    // Screenshot
+    captureScreenshot,
+
+    // Session
+    saveSession,
+    loadSession,
+
+    // History
+    historyAction,
+    getHistory,
   };
 };
@@ -1,8 +1,9 @@
 import { useState, useEffect, useRef } from "react";
-import { useWebRpc } from "../hooks/useWebRpc";
+import { useWebRpc, MeasureMode } from "../hooks/useWebRpc";
 import AssetLibrary from "../components/AssetLibrary";
 import ToolPalette from "../components/ToolPalette";
 import PolygonToolPanel from "../components/PolygonSelection";
+import ProfilePanel from "../components/ProfilePanel";
 import LoadingPanel from "../components/LoadingMessage";
 import { TutorialOverlay } from "../components/TutorialOverlay";
 import { theme, styleUtils } from "../theme";
@@ -16,6 +17,7 @@ const ScannerApp: React.FC = () => {
   const [showAssetLibrary, setShowAssetLibrary] = useState<boolean>(false);
   const [showPolygonPanel, setShowPolygonPanel] = useState<boolean>(false);
   const [showMeasurePanel, setShowMeasurePanel] = useState<boolean>(false);
+  const [showProfilePanel, setShowProfilePanel] = useState<boolean>(false);
   const [renderMode, setRenderMode] = useState<string>("RGB");
   const [showTutorial, setShowTutorial] = useState(true);
   const [hasSelection, setHasSelection] = useState(false);
@@ -33,8 +35,91 @@ const ScannerApp: React.FC = () => {
     clearTool,
     currentMeasurement,
     completedMeasurements,
+    measureMode,
+    measureSnap,
+    setMeasureSnap,
+    setMeasureMode,
+    measureAction,
+    deleteMeasurement,
+    exportMeasurements,
+    saveSession,
+    loadSession,
+    history,
+    historyAction,
+    cameraBookmarks,
+    topView,
+    saveCameraBookmark,
+    goToCameraBookmark,
+    setTopView,
+    walkMode,
+    setWalkMode,
+    cameraPath,
+    recordCameraPath,
+    cameraPathFromBookmarks,
+    addCameraPathKeyframe,
+    cameraPathAction,
+    captureScreenshot,
   } = useWebRpc(canvasRef);
 
+  const sessionInputRef = useRef<HTMLInputElement | null>(null);
+
+  // Saved sessions come back as file content to download
+  useEffect(() => {
+    onNotification("session_saved", (params) => {
+      if (!params?.content) return;
+      const url = URL.createObjectURL(
+        new Blob([params.content], { type: "application/json" }),
+      );
+      const link = document.createElement("a");
+      link.href = url;
+      link.download = params.filename ?? "session.json";
+      link.click();
+      URL.revokeObjectURL(url);
+    });
+    onNotification("measurements_exported", (params) => {
+      if (!params?.content) return;
+      const type = params.format === "json" ? "application/json" : "text/csv";
+      const url = URL.createObjectURL(new Blob([params.content], { type }));
+      const link = document.createElement("a");
+      link.href = url;
+      link.download = params.filename ?? `measurements.${params.format}`;
+      link.click();
+      URL.revokeObjectURL(url);
+    });
+    onNotification("screenshot_captured", (params) => {
+      // Native builds write the file themselves and only report its path
+      if (!params?.data) return;
+      const bytes = Uint8Array.from(atob(params.data), (c) => c.charCodeAt(0));
+      const url = URL.createObjectURL(
+        new Blob([bytes], { type: params.mime_type ?? "image/png" }),
+      );
+      const link = document.createElement("a");
+      link.href = url;
+      link.download = params.filename ?? "screenshot.png";
+      link.click();
+      URL.revokeObjectURL(url);
+    });
+    onNotification("screenshot_error", (params) => {
+      console.error("Screenshot error:", params?.error);
+    });
+    onNotification("session_loaded", (params) => {
+      console.log("Session loaded:", params);
+      params?.warnings?.forEach((w: string) => console.warn(w));
+    });
+    onNotification("session_error", (params) => {
+      console.error("Session error:", params?.error);
+    });
+  }, [onNotification]);
+
+  const handleSessionFile = async (
+    e: React.ChangeEvent<HTMLInputElement>,
+  ): Promise<void> => {
+    const file = e.target.files?.[0];
+    e.target.value = "";
+    if (!file) return;
+    loadSession(await file.text()).catch(console.error);
+  };
+
   // listen for the message that all files are loaded.
   useEffect(() => {
     onNotification("loading", (params?: Record<string, number>) => {
@@ -89,11 +174,16 @@ const ScannerApp: React.FC = () => {
 
     // Panels visibility
     setShowAssetLibrary(newTool === "assets");
-    setShowPolygonPanel(newTool === "polygon");
+    // Screen-space selections are hidden or reclassified from the polygon panel
+    setShowPolygonPanel(newTool === "polygon" || newTool === "selection");
     setShowMeasurePanel(newTool === "measure");
+    setShowProfilePanel(newTool === "profile");
 
     // Render mode adjustment only when enabling polygon
-    if ((toolId === "polygon" || toolId === "assets") && !isSameTool) {
+    if (
+      (toolId === "polygon" || toolId === "selection" || toolId === "assets") &&
+      !isSameTool
+    ) {
       setRenderMode("modified");
       await handleRenderModeChange("modified");
     }
@@ -143,6 +233,7 @@ const ScannerApp: React.FC = () => {
             setSelectedTool(null);
             setShowAssetLibrary(false);
             setShowPolygonPanel(false);
+            setShowProfilePanel(false);
             refocusCanvas();
           });
       }
@@ -165,6 +256,7 @@ const ScannerApp: React.FC = () => {
 
     const cursorForTool: Record<string, string> = {
       measure: "/icons/measure_cursor.svg",
+      profile: "/icons/measure_cursor.svg",
       polygon: "/icons/polygon_cursor.svg",
       assets: "/icons/assets_cursor.svg",
     };
@@ -229,6 +321,7 @@ const ScannerApp: React.FC = () => {
             setSelectedTool(null);
             setShowAssetLibrary(false);
             setShowPolygonPanel(false);
+            setShowProfilePanel(false);
           });
       }
     };
@@ -392,6 +485,242 @@ const ScannerApp: React.FC = () => {
           >
             {fps > 0 ? `${fps.toFixed(1)} fps` : "--"}
           </span>
+          {["save", "load"].map((action) => (
+            <button
+              key={action}
+              onClick={() =>
+                action === "save"
+                  ? saveSession().catch(console.error)
+                  : sessionInputRef.current?.click()
+              }
+              onMouseDown={(e) => e.preventDefault()}
+              disabled={!isConnected}
+              style={{
+                padding: "4px 12px",
+                fontSize: "12px",
+                border: "1px solid rgba(255, 255, 255, 0.2)",
+                borderRadius: "4px",
+                background: "rgba(255, 255, 255, 0.05)",
+                color: "#999",
+                cursor: "pointer",
+                fontFamily: theme.fonts.mono,
+              }}
+            >
+              {action} session
+            </button>
+          ))}
+          {(["undo", "redo"] as const).map((action) => {
+            const enabled =
+              action === "undo" ? history?.can_undo : history?.can_redo;
+            const next =
+              action === "undo"
+                ? history?.undo[history.undo.length - 1]
+                : history?.redo[0];
+            return (
+              <button
+                key={action}
+                onClick={() => historyAction(action).catch(console.error)}
+                onMouseDown={(e) => e.preventDefault()}
+                disabled={!isConnected || !enabled}
+                title={next ? `${action}: ${next}` : `Nothing to ${action}`}
+                style={{
+                  padding: "4px 12px",
+                  fontSize: "12px",
+                  border: "1px solid rgba(255, 255, 255, 0.2)",
+                  borderRadius: "4px",
+                  background: "rgba(255, 255, 255, 0.05)",
+                  color: enabled ? "#999" : "#555",
+                  cursor: enabled ? "pointer" : "default",
+                  fontFamily: theme.fonts.mono,
+                }}
+              >
+                {action}
+              </button>
+            );
+          })}
+          <select
+            value=""
+            onChange={(e) => {
+              if (e.target.value === "__save") {
+                saveCameraBookmark().catch(console.error);
+              } else if (e.target.value) {
+                goToCameraBookmark(e.target.value).catch(console.error);
+              }
+            }}
+            disabled={!isConnected}
+            style={{
+              padding: "4px 8px",
+              fontSize: "12px",
+              border: "1px solid rgba(255, 255, 255, 0.2)",
+              borderRadius: "4px",
+              background: "rgba(255, 255, 255, 0.05)",
+              color: "#999",
+              fontFamily: theme.fonts.mono,
+            }}
+          >
+            <option value="">views</option>
+            {cameraBookmarks.map((bookmark) => (
+              <option key={bookmark.name} value={bookmark.name}>
+                {bookmark.name}
+              </option>
+            ))}
+            <option value="__save">save current view</option>
+          </select>
+          <button
+            onClick={() => setTopView(!topView).catch(console.error)}
+            onMouseDown={(e) => e.preventDefault()}
+            disabled={!isConnected}
+            style={{
+              padding: "4px 12px",
+              fontSize: "12px",
+              border: "1px solid rgba(255, 255, 255, 0.2)",
+              borderRadius: "4px",
+              background: topView
+                ? "rgba(255, 255, 255, 0.2)"
+                : "rgba(255, 255, 255, 0.05)",
+              color: "#999",
+              cursor: "pointer",
+              fontFamily: theme.fonts.mono,
+            }}
+          >
+            top view
+          </button>
+          <button
+            onClick={() =>
+              setWalkMode({ enabled: !walkMode?.enabled }).catch(console.error)
+            }
+            onMouseDown={(e) => e.preventDefault()}
+            disabled={!isConnected}
+            title="First-person walk at eye height (WASD to walk, right-drag to look)"
+            style={{
+              padding: "4px 12px",
+              fontSize: "12px",
+              border: "1px solid rgba(255, 255, 255, 0.2)",
+              borderRadius: "4px",
+              background: walkMode?.enabled
+                ? "rgba(255, 255, 255, 0.2)"
+                : "rgba(255, 255, 255, 0.05)",
+              color: "#999",
+              cursor: "pointer",
+              fontFamily: theme.fonts.mono,
+            }}
+          >
+            walk
+          </button>
+          <select
+            value={walkMode?.preset ?? "pedestrian"}
+            onChange={(e) => {
+              if (e.target.value !== "custom") {
+                setWalkMode({
+                  preset: e.target.value as "pedestrian" | "wheelchair",
+                }).catch(console.error);
+              }
+            }}
+            disabled={!isConnected}
+            style={{
+              padding: "4px 8px",
+              fontSize: "12px",
+              border: "1px solid rgba(255, 255, 255, 0.2)",
+              borderRadius: "4px",
+              background: "rgba(255, 255, 255, 0.05)",
+              color: "#999",
+              fontFamily: theme.fonts.mono,
+            }}
+          >
+            <option value="pedestrian">pedestrian eye</option>
+            <option value="wheelchair">wheelchair eye</option>
+            {walkMode?.preset === "custom" && (
+              <option value="custom">
+                {walkMode.eye_height.toFixed(2)} m eye
+              </option>
+            )}
+          </select>
+          <select
+            value=""
+            onChange={(e) => {
+              const action = e.target.value;
+              if (action === "record") {
+                recordCameraPath().catch(console.error);
+              } else if (action === "bookmarks") {
+                cameraPathFromBookmarks().catch(console.error);
+              } else if (action === "keyframe") {
+                addCameraPathKeyframe().catch(console.error);
+              } else if (
+                action === "play" ||
+                action === "stop" ||
+                action === "clear"
+              ) {
+                cameraPathAction(action).catch(console.error);
+              }
+            }}
+            disabled={!isConnected}
+            style={{
+              padding: "4px 8px",
+              fontSize: "12px",
+              border: "1px solid rgba(255, 255, 255, 0.2)",
+              borderRadius: "4px",
+              background:
+                cameraPath && cameraPath.state !== "idle"
+                  ? "rgba(255, 255, 255, 0.2)"
+                  : "rgba(255, 255, 255, 0.05)",
+              color: "#999",
+              fontFamily: theme.fonts.mono,
+            }}
+          >
+            <option value="">
+              {cameraPath?.keyframes.length
+                ? `path ${cameraPath.duration.toFixed(1)} s`
+                : "path"}
+            </option>
+            <option value="record">
+              {cameraPath?.state === "recording"
+                ? "stop recording"
+                : "record navigation"}
+            </option>
+            <option value="bookmarks">from views</option>
+            <option value="keyframe">add current view</option>
+            <option value="play">play</option>
+            <option value="stop">stop</option>
+            <option value="clear">clear</option>
+          </select>
+          <select
+            value=""
+            onChange={(e) => {
+              const [scale, layers] = e.target.value.split(":");
+              if (!scale) return;
+              const overlays =
+                layers === "bare"
+                  ? { polygons: false, measurements: false, grid: false }
+                  : undefined;
+              captureScreenshot({ scale: Number(scale), overlays }).catch(
+                console.error,
+              );
+            }}
+            disabled={!isConnected}
+            style={{
+              padding: "4px 8px",
+              fontSize: "12px",
+              border: "1px solid rgba(255, 255, 255, 0.2)",
+              borderRadius: "4px",
+              background: "rgba(255, 255, 255, 0.05)",
+              color: "#999",
+              fontFamily: theme.fonts.mono,
+            }}
+          >
+            <option value="">screenshot</option>
+            <option value="1">1× view</option>
+            <option value="2">2× view</option>
+            <option value="4">4× view</option>
+            <option value="1:bare">1× points only</option>
+            <option value="4:bare">4× points only</option>
+          </select>
+          <input
+            ref={sessionInputRef}
+            type="file"
+            accept=".json,application/json"
+            onChange={handleSessionFile}
+            style={{ display: "none" }}
+          />
         </div>
 
         <div
@@ -413,7 +742,7 @@ const ScannerApp: React.FC = () => {
           >
             Render Mode:
           </span>
-          {["original", "modified", "connectivity", "RGB"].map((mode) => (
+          {["original", "modified", "connectivity", "RGB", "normals", "verticality", "change"].map((mode) => (
             <button
               key={mode}
               onClick={() => handleRenderModeChange(mode)}
@@ -474,6 +803,7 @@ const ScannerApp: React.FC = () => {
         setHasSelection={setHasSelection}
         canvasRef={canvasRef}
       />
+      <ProfilePanel isVisible={showProfilePanel} canvasRef={canvasRef} />
       {!showLoadingPanel && (
         <TutorialOverlay
           isOpen={showTutorial}
@@ -499,18 +829,117 @@ const ScannerApp: React.FC = () => {
             maxWidth: "240px",
           }}
         >
-          {currentMeasurement?.distance ? (
-            <div>Current: {currentMeasurement.distance.toFixed(2)} m</div>
+          {measureSnap && (
+            <div
+              style={{
+                display: "flex",
+                alignItems: "center",
+                gap: "8px",
+                marginBottom: "8px",
+              }}
+            >
+              <label>
+                <input
+                  type="checkbox"
+                  checked={measureSnap.enabled}
+                  onChange={(e) =>
+                    setMeasureSnap({ enabled: e.target.checked }).catch(
+                      console.error,
+                    )
+                  }
+                />{" "}
+                Snap
+              </label>
+              <select
+                value={measureSnap.mode}
+                disabled={!measureSnap.enabled}
+                onChange={(e) =>
+                  setMeasureSnap({
+                    mode: e.target.value as typeof measureSnap.mode,
+                  }).catch(console.error)
+                }
+                style={{
+                  ...styleUtils.inputField(),
+                  padding: "2px 4px",
+                  fontSize: "12px",
+                }}
+              >
+                <option value="point">Nearest point</option>
+                <option value="ground">Nearest ground</option>
+                <option value="vertex">Vertex</option>
+                <option value="midpoint">Edge midpoint</option>
+              </select>
+            </div>
+          )}
+          <select
+            value={measureMode}
+            onChange={(e) =>
+              setMeasureMode(e.target.value as MeasureMode).catch(
+                console.error,
+              )
+            }
+            style={{
+              ...styleUtils.inputField(),
+              padding: "2px 4px",
+              fontSize: "12px",
+              marginBottom: "8px",
+            }}
+          >
+            <option value="distance">Distance</option>
+            <option value="polyline">Polyline</option>
+            <option value="area">Area</option>
+            <option value="height">Height above ground</option>
+            <option value="slope">Slope</option>
+            <option value="angle">Angle</option>
+          </select>
+          {currentMeasurement?.label ? (
+            <div>Current: {currentMeasurement.label}</div>
           ) : (
-            <div>Click and drag to measure</div>
+            <div>Click to place points</div>
+          )}
+          {(measureMode === "polyline" || measureMode === "area") && (
+            <button
+              onClick={() => measureAction("complete").catch(console.error)}
+              style={{ marginTop: "4px", fontSize: "12px" }}
+            >
+              Finish
+            </button>
           )}
           {completedMeasurements.length > 0 && (
             <div style={{ marginTop: "8px" }}>
               <ul style={{ margin: 0, paddingLeft: "16px" }}>
                 {completedMeasurements.map((m) => (
-                  <li key={m.id}>{m.distance?.toFixed(2)} m</li>
+                  <li key={m.id}>
+                    {m.name}: {m.label}{" "}
+                    <button
+                      onClick={() =>
+                        deleteMeasurement(m.id).catch(console.error)
+                      }
+                      style={{ fontSize: "11px", padding: "0 4px" }}
+                    >
+                      ×
+                    </button>
+                  </li>
                 ))}
               </ul>
+              <div style={{ display: "flex", gap: "4px", marginTop: "8px" }}>
+                <button
+                  onClick={() =>
+                    exportMeasurements("csv").catch(console.error)
+                  }
+                  style={{ fontSize: "12px" }}
+                >
+                  Export CSV
+                </button>
+                <button
+                  onClick={() =>
+                    exportMeasurements("json").catch(console.error)
+                  }
+                  style={{ fontSize: "12px" }}
+                >
+                  Export JSON
+                </button>
+              </div>
             </div>
           )}
         </div>
@@ -5,10 +5,12 @@ use crate::atlas::{
 use crate::bounds::PointCloudBounds;
 use crate::bounds::calculate_bounds;
 use crate::dds_writer::write_f32_texture;
+use crate::error::PreprocessError;
+use crate::logging::{FailureClass, Reporter, Stage, log_info};
 use crate::spatial_layout::SpatialPoint;
 use constants::coordinate_system::transform_coordinates;
 use constants::texture::TEXTURE_SIZE;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressStyle;
 use las::Reader;
 use std::fs::{self, File};
 use std::io::BufReader;
@@ -21,15 +23,18 @@ pub struct AssetProcessor {
     output_dir: std::path::PathBuf,
     /// Base name for generated files.
     output_name: String,
+    /// Destination for stages, progress and log messages.
+    reporter: Reporter,
 }
 
 impl AssetProcessor {
     /// Creates new asset processor with output configuration.
     /// Sets up directory structure for atlas organisation.
-    pub fn new(output_dir: &Path, output_name: &str) -> Self {
+    pub fn new(output_dir: &Path, output_name: &str, reporter: Reporter) -> Self {
         Self {
             output_dir: output_dir.to_path_buf(),
             output_name: output_name.to_string(),
+            reporter,
         }
     }
 
@@ -38,17 +43,25 @@ impl AssetProcessor {
     pub fn process_asset_library(
         &self,
         asset_dir: &Path,
-    ) -> Result<AssetAtlasInfo, Box<dyn std::error::Error>> {
-        println!("Processing asset library: {}", asset_dir.display());
-        let candidates = discover_asset_files(asset_dir)?;
-        println!("Found {} asset candidates", candidates.len());
+    ) -> Result<AssetAtlasInfo, PreprocessError> {
+        let stage = Stage::start(&self.reporter, "asset_loading", FailureClass::Input);
+        log_info!(
+            self.reporter,
+            "Processing asset library: {}",
+            asset_dir.display()
+        );
+        let candidates = discover_asset_files(asset_dir, &self.reporter)?;
+        log_info!(self.reporter, "Found {} asset candidates", candidates.len());
         if candidates.is_empty() {
-            return Err("No valid asset files found in library directory".into());
+            return Err(PreprocessError::InvalidData(
+                "No valid asset files found in library directory".to_string(),
+            ));
         }
 
-        let mut atlas_gen = AtlasTextureGenerator::new();
-        let pb = ProgressBar::new(candidates.len() as u64);
-        pb.set_style(
+        let mut atlas_gen = AtlasTextureGenerator::new(self.reporter.clone());
+        let pb = self.reporter.progress_bar(
+            "asset_loading",
+            candidates.len() as u64,
             ProgressStyle::default_bar()
                 .template("[{bar:40.green/blue}] {pos}/{len} assets ({percent}%) {msg}")
                 .unwrap()
@@ -56,18 +69,23 @@ impl AssetProcessor {
         );
         pb.set_message("Processing assets");
         for (idx, candidate) in candidates.iter().enumerate() {
-            println!(
+            log_info!(
+                self.reporter,
                 "Processing #{}: {} -> will assign to tile",
-                idx, candidate.name
+                idx,
+                candidate.name
             );
             let (asset_points, asset_bound) = self.load_asset_points(&candidate)?;
             atlas_gen.add_asset(&asset_points, candidate.name.clone(), asset_bound)?;
             pb.inc(1);
         }
         pb.finish_with_message("Assets processed");
+        stage.finish();
 
         // Generate and save atlas textures.
+        let stage = Stage::start(&self.reporter, "atlas_output", FailureClass::Output);
         self.generate_and_save_atlas(&atlas_gen)?;
+        stage.finish();
 
         // Build atlas info for manifest.
         let texture_files = AtlasTextureFiles {
@@ -93,8 +111,8 @@ impl AssetProcessor {
     fn generate_and_save_atlas(
         &self,
         atlas_gen: &AtlasTextureGenerator,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!("Generating atlas textures...");
+    ) -> Result<(), PreprocessError> {
+        log_info!(self.reporter, "Generating atlas textures...");
 
         let atlas_textures = atlas_gen.generate_atlas_textures();
 
@@ -124,7 +142,7 @@ impl AssetProcessor {
             ddsfile::DxgiFormat::R32G32B32A32_Float,
         )?;
 
-        println!("Saved asset atlas textures");
+        log_info!(self.reporter, "Saved asset atlas textures");
         Ok(())
     }
 
@@ -133,7 +151,7 @@ impl AssetProcessor {
     fn load_asset_points(
         &self,
         candidate: &AssetCandidate,
-    ) -> Result<(Vec<SpatialPoint>, PointCloudBounds), Box<dyn std::error::Error>> {
+    ) -> Result<(Vec<SpatialPoint>, PointCloudBounds), PreprocessError> {
         let file = File::open(&candidate.path)?;
 
         let buf_reader = BufReader::new(file);
@@ -141,7 +159,7 @@ impl AssetProcessor {
 
         // --- First pass: collect raw coordinates & compute bounds ---
         let mut raw_points = Vec::new();
-        let asset_bound = calculate_bounds(&candidate.path, false).unwrap();
+        let asset_bound = calculate_bounds(&candidate.path, false, &self.reporter).unwrap();
 
         for point_result in reader.points() {
             let point = point_result?;
@@ -190,7 +208,8 @@ impl AssetProcessor {
             });
         }
 
-        println!(
+        log_info!(
+            self.reporter,
             "Loaded {} points from {}",
             asset_points.len(),
             candidate.name
@@ -46,7 +46,7 @@
 //! Each asset contributes its texel data (such as positions or colour maps) via
 //! `add_asset()`, and the generator computes a contiguous layout in atlas space.
 //!
-//! ```rust
+//! ```rust,ignore
 //! let mut atlas = AtlasTextureGenerator::new(2048, 2048);
 //! atlas.add_asset("car", &car_points);
 //! atlas.add_asset("bike", &bike_points);
@@ -75,7 +75,9 @@
 //! - [`AtlasMetadata`] — summarises layout info such as stride and tile count.
 //!
 use crate::bounds::PointCloudBounds;
+use crate::logging::{Reporter, log_info};
 use crate::spatial_layout::SpatialPoint;
+use constants::class::encode_object_id;
 use constants::texture::TEXTURE_SIZE;
 use las::Reader;
 use serde::{Deserialize, Serialize};
@@ -243,12 +245,14 @@ pub struct AtlasTextureGenerator {
     asset_metadata: Vec<AssetMetadata>,
     /// Current tile assignment counter.
     next_tile_position: (u32, u32),
+    /// Destination for tile assignment messages.
+    reporter: Reporter,
 }
 
 impl AtlasTextureGenerator {
     /// Creates new atlas generator with standard configuration.
     /// Initializes empty tile grid and metadata tracking.
-    pub fn new() -> Self {
+    pub fn new(reporter: Reporter) -> Self {
         let config = AtlasConfig::standard();
 
         Self {
@@ -256,6 +260,7 @@ impl AtlasTextureGenerator {
             asset_tiles: HashMap::new(),
             asset_metadata: Vec::new(),
             next_tile_position: (0, 0),
+            reporter,
         }
     }
 
@@ -285,7 +290,8 @@ impl AtlasTextureGenerator {
         // Store asset data in the tile.
         self.asset_tiles.insert(tile_pos, asset_data.to_vec());
 
-        println!(
+        log_info!(
+            self.reporter,
             "[Atlas] Added asset '{}' at tile ({}, {}), UV=({:.3?} → {:.3?}), points={}",
             asset_name,
             tile_pos.0,
@@ -327,7 +333,8 @@ impl AtlasTextureGenerator {
     /// Generates position and color/classification atlas textures.
     /// Spatial indexing not required for instanced asset rendering.
     pub fn generate_atlas_textures(&self) -> AtlasTextureSet {
-        println!(
+        log_info!(
+            self.reporter,
             "[Atlas] Generating textures: atlas {}x{}, tile {}x{}, total assets={}",
             self.config.atlas_size,
             self.config.atlas_size,
@@ -349,7 +356,8 @@ impl AtlasTextureGenerator {
             // let valid_points = points.len().min(max_points);
 
             if points.len() > (self.config.tile_size * self.config.tile_size) as usize {
-                println!(
+                log_info!(
+                    self.reporter,
                     "Asset overflow: {} points, but tile can hold only {}",
                     points.len(),
                     self.config.tile_size * self.config.tile_size
@@ -374,7 +382,7 @@ impl AtlasTextureGenerator {
                     position_data[pixel_idx] = point.world_pos.0 as f32;
                     position_data[pixel_idx + 1] = point.world_pos.1 as f32;
                     position_data[pixel_idx + 2] = point.world_pos.2 as f32;
-                    position_data[pixel_idx + 3] = point.object_number / 121.0;
+                    position_data[pixel_idx + 3] = encode_object_id(point.object_number);
 
                     // Color and classification data.
                     if let Some((r, g, b)) = point.color {
@@ -412,7 +420,10 @@ impl AtlasTextureGenerator {
 
 /// Discovers and validates asset library files for atlas generation.
 /// Filters files by extension and validates point cloud structure.
-pub fn discover_asset_files(asset_dir: &Path) -> Result<Vec<AssetCandidate>, AtlasError> {
+pub fn discover_asset_files(
+    asset_dir: &Path,
+    reporter: &Reporter,
+) -> Result<Vec<AssetCandidate>, AtlasError> {
     let mut candidates = Vec::new();
 
     // Scan directory for .laz and .las files.
@@ -446,9 +457,9 @@ pub fn discover_asset_files(asset_dir: &Path) -> Result<Vec<AssetCandidate>, Atl
 
     candidates.sort_by(|a, b| a.name.cmp(&b.name));
 
-    println!("Asset processing order:");
+    log_info!(reporter, "Asset processing order:");
     for (i, candidate) in candidates.iter().enumerate() {
-        println!("  {}: {}", i, candidate.name);
+        log_info!(reporter, "  {}: {}", i, candidate.name);
     }
 
     Ok(candidates)
@@ -1,6 +1,8 @@
+use crate::error::PreprocessError;
 use crate::laz::create_reader;
+use crate::logging::Reporter;
 use constants::coordinate_system::transform_coordinates;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressStyle;
 use rayon::prelude::{ParallelIterator, ParallelSlice};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -70,7 +72,19 @@ impl PointCloudBounds {
 pub fn calculate_bounds(
     file_path: &Path,
     use_header_transform: bool,
-) -> Result<PointCloudBounds, Box<dyn std::error::Error>> {
+    reporter: &Reporter,
+) -> Result<PointCloudBounds, PreprocessError> {
+    calculate_bounds_filtered(file_path, use_header_transform, None, reporter)
+}
+
+/// Calculate coordinate bounds while skipping points flagged in `excluded`.
+/// The mask is indexed by file order so removed noise cannot stretch the bounds.
+pub fn calculate_bounds_filtered(
+    file_path: &Path,
+    use_header_transform: bool,
+    excluded: Option<&[bool]>,
+    reporter: &Reporter,
+) -> Result<PointCloudBounds, PreprocessError> {
     let mut reader = create_reader(file_path)?;
     let total_points = reader.header().number_of_points() as usize;
 
@@ -89,8 +103,9 @@ pub fn calculate_bounds(
     let origin = transform_coordinates(x_offset, y_offset, z_offset);
 
     // Load points with progress tracking.
-    let pb = ProgressBar::new(total_points as u64);
-    pb.set_style(
+    let pb = reporter.progress_bar(
+        "bounds",
+        total_points as u64,
         ProgressStyle::default_bar()
             .template("[{bar:40.cyan/blue}] {pos}/{len} points ({percent}%) {msg}")
             .unwrap()
@@ -100,7 +115,10 @@ pub fn calculate_bounds(
 
     let mut all_points = Vec::with_capacity(total_points);
     for (idx, point_result) in reader.points().enumerate() {
-        all_points.push(point_result?);
+        let point = point_result?;
+        if !excluded.is_some_and(|mask| mask.get(idx).copied().unwrap_or(false)) {
+            all_points.push(point);
+        }
 
         if idx % 50_000 == 0 {
             pb.set_position(idx as u64);
@@ -109,8 +127,9 @@ pub fn calculate_bounds(
     pb.finish_with_message("Points loaded");
 
     // Process bounds calculation in parallel chunks for efficiency.
-    let pb = ProgressBar::new(all_points.len() as u64);
-    pb.set_style(
+    let pb = reporter.progress_bar(
+        "bounds",
+        all_points.len() as u64,
         ProgressStyle::default_bar()
             .template("[{bar:40.green/blue}] {pos}/{len} chunks ({percent}%) {msg}")
             .unwrap()
@@ -0,0 +1,533 @@
+/// Cloud-to-cloud change detection against an earlier scan of the same area.
+use crate::bounds::PointCloudBounds;
+use crate::error::PreprocessError;
+use crate::laz::{UniformSampler, create_reader};
+use crate::logging::{Progress, Reporter};
+use crate::normals::estimate_normal;
+use crate::plan_raster::round_cm;
+use crate::spatial_hash::{SpatialHash, distance_sq};
+use crate::spatial_layout::SpatialPoint;
+use constants::class::get_class_name;
+use constants::coordinate_system::transform_coordinates;
+use constants::texture::TEXTURE_SIZE;
+use indicatif::ProgressStyle;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Distance measured from each point to the other epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeMethod {
+    /// Distance to the nearest point, signed by its vertical direction.
+    PointToPoint,
+    /// Distance along the normal of a plane fitted to the nearest points.
+    PointToSurface,
+}
+
+/// Reference epoch and comparison parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChangeSettings {
+    /// Earlier LAS/LAZ scan of the same area.
+    pub reference: PathBuf,
+    pub method: ChangeMethod,
+    /// Distances at or beyond this many metres count as change.
+    pub threshold: f64,
+    /// Search limit in metres; points without a neighbour this close are new or removed.
+    pub max_distance: f64,
+    /// Neighbours used for each plane fit in `point_to_surface` mode.
+    pub k_neighbours: usize,
+    /// Voxel size of the neighbour search hash in world units.
+    pub search_cell_size: f64,
+}
+
+impl Default for ChangeSettings {
+    fn default() -> Self {
+        Self {
+            reference: PathBuf::new(),
+            method: ChangeMethod::PointToSurface,
+            threshold: 0.1,
+            max_distance: 2.0,
+            k_neighbours: 8,
+            search_cell_size: 0.5,
+        }
+    }
+}
+
+/// Fraction of an object's points that must change for the object to be reported.
+const OBJECT_CHANGE_FRACTION: f64 = 0.5;
+
+/// Change counts for one classification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassChange {
+    pub class_id: u8,
+    pub class_name: String,
+    /// New epoch points at least `threshold` from the reference.
+    pub changed_points: usize,
+    /// Changed points above or in front of the reference surface.
+    pub raised_points: usize,
+    /// Changed points below or behind the reference surface.
+    pub lowered_points: usize,
+    /// Reference points with no new epoch point within `threshold`.
+    pub removed_points: usize,
+    /// New epoch objects with at least half their points changed.
+    pub changed_objects: Vec<u32>,
+    /// Reference objects with at least half their points removed.
+    pub removed_objects: Vec<u32>,
+}
+
+/// Epoch comparison totals and per-class breakdown.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeSummary {
+    /// Reference file as given in the configuration.
+    pub reference: String,
+    pub method: ChangeMethod,
+    pub threshold_m: f64,
+    pub max_distance_m: f64,
+    pub compared_points: usize,
+    /// Sampled reference points inside the new epoch's bounds plus `max_distance`.
+    pub reference_points: usize,
+    pub changed_points: usize,
+    pub removed_points: usize,
+    pub mean_abs_distance_m: f64,
+    /// Classes with any changed or removed points, by class ID.
+    pub classes: Vec<ClassChange>,
+}
+
+/// Change texture in position texture order with its summary.
+pub struct ChangeResult {
+    /// RGBA32F texels: signed distance, changed flag (0 or 1), nearest
+    /// reference class (-1 when none within `max_distance`), unused.
+    pub texture: Vec<f32>,
+    pub summary: ChangeSummary,
+}
+
+/// Reference epoch points cropped to the compared area, sampled like the new epoch.
+struct ReferenceCloud {
+    positions: Vec<(f64, f64, f64)>,
+    classes: Vec<u8>,
+    objects: Vec<u32>,
+}
+
+/// Compare the sampled new epoch against the reference file.
+/// Both clouds are transformed to absolute world coordinates, so differing LAS
+/// header offsets between epochs do not affect the alignment. `sampling_ratio`
+/// is the fraction of new epoch points kept; the reference is thinned by the
+/// same ratio so points dropped by sampling are not reported as removed.
+pub fn detect_changes(
+    points: &[SpatialPoint],
+    bounds: &PointCloudBounds,
+    settings: &ChangeSettings,
+    sampling_ratio: f64,
+    reporter: &Reporter,
+) -> Result<ChangeResult, PreprocessError> {
+    let reference = load_reference(
+        &settings.reference,
+        bounds,
+        settings.max_distance,
+        sampling_ratio,
+        reporter,
+    )?;
+    if reference.positions.is_empty() {
+        return Err(PreprocessError::InvalidData(format!(
+            "Reference cloud '{}' does not overlap the input",
+            settings.reference.display()
+        )));
+    }
+
+    let max_rings = (settings.max_distance / settings.search_cell_size).ceil() as i64;
+
+    // New epoch against the reference: additions, moves and resurfacing.
+    let reference_hash = SpatialHash::new(&reference.positions, settings.search_cell_size);
+    let pb = change_progress_bar(reporter, points.len(), "Measuring distances to reference");
+    let forward: Vec<Option<(f64, usize)>> = points
+        .par_iter()
+        .enumerate()
+        .map_init(Vec::new, |neighbours, (i, point)| {
+            if i % 50_000 == 0 {
+                pb.set_position(i as u64);
+            }
+            signed_distance(
+                point.world_pos,
+                &reference.positions,
+                &reference_hash,
+                max_rings,
+                settings,
+                neighbours,
+            )
+        })
+        .collect();
+    pb.finish_with_message("Reference distances measured");
+
+    // Reference against the new epoch: anything no longer there.
+    let positions: Vec<(f64, f64, f64)> = points.iter().map(|p| p.world_pos).collect();
+    let current_hash = SpatialHash::new(&positions, settings.search_cell_size);
+    let pb = change_progress_bar(
+        reporter,
+        reference.positions.len(),
+        "Finding removed points",
+    );
+    let removed: Vec<bool> = reference
+        .positions
+        .par_iter()
+        .enumerate()
+        .map_init(Vec::new, |neighbours, (i, &pos)| {
+            if i % 50_000 == 0 {
+                pb.set_position(i as u64);
+            }
+            signed_distance(
+                pos,
+                &positions,
+                &current_hash,
+                max_rings,
+                settings,
+                neighbours,
+            )
+            .is_none_or(|(distance, _)| distance.abs() >= settings.threshold)
+        })
+        .collect();
+    pb.finish_with_message("Removed points found");
+
+    let mut texture = vec![0.0f32; TEXTURE_SIZE * TEXTURE_SIZE * 4];
+    for (texel, distance) in texture.chunks_exact_mut(4).zip(&forward) {
+        let (signed, class) = match distance {
+            Some((distance, nearest)) => (*distance, f32::from(reference.classes[*nearest])),
+            None => (settings.max_distance, -1.0),
+        };
+        texel[0] = signed as f32;
+        texel[1] = if signed.abs() >= settings.threshold {
+            1.0
+        } else {
+            0.0
+        };
+        texel[2] = class;
+    }
+
+    let summary = summarise(points, &forward, &reference, &removed, settings);
+    Ok(ChangeResult { texture, summary })
+}
+
+/// Signed distance from `pos` to the target cloud and the nearest target index,
+/// or `None` when no target point lies within `max_distance`.
+fn signed_distance(
+    pos: (f64, f64, f64),
+    targets: &[(f64, f64, f64)],
+    hash: &SpatialHash,
+    max_rings: i64,
+    settings: &ChangeSettings,
+    neighbours: &mut Vec<usize>,
+) -> Option<(f64, usize)> {
+    let k = match settings.method {
+        ChangeMethod::PointToPoint => 1,
+        ChangeMethod::PointToSurface => settings.k_neighbours.max(3),
+    };
+    hash.nearest_k(pos, k, max_rings, neighbours);
+
+    let &nearest = neighbours.first()?;
+    let nearest_distance = distance_sq(targets[nearest], pos).sqrt();
+    if nearest_distance > settings.max_distance {
+        return None;
+    }
+
+    let point_distance = || {
+        if pos.1 < targets[nearest].1 {
+            -nearest_distance
+        } else {
+            nearest_distance
+        }
+    };
+
+    let distance = match settings.method {
+        ChangeMethod::PointToSurface if neighbours.len() >= 3 => {
+            // Normals are oriented upwards, so positive means above the surface.
+            let (normal, _) = estimate_normal(targets, neighbours);
+            let count = neighbours.len() as f64;
+            let centroid = neighbours.iter().fold((0.0, 0.0, 0.0), |sum, &idx| {
+                let p = targets[idx];
+                (
+                    sum.0 + p.0 / count,
+                    sum.1 + p.1 / count,
+                    sum.2 + p.2 / count,
+                )
+            });
+            (pos.0 - centroid.0) * normal[0]
+                + (pos.1 - centroid.1) * normal[1]
+                + (pos.2 - centroid.2) * normal[2]
+        }
+        _ => point_distance(),
+    };
+
+    Some((
+        distance.clamp(-settings.max_distance, settings.max_distance),
+        nearest,
+    ))
+}
+
+/// Read the reference file at `sampling_ratio`, keeping points within `margin`
+/// of the new epoch's bounds.
+fn load_reference(
+    path: &Path,
+    bounds: &PointCloudBounds,
+    margin: f64,
+    sampling_ratio: f64,
+    reporter: &Reporter,
+) -> Result<ReferenceCloud, PreprocessError> {
+    let mut reader = create_reader(path)?;
+    let total_points = reader.header().number_of_points() as usize;
+
+    // Bounds are stored relative to the new epoch's origin.
+    let min = (
+        bounds.origin.0 + bounds.min_x - margin,
+        bounds.origin.1 + bounds.min_y - margin,
+        bounds.origin.2 + bounds.min_z - margin,
+    );
+    let max = (
+        bounds.origin.0 + bounds.max_x + margin,
+        bounds.origin.1 + bounds.max_y + margin,
+        bounds.origin.2 + bounds.max_z + margin,
+    );
+
+    let pb = change_progress_bar(reporter, total_points, "Loading reference epoch");
+    let mut reference = ReferenceCloud {
+        positions: Vec::new(),
+        classes: Vec::new(),
+        objects: Vec::new(),
+    };
+    let mut sampler = UniformSampler::new(sampling_ratio);
+
+    for (idx, point_result) in reader.points().enumerate() {
+        if idx % 50_000 == 0 {
+            pb.set_position(idx as u64);
+        }
+        if !sampler.keep() {
+            continue;
+        }
+
+        let point = point_result?;
+        let pos = transform_coordinates(point.x, point.y, point.z);
+        if pos.0 < min.0
+            || pos.0 > max.0
+            || pos.1 < min.1
+            || pos.1 > max.1
+            || pos.2 < min.2
+            || pos.2 > max.2
+        {
+            continue;
+        }
+
+        let object_number = if point.extra_bytes.len() >= 4 {
+            f32::from_le_bytes([
+                point.extra_bytes[0],
+                point.extra_bytes[1],
+                point.extra_bytes[2],
+                point.extra_bytes[3],
+            ])
+        } else {
+            0.0
+        };
+
+        reference.positions.push(pos);
+        reference.classes.push(u8::from(point.classification));
+        reference.objects.push(object_number as u32);
+    }
+
+    pb.finish_with_message("Reference epoch loaded");
+    Ok(reference)
+}
+
+/// Point and object tallies for one class.
+#[derive(Default)]
+struct ClassTally {
+    changed: usize,
+    raised: usize,
+    lowered: usize,
+    removed: usize,
+    /// Object ID to (points, changed points) in the new epoch.
+    objects: HashMap<u32, (usize, usize)>,
+    /// Object ID to (points, removed points) in the reference.
+    reference_objects: HashMap<u32, (usize, usize)>,
+}
+
+fn summarise(
+    points: &[SpatialPoint],
+    forward: &[Option<(f64, usize)>],
+    reference: &ReferenceCloud,
+    removed: &[bool],
+    settings: &ChangeSettings,
+) -> ChangeSummary {
+    let mut tallies: BTreeMap<u8, ClassTally> = BTreeMap::new();
+    let mut changed_points = 0;
+    let mut abs_sum = 0.0;
+
+    for (point, distance) in points.iter().zip(forward) {
+        let distance = distance.map_or(settings.max_distance, |(d, _)| d);
+        abs_sum += distance.abs();
+
+        let tally = tallies.entry(point.classification).or_default();
+        let changed = distance.abs() >= settings.threshold;
+        if changed {
+            changed_points += 1;
+            tally.changed += 1;
+            if distance >= 0.0 {
+                tally.raised += 1;
+            } else {
+                tally.lowered += 1;
+            }
+        }
+
+        let object = point.object_number as u32;
+        if object != 0 {
+            let entry = tally.objects.entry(object).or_default();
+            entry.0 += 1;
+            entry.1 += usize::from(changed);
+        }
+    }
+
+    for ((&class, &object), &gone) in reference
+        .classes
+        .iter()
+        .zip(&reference.objects)
+        .zip(removed)
+    {
+        let tally = tallies.entry(class).or_default();
+        tally.removed += usize::from(gone);
+        if object != 0 {
+            let entry = tally.reference_objects.entry(object).or_default();
+            entry.0 += 1;
+            entry.1 += usize::from(gone);
+        }
+    }
+
+    let classes = tallies
+        .into_iter()
+        .filter(|(_, tally)| tally.changed > 0 || tally.removed > 0)
+        .map(|(class_id, tally)| ClassChange {
+            class_id,
+            class_name: get_class_name(class_id),
+            changed_points: tally.changed,
+            raised_points: tally.raised,
+            lowered_points: tally.lowered,
+            removed_points: tally.removed,
+            changed_objects: mostly_changed(&tally.objects),
+            removed_objects: mostly_changed(&tally.reference_objects),
+        })
+        .collect();
+
+    ChangeSummary {
+        reference: settings.reference.display().to_string(),
+        method: settings.method,
+        threshold_m: settings.threshold,
+        max_distance_m: settings.max_distance,
+        compared_points: points.len(),
+        reference_points: reference.positions.len(),
+        changed_points,
+        removed_points: removed.iter().filter(|&&gone| gone).count(),
+        mean_abs_distance_m: round_cm(abs_sum / points.len().max(1) as f64),
+        classes,
+    }
+}
+
+/// Sorted IDs of objects whose changed fraction reaches `OBJECT_CHANGE_FRACTION`.
+fn mostly_changed(objects: &HashMap<u32, (usize, usize)>) -> Vec<u32> {
+    let mut ids: Vec<u32> = objects
+        .iter()
+        .filter(|(_, (total, changed))| *changed as f64 >= *total as f64 * OBJECT_CHANGE_FRACTION)
+        .map(|(&id, _)| id)
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+fn change_progress_bar(reporter: &Reporter, len: usize, message: &'static str) -> Progress {
+    let pb = reporter.progress_bar(
+        "change_detection",
+        len as u64,
+        ProgressStyle::default_bar()
+            .template("[{bar:40.magenta/blue}] {pos}/{len} points ({percent}%) {msg}")
+            .unwrap()
+            .progress_chars("▉▊▋▌▍▎▏ "),
+    );
+    pb.set_message(message);
+    pb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::calculate_bounds;
+    use crate::logging::ProgressEvent;
+    use std::sync::Arc;
+
+    /// Gently sloping 20 m square at 0.1 m spacing.
+    fn write_epoch(path: &Path) {
+        let header = las::Builder::default().into_header().unwrap();
+        let mut writer = las::Writer::from_path(path, header).unwrap();
+        for i in 0..200 {
+            for j in 0..200 {
+                let (x, y) = (i as f64 * 0.1, j as f64 * 0.1);
+                writer
+                    .write_point(las::Point {
+                        x,
+                        y,
+                        z: 0.3 * (x / 4.0).sin() + 0.02 * y,
+                        classification: las::point::Classification::Ground,
+                        ..Default::default()
+                    })
+                    .unwrap();
+            }
+        }
+        writer.close().unwrap();
+    }
+
+    /// Sample the epoch the way the converter does for a cloud above the texture limit.
+    fn sample_epoch(path: &Path, ratio: f64) -> Vec<SpatialPoint> {
+        let mut reader = create_reader(path).unwrap();
+        let mut sampler = UniformSampler::new(ratio);
+        reader
+            .points()
+            .filter(|_| sampler.keep())
+            .map(|point| {
+                let point = point.unwrap();
+                SpatialPoint {
+                    world_pos: transform_coordinates(point.x, point.y, point.z),
+                    norm_pos: (0.0, 0.0, 0.0),
+                    morton_index: 0,
+                    spatial_cell_id: 0,
+                    classification: u8::from(point.classification),
+                    color: None,
+                    object_number: 0.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn identical_sampled_epochs_report_no_change() {
+        let path = std::env::temp_dir().join(format!("change_epoch_{}.las", std::process::id()));
+        write_epoch(&path);
+
+        let reporter = Reporter::with_listener(Arc::new(|_: &ProgressEvent| {}));
+        let bounds = calculate_bounds(&path, true, &reporter).unwrap();
+        let ratio = 0.25;
+        let points = sample_epoch(&path, ratio);
+
+        for method in [ChangeMethod::PointToPoint, ChangeMethod::PointToSurface] {
+            let settings = ChangeSettings {
+                reference: path.clone(),
+                method,
+                ..Default::default()
+            };
+            let summary = detect_changes(&points, &bounds, &settings, ratio, &reporter)
+                .unwrap()
+                .summary;
+
+            assert_eq!(summary.reference_points, points.len());
+            assert_eq!(summary.removed_points, 0, "{:?}", method);
+            assert_eq!(summary.changed_points, 0, "{:?}", method);
+        }
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
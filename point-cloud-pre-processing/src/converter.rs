@@ -2,15 +2,28 @@
 use crate::asset_processor::AssetProcessor;
 use crate::atlas::generate_programmatic_name;
 use crate::bounds::PointCloudBounds;
-use crate::bounds::calculate_bounds;
+use crate::bounds::calculate_bounds_filtered;
+use crate::change_detection::detect_changes;
 use crate::dds_writer::write_f32_texture;
+use crate::error::PreprocessError;
 use crate::heightmap::HeightmapGenerator;
-use crate::manifest::{ClassificationInfo, ManifestGenerator, TerrainInfo, TerrainTextureFiles};
-use crate::spatial_layout::SpatialTextureGenerator;
+use crate::kerbs::{KerbSettings, detect_kerbs};
+use crate::laz::UniformSampler;
+use crate::logging::{FailureClass, Reporter, Stage, log_info, log_warn};
+use crate::manifest::{
+    AnalysisInfo, ClassificationInfo, KerbInfo, ManifestGenerator, SidewalkWidthInfo, TerrainInfo,
+    TerrainTextureFiles,
+};
+use crate::normals::generate_normal_texture;
+use crate::outliers::{NOISE_CLASSIFICATION, NoiseMask, detect_outliers};
+use crate::pipeline_config::PipelineConfig;
+use crate::segmentation::{has_object_ids, segment_instances};
+use crate::sidewalk_width::{SidewalkWidthSettings, analyse_sidewalk_width};
+use crate::spatial_layout::{SpatialPoint, SpatialTextureGenerator};
 use constants::class::{ROAD_CLASSIFICATIONS, get_class_name};
 use constants::coordinate_system::transform_coordinates;
-use constants::texture::{COLOUR_DETECTION_SAMPLE_SIZE, MAX_POINTS, TEXTURE_SIZE};
-use indicatif::{ProgressBar, ProgressStyle};
+use constants::texture::{MAX_POINTS, TEXTURE_SIZE};
+use indicatif::ProgressStyle;
 use las::Reader;
 use std::collections::HashMap;
 use std::fs::{self, File};
@@ -28,12 +41,16 @@ pub struct PointCloudConverter {
     output_dir: PathBuf,
     /// Programmatic output name derived from input filename.
     output_name: String,
+    /// Stage parameters for filters, sampling, heightmap and derivatives.
+    config: PipelineConfig,
+    /// Destination for this run's stages, progress and warnings.
+    reporter: Reporter,
 }
 
 impl PointCloudConverter {
     /// Create new converter instance for single file processing.
     /// Maintains backward compatibility with existing workflows.
-    pub fn new(input_path: &str, output_stem: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(input_path: &str, output_stem: &str) -> Result<Self, PreprocessError> {
         let main_cloud_path = PathBuf::from(input_path);
         let output_dir = main_cloud_path
             .parent()
@@ -45,6 +62,8 @@ impl PointCloudConverter {
             asset_library_dir: None,
             output_dir,
             output_name: generate_programmatic_name(output_stem),
+            config: PipelineConfig::new(Path::new(input_path), None, None),
+            reporter: Reporter::default(),
         })
     }
 
@@ -54,13 +73,13 @@ impl PointCloudConverter {
         main_cloud: &Path,
         asset_dir: &Path,
         output_dir: &Path,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
+    ) -> Result<Self, PreprocessError> {
         if !main_cloud.exists() {
-            return Err(format!("Main cloud file does not exist: {}", main_cloud.display()).into());
+            return Err(PreprocessError::MissingInput(main_cloud.to_path_buf()));
         }
 
         if !asset_dir.is_dir() {
-            return Err(format!("Asset directory does not exist: {}", asset_dir.display()).into());
+            return Err(PreprocessError::MissingInput(asset_dir.to_path_buf()));
         }
 
         // Create organized output directory structure.
@@ -82,57 +101,144 @@ impl PointCloudConverter {
             asset_library_dir: Some(asset_dir.to_path_buf()),
             output_dir: output_dir.to_path_buf(),
             output_name: generate_programmatic_name(output_name),
+            config: PipelineConfig::new(main_cloud, Some(asset_dir), Some(output_dir)),
+            reporter: Reporter::default(),
         })
     }
 
+    /// Creates converter instance from a validated pipeline configuration.
+    /// Uses the asset library workflow when the config lists an asset directory,
+    /// and reports through `reporter`.
+    pub fn from_config(
+        config: PipelineConfig,
+        reporter: Reporter,
+    ) -> Result<Self, PreprocessError> {
+        let main_cloud = config.input.main_cloud.clone();
+        let default_output = main_cloud.parent().unwrap_or(Path::new(".")).to_path_buf();
+
+        let mut converter = if let Some(asset_dir) = &config.input.asset_library {
+            let output_dir = config.output.directory.clone().unwrap_or(default_output);
+            Self::with_asset_library(&main_cloud, asset_dir, &output_dir)?
+        } else {
+            let input_path = main_cloud.to_string_lossy().to_string();
+            let output_stem = input_path.trim_end_matches(".laz").trim_end_matches(".las");
+            let mut converter = Self::new(&input_path, output_stem)?;
+            if let Some(output_dir) = &config.output.directory {
+                fs::create_dir_all(output_dir)?;
+                converter.output_dir = output_dir.clone();
+            }
+            converter
+        };
+
+        converter.config = config;
+        converter.reporter = reporter;
+        Ok(converter)
+    }
+
     /// Executes complete preprocessing pipeline for terrain and assets.
     /// Generates both terrain textures and asset atlas with unified manifest.
-    pub fn convert(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        println!("Starting terrain and asset library processing...");
+    pub fn convert(&mut self) -> Result<(), PreprocessError> {
+        log_info!(
+            self.reporter,
+            "Starting terrain and asset library processing..."
+        );
 
         // Process main terrain point cloud.
-        let (terrain_info, classes) = self.process_main_terrain()?;
+        let (terrain_info, classes, analysis) = self.process_main_terrain()?;
 
         // Process asset library if available.
         let asset_atlas_info = if let Some(asset_dir) = &self.asset_library_dir {
-            let asset_processor = AssetProcessor::new(&self.output_dir, &self.output_name);
+            let asset_processor =
+                AssetProcessor::new(&self.output_dir, &self.output_name, self.reporter.clone());
             Some(asset_processor.process_asset_library(asset_dir)?)
         } else {
             None
         };
 
         // Generate unified manifest linking terrain and assets.
-        let manifest_gen = ManifestGenerator::new(&self.output_dir, &self.output_name);
-        manifest_gen.generate_unified_manifest(terrain_info, asset_atlas_info, classes)?;
+        let stage = Stage::start(&self.reporter, "manifest", FailureClass::Output);
+        let manifest_gen =
+            ManifestGenerator::new(&self.output_dir, &self.output_name, self.reporter.clone());
+        manifest_gen.generate_unified_manifest(
+            terrain_info,
+            asset_atlas_info,
+            classes,
+            &self.config,
+            analysis,
+        )?;
+        stage.finish();
 
-        println!("Asset library processing complete!");
+        log_info!(self.reporter, "Asset library processing complete!");
         Ok(())
     }
 
     /// Processes the main terrain point cloud for organised output.
-    /// Returns terrain information for manifest generation.
+    /// Returns terrain information and analysis results for manifest generation.
     fn process_main_terrain(
         &self,
-    ) -> Result<(TerrainInfo, ClassificationInfo), Box<dyn std::error::Error>> {
-        println!(
+    ) -> Result<(TerrainInfo, ClassificationInfo, Option<AnalysisInfo>), PreprocessError> {
+        log_info!(
+            self.reporter,
             "Processing main terrain: {}",
             self.main_cloud_path.display()
         );
 
+        let stage = Stage::start(&self.reporter, "inspect_input", FailureClass::Input);
         self.log_file_info(&self.main_cloud_path)?;
-
         let has_colour = self.detect_colour_data(&self.main_cloud_path)?;
-        let bounds = calculate_bounds(&self.main_cloud_path, true)?;
+        stage.finish();
+
+        // Flag noise before bounds so dropped outliers cannot stretch them.
+        let noise_mask = match self.config.filters.outliers.active() {
+            Some(settings) => {
+                let stage = Stage::start(&self.reporter, "outlier_detection", FailureClass::Input);
+                log_info!(
+                    self.reporter,
+                    "Detecting outliers ({:?})...",
+                    settings.method
+                );
+                let mask = detect_outliers(&self.main_cloud_path, settings, &self.reporter)?;
+                stage.finish();
+                log_info!(
+                    self.reporter,
+                    "  Flagged {} noise points ({})",
+                    mask.noise_count,
+                    if settings.keep_noise {
+                        "reclassifying to noise"
+                    } else {
+                        "removing"
+                    }
+                );
+                Some(mask)
+            }
+            None => None,
+        };
+        let excluded = noise_mask
+            .as_ref()
+            .filter(|_| !self.keep_noise())
+            .map(|mask| mask.flags.as_slice());
+
+        let stage = Stage::start(&self.reporter, "bounds", FailureClass::Input);
+        let bounds =
+            calculate_bounds_filtered(&self.main_cloud_path, true, excluded, &self.reporter)?;
         self.print_bounds(&bounds);
+        stage.finish();
 
         // Generate textures and save them to disk.
-        let (stats, road_points, classes) =
-            self.generate_textures(&self.main_cloud_path, &bounds, has_colour)?;
+        let sampled = self.generate_textures(
+            &self.main_cloud_path,
+            &bounds,
+            has_colour,
+            noise_mask.as_ref(),
+        )?;
 
         // Generate heightmap using road surface points.
-        self.generate_flood_fill_heightmap(&road_points)?;
+        let stage = Stage::start(&self.reporter, "heightmap", FailureClass::Output);
+        self.generate_flood_fill_heightmap(&sampled.road_points)?;
+        stage.finish();
 
         // Create organized terrain directory structure.
+        let stage = Stage::start(&self.reporter, "organise_outputs", FailureClass::Output);
         let terrain_dir = self
             .output_dir
             .join("terrain")
@@ -141,6 +247,7 @@ impl PointCloudConverter {
 
         // Move terrain files to organized structure with programmatic names.
         self.organize_terrain_files(&terrain_dir)?;
+        stage.finish();
 
         let texture_files = TerrainTextureFiles {
             position: format!(
@@ -159,22 +266,44 @@ impl PointCloudConverter {
                 "terrain/{0}{1}x{1}/heightmap.dds",
                 self.output_name, TEXTURE_SIZE
             ),
+            normal: self.config.derivatives.normals.active().map(|_| {
+                format!(
+                    "terrain/{0}{1}x{1}/normal.dds",
+                    self.output_name, TEXTURE_SIZE
+                )
+            }),
+            change: self.config.derivatives.change.active().map(|_| {
+                format!(
+                    "terrain/{0}{1}x{1}/change.dds",
+                    self.output_name, TEXTURE_SIZE
+                )
+            }),
         };
 
         Ok((
             TerrainInfo {
                 texture_files,
                 bounds,
-                point_count: stats.loaded_points,
+                point_count: sampled.stats.loaded_points,
                 has_colour,
             },
-            classes,
+            sampled.classes,
+            sampled.analysis,
         ))
     }
 
+    /// Whether flagged noise is kept as class 7 rather than dropped.
+    fn keep_noise(&self) -> bool {
+        self.config
+            .filters
+            .outliers
+            .active()
+            .is_some_and(|settings| settings.keep_noise)
+    }
+
     /// Organizes terrain files into programmatic directory structure.
     /// Moves generated textures from working directory to organized layout.
-    fn organize_terrain_files(&self, terrain_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    fn organize_terrain_files(&self, terrain_dir: &Path) -> Result<(), PreprocessError> {
         // Define source and target file mappings with programmatic names.
         let file_mappings = [
             (
@@ -205,6 +334,20 @@ impl PointCloudConverter {
                 ),
                 "heightmap.dds",
             ),
+            (
+                format!(
+                    "{}_normal_{}x{}.dds",
+                    self.output_name, TEXTURE_SIZE, TEXTURE_SIZE
+                ),
+                "normal.dds",
+            ),
+            (
+                format!(
+                    "{}_change_{}x{}.dds",
+                    self.output_name, TEXTURE_SIZE, TEXTURE_SIZE
+                ),
+                "change.dds",
+            ),
         ];
 
         // Move files to organized structure.
@@ -214,7 +357,12 @@ impl PointCloudConverter {
 
             if source_path.exists() {
                 fs::rename(&source_path, &target_path)?;
-                println!("Organized: {} -> {}", source_name, target_path.display());
+                log_info!(
+                    self.reporter,
+                    "Organized: {} -> {}",
+                    source_name,
+                    target_path.display()
+                );
             }
         }
 
@@ -223,13 +371,16 @@ impl PointCloudConverter {
 
     /// Detect if colour data exists in the point cloud.
     /// Samples initial points to determine RGB availability for processing.
-    fn detect_colour_data(&self, file_path: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+    fn detect_colour_data(&self, file_path: &Path) -> Result<bool, PreprocessError> {
         let mut reader = self.create_reader(file_path)?;
 
         let mut colour_count = 0;
         let mut total_checked = 0;
 
-        for point_result in reader.points().take(COLOUR_DETECTION_SAMPLE_SIZE) {
+        for point_result in reader
+            .points()
+            .take(self.config.sampling.colour_detection_sample_size)
+        {
             if let Ok(point) = point_result {
                 if point.color.is_some() {
                     colour_count += 1;
@@ -240,52 +391,67 @@ impl PointCloudConverter {
 
         let has_colour = colour_count > 0;
         if has_colour {
-            println!(
+            log_info!(
+                self.reporter,
                 "Colour data detected: {}/{} sample points have RGB",
-                colour_count, total_checked
+                colour_count,
+                total_checked
             );
         } else {
-            println!("No colour data found");
+            log_warn!(
+                self.reporter,
+                "No colour data found, RGB render mode will be unavailable"
+            );
         }
 
         Ok(has_colour)
     }
 
     /// Generate spatial textures using Z-order layout and sampling.
-    /// Returns processing statistics and road points for heightmap generation.
+    /// Returns processing statistics, road points for heightmap generation and
+    /// results of any analysis stages run over the sampled points.
     fn generate_textures(
         &self,
         file_path: &Path,
         bounds: &PointCloudBounds,
         has_colour: bool,
-    ) -> Result<
-        (ProcessingStats, Vec<(f32, f32, f32)>, ClassificationInfo),
-        Box<dyn std::error::Error>,
-    > {
+        noise_mask: Option<&NoiseMask>,
+    ) -> Result<SampledTerrain, PreprocessError> {
+        let stage = Stage::start(&self.reporter, "point_sampling", FailureClass::Input);
         let mut reader = self.create_reader(file_path)?;
         let total_points = reader.header().number_of_points() as usize;
 
-        let sampling_ratio = if total_points > MAX_POINTS {
-            MAX_POINTS as f64 / total_points as f64
+        // Removed noise does not consume texture slots.
+        let keep_noise = self.keep_noise();
+        let candidate_points = match noise_mask {
+            Some(mask) if !keep_noise => total_points - mask.noise_count.min(total_points),
+            _ => total_points,
+        };
+
+        let sampling_ratio = if candidate_points > MAX_POINTS {
+            MAX_POINTS as f64 / candidate_points as f64
         } else {
             1.0
         };
 
         // Create spatial generator with n*n grid for Z-order organisation.
-        let mut spatial_gen = SpatialTextureGenerator::new(bounds.clone(), 1024);
+        let mut spatial_gen =
+            SpatialTextureGenerator::new(bounds.clone(), self.config.sampling.grid_resolution);
         let mut road_points = Vec::new();
         let mut stats = ProcessingStats::new();
-        let mut expected_loaded = 0.0;
+        let mut sampler = UniformSampler::new(sampling_ratio);
 
-        println!(
+        log_info!(
+            self.reporter,
             "Sampling ratio: {:.3} ({:.1}% of points)",
             sampling_ratio,
             sampling_ratio * 100.0
         );
 
         // Process points with progress tracking and spatial organisation.
-        let pb = ProgressBar::new(total_points as u64);
-        pb.set_style(
+        let pb = self.reporter.progress_bar(
+            "point_sampling",
+            total_points as u64,
             ProgressStyle::default_bar()
                 .template("[{bar:40.green/blue}] {pos}/{len} points ({percent}%) {msg}")
                 .unwrap()
@@ -293,25 +459,30 @@ impl PointCloudConverter {
         );
         pb.set_message("Processing points spatially");
 
-        let mut classes = ClassificationInfo {
-            class_types: HashMap::new(),
-        };
-
         for (point_idx, point_result) in reader.points().enumerate() {
-            expected_loaded += sampling_ratio;
-
             if point_idx % 10_000 == 0 {
                 pb.set_position(point_idx as u64);
             }
 
+            let is_noise = noise_mask.is_some_and(|mask| mask.is_noise(point_idx));
+            if is_noise && !keep_noise {
+                stats.noise_removed += 1;
+                continue;
+            }
+
             // Apply sampling to stay within texture limits.
-            if stats.loaded_points as f64 >= expected_loaded || stats.loaded_points >= MAX_POINTS {
+            if !sampler.keep() || stats.loaded_points >= MAX_POINTS {
                 continue;
             }
 
             let point = point_result?;
             let (x, y, z) = transform_coordinates(point.x, point.y, point.z);
-            let classification = u8::from(point.classification);
+            let classification = if is_noise {
+                stats.noise_reclassified += 1;
+                NOISE_CLASSIFICATION
+            } else {
+                u8::from(point.classification)
+            };
             let color = point.color.map(|c| (c.red, c.green, c.blue));
 
             // Extract object number from extra bytes if available.
@@ -326,13 +497,6 @@ impl PointCloudConverter {
                 0.0
             };
 
-            // Store unique point class combinations in our class info struct
-            classes.insert_or_update(
-                classification,
-                get_class_name(classification),
-                object_number as u32,
-            );
-
             // Add to spatial structure for Z-order organisation.
             spatial_gen.add_point((x, y, z), classification, color, object_number);
 
@@ -353,23 +517,282 @@ impl PointCloudConverter {
         }
 
         pb.finish_with_message("Points processed");
+        stage.finish();
+
+        // Derive object IDs by clustering when the source has none.
+        if let Some(settings) = self.config.derivatives.segmentation.active() {
+            if has_object_ids(&spatial_gen.points) {
+                log_warn!(
+                    self.reporter,
+                    "Object IDs present in extra bytes, skipping instance segmentation"
+                );
+            } else {
+                let stage = Stage::start(&self.reporter, "segmentation", FailureClass::Processing);
+                log_info!(
+                    self.reporter,
+                    "Segmenting instances (distance {:.2}, minimum cluster size {})...",
+                    settings.cluster_distance,
+                    settings.min_cluster_size
+                );
+                let result = segment_instances(&mut spatial_gen.points, settings, &self.reporter);
+                log_info!(
+                    self.reporter,
+                    "  Created {} objects ({} points left unassigned)",
+                    result.objects_created,
+                    result.unassigned_points
+                );
+                stage.finish();
+            }
+        }
 
-        println!("Found Class Info: {:?}", classes);
+        // Store unique point class combinations in our class info struct.
+        let mut classes = ClassificationInfo {
+            class_types: HashMap::new(),
+        };
+        for point in &spatial_gen.points {
+            classes.insert_or_update(
+                point.classification,
+                get_class_name(point.classification),
+                point.object_number as u32,
+            );
+        }
+
+        log_info!(self.reporter, "Found Class Info: {:?}", classes);
+
+        let mut analysis = self.run_analysis(&spatial_gen.points, bounds.origin)?;
 
         // Apply spatial sorting and generate textures.
-        println!("Applying Z-order spatial sorting...");
+        let stage = Stage::start(
+            &self.reporter,
+            "texture_generation",
+            FailureClass::Processing,
+        );
+        log_info!(self.reporter, "Applying Z-order spatial sorting...");
         spatial_gen.sort_spatially();
 
         let position_data = spatial_gen.generate_position_texture();
         let colour_class_data = spatial_gen.generate_colour_class_texture();
         let spatial_index_data = spatial_gen.generate_spatial_index_texture();
+        stage.finish();
+
+        // Estimate normals after sorting so texels line up with the position texture.
+        let normal_data = self.config.derivatives.normals.active().map(|settings| {
+            let stage = Stage::start(&self.reporter, "normals", FailureClass::Processing);
+            log_info!(
+                self.reporter,
+                "Estimating normals (k = {}, curvature {})...",
+                settings.k_neighbours,
+                if settings.compute_curvature {
+                    "on"
+                } else {
+                    "off"
+                }
+            );
+            let normal_data =
+                generate_normal_texture(&spatial_gen.points, settings, &self.reporter);
+            stage.finish();
+            normal_data
+        });
+
+        // Compare against the reference epoch in the same texel order.
+        let change_data = match self.config.derivatives.change.active() {
+            Some(settings) => {
+                let stage =
+                    Stage::start(&self.reporter, "change_detection", FailureClass::Processing);
+                log_info!(
+                    self.reporter,
+                    "Comparing against {} ({:?}, threshold {:.2} m)...",
+                    settings.reference.display(),
+                    settings.method,
+                    settings.threshold
+                );
+                let result = detect_changes(
+                    &spatial_gen.points,
+                    bounds,
+                    settings,
+                    sampling_ratio,
+                    &self.reporter,
+                )?;
+                stage.finish();
+
+                let summary = result.summary;
+                log_info!(
+                    self.reporter,
+                    "  {} of {} points changed, {} of {} reference points removed",
+                    summary.changed_points,
+                    summary.compared_points,
+                    summary.removed_points,
+                    summary.reference_points
+                );
+                for class in &summary.classes {
+                    log_info!(
+                        self.reporter,
+                        "  {}: {} changed ({} raised, {} lowered), {} removed, {} objects changed, {} removed",
+                        class.class_name,
+                        class.changed_points,
+                        class.raised_points,
+                        class.lowered_points,
+                        class.removed_points,
+                        class.changed_objects.len(),
+                        class.removed_objects.len()
+                    );
+                }
+                self.reporter
+                    .statistics("change_detection", serde_json::to_value(&summary)?);
+                analysis.change = Some(summary);
+                Some(result.texture)
+            }
+            None => None,
+        };
 
         self.print_processing_stats(&stats, total_points, has_colour);
 
         // Save generated textures with programmatic names.
+        let stage = Stage::start(&self.reporter, "texture_output", FailureClass::Output);
         self.save_textures(&position_data, &colour_class_data, &spatial_index_data)?;
+        if let Some(normal_data) = &normal_data {
+            self.save_normal_texture(normal_data)?;
+        }
+        if let Some(change_data) = &change_data {
+            self.save_change_texture(change_data)?;
+        }
+        stage.finish();
+
+        Ok(SampledTerrain {
+            stats,
+            road_points,
+            classes,
+            analysis: (!analysis.is_empty()).then_some(analysis),
+        })
+    }
 
-        Ok((stats, road_points, classes))
+    /// Run enabled analysis stages and write their outputs under `analysis/`.
+    fn run_analysis(
+        &self,
+        points: &[SpatialPoint],
+        origin: (f64, f64, f64),
+    ) -> Result<AnalysisInfo, PreprocessError> {
+        let mut analysis = AnalysisInfo::default();
+
+        if let Some(settings) = self.config.analysis.sidewalk_width.active() {
+            analysis.sidewalk_width = self.run_sidewalk_width(points, settings)?;
+        }
+        if let Some(settings) = self.config.analysis.kerbs.active() {
+            analysis.kerbs = self.run_kerb_detection(points, settings, origin)?;
+        }
+
+        Ok(analysis)
+    }
+
+    fn run_sidewalk_width(
+        &self,
+        points: &[SpatialPoint],
+        settings: &SidewalkWidthSettings,
+    ) -> Result<Option<SidewalkWidthInfo>, PreprocessError> {
+        let stage = Stage::start(&self.reporter, "sidewalk_width", FailureClass::Processing);
+        log_info!(
+            self.reporter,
+            "Measuring sidewalk clear width (minimum {:.2} m)...",
+            settings.minimum_width
+        );
+        let result = analyse_sidewalk_width(points, settings);
+        stage.finish();
+
+        let Some(result) = result else {
+            log_warn!(
+                self.reporter,
+                "No sidewalk points found, skipping sidewalk width analysis"
+            );
+            return Ok(None);
+        };
+
+        let geojson = self.write_analysis_geojson("sidewalk_width", &result.geojson)?;
+        let summary = result.summary;
+        log_info!(
+            self.reporter,
+            "  {} centrelines, {:.1} m long, {} stations",
+            summary.centreline_count,
+            summary.centreline_length_m,
+            summary.station_count
+        );
+        log_info!(
+            self.reporter,
+            "  {} segments ({:.1} m) below {:.2} m clear width",
+            summary.narrow_segments.len(),
+            summary.below_minimum_length_m,
+            summary.minimum_width_m
+        );
+        self.reporter
+            .statistics("sidewalk_width", serde_json::to_value(&summary)?);
+
+        Ok(Some(SidewalkWidthInfo { geojson, summary }))
+    }
+
+    fn run_kerb_detection(
+        &self,
+        points: &[SpatialPoint],
+        settings: &KerbSettings,
+        origin: (f64, f64, f64),
+    ) -> Result<Option<KerbInfo>, PreprocessError> {
+        let stage = Stage::start(&self.reporter, "kerbs", FailureClass::Processing);
+        log_info!(
+            self.reporter,
+            "Detecting kerbs (ramps below {:.2} m)...",
+            settings.ramp_height
+        );
+        let result = detect_kerbs(points, settings);
+        stage.finish();
+
+        let Some(result) = result else {
+            log_warn!(
+                self.reporter,
+                "No road to sidewalk edges found, skipping kerb detection"
+            );
+            return Ok(None);
+        };
+
+        let geojson = self.write_analysis_geojson("kerbs", &result.geojson)?;
+        let summary = result.summary;
+        log_info!(
+            self.reporter,
+            "  {} kerb lines, {:.1} m long, mean height {:.2} m, {} ramps",
+            summary.line_count,
+            summary.total_length_m,
+            summary.mean_height_m,
+            summary.ramps.len()
+        );
+        self.reporter
+            .statistics("kerbs", serde_json::to_value(&summary)?);
+
+        // The renderer positions geometry relative to the terrain origin.
+        let lines = result
+            .lines
+            .into_iter()
+            .map(|line| line.relative_to(origin))
+            .collect();
+
+        Ok(Some(KerbInfo {
+            geojson,
+            summary,
+            lines,
+        }))
+    }
+
+    /// Write an analysis GeoJSON file, returning its manifest-relative path.
+    fn write_analysis_geojson(
+        &self,
+        name: &str,
+        geojson: &serde_json::Value,
+    ) -> Result<String, PreprocessError> {
+        let stage = Stage::start(&self.reporter, "analysis_output", FailureClass::Output);
+        let analysis_dir = self.output_dir.join("analysis");
+        fs::create_dir_all(&analysis_dir)?;
+        let geojson_path = analysis_dir.join(format!("{}.geojson", name));
+        fs::write(&geojson_path, serde_json::to_string(geojson)?)?;
+        log_info!(self.reporter, "  Saved {}", geojson_path.display());
+        stage.finish();
+
+        Ok(format!("analysis/{}.geojson", name))
     }
 
     /// Generate flood-fill heightmap from road surface points.
@@ -377,9 +800,14 @@ impl PointCloudConverter {
     fn generate_flood_fill_heightmap(
         &self,
         road_points: &[(f32, f32, f32)],
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), PreprocessError> {
         // Create heightmap generator with output directory context.
-        let heightmap_gen = HeightmapGenerator::new(&self.output_name);
+        let heightmap_gen = HeightmapGenerator::new(
+            &self.output_name,
+            self.config.heightmap.gaussian_sigma,
+            self.config.heightmap.blend_radius,
+            self.reporter.clone(),
+        );
 
         // Generate heightmap in the correct output directory.
         let original_dir = std::env::current_dir()?;
@@ -400,7 +828,7 @@ impl PointCloudConverter {
         position_data: &[f32],
         colour_class_data: &[f32],
         spatial_index_data: &[f32],
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> Result<(), PreprocessError> {
         let pos_path = self.output_dir.join(format!(
             "{}_position_{}x{}.dds",
             self.output_name, TEXTURE_SIZE, TEXTURE_SIZE
@@ -433,16 +861,72 @@ impl PointCloudConverter {
             ddsfile::DxgiFormat::R32G32_Float,
         )?;
 
-        println!("Saved {} (Position RGBA32F)", pos_path.display());
-        println!("Saved {} (Colour+Class RGBA32F)", colour_path.display());
-        println!("Saved {} (Spatial Index RGBA32F)", spatial_path.display());
+        log_info!(
+            self.reporter,
+            "Saved {} (Position RGBA32F)",
+            pos_path.display()
+        );
+        log_info!(
+            self.reporter,
+            "Saved {} (Colour+Class RGBA32F)",
+            colour_path.display()
+        );
+        log_info!(
+            self.reporter,
+            "Saved {} (Spatial Index RGBA32F)",
+            spatial_path.display()
+        );
 
         Ok(())
     }
 
+    /// Save normal texture with XYZ normal and curvature in RGBA.
+    fn save_normal_texture(&self, normal_data: &[f32]) -> Result<(), PreprocessError> {
+        let normal_path = self.output_dir.join(format!(
+            "{}_normal_{}x{}.dds",
+            self.output_name, TEXTURE_SIZE, TEXTURE_SIZE
+        ));
+
+        write_f32_texture(
+            normal_path.to_str().unwrap(),
+            TEXTURE_SIZE,
+            normal_data,
+            ddsfile::DxgiFormat::R32G32B32A32_Float,
+        )?;
+
+        log_info!(
+            self.reporter,
+            "Saved {} (Normal+Curvature RGBA32F)",
+            normal_path.display()
+        );
+        Ok(())
+    }
+
+    /// Save change texture with signed distance, changed flag and reference class in RGBA.
+    fn save_change_texture(&self, change_data: &[f32]) -> Result<(), PreprocessError> {
+        let change_path = self.output_dir.join(format!(
+            "{}_change_{}x{}.dds",
+            self.output_name, TEXTURE_SIZE, TEXTURE_SIZE
+        ));
+
+        write_f32_texture(
+            change_path.to_str().unwrap(),
+            TEXTURE_SIZE,
+            change_data,
+            ddsfile::DxgiFormat::R32G32B32A32_Float,
+        )?;
+
+        log_info!(
+            self.reporter,
+            "Saved {} (Change RGBA32F)",
+            change_path.display()
+        );
+        Ok(())
+    }
+
     /// Create LAS file reader for point cloud access.
     /// Handles both .las and .laz compressed formats.
-    fn create_reader(&self, file_path: &Path) -> Result<Reader, Box<dyn std::error::Error>> {
+    fn create_reader(&self, file_path: &Path) -> Result<Reader, PreprocessError> {
         let file = File::open(file_path)?;
         let buf_reader = BufReader::new(file);
         Ok(Reader::new(buf_reader)?)
@@ -451,13 +935,25 @@ impl PointCloudConverter {
     /// Print coordinate bounds information for validation.
     /// Displays transformed bounds for debugging and verification.
     fn print_bounds(&self, bounds: &PointCloudBounds) {
-        println!("Transformed bounds:");
-        println!("  X: {:.2} to {:.2}", bounds.min_x, bounds.max_x);
-        println!(
+        log_info!(self.reporter, "Transformed bounds:");
+        log_info!(
+            self.reporter,
+            "  X: {:.2} to {:.2}",
+            bounds.min_x,
+            bounds.max_x
+        );
+        log_info!(
+            self.reporter,
             "  Y: {:.2} to {:.2} (elevation)",
-            bounds.min_y, bounds.max_y
+            bounds.min_y,
+            bounds.max_y
+        );
+        log_info!(
+            self.reporter,
+            "  Z: {:.2} to {:.2} (depth)",
+            bounds.min_z,
+            bounds.max_z
         );
-        println!("  Z: {:.2} to {:.2} (depth)", bounds.min_z, bounds.max_z);
     }
 
     /// Print processing statistics for verification and debugging.
@@ -468,83 +964,136 @@ impl PointCloudConverter {
         total_points: usize,
         has_colour: bool,
     ) {
-        println!("Processing complete:");
-        println!(
+        log_info!(self.reporter, "Processing complete:");
+        log_info!(
+            self.reporter,
             "  Loaded: {} points ({:.1}% texture utilisation)",
             stats.loaded_points,
             (stats.loaded_points as f32 / MAX_POINTS as f32) * 100.0
         );
 
         if has_colour {
-            println!(
+            log_info!(
+                self.reporter,
                 "  Colour points: {} ({:.1}%)",
                 stats.colour_points,
                 (stats.colour_points as f32 / stats.loaded_points as f32) * 100.0
             );
         }
+
+        if self.config.filters.outliers.active().is_some() {
+            log_info!(
+                self.reporter,
+                "  Noise removed: {} points",
+                stats.noise_removed
+            );
+            log_info!(
+                self.reporter,
+                "  Noise reclassified: {} loaded points (class {})",
+                stats.noise_reclassified,
+                NOISE_CLASSIFICATION
+            );
+        }
+
+        self.reporter.statistics(
+            "terrain",
+            serde_json::json!({
+                "total_points": total_points,
+                "loaded_points": stats.loaded_points,
+                "texture_utilisation": stats.loaded_points as f64 / MAX_POINTS as f64,
+                "colour_points": stats.colour_points,
+                "noise_removed": stats.noise_removed,
+                "noise_reclassified": stats.noise_reclassified,
+            }),
+        );
     }
 
     /// Log coordinate system and file information for debugging.
     /// Provides detailed information about LAS file structure and coordinate systems.
-    fn log_file_info(&self, file_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    fn log_file_info(&self, file_path: &Path) -> Result<(), PreprocessError> {
         let reader = self.create_reader(file_path)?;
         let header = reader.header();
 
-        println!("LAS/LAZ File Information:");
-        println!("  File: {}", file_path.display());
-        println!(
+        log_info!(self.reporter, "LAS/LAZ File Information:");
+        log_info!(self.reporter, "  File: {}", file_path.display());
+        log_info!(
+            self.reporter,
             "  Version: {}.{}",
             header.version().major,
             header.version().minor
         );
-        println!("  Points: {}", header.number_of_points());
-        println!("  Point format: {:?}", header.point_format().to_u8());
+        log_info!(self.reporter, "  Points: {}", header.number_of_points());
+        log_info!(
+            self.reporter,
+            "  Point format: {:?}",
+            header.point_format().to_u8()
+        );
 
         // Display coordinate system information.
-        println!("  Coordinate System:");
+        log_info!(self.reporter, "  Coordinate System:");
         let x_scale = header.transforms().x.scale;
         let y_scale = header.transforms().y.scale;
         let z_scale = header.transforms().z.scale;
 
-        println!(
+        log_info!(
+            self.reporter,
             "    Scale factors: X={}, Y={}, Z={}",
-            x_scale, y_scale, z_scale
+            x_scale,
+            y_scale,
+            z_scale
         );
 
         let x_offset = header.transforms().x.offset;
         let y_offset = header.transforms().y.offset;
         let z_offset = header.transforms().z.offset;
 
-        println!(
+        log_info!(
+            self.reporter,
             "    Offsets: X={}, Y={}, Z={}",
-            x_offset, y_offset, z_offset
+            x_offset,
+            y_offset,
+            z_offset
         );
 
         // Check for extra bytes and coordinate reference system data.
         for vlr in header.vlrs() {
             if vlr.record_id == 4 {
-                println!("    Extra Bytes VLR found: {} bytes", vlr.data.len());
+                log_info!(
+                    self.reporter,
+                    "    Extra Bytes VLR found: {} bytes",
+                    vlr.data.len()
+                );
                 if vlr.data.len() >= 17 {
                     let field_name = String::from_utf8_lossy(&vlr.data[4..17]);
                     let field_name = field_name.trim_end_matches('\0');
                     let data_type = vlr.data[2];
-                    println!("    Field name: '{}'", field_name);
-                    println!("    Data type: {} (9=f32)", data_type);
+                    log_info!(self.reporter, "    Field name: '{}'", field_name);
+                    log_info!(self.reporter, "    Data type: {} (9=f32)", data_type);
                 }
             }
         }
 
-        println!();
+        log_info!(self.reporter, "");
         Ok(())
     }
 }
 
+/// Sampled terrain data handed from texture generation to later stages.
+struct SampledTerrain {
+    stats: ProcessingStats,
+    road_points: Vec<(f32, f32, f32)>,
+    classes: ClassificationInfo,
+    analysis: Option<AnalysisInfo>,
+}
+
 /// Processing statistics tracker for monitoring conversion progress.
-/// Tracks point counts, color availability, and elevation data.
+/// Tracks point counts, color availability, elevation data, and noise handling.
 struct ProcessingStats {
     loaded_points: usize,
     colour_points: usize,
     total_elevation: f64,
+    noise_removed: usize,
+    noise_reclassified: usize,
 }
 
 impl ProcessingStats {
@@ -553,6 +1102,8 @@ impl ProcessingStats {
             loaded_points: 0,
             colour_points: 0,
             total_elevation: 0.0,
+            noise_removed: 0,
+            noise_reclassified: 0,
         }
     }
 }
@@ -1,4 +1,5 @@
 /// DDS texture file writer with unified 32-bit float formats
+use crate::error::PreprocessError;
 use ddsfile::{AlphaMode, D3D10ResourceDimension, Dds, DxgiFormat, NewDxgiParams};
 
 pub fn write_f32_texture(
@@ -6,7 +7,7 @@ pub fn write_f32_texture(
     size: usize,
     data: &[f32],
     format: DxgiFormat,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<(), PreprocessError> {
     let mut bytes = Vec::with_capacity(data.len() * 4);
     for &val in data {
         bytes.extend_from_slice(&val.to_le_bytes());
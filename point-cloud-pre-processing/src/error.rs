@@ -0,0 +1,100 @@
+/// Library error type covering every preprocessing stage.
+use crate::atlas::AtlasError;
+use crate::logging::{FailureClass, Reporter};
+use std::path::PathBuf;
+
+/// Errors returned by the preprocessing pipeline.
+#[derive(Debug)]
+pub enum PreprocessError {
+    /// Pipeline configuration could not be read, parsed or validated.
+    Config(String),
+    /// A required input file or directory does not exist.
+    MissingInput(PathBuf),
+    /// Filesystem read or write failure.
+    IoError(std::io::Error),
+    /// LAS/LAZ header or point decoding failure.
+    LasError(las::Error),
+    /// DDS texture encoding failure.
+    DdsError(ddsfile::Error),
+    /// Manifest or metadata serialisation failure.
+    JsonError(serde_json::Error),
+    /// Asset discovery or atlas tile allocation failure.
+    AtlasError(AtlasError),
+    /// Input that was read successfully but cannot be processed.
+    InvalidData(String),
+}
+
+impl PreprocessError {
+    /// Failure class used for exit codes. Config and decoding errors are
+    /// classified directly; filesystem errors by the stage `reporter` saw fail.
+    pub fn failure_class(&self, reporter: &Reporter) -> FailureClass {
+        match self {
+            PreprocessError::Config(_) => FailureClass::Config,
+            PreprocessError::MissingInput(_) | PreprocessError::LasError(_) => FailureClass::Input,
+            PreprocessError::DdsError(_) | PreprocessError::JsonError(_) => FailureClass::Output,
+            PreprocessError::IoError(_)
+            | PreprocessError::AtlasError(_)
+            | PreprocessError::InvalidData(_) => reporter.failure_class(),
+        }
+    }
+}
+
+impl From<std::io::Error> for PreprocessError {
+    fn from(err: std::io::Error) -> Self {
+        PreprocessError::IoError(err)
+    }
+}
+
+impl From<las::Error> for PreprocessError {
+    fn from(err: las::Error) -> Self {
+        PreprocessError::LasError(err)
+    }
+}
+
+impl From<ddsfile::Error> for PreprocessError {
+    fn from(err: ddsfile::Error) -> Self {
+        PreprocessError::DdsError(err)
+    }
+}
+
+impl From<serde_json::Error> for PreprocessError {
+    fn from(err: serde_json::Error) -> Self {
+        PreprocessError::JsonError(err)
+    }
+}
+
+impl From<AtlasError> for PreprocessError {
+    fn from(err: AtlasError) -> Self {
+        PreprocessError::AtlasError(err)
+    }
+}
+
+impl std::fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreprocessError::Config(msg) => write!(f, "{}", msg),
+            PreprocessError::MissingInput(path) => {
+                write!(f, "Input does not exist: {}", path.display())
+            }
+            PreprocessError::IoError(e) => write!(f, "IO error: {}", e),
+            PreprocessError::LasError(e) => write!(f, "LAS error: {}", e),
+            PreprocessError::DdsError(e) => write!(f, "DDS error: {}", e),
+            PreprocessError::JsonError(e) => write!(f, "JSON error: {}", e),
+            PreprocessError::AtlasError(e) => write!(f, "Atlas error: {}", e),
+            PreprocessError::InvalidData(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PreprocessError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PreprocessError::IoError(e) => Some(e),
+            PreprocessError::LasError(e) => Some(e),
+            PreprocessError::DdsError(e) => Some(e),
+            PreprocessError::JsonError(e) => Some(e),
+            PreprocessError::AtlasError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -1,18 +1,33 @@
 use crate::dds_writer::write_f32_texture;
+use crate::error::PreprocessError;
+use crate::logging::{Reporter, log_info, log_warn};
 /// Fast parallel heightmap generation with smooth blending
 use constants::texture::TEXTURE_SIZE;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::ProgressStyle;
 use rayon::prelude::*;
 use std::collections::VecDeque;
 
 pub struct HeightmapGenerator {
     output_stem: String,
+    /// Gaussian smoothing sigma in pixels.
+    gaussian_sigma: f32,
+    /// Maximum flood-fill distance in pixels from the nearest road sample.
+    blend_radius: f32,
+    reporter: Reporter,
 }
 
 impl HeightmapGenerator {
-    pub fn new(output_stem: &str) -> Self {
+    pub fn new(
+        output_stem: &str,
+        gaussian_sigma: f32,
+        blend_radius: f32,
+        reporter: Reporter,
+    ) -> Self {
         Self {
             output_stem: output_stem.to_string(),
+            gaussian_sigma,
+            blend_radius,
+            reporter,
         }
     }
 
@@ -20,14 +35,19 @@ impl HeightmapGenerator {
     pub fn generate_flood_fill_from_road_points(
         &self,
         road_points: &[(f32, f32, f32)], // (norm_x, norm_z, norm_y)
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        println!(
+    ) -> Result<(), PreprocessError> {
+        log_info!(
+            self.reporter,
             "Generating flood-fill heightmap from {} road points...",
             road_points.len()
         );
 
         // Calculate median elevation from road points
         let median_elevation = if road_points.is_empty() {
+            log_warn!(
+                self.reporter,
+                "No road points found, heightmap will be flat"
+            );
             0.5
         } else {
             let mut elevations: Vec<f32> = road_points.iter().map(|(_, _, y)| *y).collect();
@@ -35,7 +55,11 @@ impl HeightmapGenerator {
             elevations[elevations.len() / 2]
         };
 
-        println!("Using median road elevation: {:.3}", median_elevation);
+        log_info!(
+            self.reporter,
+            "Using median road elevation: {:.3}",
+            median_elevation
+        );
         let mut heightmap = vec![median_elevation; TEXTURE_SIZE * TEXTURE_SIZE];
         let mut valid_mask = vec![false; TEXTURE_SIZE * TEXTURE_SIZE];
 
@@ -53,7 +77,7 @@ impl HeightmapGenerator {
         self.flood_fill_gaps(&mut heightmap, &valid_mask, median_elevation)?;
 
         // Apply smoothing
-        let smoothed_heightmap = self.apply_gaussian_blur(&heightmap, 3.0)?;
+        let smoothed_heightmap = self.apply_gaussian_blur(&heightmap, self.gaussian_sigma)?;
 
         // Save heightmap
         let heightmap_path = format!(
@@ -66,7 +90,7 @@ impl HeightmapGenerator {
             &smoothed_heightmap,
             ddsfile::DxgiFormat::R32_Float,
         )?;
-        println!("Saved {} (R32F heightmap)", heightmap_path);
+        log_info!(self.reporter, "Saved {} (R32F heightmap)", heightmap_path);
 
         Ok(())
     }
@@ -77,9 +101,10 @@ impl HeightmapGenerator {
         heightmap: &mut [f32],
         valid_mask: &[bool],
         default_elevation: f32,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let pb = ProgressBar::new(TEXTURE_SIZE as u64);
-        pb.set_style(
+    ) -> Result<(), PreprocessError> {
+        let pb = self.reporter.progress_bar(
+            "heightmap",
+            TEXTURE_SIZE as u64,
             ProgressStyle::default_bar()
                 .template("[{bar:40.yellow/blue}] {pos}/{len} flood-fill ({percent}%) {msg}")
                 .unwrap()
@@ -95,17 +120,22 @@ impl HeightmapGenerator {
             for x in 0..TEXTURE_SIZE {
                 let idx = z * TEXTURE_SIZE + x;
                 if valid_mask[idx] {
-                    queue.push_back((x, z));
+                    queue.push_back((x, z, 0u32));
                 }
             }
         }
 
         // Propagate in waves
         while !queue.is_empty() {
-            let (x, z) = queue.pop_front().unwrap();
+            let (x, z, steps) = queue.pop_front().unwrap();
             let current_idx = z * TEXTURE_SIZE + x;
             let current_height = heightmap[current_idx];
 
+            // Cells beyond the blend radius keep the default elevation.
+            if (steps + 1) as f32 > self.blend_radius {
+                continue;
+            }
+
             // Check 8-connected neighbors
             for dz in -1..=1i32 {
                 for dx in -1..=1i32 {
@@ -126,7 +156,7 @@ impl HeightmapGenerator {
                             heightmap[neighbor_idx] =
                                 current_height * weight + default_elevation * (1.0 - weight);
                             filled_mask[neighbor_idx] = true;
-                            queue.push_back((nx as usize, nz as usize));
+                            queue.push_back((nx as usize, nz as usize, steps + 1));
                         }
                     }
                 }
@@ -146,9 +176,10 @@ impl HeightmapGenerator {
         &self,
         heightmap: &[f32],
         sigma: f32,
-    ) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
-        let pb = ProgressBar::new(TEXTURE_SIZE as u64);
-        pb.set_style(
+    ) -> Result<Vec<f32>, PreprocessError> {
+        let pb = self.reporter.progress_bar(
+            "heightmap",
+            TEXTURE_SIZE as u64,
             ProgressStyle::default_bar()
                 .template("[{bar:40.green/blue}] {pos}/{len} blur ({percent}%) {msg}")
                 .unwrap()
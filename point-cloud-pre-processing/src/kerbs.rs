@@ -0,0 +1,434 @@
+/// Kerb line detection from road to sidewalk elevation steps.
+use crate::plan_raster::{
+    PlanExtent, dilate, point_at, polyline_length, prune_spurs, round_cm, source_xy, thin,
+    trace_skeleton,
+};
+use crate::spatial_layout::SpatialPoint;
+use constants::class::{CARRIAGEWAY_CLASSIFICATIONS, SIDEWALK_CLASSIFICATIONS};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Kerb detection parameters. Distances are in world units (metres).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KerbSettings {
+    /// Classes forming the carriageway surface.
+    pub road_classes: Vec<u8>,
+    /// Classes forming the raised sidewalk surface.
+    pub sidewalk_classes: Vec<u8>,
+    /// Plan-view raster cell size.
+    pub cell_size: f64,
+    /// Distance between measured vertices along each kerb line.
+    pub vertex_spacing: f64,
+    /// How far either side of the edge surface heights are sampled.
+    pub sample_offset: f64,
+    /// Kerb heights below this are flagged as ramps.
+    pub ramp_height: f64,
+    /// Kerb lines shorter than this are discarded.
+    pub min_length: f64,
+}
+
+impl Default for KerbSettings {
+    fn default() -> Self {
+        Self {
+            road_classes: CARRIAGEWAY_CLASSIFICATIONS.to_vec(),
+            sidewalk_classes: SIDEWALK_CLASSIFICATIONS.to_vec(),
+            cell_size: 0.1,
+            vertex_spacing: 0.5,
+            sample_offset: 0.4,
+            ramp_height: 0.04,
+            min_length: 1.0,
+        }
+    }
+}
+
+/// Measured point on a kerb line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KerbVertex {
+    /// World position at the top of the kerb.
+    pub position: [f64; 3],
+    /// Sidewalk surface height above the adjacent road surface.
+    pub height_m: f64,
+    /// Whether the kerb is dropped below the ramp threshold here.
+    pub ramp: bool,
+}
+
+/// Continuous kerb polyline with per-vertex heights.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KerbLine {
+    pub vertices: Vec<KerbVertex>,
+}
+
+impl KerbLine {
+    /// Shift vertex positions so they are relative to `origin`.
+    pub fn relative_to(mut self, origin: (f64, f64, f64)) -> Self {
+        for vertex in &mut self.vertices {
+            vertex.position[0] -= origin.0;
+            vertex.position[1] -= origin.1;
+            vertex.position[2] -= origin.2;
+        }
+        self
+    }
+}
+
+/// Consecutive ramp vertices along one kerb line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KerbRamp {
+    pub line_id: usize,
+    /// World position of the ramp's middle vertex.
+    pub position: [f64; 3],
+    pub length_m: f64,
+    pub min_height_m: f64,
+}
+
+/// Scene-wide totals for kerb detection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KerbSummary {
+    pub ramp_height_m: f64,
+    pub line_count: usize,
+    pub total_length_m: f64,
+    pub mean_height_m: f64,
+    pub ramps: Vec<KerbRamp>,
+}
+
+/// Kerb lines, their GeoJSON form and summary from [`detect_kerbs`].
+pub struct KerbResult {
+    pub lines: Vec<KerbLine>,
+    pub geojson: Value,
+    pub summary: KerbSummary,
+}
+
+/// Ground surface label per raster cell.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Surface {
+    None,
+    Road,
+    Sidewalk,
+}
+
+/// Find kerb edges where road and sidewalk surfaces meet and measure the
+/// step between them. The edge is traced along the boundary between the
+/// two classified ground surfaces; heights are the median surface
+/// elevations within `sample_offset` on each side. Lines are split where
+/// either side has no measurements.
+pub fn detect_kerbs(points: &[SpatialPoint], settings: &KerbSettings) -> Option<KerbResult> {
+    let grid = GroundGrid::from_points(points, settings)?;
+
+    // Widen the ragged class boundary into a band so thinning finds its middle.
+    let (width, height) = (grid.extent.width, grid.extent.height);
+    let edge: Vec<bool> = (0..grid.extent.len())
+        .map(|idx| grid.is_edge(idx))
+        .collect();
+    let mut edge = dilate(&edge, width, height);
+    thin(&mut edge, width, height);
+    let min_cells = (settings.min_length / grid.extent.cell_size) as usize;
+    prune_spurs(&mut edge, width, height, min_cells);
+
+    let lines: Vec<KerbLine> = trace_skeleton(&edge, grid.extent.width)
+        .into_iter()
+        .map(|trace| {
+            trace
+                .cells
+                .iter()
+                .map(|&idx| grid.extent.cell_centre(idx))
+                .collect::<Vec<_>>()
+        })
+        .filter(|line| line.len() >= 2 && polyline_length(line) >= settings.min_length)
+        .flat_map(|line| grid.measure(&line, settings))
+        .filter(|line| line.vertices.len() >= 2)
+        .collect();
+
+    if lines.is_empty() {
+        return None;
+    }
+
+    let summary = summarise(&lines, settings);
+    let features: Vec<Value> = lines
+        .iter()
+        .enumerate()
+        .map(|(line_id, line)| {
+            let coordinates: Vec<[f64; 3]> = line
+                .vertices
+                .iter()
+                .map(|v| {
+                    let [x, y] = source_xy((v.position[0], v.position[2]));
+                    [x, y, v.position[1]]
+                })
+                .collect();
+            json!({
+                "type": "Feature",
+                "geometry": { "type": "LineString", "coordinates": coordinates },
+                "properties": {
+                    "line_id": line_id,
+                    "kerb_heights_m": line.vertices.iter().map(|v| v.height_m).collect::<Vec<_>>(),
+                    "ramp": line.vertices.iter().map(|v| v.ramp).collect::<Vec<_>>(),
+                },
+            })
+        })
+        .collect();
+
+    let geojson = json!({
+        "type": "FeatureCollection",
+        "features": features,
+    });
+
+    Some(KerbResult {
+        lines,
+        geojson,
+        summary,
+    })
+}
+
+fn summarise(lines: &[KerbLine], settings: &KerbSettings) -> KerbSummary {
+    let mut total_length = 0.0;
+    let mut height_sum = 0.0;
+    let mut vertex_count = 0usize;
+    let mut ramps = Vec::new();
+
+    for (line_id, line) in lines.iter().enumerate() {
+        let plan: Vec<(f64, f64)> = line
+            .vertices
+            .iter()
+            .map(|v| (v.position[0], v.position[2]))
+            .collect();
+        total_length += polyline_length(&plan);
+        height_sum += line.vertices.iter().map(|v| v.height_m).sum::<f64>();
+        vertex_count += line.vertices.len();
+
+        // Group consecutive ramp vertices into single ramps.
+        let mut start = None;
+        for i in 0..=line.vertices.len() {
+            let is_ramp = line.vertices.get(i).is_some_and(|v| v.ramp);
+            match (is_ramp, start) {
+                (true, None) => start = Some(i),
+                (false, Some(first)) => {
+                    let run = &line.vertices[first..i];
+                    ramps.push(KerbRamp {
+                        line_id,
+                        position: run[run.len() / 2].position,
+                        length_m: round_cm(polyline_length(&plan[first..i])),
+                        min_height_m: run.iter().map(|v| v.height_m).fold(f64::MAX, f64::min),
+                    });
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    KerbSummary {
+        ramp_height_m: settings.ramp_height,
+        line_count: lines.len(),
+        total_length_m: round_cm(total_length),
+        mean_height_m: round_cm(height_sum / vertex_count.max(1) as f64),
+        ramps,
+    }
+}
+
+/// Road and sidewalk ground raster.
+struct GroundGrid {
+    extent: PlanExtent,
+    surface: Vec<Surface>,
+    /// Mean road elevation per cell, NaN where no road points fell.
+    road_height: Vec<f32>,
+    /// Mean sidewalk elevation per cell, NaN where no sidewalk points fell.
+    sidewalk_height: Vec<f32>,
+}
+
+impl GroundGrid {
+    fn from_points(points: &[SpatialPoint], settings: &KerbSettings) -> Option<Self> {
+        let of_classes = |classes: &[u8]| -> Vec<&SpatialPoint> {
+            points
+                .iter()
+                .filter(|p| classes.contains(&p.classification))
+                .collect()
+        };
+        let road = of_classes(&settings.road_classes);
+        let sidewalk = of_classes(&settings.sidewalk_classes);
+        if road.is_empty() || sidewalk.is_empty() {
+            return None;
+        }
+
+        let road_plan: Vec<(f64, f64)> = road
+            .iter()
+            .map(|p| (p.world_pos.0, p.world_pos.2))
+            .collect();
+        let sidewalk_plan: Vec<(f64, f64)> = sidewalk
+            .iter()
+            .map(|p| (p.world_pos.0, p.world_pos.2))
+            .collect();
+        let all_plan: Vec<(f64, f64)> = road_plan.iter().chain(&sidewalk_plan).copied().collect();
+        let extent = PlanExtent::around(&all_plan, settings.cell_size)?;
+
+        let (road_height, road_count) = mean_heights(&extent, &road);
+        let (sidewalk_height, sidewalk_count) = mean_heights(&extent, &sidewalk);
+        let road_mask = extent.close_gaps(
+            &road_count.iter().map(|&c| c > 0).collect::<Vec<_>>(),
+            &road_plan,
+        );
+        let sidewalk_mask = extent.close_gaps(
+            &sidewalk_count.iter().map(|&c| c > 0).collect::<Vec<_>>(),
+            &sidewalk_plan,
+        );
+
+        // Measured cells go to the majority class; closed gaps prefer sidewalk.
+        let surface = (0..extent.len())
+            .map(|idx| match (road_count[idx], sidewalk_count[idx]) {
+                (r, s) if r > 0 || s > 0 => {
+                    if s >= r {
+                        Surface::Sidewalk
+                    } else {
+                        Surface::Road
+                    }
+                }
+                _ if sidewalk_mask[idx] => Surface::Sidewalk,
+                _ if road_mask[idx] => Surface::Road,
+                _ => Surface::None,
+            })
+            .collect();
+
+        Some(Self {
+            extent,
+            surface,
+            road_height,
+            sidewalk_height,
+        })
+    }
+
+    /// Sidewalk cells bordering road cells.
+    fn is_edge(&self, idx: usize) -> bool {
+        let (x, z) = (idx % self.extent.width, idx / self.extent.width);
+        if self.surface[idx] != Surface::Sidewalk
+            || x == 0
+            || z == 0
+            || x == self.extent.width - 1
+            || z == self.extent.height - 1
+        {
+            return false;
+        }
+        [
+            idx - self.extent.width,
+            idx + 1,
+            idx + self.extent.width,
+            idx - 1,
+        ]
+        .iter()
+        .any(|&n| self.surface[n] == Surface::Road)
+    }
+
+    /// Resample a traced edge and measure the step at each vertex. Runs of
+    /// unmeasurable vertices split the edge into separate kerb lines.
+    fn measure(&self, line: &[(f64, f64)], settings: &KerbSettings) -> Vec<KerbLine> {
+        let length = polyline_length(line);
+        let half_window = settings.vertex_spacing.max(self.extent.cell_size * 10.0) * 0.5;
+
+        let mut distances: Vec<f64> = (0..)
+            .map(|i| i as f64 * settings.vertex_spacing)
+            .take_while(|&d| d < length)
+            .collect();
+        distances.push(length);
+
+        let mut lines = Vec::new();
+        let mut current = KerbLine {
+            vertices: Vec::new(),
+        };
+        for distance in distances {
+            let position = point_at(line, distance);
+            let ahead = point_at(line, (distance + half_window).min(length));
+            let behind = point_at(line, (distance - half_window).max(0.0));
+            let (tx, tz) = (ahead.0 - behind.0, ahead.1 - behind.1);
+            let norm = (tx * tx + tz * tz).sqrt().max(f64::EPSILON);
+            let normal = (-tz / norm, tx / norm);
+
+            match self.step_at(position, normal, settings) {
+                Some((top, height)) => current.vertices.push(KerbVertex {
+                    position: [position.0, top, position.1],
+                    height_m: round_cm(height),
+                    ramp: height < settings.ramp_height,
+                }),
+                None if !current.vertices.is_empty() => {
+                    lines.push(std::mem::replace(
+                        &mut current,
+                        KerbLine {
+                            vertices: Vec::new(),
+                        },
+                    ));
+                }
+                None => {}
+            }
+        }
+        lines.push(current);
+        lines
+    }
+
+    /// Sidewalk top elevation and step height across the edge at `centre`.
+    /// Heights come from a window `offset` deep on each side and one vertex
+    /// spacing long, so sparse rows of points still contribute.
+    fn step_at(
+        &self,
+        centre: (f64, f64),
+        normal: (f64, f64),
+        settings: &KerbSettings,
+    ) -> Option<(f64, f64)> {
+        let step = self.extent.cell_size * 0.5;
+        let across = (settings.sample_offset / step).ceil() as i64;
+        let along = (settings.vertex_spacing * 0.5 / step).ceil() as i64;
+        let tangent = (normal.1, -normal.0);
+
+        let mut road = Vec::new();
+        let mut sidewalk = Vec::new();
+        for i in (-across..=across).filter(|&i| i != 0) {
+            for j in -along..=along {
+                let (s, t) = (i as f64 * step, j as f64 * step);
+                let Some(idx) = self.extent.cell_index(
+                    centre.0 + normal.0 * s + tangent.0 * t,
+                    centre.1 + normal.1 * s + tangent.1 * t,
+                ) else {
+                    continue;
+                };
+                if !self.road_height[idx].is_nan() {
+                    road.push(self.road_height[idx]);
+                }
+                if !self.sidewalk_height[idx].is_nan() {
+                    sidewalk.push(self.sidewalk_height[idx]);
+                }
+            }
+        }
+
+        let top = median(&mut sidewalk)?;
+        let bottom = median(&mut road)?;
+        Some((top as f64, (top - bottom) as f64))
+    }
+}
+
+fn mean_heights(extent: &PlanExtent, points: &[&SpatialPoint]) -> (Vec<f32>, Vec<u32>) {
+    let mut sums = vec![0.0f64; extent.len()];
+    let mut counts = vec![0u32; extent.len()];
+    for p in points {
+        if let Some(idx) = extent.cell_index(p.world_pos.0, p.world_pos.2) {
+            sums[idx] += p.world_pos.1;
+            counts[idx] += 1;
+        }
+    }
+    let heights = sums
+        .iter()
+        .zip(&counts)
+        .map(|(&sum, &count)| {
+            if count > 0 {
+                (sum / count as f64) as f32
+            } else {
+                f32::NAN
+            }
+        })
+        .collect();
+    (heights, counts)
+}
+
+fn median(values: &mut [f32]) -> Option<f32> {
+    if values.is_empty() {
+        return None;
+    }
+    let mid = values.len() / 2;
+    let (_, value, _) = values.select_nth_unstable_by(mid, f32::total_cmp);
+    Some(*value)
+}
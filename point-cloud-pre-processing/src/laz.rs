@@ -1,3 +1,4 @@
+use crate::error::PreprocessError;
 use las::Reader;
 use std::fs::File;
 use std::io::BufReader;
@@ -5,8 +6,36 @@ use std::path::Path;
 
 /// Create LAS file reader for point cloud access.
 /// Handles both .las and .laz compressed formats.
-pub fn create_reader(file_path: &Path) -> Result<Reader, Box<dyn std::error::Error>> {
+pub fn create_reader(file_path: &Path) -> Result<Reader, PreprocessError> {
     let file = File::open(file_path)?;
     let buf_reader = BufReader::new(file);
     Ok(Reader::new(buf_reader)?)
 }
+
+/// Keeps an evenly spread `ratio` of points in file order. Sampling two files
+/// with the same ratio keeps the same indices, so identical epochs line up.
+pub struct UniformSampler {
+    ratio: f64,
+    expected: f64,
+    kept: usize,
+}
+
+impl UniformSampler {
+    pub fn new(ratio: f64) -> Self {
+        Self {
+            ratio,
+            expected: 0.0,
+            kept: 0,
+        }
+    }
+
+    /// Whether to keep the next point. Call once for every candidate point.
+    pub fn keep(&mut self) -> bool {
+        self.expected += self.ratio;
+        if self.kept as f64 >= self.expected {
+            return false;
+        }
+        self.kept += 1;
+        true
+    }
+}
@@ -0,0 +1,108 @@
+//! Point cloud preprocessing pipeline for terrain and asset library processing.
+//!
+//! Converts LAS/LAZ point clouds into GPU-optimised texture atlases with spatial
+//! organisation, heightmap generation, and unified manifest output.
+//!
+//! [`run`] executes the whole pipeline from a [`PipelineConfig`], reporting
+//! stages, progress and warnings to an optional [`ProgressListener`]; each run
+//! reports through its own [`Reporter`]. The
+//! individual stages ([`PointCloudConverter`], [`AssetProcessor`],
+//! [`HeightmapGenerator`], [`calculate_bounds`]) are also public for callers
+//! that need finer control. Every fallible call returns [`PreprocessError`].
+
+/// Asset library processing workflow and atlas generation orchestration.
+pub mod asset_processor;
+
+/// Asset atlas data structures, texture generation, and tile management.
+pub mod atlas;
+
+/// Point cloud coordinate bounds calculation and normalisation.
+pub mod bounds;
+
+/// Cloud-to-cloud distances between two scan epochs with a per-class change summary.
+pub mod change_detection;
+
+/// Main point cloud converter orchestrating terrain and asset processing pipelines.
+pub mod converter;
+
+/// DDS texture file writer with unified 32-bit float formats.
+mod dds_writer;
+
+/// Typed error returned by every fallible library call.
+pub mod error;
+
+/// Fast parallel heightmap generation with flood-fill and Gaussian smoothing.
+pub mod heightmap;
+
+/// Kerb edge detection and height measurement between road and sidewalk.
+pub mod kerbs;
+
+/// LAS/LAZ file reader creation and uniform point sampling.
+mod laz;
+
+/// Text, JSON-lines or callback reporting of stages, progress and warnings.
+pub mod logging;
+
+/// Scene manifest generation linking terrain and asset atlas data.
+pub mod manifest;
+
+/// PCA normal and curvature estimation over k nearest neighbours.
+pub mod normals;
+
+/// Statistical and radius outlier detection run before bounds calculation.
+pub mod outliers;
+
+/// Declarative TOML/JSON pipeline configuration with validation.
+pub mod pipeline_config;
+
+/// Plan-view rasterisation, thinning and polyline tracing for ground analysis.
+mod plan_raster;
+
+/// Per-class Euclidean clustering assigning object IDs to unlabelled clouds.
+pub mod segmentation;
+
+/// Sidewalk centreline extraction and clear width analysis.
+pub mod sidewalk_width;
+
+/// Voxel hash for fixed-radius neighbour queries over world positions.
+mod spatial_hash;
+
+/// Z-order spatial layout and texture generation for point cloud data.
+pub mod spatial_layout;
+
+pub use asset_processor::AssetProcessor;
+pub use bounds::{PointCloudBounds, calculate_bounds};
+pub use converter::PointCloudConverter;
+pub use error::PreprocessError;
+pub use heightmap::HeightmapGenerator;
+pub use logging::{FailureClass, LogLevel, ProgressEvent, ProgressListener, Reporter};
+pub use pipeline_config::PipelineConfig;
+
+use logging::Stage;
+use std::sync::Arc;
+
+/// Validate the configuration and run every enabled stage, writing textures
+/// and the manifest to the configured output directory. Events go to
+/// `listener` when one is given and are printed as text otherwise.
+pub fn run(
+    config: PipelineConfig,
+    listener: Option<Arc<dyn ProgressListener>>,
+) -> Result<(), PreprocessError> {
+    let reporter = listener.map_or_else(Reporter::default, Reporter::with_listener);
+    run_with_reporter(config, &reporter)
+}
+
+/// Run the pipeline reporting to `reporter`. Use a new reporter for every run;
+/// after a failure it holds the stage the run stopped in.
+pub fn run_with_reporter(
+    config: PipelineConfig,
+    reporter: &Reporter,
+) -> Result<(), PreprocessError> {
+    config.validate()?;
+
+    let stage = Stage::start(reporter, "prepare_output", FailureClass::Output);
+    let mut converter = PointCloudConverter::from_config(config, reporter.clone())?;
+    stage.finish();
+
+    converter.convert()
+}
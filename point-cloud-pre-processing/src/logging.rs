@@ -0,0 +1,606 @@
+/// Text, JSON-lines or callback reporting of stages, progress, warnings and statistics.
+use indicatif::{ProgressBar, ProgressStyle};
+use serde_json::{Map, Value, json};
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Output format selected with `--log-format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines with interactive progress bars.
+    Text,
+    /// One JSON object per line; progress bars become `progress` events.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "Unknown log format '{}' (expected text or json)",
+                other
+            )),
+        }
+    }
+}
+
+/// Failure categories reported to job runners through the process exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureClass {
+    /// Unknown flags or wrong positional arguments.
+    Usage,
+    /// Pipeline config could not be loaded or failed validation.
+    Config,
+    /// Source point clouds could not be opened or decoded.
+    Input,
+    /// A derivation stage failed on otherwise valid input.
+    Processing,
+    /// Textures, heightmap or manifest could not be written.
+    Output,
+}
+
+impl FailureClass {
+    /// Process exit code for this failure class. Zero is reserved for success.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Self::Usage => 2,
+            Self::Config => 3,
+            Self::Input => 4,
+            Self::Processing => 5,
+            Self::Output => 6,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Usage => "usage",
+            Self::Config => "config",
+            Self::Input => "input",
+            Self::Processing => "processing",
+            Self::Output => "output",
+        }
+    }
+}
+
+/// Severity of a log message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Info,
+    Warning,
+}
+
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+        }
+    }
+}
+
+/// Structured event emitted while the pipeline runs.
+#[derive(Debug, Clone, Copy)]
+pub enum ProgressEvent<'a> {
+    StageStarted {
+        stage: &'a str,
+    },
+    StageFinished {
+        stage: &'a str,
+        elapsed: Duration,
+    },
+    /// Throttled to whole-percent or message changes.
+    Progress {
+        stage: &'a str,
+        position: u64,
+        total: u64,
+        percent: u64,
+        message: &'a str,
+    },
+    Log {
+        level: LogLevel,
+        message: &'a str,
+    },
+    Statistics {
+        name: &'a str,
+        values: &'a Value,
+    },
+    Completed {
+        elapsed: Duration,
+    },
+    Failed {
+        class: FailureClass,
+        stage: Option<&'a str>,
+        message: &'a str,
+    },
+}
+
+/// Receiver for pipeline events. Implemented for any `Fn(&ProgressEvent)`
+/// closure, so a callback can be passed directly.
+pub trait ProgressListener: Send + Sync {
+    fn on_event(&self, event: &ProgressEvent);
+}
+
+impl<F> ProgressListener for F
+where
+    F: Fn(&ProgressEvent) + Send + Sync,
+{
+    fn on_event(&self, event: &ProgressEvent) {
+        self(event)
+    }
+}
+
+/// Listener writing one JSON object per event, used by `--log-format json`.
+pub struct JsonLinesWriter {
+    sink: Mutex<Box<dyn Write + Send>>,
+}
+
+impl JsonLinesWriter {
+    pub fn new(sink: Box<dyn Write + Send>) -> Self {
+        Self {
+            sink: Mutex::new(sink),
+        }
+    }
+}
+
+impl ProgressListener for JsonLinesWriter {
+    fn on_event(&self, event: &ProgressEvent) {
+        let (name, fields) = match *event {
+            ProgressEvent::StageStarted { stage } => ("stage_started", json!({ "stage": stage })),
+            ProgressEvent::StageFinished { stage, elapsed } => (
+                "stage_finished",
+                json!({ "stage": stage, "elapsed_ms": elapsed.as_millis() as u64 }),
+            ),
+            ProgressEvent::Progress {
+                stage,
+                position,
+                total,
+                percent,
+                message,
+            } => (
+                "progress",
+                json!({
+                    "stage": stage,
+                    "position": position,
+                    "total": total,
+                    "percent": percent,
+                    "message": message,
+                }),
+            ),
+            ProgressEvent::Log { level, message } => (
+                "log",
+                json!({ "level": level.as_str(), "message": message }),
+            ),
+            ProgressEvent::Statistics { name, values } => {
+                ("statistics", json!({ "name": name, "values": values }))
+            }
+            ProgressEvent::Completed { elapsed } => (
+                "completed",
+                json!({ "elapsed_ms": elapsed.as_millis() as u64, "exit_code": 0 }),
+            ),
+            ProgressEvent::Failed {
+                class,
+                stage,
+                message,
+            } => (
+                "failed",
+                json!({
+                    "class": class.as_str(),
+                    "exit_code": class.exit_code(),
+                    "stage": stage,
+                    "message": message,
+                }),
+            ),
+        };
+
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+
+        let mut record = Map::new();
+        record.insert("event".into(), name.into());
+        record.insert("timestamp_ms".into(), timestamp_ms.into());
+        if let Value::Object(fields) = fields {
+            record.extend(fields);
+        }
+
+        let mut sink = self.sink.lock().unwrap();
+        let _ = writeln!(sink, "{}", Value::Object(record));
+        let _ = sink.flush();
+    }
+}
+
+/// Where a run's reports go.
+enum Output {
+    Text(Mutex<Box<dyn Write + Send>>),
+    Events(Arc<dyn ProgressListener>),
+}
+
+struct ReporterState {
+    output: Output,
+    /// Innermost stage that was dropped without finishing.
+    failed_stage: Mutex<Option<(&'static str, FailureClass)>>,
+}
+
+/// Destination for one pipeline run's reports, together with the stage the run
+/// failed in. Clones share the same run, so each run gets its own reporter and
+/// concurrent runs never see each other's events.
+#[derive(Clone)]
+pub struct Reporter {
+    state: Arc<ReporterState>,
+}
+
+impl Default for Reporter {
+    /// Text output on stdout.
+    fn default() -> Self {
+        Self::from_output(Output::Text(Mutex::new(Box::new(io::stdout()))))
+    }
+}
+
+impl Reporter {
+    /// Reporter for the command-line tool in the selected format, writing to
+    /// `log_file` or stdout.
+    pub fn new(format: LogFormat, log_file: Option<&Path>) -> io::Result<Self> {
+        let sink: Box<dyn Write + Send> = match log_file {
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(io::stdout()),
+        };
+
+        Ok(Self::from_output(match format {
+            LogFormat::Text => Output::Text(Mutex::new(sink)),
+            LogFormat::Json => Output::Events(Arc::new(JsonLinesWriter::new(sink))),
+        }))
+    }
+
+    /// Reporter sending every event to `listener`.
+    pub fn with_listener(listener: Arc<dyn ProgressListener>) -> Self {
+        Self::from_output(Output::Events(listener))
+    }
+
+    fn from_output(output: Output) -> Self {
+        Self {
+            state: Arc::new(ReporterState {
+                output,
+                failed_stage: Mutex::new(None),
+            }),
+        }
+    }
+
+    fn is_structured(&self) -> bool {
+        matches!(self.state.output, Output::Events(_))
+    }
+
+    fn dispatch(&self, event: ProgressEvent) {
+        match &self.state.output {
+            Output::Events(listener) => listener.on_event(&event),
+            Output::Text(sink) => write_text(&mut **sink.lock().unwrap(), &event),
+        }
+    }
+
+    /// Report an informational message. Prefer the `log_info!` macro.
+    pub fn info(&self, message: String) {
+        if message.is_empty() && self.is_structured() {
+            return;
+        }
+        self.dispatch(ProgressEvent::Log {
+            level: LogLevel::Info,
+            message: &message,
+        });
+    }
+
+    /// Report a recoverable problem. Prefer the `log_warn!` macro.
+    pub fn warning(&self, message: String) {
+        self.dispatch(ProgressEvent::Log {
+            level: LogLevel::Warning,
+            message: &message,
+        });
+    }
+
+    /// Report named statistics.
+    pub fn statistics(&self, name: &str, values: Value) {
+        self.dispatch(ProgressEvent::Statistics {
+            name,
+            values: &values,
+        });
+    }
+
+    /// Report successful completion of the whole run.
+    pub fn completed(&self, elapsed: Duration) {
+        self.dispatch(ProgressEvent::Completed { elapsed });
+    }
+
+    /// Report a fatal error with its failure class before the process exits.
+    pub fn failed(&self, class: FailureClass, message: &str) {
+        let stage = self.failed_stage().map(|(name, _)| name);
+        self.dispatch(ProgressEvent::Failed {
+            class,
+            stage,
+            message,
+        });
+    }
+
+    /// Failure class of the innermost stage that did not finish, or
+    /// `Processing` when the error happened outside any stage.
+    pub fn failure_class(&self) -> FailureClass {
+        self.failed_stage()
+            .map(|(_, class)| class)
+            .unwrap_or(FailureClass::Processing)
+    }
+
+    fn failed_stage(&self) -> Option<(&'static str, FailureClass)> {
+        *self.state.failed_stage.lock().unwrap()
+    }
+
+    /// Create a progress bar for a stage. Text mode draws `style` to the
+    /// terminal; structured output reports the counters as throttled
+    /// `Progress` events instead.
+    pub(crate) fn progress_bar(
+        &self,
+        stage: &'static str,
+        len: u64,
+        style: ProgressStyle,
+    ) -> Progress {
+        if !self.is_structured() {
+            let pb = ProgressBar::new(len);
+            pb.set_style(style);
+            return Progress::Bar(pb);
+        }
+
+        Progress::Events(EventProgress {
+            reporter: self.clone(),
+            stage,
+            total: len,
+            position: AtomicU64::new(0),
+            reported_percent: AtomicU64::new(0),
+            message: Mutex::new(Cow::Borrowed("")),
+        })
+    }
+}
+
+/// Text mode only prints log lines; stage and statistics output comes from
+/// the pipeline's own summaries.
+fn write_text(sink: &mut dyn Write, event: &ProgressEvent) {
+    match *event {
+        ProgressEvent::Log {
+            level: LogLevel::Info,
+            message,
+        } => {
+            let _ = writeln!(sink, "{}", message);
+        }
+        ProgressEvent::Log {
+            level: LogLevel::Warning,
+            message,
+        } => {
+            let _ = writeln!(sink, "Warning: {}", message);
+        }
+        ProgressEvent::Failed { message, .. } => eprintln!("Error: {}", message),
+        _ => return,
+    }
+    let _ = sink.flush();
+}
+
+/// Timed pipeline stage. Dropping it without calling `finish` (for example
+/// when `?` returns early) records it as the stage the run failed in.
+pub(crate) struct Stage {
+    reporter: Reporter,
+    name: &'static str,
+    class: FailureClass,
+    started: Instant,
+    finished: bool,
+}
+
+impl Stage {
+    /// Start a stage whose errors are reported with the given failure class.
+    pub fn start(reporter: &Reporter, name: &'static str, class: FailureClass) -> Self {
+        reporter.dispatch(ProgressEvent::StageStarted { stage: name });
+
+        Self {
+            reporter: reporter.clone(),
+            name,
+            class,
+            started: Instant::now(),
+            finished: false,
+        }
+    }
+
+    /// Mark the stage as successfully completed.
+    pub fn finish(mut self) {
+        self.finished = true;
+        self.reporter.dispatch(ProgressEvent::StageFinished {
+            stage: self.name,
+            elapsed: self.started.elapsed(),
+        });
+    }
+}
+
+impl Drop for Stage {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+
+        let mut failed = self.reporter.state.failed_stage.lock().unwrap();
+        if failed.is_none() {
+            *failed = Some((self.name, self.class));
+        }
+    }
+}
+
+/// Stage progress: an indicatif bar in text mode, or counters reported as
+/// `Progress` events. Safe to update from parallel workers.
+pub(crate) enum Progress {
+    Bar(ProgressBar),
+    Events(EventProgress),
+}
+
+impl Progress {
+    pub fn set_message(&self, message: impl Into<Cow<'static, str>>) {
+        match self {
+            Self::Bar(pb) => pb.set_message(message),
+            Self::Events(progress) => progress.set_message(message.into()),
+        }
+    }
+
+    pub fn inc(&self, delta: u64) {
+        match self {
+            Self::Bar(pb) => pb.inc(delta),
+            Self::Events(progress) => {
+                let position = progress.position.fetch_add(delta, Ordering::Relaxed) + delta;
+                progress.update(position);
+            }
+        }
+    }
+
+    pub fn set_position(&self, position: u64) {
+        match self {
+            Self::Bar(pb) => pb.set_position(position),
+            Self::Events(progress) => {
+                // Parallel workers report out of order; never move backwards.
+                let previous = progress.position.fetch_max(position, Ordering::Relaxed);
+                progress.update(previous.max(position));
+            }
+        }
+    }
+
+    pub fn finish_with_message(&self, message: impl Into<Cow<'static, str>>) {
+        match self {
+            Self::Bar(pb) => pb.finish_with_message(message),
+            Self::Events(progress) => progress.finish(message.into()),
+        }
+    }
+}
+
+/// Progress counters reported directly as events, one per whole-percent or
+/// message change.
+pub(crate) struct EventProgress {
+    reporter: Reporter,
+    stage: &'static str,
+    total: u64,
+    position: AtomicU64,
+    reported_percent: AtomicU64,
+    message: Mutex<Cow<'static, str>>,
+}
+
+impl EventProgress {
+    fn percent(&self, position: u64) -> u64 {
+        // Empty bars count as complete.
+        (position * 100)
+            .checked_div(self.total)
+            .map_or(100, |p| p.min(100))
+    }
+
+    fn update(&self, position: u64) {
+        let percent = self.percent(position);
+        if self.reported_percent.fetch_max(percent, Ordering::Relaxed) < percent {
+            self.report(position, &self.message.lock().unwrap());
+        }
+    }
+
+    fn set_message(&self, message: Cow<'static, str>) {
+        let mut current = self.message.lock().unwrap();
+        if *current == message {
+            return;
+        }
+        *current = message;
+        self.report(self.position.load(Ordering::Relaxed), &current);
+    }
+
+    /// Report completion once, with the final message.
+    fn finish(&self, message: Cow<'static, str>) {
+        self.position.store(self.total, Ordering::Relaxed);
+        self.reported_percent.store(100, Ordering::Relaxed);
+        let mut current = self.message.lock().unwrap();
+        *current = message;
+        self.report(self.total, &current);
+    }
+
+    fn report(&self, position: u64, message: &str) {
+        self.reporter.dispatch(ProgressEvent::Progress {
+            stage: self.stage,
+            position,
+            total: self.total,
+            percent: self.percent(position),
+            message,
+        });
+    }
+}
+
+/// Report an informational message in the selected log format.
+macro_rules! log_info {
+    ($reporter:expr, $($arg:tt)*) => {
+        $reporter.info(format!($($arg)*))
+    };
+}
+
+/// Report a warning in the selected log format.
+macro_rules! log_warn {
+    ($reporter:expr, $($arg:tt)*) => {
+        $reporter.warning(format!($($arg)*))
+    };
+}
+
+pub(crate) use {log_info, log_warn};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn silent() -> Reporter {
+        Reporter::with_listener(Arc::new(|_: &ProgressEvent| {}))
+    }
+
+    #[test]
+    fn failed_stage_belongs_to_its_own_run() {
+        let failed = silent();
+        let succeeded = silent();
+
+        drop(Stage::start(&failed, "bounds", FailureClass::Input));
+        Stage::start(&succeeded, "manifest", FailureClass::Output).finish();
+
+        assert_eq!(failed.failure_class(), FailureClass::Input);
+        assert_eq!(succeeded.failure_class(), FailureClass::Processing);
+    }
+
+    #[test]
+    fn progress_events_report_whole_percent_and_message_changes() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let reporter = Reporter::with_listener(Arc::new(move |event: &ProgressEvent| {
+            if let ProgressEvent::Progress {
+                position,
+                percent,
+                message,
+                ..
+            } = *event
+            {
+                sink.lock()
+                    .unwrap()
+                    .push((position, percent, message.to_string()));
+            }
+        }));
+
+        let pb = reporter.progress_bar("test", 200, ProgressStyle::default_bar());
+        pb.set_message("Working");
+        for _ in 0..200 {
+            pb.inc(1);
+        }
+        pb.finish_with_message("Done");
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.first(), Some(&(0, 0, "Working".to_string())));
+        assert_eq!(events[1], (2, 1, "Working".to_string()));
+        assert_eq!(events.last(), Some(&(200, 100, "Done".to_string())));
+        // One event per whole percent, plus one per message.
+        assert_eq!(events.len(), 102);
+    }
+}
@@ -1,90 +1,360 @@
-//! Point cloud preprocessing pipeline for terrain and asset library processing.
+//! Command-line entry point for the point cloud preprocessing pipeline.
 //!
-//! Converts LAS/LAZ point clouds into GPU-optimised texture atlases with spatial
-//! organisation, heightmap generation, and unified manifest output.
+//! Parses positional arguments or a `--config` file, applies stage flags on
+//! top, and maps pipeline failures to per-class exit codes.
 
-/// Asset library processing workflow and atlas generation orchestration.
-mod asset_processor;
+use point_cloud_pre_processing::change_detection::ChangeMethod;
+use point_cloud_pre_processing::logging::{FailureClass, LogFormat, Reporter};
+use point_cloud_pre_processing::outliers::{OutlierMethod, OutlierSettings};
+use point_cloud_pre_processing::pipeline_config::{PipelineConfig, Stage};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
-/// Asset atlas data structures, texture generation, and tile management.
-mod atlas;
+/// Stage flags given on the command line, applied on top of the base config.
+#[derive(Default)]
+struct StageOverrides {
+    segment: bool,
+    segment_distance: Option<f64>,
+    segment_min_size: Option<usize>,
+    normals_k: Option<usize>,
+    curvature: bool,
+    no_normals: bool,
+    compare: Option<PathBuf>,
+    change_method: Option<String>,
+    change_threshold: Option<f64>,
+    outlier_method: Option<String>,
+    outlier_k: Option<usize>,
+    outlier_std_ratio: Option<f64>,
+    outlier_radius: Option<f64>,
+    outlier_min_neighbours: Option<usize>,
+    keep_noise: bool,
+    sidewalk_width: bool,
+    min_clear_width: Option<f64>,
+    kerbs: bool,
+    ramp_height: Option<f64>,
+}
 
-/// Point cloud coordinate bounds calculation and normalisation.
-mod bounds;
+impl StageOverrides {
+    /// Whether any flag touches the outlier filter.
+    fn touches_outliers(&self) -> bool {
+        self.outlier_method.is_some()
+            || self.outlier_k.is_some()
+            || self.outlier_std_ratio.is_some()
+            || self.outlier_radius.is_some()
+            || self.outlier_min_neighbours.is_some()
+            || self.keep_noise
+    }
 
-/// Main point cloud converter orchestrating terrain and asset processing pipelines.
-mod converter;
+    /// Write overridden values into the config, enabling touched stages.
+    fn apply(&self, config: &mut PipelineConfig) -> Result<(), Box<dyn std::error::Error>> {
+        let segmentation = &mut config.derivatives.segmentation;
+        if self.segment || self.segment_distance.is_some() || self.segment_min_size.is_some() {
+            segmentation.enabled = true;
+        }
+        if let Some(distance) = self.segment_distance {
+            segmentation.settings.cluster_distance = distance;
+        }
+        if let Some(min_size) = self.segment_min_size {
+            segmentation.settings.min_cluster_size = min_size;
+        }
 
-/// DDS texture file writer with unified 32-bit float formats.
-mod dds_writer;
+        let normals = &mut config.derivatives.normals;
+        if let Some(k) = self.normals_k {
+            normals.settings.k_neighbours = k;
+        }
+        if self.curvature {
+            normals.settings.compute_curvature = true;
+        }
+        if self.no_normals {
+            normals.enabled = false;
+        }
 
-/// Fast parallel heightmap generation with flood-fill and Gaussian smoothing.
-mod heightmap;
+        let change = &mut config.derivatives.change;
+        if self.compare.is_some() || self.change_method.is_some() || self.change_threshold.is_some()
+        {
+            change.enabled = true;
+        }
+        if let Some(reference) = &self.compare {
+            change.settings.reference = reference.clone();
+        }
+        if let Some(method) = &self.change_method {
+            change.settings.method = match method.as_str() {
+                "point" => ChangeMethod::PointToPoint,
+                "surface" => ChangeMethod::PointToSurface,
+                other => return Err(format!("Unknown change method '{}'", other).into()),
+            };
+        }
+        if let Some(threshold) = self.change_threshold {
+            change.settings.threshold = threshold;
+        }
 
-/// LAS/LAZ file reader creation for point cloud access.
-mod laz;
+        let sidewalk = &mut config.analysis.sidewalk_width;
+        if self.sidewalk_width || self.min_clear_width.is_some() {
+            sidewalk.enabled = true;
+        }
+        if let Some(width) = self.min_clear_width {
+            sidewalk.settings.minimum_width = width;
+        }
 
-/// Scene manifest generation linking terrain and asset atlas data.
-mod manifest;
+        let kerbs = &mut config.analysis.kerbs;
+        if self.kerbs || self.ramp_height.is_some() {
+            kerbs.enabled = true;
+        }
+        if let Some(height) = self.ramp_height {
+            kerbs.settings.ramp_height = height;
+        }
 
-/// Z-order spatial layout and texture generation for point cloud data.
-mod spatial_layout;
+        if !self.touches_outliers() {
+            return Ok(());
+        }
 
-use converter::PointCloudConverter;
-use std::env;
+        let outliers = &mut config.filters.outliers;
+        if !outliers.enabled {
+            *outliers = Stage::enabled();
+        }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = env::args().collect();
+        // Switching method resets its parameters to their defaults.
+        if let Some(method) = &self.outlier_method {
+            outliers.settings.method = match method.as_str() {
+                "statistical" => OutlierSettings::default().method,
+                "radius" => OutlierMethod::Radius {
+                    radius: 0.5,
+                    min_neighbours: 4,
+                },
+                other => return Err(format!("Unknown outlier method '{}'", other).into()),
+            };
+        }
 
-    // Support both old single-file format and new asset library format
-    match args.len() {
-        2 => {
-            // Legacy single file processing
-            let input_path = &args[1];
-            let output_stem = input_path.trim_end_matches(".laz").trim_end_matches(".las");
+        match &mut outliers.settings.method {
+            OutlierMethod::Statistical { k, std_ratio } => {
+                if let Some(value) = self.outlier_k {
+                    *k = value;
+                }
+                if let Some(value) = self.outlier_std_ratio {
+                    *std_ratio = value;
+                }
+            }
+            OutlierMethod::Radius {
+                radius,
+                min_neighbours,
+            } => {
+                if let Some(value) = self.outlier_radius {
+                    *radius = value;
+                }
+                if let Some(value) = self.outlier_min_neighbours {
+                    *min_neighbours = value;
+                }
+            }
+        }
 
-            let mut converter = PointCloudConverter::new(input_path, output_stem)?;
-            converter.convert()?;
+        if self.keep_noise {
+            outliers.settings.keep_noise = true;
         }
-        4 => {
-            // New asset library processing: <main_cloud.laz> <asset_library_dir> <output_dir>
-            let main_cloud = std::path::Path::new(&args[1]);
-            let asset_library_dir = std::path::Path::new(&args[2]);
-            let output_dir = std::path::Path::new(&args[3]);
-
-            if !main_cloud.exists() {
-                eprintln!(
-                    "Error: Main cloud file '{}' does not exist",
-                    main_cloud.display()
-                );
-                std::process::exit(1);
-            }
 
-            if !asset_library_dir.is_dir() {
-                eprintln!(
-                    "Error: Asset library directory '{}' does not exist",
-                    asset_library_dir.display()
-                );
-                std::process::exit(1);
-            }
+        Ok(())
+    }
+}
+
+fn main() {
+    let started = Instant::now();
+    let mut args: Vec<String> = env::args().collect();
+    let flags = match extract_flags(&mut args) {
+        Ok(flags) => flags,
+        Err(e) => fail(&Reporter::default(), FailureClass::Usage, e),
+    };
 
-            let mut converter =
-                PointCloudConverter::with_asset_library(main_cloud, asset_library_dir, output_dir)?;
-            converter.convert()?;
+    let reporter = match Reporter::new(flags.log_format, flags.log_file.as_deref()) {
+        Ok(reporter) => reporter,
+        Err(e) => fail(&Reporter::default(), FailureClass::Output, e.into()),
+    };
+
+    // A config file replaces the positional arguments entirely.
+    let mut config = match (flags.config_path, args.len()) {
+        (Some(path), 1) => match PipelineConfig::load(Path::new(&path)) {
+            Ok(config) => config,
+            Err(e) => fail(&reporter, e.failure_class(&reporter), e.into()),
+        },
+        (None, 2) => {
+            // Legacy single file processing
+            PipelineConfig::new(Path::new(&args[1]), None, None)
+        }
+        (None, 4) => {
+            // New asset library processing: <main_cloud.laz> <asset_library_dir> <output_dir>
+            PipelineConfig::new(
+                Path::new(&args[1]),
+                Some(Path::new(&args[2])),
+                Some(Path::new(&args[3])),
+            )
         }
         _ => {
-            eprintln!("Usage:");
-            eprintln!(
-                "  {} <input.laz>                              (single file)",
-                args[0]
-            );
-            eprintln!(
-                "  {} <main_cloud.laz> <asset_dir> <output_dir> (with assets)",
-                args[0]
-            );
-            std::process::exit(1);
+            print_usage(&args[0]);
+            std::process::exit(FailureClass::Usage.exit_code());
+        }
+    };
+
+    if let Err(e) = flags.overrides.apply(&mut config) {
+        fail(&reporter, FailureClass::Usage, e);
+    }
+
+    match point_cloud_pre_processing::run_with_reporter(config, &reporter) {
+        Ok(()) => reporter.completed(started.elapsed()),
+        Err(e) => fail(&reporter, e.failure_class(&reporter), e.into()),
+    }
+}
+
+/// Report a fatal error and exit with the code for its failure class.
+fn fail(reporter: &Reporter, class: FailureClass, error: Box<dyn std::error::Error>) -> ! {
+    reporter.failed(class, &error.to_string());
+    std::process::exit(class.exit_code());
+}
+
+fn print_usage(program: &str) {
+    eprintln!("Usage:");
+    eprintln!(
+        "  {} <input.laz>                              (single file)",
+        program
+    );
+    eprintln!(
+        "  {} <main_cloud.laz> <asset_dir> <output_dir> (with assets)",
+        program
+    );
+    eprintln!(
+        "  {} --config <pipeline.toml|json>            (declarative pipeline)",
+        program
+    );
+    eprintln!();
+    eprintln!("Options (override the config file when both are given):");
+    eprintln!("  --segment                    Cluster object IDs when none are present");
+    eprintln!("  --segment-distance <metres>  Neighbour distance for clustering (default 0.5)");
+    eprintln!("  --segment-min-size <points>  Minimum points per object (default 50)");
+    eprintln!("  --normals-k <count>          Neighbours per normal estimate (default 16)");
+    eprintln!("  --curvature                  Store surface curvature with normals");
+    eprintln!("  --no-normals                 Skip normal texture generation");
+    eprintln!("  --compare <reference.laz>    Measure change against an earlier scan epoch");
+    eprintln!(
+        "  --change-method <point|surface>  Cloud-to-cloud distance method (default surface)"
+    );
+    eprintln!("  --change-threshold <metres>  Distance reported as change (default 0.1)");
+    eprintln!("  --outliers <statistical|radius>  Filter noise before bounds calculation");
+    eprintln!("  --outlier-k <count>          Neighbours for statistical filter (default 8)");
+    eprintln!("  --outlier-std-ratio <ratio>  Standard deviations allowed (default 2.0)");
+    eprintln!("  --outlier-radius <metres>    Search radius for radius filter (default 0.5)");
+    eprintln!("  --outlier-min-neighbours <count>  Minimum neighbours in radius (default 4)");
+    eprintln!("  --keep-noise                 Reclassify noise to class 7 instead of dropping");
+    eprintln!("  --sidewalk-width             Measure sidewalk clear width along centrelines");
+    eprintln!("  --min-clear-width <metres>   Flag clear widths below this (default 1.5)");
+    eprintln!("  --kerbs                      Detect kerb lines between road and sidewalk");
+    eprintln!("  --ramp-height <metres>       Flag kerbs lower than this as ramps (default 0.04)");
+    eprintln!("  --log-format <text|json>     Emit JSON-lines events for job runners");
+    eprintln!("  --log-file <path>            Write log output to a file instead of stdout");
+}
+
+/// Flags removed from the argument list before positional parsing.
+struct CliFlags {
+    config_path: Option<String>,
+    log_format: LogFormat,
+    log_file: Option<PathBuf>,
+    overrides: StageOverrides,
+}
+
+/// Removes `--config`, logging and stage flags from the argument list.
+/// Positional arguments are left in place.
+fn extract_flags(args: &mut Vec<String>) -> Result<CliFlags, Box<dyn std::error::Error>> {
+    let mut config_path = None;
+    let mut log_format = LogFormat::Text;
+    let mut log_file = None;
+    let mut overrides = StageOverrides::default();
+    let mut remaining = Vec::with_capacity(args.len());
+    let mut iter = args.drain(..);
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--config" => {
+                config_path = Some(iter.next().ok_or("--config requires a path")?);
+            }
+            "--log-format" => {
+                let value = iter.next().ok_or("--log-format requires text or json")?;
+                log_format = value.parse()?;
+            }
+            "--log-file" => {
+                log_file = Some(PathBuf::from(
+                    iter.next().ok_or("--log-file requires a path")?,
+                ));
+            }
+            "--segment" => overrides.segment = true,
+            "--segment-distance" => {
+                let value = iter.next().ok_or("--segment-distance requires a value")?;
+                overrides.segment_distance = Some(value.parse()?);
+            }
+            "--segment-min-size" => {
+                let value = iter.next().ok_or("--segment-min-size requires a value")?;
+                overrides.segment_min_size = Some(value.parse()?);
+            }
+            "--normals-k" => {
+                let value = iter.next().ok_or("--normals-k requires a value")?;
+                overrides.normals_k = Some(value.parse()?);
+            }
+            "--curvature" => overrides.curvature = true,
+            "--no-normals" => overrides.no_normals = true,
+            "--compare" => {
+                overrides.compare = Some(PathBuf::from(
+                    iter.next().ok_or("--compare requires a path")?,
+                ));
+            }
+            "--change-method" => {
+                overrides.change_method = Some(
+                    iter.next()
+                        .ok_or("--change-method requires point or surface")?,
+                );
+            }
+            "--change-threshold" => {
+                let value = iter.next().ok_or("--change-threshold requires a value")?;
+                overrides.change_threshold = Some(value.parse()?);
+            }
+            "--outliers" => {
+                overrides.outlier_method = Some(iter.next().ok_or("--outliers requires a method")?);
+            }
+            "--outlier-k" => {
+                let value = iter.next().ok_or("--outlier-k requires a value")?;
+                overrides.outlier_k = Some(value.parse()?);
+            }
+            "--outlier-std-ratio" => {
+                let value = iter.next().ok_or("--outlier-std-ratio requires a value")?;
+                overrides.outlier_std_ratio = Some(value.parse()?);
+            }
+            "--outlier-radius" => {
+                let value = iter.next().ok_or("--outlier-radius requires a value")?;
+                overrides.outlier_radius = Some(value.parse()?);
+            }
+            "--outlier-min-neighbours" => {
+                let value = iter
+                    .next()
+                    .ok_or("--outlier-min-neighbours requires a value")?;
+                overrides.outlier_min_neighbours = Some(value.parse()?);
+            }
+            "--keep-noise" => overrides.keep_noise = true,
+            "--sidewalk-width" => overrides.sidewalk_width = true,
+            "--min-clear-width" => {
+                let value = iter.next().ok_or("--min-clear-width requires a value")?;
+                overrides.min_clear_width = Some(value.parse()?);
+            }
+            "--kerbs" => overrides.kerbs = true,
+            "--ramp-height" => {
+                let value = iter.next().ok_or("--ramp-height requires a value")?;
+                overrides.ramp_height = Some(value.parse()?);
+            }
+            _ => remaining.push(arg),
         }
     }
 
-    Ok(())
+    drop(iter);
+    *args = remaining;
+
+    Ok(CliFlags {
+        config_path,
+        log_format,
+        log_file,
+        overrides,
+    })
 }
@@ -1,6 +1,12 @@
 /// Scene manifest generation for unified terrain and asset integration.
 use crate::atlas::AssetAtlasInfo;
 use crate::bounds::PointCloudBounds;
+use crate::change_detection::ChangeSummary;
+use crate::error::PreprocessError;
+use crate::kerbs::{KerbLine, KerbSummary};
+use crate::logging::{Reporter, log_info};
+use crate::pipeline_config::PipelineConfig;
+use crate::sidewalk_width::SidewalkWidthSummary;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -50,6 +56,51 @@ pub struct SceneManifest {
     // pub scene_bounds: PointCloudBounds,
     /// Describes the class types and object id's found in the specific dataset
     pub classes: ClassificationInfo,
+    /// Pipeline configuration the dataset was generated with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipeline: Option<PipelineConfig>,
+    /// Results of optional analysis stages run over the terrain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analysis: Option<AnalysisInfo>,
+}
+
+/// Outputs of analysis stages, keyed by stage.
+#[derive(Serialize, Deserialize, Default)]
+pub struct AnalysisInfo {
+    /// Sidewalk centreline width sampling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sidewalk_width: Option<SidewalkWidthInfo>,
+    /// Kerb edge detection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kerbs: Option<KerbInfo>,
+    /// Comparison against an earlier scan epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change: Option<ChangeSummary>,
+}
+
+impl AnalysisInfo {
+    /// Whether no analysis stage produced output.
+    pub fn is_empty(&self) -> bool {
+        self.sidewalk_width.is_none() && self.kerbs.is_none() && self.change.is_none()
+    }
+}
+
+/// Sidewalk width GeoJSON location and narrow segment summary.
+#[derive(Serialize, Deserialize)]
+pub struct SidewalkWidthInfo {
+    /// GeoJSON file of centreline segments, relative to the manifest.
+    pub geojson: String,
+    pub summary: SidewalkWidthSummary,
+}
+
+/// Kerb polylines for the renderer overlay, with their GeoJSON location.
+#[derive(Serialize, Deserialize)]
+pub struct KerbInfo {
+    /// GeoJSON file of kerb lines, relative to the manifest.
+    pub geojson: String,
+    pub summary: KerbSummary,
+    /// Kerb lines relative to the terrain origin, with per-vertex heights.
+    pub lines: Vec<KerbLine>,
 }
 
 /// Terrain dataset information for main point cloud processing.
@@ -76,6 +127,13 @@ pub struct TerrainTextureFiles {
     pub spatial_index: String,
     /// Height field texture (R32F) for terrain surface reconstruction.
     pub heightmap: String,
+    /// Normal texture (RGBA32F) with XYZ normal and curvature, when estimated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub normal: Option<String>,
+    /// Change texture (RGBA32F) with signed distance to a reference epoch, changed
+    /// flag and nearest reference class, when compared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change: Option<String>,
 }
 
 /// Scene manifest generator for unified terrain and asset output.
@@ -85,13 +143,16 @@ pub struct ManifestGenerator {
     output_dir: std::path::PathBuf,
     /// Programmatic name for consistent file identification.
     output_name: String,
+    /// Destination for the manifest summary.
+    reporter: Reporter,
 }
 
 impl ManifestGenerator {
-    pub fn new(output_dir: &Path, output_name: &str) -> Self {
+    pub fn new(output_dir: &Path, output_name: &str, reporter: Reporter) -> Self {
         Self {
             output_dir: output_dir.to_path_buf(),
             output_name: output_name.to_string(),
+            reporter,
         }
     }
 
@@ -102,7 +163,9 @@ impl ManifestGenerator {
         terrain_info: TerrainInfo,
         asset_atlas_info: Option<AssetAtlasInfo>,
         classes: ClassificationInfo,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+        pipeline: &PipelineConfig,
+        analysis: Option<AnalysisInfo>,
+    ) -> Result<(), PreprocessError> {
         // Calculate global scene bounds encompassing terrain and assets.
         // let scene_bounds = self.calculate_global_bounds(&terrain_info, &asset_atlas_info);
 
@@ -111,6 +174,8 @@ impl ManifestGenerator {
             asset_atlas: asset_atlas_info,
             // scene_bounds,
             classes,
+            pipeline: Some(pipeline.clone()),
+            analysis,
         };
 
         // Write manifest to root output directory for easy discovery.
@@ -118,7 +183,11 @@ impl ManifestGenerator {
         let manifest_json = serde_json::to_string_pretty(&manifest)?;
         fs::write(&manifest_path, manifest_json)?;
 
-        println!("Generated unified manifest: {}", manifest_path.display());
+        log_info!(
+            self.reporter,
+            "Generated unified manifest: {}",
+            manifest_path.display()
+        );
         self.print_manifest_summary(&manifest);
 
         Ok(())
@@ -127,9 +196,14 @@ impl ManifestGenerator {
     /// Prints manifest summary for verification and debugging.
     /// Displays key statistics about processed terrain and assets.
     fn print_manifest_summary(&self, manifest: &SceneManifest) {
-        println!("Manifest Summary:");
-        println!("  Terrain points: {}", manifest.terrain.point_count);
-        println!(
+        log_info!(self.reporter, "Manifest Summary:");
+        log_info!(
+            self.reporter,
+            "  Terrain points: {}",
+            manifest.terrain.point_count
+        );
+        log_info!(
+            self.reporter,
             "  Terrain bounds: ({:.2}, {:.2}) to ({:.2}, {:.2})",
             manifest.terrain.bounds.min_x,
             manifest.terrain.bounds.min_z,
@@ -138,7 +212,8 @@ impl ManifestGenerator {
         );
 
         if let Some(atlas) = &manifest.asset_atlas {
-            println!(
+            log_info!(
+                self.reporter,
                 "  Asset atlas: {} assets in {}x{} texture",
                 atlas.assets.len(),
                 atlas.atlas_config.atlas_size,
@@ -147,9 +222,51 @@ impl ManifestGenerator {
 
             // Calculate total asset points for statistics.
             let total_asset_points: u32 = atlas.assets.iter().map(|a| a.point_count).sum();
-            println!("  Asset points: {}", total_asset_points);
+            log_info!(self.reporter, "  Asset points: {}", total_asset_points);
         } else {
-            println!("  No asset atlas generated");
+            log_info!(self.reporter, "  No asset atlas generated");
+        }
+
+        if let Some(sidewalk) = manifest
+            .analysis
+            .as_ref()
+            .and_then(|analysis| analysis.sidewalk_width.as_ref())
+        {
+            log_info!(
+                self.reporter,
+                "  Sidewalk width: {:.1} m of centreline, {:.1} m below {:.2} m",
+                sidewalk.summary.centreline_length_m,
+                sidewalk.summary.below_minimum_length_m,
+                sidewalk.summary.minimum_width_m
+            );
+        }
+
+        if let Some(kerbs) = manifest
+            .analysis
+            .as_ref()
+            .and_then(|analysis| analysis.kerbs.as_ref())
+        {
+            log_info!(
+                self.reporter,
+                "  Kerbs: {} lines, {:.1} m long, {} ramps",
+                kerbs.summary.line_count,
+                kerbs.summary.total_length_m,
+                kerbs.summary.ramps.len()
+            );
+        }
+
+        if let Some(change) = manifest
+            .analysis
+            .as_ref()
+            .and_then(|analysis| analysis.change.as_ref())
+        {
+            log_info!(
+                self.reporter,
+                "  Change: {} of {} points changed, {} reference points removed",
+                change.changed_points,
+                change.compared_points,
+                change.removed_points
+            );
         }
     }
 }
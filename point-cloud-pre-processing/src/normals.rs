@@ -0,0 +1,200 @@
+/// Per-point surface normal estimation using PCA over k nearest neighbours.
+use crate::logging::Reporter;
+use crate::spatial_hash::SpatialHash;
+use crate::spatial_layout::SpatialPoint;
+use constants::texture::TEXTURE_SIZE;
+use indicatif::ProgressStyle;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Neighbourhood parameters for normal estimation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NormalSettings {
+    /// Number of nearest neighbours used for each covariance estimate.
+    pub k_neighbours: usize,
+    /// Voxel size of the neighbour search hash in world units.
+    pub search_cell_size: f64,
+    /// Store surface variation (λ0 / Σλ) in the W channel when enabled.
+    pub compute_curvature: bool,
+}
+
+impl Default for NormalSettings {
+    fn default() -> Self {
+        Self {
+            k_neighbours: 16,
+            search_cell_size: 0.25,
+            compute_curvature: false,
+        }
+    }
+}
+
+/// Maximum shells visited per query before accepting fewer neighbours.
+const MAX_SEARCH_RINGS: i64 = 8;
+
+/// Generate normal texture in the same order as the other spatial textures.
+/// RGB holds the unit normal in world space (Y up, oriented upwards where
+/// ambiguous) and A holds curvature, or 0.0 when curvature is disabled.
+pub fn generate_normal_texture(
+    points: &[SpatialPoint],
+    settings: &NormalSettings,
+    reporter: &Reporter,
+) -> Vec<f32> {
+    let positions: Vec<(f64, f64, f64)> = points.iter().map(|p| p.world_pos).collect();
+    let hash = SpatialHash::new(&positions, settings.search_cell_size);
+
+    let pb = reporter.progress_bar(
+        "normals",
+        positions.len() as u64,
+        ProgressStyle::default_bar()
+            .template("[{bar:40.cyan/blue}] {pos}/{len} normals ({percent}%) {msg}")
+            .unwrap()
+            .progress_chars("▉▊▋▌▍▎▏ "),
+    );
+    pb.set_message("Estimating normals");
+
+    let k = settings.k_neighbours.max(3);
+    let mut normal_data = vec![0.0f32; TEXTURE_SIZE * TEXTURE_SIZE * 4];
+
+    normal_data
+        .par_chunks_mut(4)
+        .take(positions.len())
+        .enumerate()
+        .for_each_init(Vec::new, |neighbours, (i, texel)| {
+            hash.nearest_k(positions[i], k, MAX_SEARCH_RINGS, neighbours);
+            let (normal, curvature) = estimate_normal(&positions, neighbours);
+
+            texel[0] = normal[0] as f32;
+            texel[1] = normal[1] as f32;
+            texel[2] = normal[2] as f32;
+            texel[3] = if settings.compute_curvature {
+                curvature as f32
+            } else {
+                0.0
+            };
+
+            if i % 50_000 == 0 {
+                pb.set_position(i as u64);
+            }
+        });
+
+    pb.finish_with_message("Normals estimated");
+    normal_data
+}
+
+/// Fit a plane to the neighbourhood and return its normal with surface variation.
+/// Falls back to straight up for degenerate neighbourhoods.
+pub(crate) fn estimate_normal(
+    positions: &[(f64, f64, f64)],
+    neighbours: &[usize],
+) -> ([f64; 3], f64) {
+    if neighbours.len() < 3 {
+        return ([0.0, 1.0, 0.0], 0.0);
+    }
+
+    let count = neighbours.len() as f64;
+    let mut centroid = [0.0f64; 3];
+    for &idx in neighbours {
+        let p = positions[idx];
+        centroid[0] += p.0;
+        centroid[1] += p.1;
+        centroid[2] += p.2;
+    }
+    for c in &mut centroid {
+        *c /= count;
+    }
+
+    let mut covariance = [[0.0f64; 3]; 3];
+    for &idx in neighbours {
+        let p = positions[idx];
+        let d = [p.0 - centroid[0], p.1 - centroid[1], p.2 - centroid[2]];
+        for row in 0..3 {
+            for col in 0..3 {
+                covariance[row][col] += d[row] * d[col];
+            }
+        }
+    }
+
+    let (eigenvalues, eigenvectors) = symmetric_eigen_3x3(covariance);
+
+    // Smallest eigenvalue's eigenvector is the plane normal.
+    let mut smallest = 0;
+    for i in 1..3 {
+        if eigenvalues[i] < eigenvalues[smallest] {
+            smallest = i;
+        }
+    }
+
+    let mut normal = [
+        eigenvectors[0][smallest],
+        eigenvectors[1][smallest],
+        eigenvectors[2][smallest],
+    ];
+
+    let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+    if length <= f64::EPSILON {
+        return ([0.0, 1.0, 0.0], 0.0);
+    }
+    for n in &mut normal {
+        *n /= length;
+    }
+
+    // PCA sign is arbitrary; orient towards the sky for consistent shading.
+    if normal[1] < 0.0 {
+        for n in &mut normal {
+            *n = -*n;
+        }
+    }
+
+    let total: f64 = eigenvalues.iter().map(|v| v.max(0.0)).sum();
+    let curvature = if total > 0.0 {
+        eigenvalues[smallest].max(0.0) / total
+    } else {
+        0.0
+    };
+
+    (normal, curvature)
+}
+
+/// Cyclic Jacobi eigen decomposition for a symmetric 3x3 matrix.
+/// Returns eigenvalues and a matrix whose columns are the matching eigenvectors.
+fn symmetric_eigen_3x3(mut a: [[f64; 3]; 3]) -> ([f64; 3], [[f64; 3]; 3]) {
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+    for _ in 0..32 {
+        let off_diagonal = a[0][1].abs() + a[0][2].abs() + a[1][2].abs();
+        if off_diagonal < 1e-12 {
+            break;
+        }
+
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q].abs() < 1e-15 {
+                continue;
+            }
+
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+
+            // Apply rotation J^T A J.
+            for row in &mut a {
+                let akp = row[p];
+                let akq = row[q];
+                row[p] = c * akp - s * akq;
+                row[q] = s * akp + c * akq;
+            }
+            let (row_p, row_q) = (a[p], a[q]);
+            a[p] = std::array::from_fn(|k| c * row_p[k] - s * row_q[k]);
+            a[q] = std::array::from_fn(|k| s * row_p[k] + c * row_q[k]);
+            for row in &mut v {
+                let vp = row[p];
+                let vq = row[q];
+                row[p] = c * vp - s * vq;
+                row[q] = s * vp + c * vq;
+            }
+        }
+    }
+
+    ([a[0][0], a[1][1], a[2][2]], v)
+}
@@ -0,0 +1,240 @@
+/// Statistical and radius-based outlier detection for airborne noise removal.
+use crate::error::PreprocessError;
+use crate::laz::create_reader;
+use crate::logging::{Progress, Reporter, log_info};
+use crate::spatial_hash::{SpatialHash, distance_sq};
+use constants::coordinate_system::transform_coordinates;
+use indicatif::ProgressStyle;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// ASPRS classification assigned to noise points when they are kept.
+pub const NOISE_CLASSIFICATION: u8 = 7;
+
+/// Outlier test applied to every point in the source cloud.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum OutlierMethod {
+    /// Flags points whose mean distance to `k` neighbours exceeds the global
+    /// mean by more than `std_ratio` standard deviations.
+    Statistical {
+        #[serde(default = "default_outlier_k")]
+        k: usize,
+        #[serde(default = "default_std_ratio")]
+        std_ratio: f64,
+    },
+    /// Flags points with fewer than `min_neighbours` others within `radius`.
+    Radius {
+        #[serde(default = "default_outlier_radius")]
+        radius: f64,
+        #[serde(default = "default_min_neighbours")]
+        min_neighbours: usize,
+    },
+}
+
+fn default_outlier_k() -> usize {
+    8
+}
+
+fn default_std_ratio() -> f64 {
+    2.0
+}
+
+fn default_outlier_radius() -> f64 {
+    0.5
+}
+
+fn default_min_neighbours() -> usize {
+    4
+}
+
+/// Outlier filter configuration.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct OutlierSettings {
+    #[serde(flatten)]
+    pub method: OutlierMethod,
+    /// Reclassify flagged points to class 7 instead of dropping them.
+    #[serde(default)]
+    pub keep_noise: bool,
+}
+
+impl Default for OutlierSettings {
+    fn default() -> Self {
+        Self {
+            method: OutlierMethod::Statistical {
+                k: default_outlier_k(),
+                std_ratio: default_std_ratio(),
+            },
+            keep_noise: false,
+        }
+    }
+}
+
+/// Per-point noise flags indexed by position in the source file.
+pub struct NoiseMask {
+    pub flags: Vec<bool>,
+    pub noise_count: usize,
+}
+
+impl NoiseMask {
+    /// Check whether the point at the given file index was flagged as noise.
+    pub fn is_noise(&self, point_idx: usize) -> bool {
+        self.flags.get(point_idx).copied().unwrap_or(false)
+    }
+}
+
+/// Voxel size used for statistical neighbour searches in world units.
+const STATISTICAL_SEARCH_CELL_SIZE: f64 = 0.5;
+
+/// Maximum shells visited per k-nearest query.
+const MAX_SEARCH_RINGS: i64 = 6;
+
+/// Load every point of the cloud and flag outliers with the configured method.
+pub fn detect_outliers(
+    file_path: &Path,
+    settings: &OutlierSettings,
+    reporter: &Reporter,
+) -> Result<NoiseMask, PreprocessError> {
+    let mut reader = create_reader(file_path)?;
+    let total_points = reader.header().number_of_points() as usize;
+
+    let pb = reporter.progress_bar(
+        "outlier_detection",
+        total_points as u64,
+        ProgressStyle::default_bar()
+            .template("[{bar:40.cyan/blue}] {pos}/{len} points ({percent}%) {msg}")
+            .unwrap()
+            .progress_chars("▉▊▋▌▍▎▏ "),
+    );
+    pb.set_message("Loading points for outlier detection");
+
+    let mut positions = Vec::with_capacity(total_points);
+    for (idx, point_result) in reader.points().enumerate() {
+        let point = point_result?;
+        positions.push(transform_coordinates(point.x, point.y, point.z));
+
+        if idx % 50_000 == 0 {
+            pb.set_position(idx as u64);
+        }
+    }
+    pb.finish_with_message("Points loaded");
+
+    let flags = match settings.method {
+        OutlierMethod::Statistical { k, std_ratio } => {
+            statistical_outliers(&positions, k.max(1), std_ratio, reporter)
+        }
+        OutlierMethod::Radius {
+            radius,
+            min_neighbours,
+        } => radius_outliers(&positions, radius, min_neighbours, reporter),
+    };
+
+    let noise_count = flags.iter().filter(|&&flag| flag).count();
+    Ok(NoiseMask { flags, noise_count })
+}
+
+/// Mean k-neighbour distance test against the global distance distribution.
+fn statistical_outliers(
+    positions: &[(f64, f64, f64)],
+    k: usize,
+    std_ratio: f64,
+    reporter: &Reporter,
+) -> Vec<bool> {
+    let hash = SpatialHash::new(positions, STATISTICAL_SEARCH_CELL_SIZE);
+    let pb = outlier_progress_bar(reporter, positions.len(), "Measuring neighbour distances");
+
+    // Query k + 1 neighbours since each point finds itself first.
+    let mean_distances: Vec<f64> = positions
+        .par_iter()
+        .enumerate()
+        .map_init(Vec::new, |neighbours, (i, &pos)| {
+            hash.nearest_k(pos, k + 1, MAX_SEARCH_RINGS, neighbours);
+
+            if i % 50_000 == 0 {
+                pb.set_position(i as u64);
+            }
+
+            let (sum, count) = neighbours
+                .iter()
+                .filter(|&&idx| idx != i)
+                .fold((0.0, 0usize), |(sum, count), &idx| {
+                    (sum + distance_sq(positions[idx], pos).sqrt(), count + 1)
+                });
+
+            if count == 0 {
+                f64::INFINITY
+            } else {
+                sum / count as f64
+            }
+        })
+        .collect();
+
+    pb.finish_with_message("Neighbour distances measured");
+
+    // Isolated points have no neighbours within the search window and are always noise.
+    let finite: Vec<f64> = mean_distances
+        .iter()
+        .copied()
+        .filter(|d| d.is_finite())
+        .collect();
+    if finite.is_empty() {
+        return vec![false; positions.len()];
+    }
+
+    let mean = finite.iter().sum::<f64>() / finite.len() as f64;
+    let variance =
+        finite.iter().map(|d| (d - mean) * (d - mean)).sum::<f64>() / finite.len() as f64;
+    let threshold = mean + std_ratio * variance.sqrt();
+
+    log_info!(
+        reporter,
+        "  Mean neighbour distance {:.3} (threshold {:.3})",
+        mean,
+        threshold
+    );
+
+    mean_distances.iter().map(|&d| d > threshold).collect()
+}
+
+/// Fixed-radius neighbour count test.
+fn radius_outliers(
+    positions: &[(f64, f64, f64)],
+    radius: f64,
+    min_neighbours: usize,
+    reporter: &Reporter,
+) -> Vec<bool> {
+    let hash = SpatialHash::new(positions, radius);
+    let pb = outlier_progress_bar(reporter, positions.len(), "Counting radius neighbours");
+
+    let flags = positions
+        .par_iter()
+        .enumerate()
+        .map_init(Vec::new, |neighbours, (i, &pos)| {
+            hash.within_radius(pos, radius, neighbours);
+
+            if i % 50_000 == 0 {
+                pb.set_position(i as u64);
+            }
+
+            // Exclude the point itself from its neighbour count.
+            neighbours.len().saturating_sub(1) < min_neighbours
+        })
+        .collect();
+
+    pb.finish_with_message("Radius neighbours counted");
+    flags
+}
+
+fn outlier_progress_bar(reporter: &Reporter, len: usize, message: &'static str) -> Progress {
+    let pb = reporter.progress_bar(
+        "outlier_detection",
+        len as u64,
+        ProgressStyle::default_bar()
+            .template("[{bar:40.red/blue}] {pos}/{len} points ({percent}%) {msg}")
+            .unwrap()
+            .progress_chars("▉▊▋▌▍▎▏ "),
+    );
+    pb.set_message(message);
+    pb
+}
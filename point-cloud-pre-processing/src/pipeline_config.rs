@@ -0,0 +1,594 @@
+/// Declarative pipeline configuration describing every processing stage.
+use crate::change_detection::{ChangeMethod, ChangeSettings};
+use crate::error::PreprocessError;
+use crate::kerbs::KerbSettings;
+use crate::normals::NormalSettings;
+use crate::outliers::{OutlierMethod, OutlierSettings};
+use crate::segmentation::SegmentationSettings;
+use crate::sidewalk_width::SidewalkWidthSettings;
+use constants::texture::{
+    COLOUR_DETECTION_SAMPLE_SIZE, HEIGHTMAP_BLEND_RADIUS, HEIGHTMAP_GAUSSIAN_SIGMA,
+    SPATIAL_GRID_RESOLUTION,
+};
+use serde::de::{self, DeserializeOwned, Deserializer};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Complete preprocessing pipeline loaded from TOML or JSON.
+/// Stages run in declaration order: inputs, filters, sampling, heightmap,
+/// derivatives, analysis and outputs. A copy is embedded in the generated manifest.
+///
+/// ```toml
+/// [input]
+/// main_cloud = "assets/riga_numbered_0.05.laz"
+/// asset_library = "assets/placeable_assets"
+///
+/// [filters.outliers]
+/// enabled = true
+/// method = "statistical"
+/// k = 8
+/// std_ratio = 2.0
+///
+/// [heightmap]
+/// gaussian_sigma = 3.0
+///
+/// [derivatives.normals]
+/// k_neighbours = 16
+/// compute_curvature = true
+///
+/// [derivatives.change]
+/// reference = "assets/riga_2024_spring.laz"
+/// method = "point_to_surface"
+/// threshold = 0.1
+///
+/// [analysis.sidewalk_width]
+/// minimum_width = 1.5
+///
+/// [analysis.kerbs]
+/// ramp_height = 0.04
+///
+/// [output]
+/// directory = "assets/output"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PipelineConfig {
+    pub input: InputConfig,
+    #[serde(default)]
+    pub filters: FilterConfig,
+    #[serde(default)]
+    pub sampling: SamplingConfig,
+    #[serde(default)]
+    pub heightmap: HeightmapConfig,
+    #[serde(default)]
+    pub derivatives: DerivativesConfig,
+    #[serde(default)]
+    pub analysis: AnalysisConfig,
+    #[serde(default)]
+    pub output: OutputConfig,
+}
+
+/// Source point clouds for the run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InputConfig {
+    /// Primary terrain LAS/LAZ file.
+    pub main_cloud: PathBuf,
+    /// Optional directory of placeable asset LAS/LAZ files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_library: Option<PathBuf>,
+}
+
+/// Point filters applied before bounds calculation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FilterConfig {
+    #[serde(default = "Stage::disabled")]
+    pub outliers: Stage<OutlierSettings>,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self {
+            outliers: Stage::disabled(),
+        }
+    }
+}
+
+/// Sampling and spatial layout parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SamplingConfig {
+    /// Points inspected when detecting RGB availability.
+    pub colour_detection_sample_size: usize,
+    /// Z-order grid resolution per axis for Morton codes and cell IDs.
+    pub grid_resolution: u32,
+}
+
+impl Default for SamplingConfig {
+    fn default() -> Self {
+        Self {
+            colour_detection_sample_size: COLOUR_DETECTION_SAMPLE_SIZE,
+            grid_resolution: SPATIAL_GRID_RESOLUTION,
+        }
+    }
+}
+
+/// Road surface heightmap generation parameters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HeightmapConfig {
+    /// Gaussian smoothing sigma in pixels.
+    pub gaussian_sigma: f32,
+    /// Maximum flood-fill distance in pixels from the nearest road sample.
+    pub blend_radius: f32,
+}
+
+impl Default for HeightmapConfig {
+    fn default() -> Self {
+        Self {
+            gaussian_sigma: HEIGHTMAP_GAUSSIAN_SIGMA,
+            blend_radius: HEIGHTMAP_BLEND_RADIUS,
+        }
+    }
+}
+
+/// Per-point attributes derived from the sampled cloud.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DerivativesConfig {
+    #[serde(default = "Stage::disabled")]
+    pub segmentation: Stage<SegmentationSettings>,
+    #[serde(default = "Stage::enabled")]
+    pub normals: Stage<NormalSettings>,
+    #[serde(default = "Stage::disabled")]
+    pub change: Stage<ChangeSettings>,
+}
+
+impl Default for DerivativesConfig {
+    fn default() -> Self {
+        Self {
+            segmentation: Stage::disabled(),
+            normals: Stage::enabled(),
+            change: Stage::disabled(),
+        }
+    }
+}
+
+/// Street-scape measurements derived from the classified cloud.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnalysisConfig {
+    #[serde(default = "Stage::disabled")]
+    pub sidewalk_width: Stage<SidewalkWidthSettings>,
+    #[serde(default = "Stage::disabled")]
+    pub kerbs: Stage<KerbSettings>,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        Self {
+            sidewalk_width: Stage::disabled(),
+            kerbs: Stage::disabled(),
+        }
+    }
+}
+
+/// Output location for generated textures and manifest.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OutputConfig {
+    /// Output directory, defaulting to the main cloud's directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directory: Option<PathBuf>,
+}
+
+/// Optional pipeline stage with an enable switch alongside its parameters.
+/// Unknown keys in a stage table are rejected like everywhere else in the config.
+#[derive(Debug, Clone, Serialize)]
+pub struct Stage<T> {
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub settings: T,
+}
+
+impl<T: Default> Stage<T> {
+    pub fn enabled() -> Self {
+        Self {
+            enabled: true,
+            settings: T::default(),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            settings: T::default(),
+        }
+    }
+}
+
+impl<T> Stage<T> {
+    /// Settings for the stage when it is enabled.
+    pub fn active(&self) -> Option<&T> {
+        self.enabled.then_some(&self.settings)
+    }
+}
+
+// A stage table present in the file is switched on unless it says otherwise.
+fn enabled_by_default() -> bool {
+    true
+}
+
+// `deny_unknown_fields` has no effect on flattened settings, so the table is
+// parsed as-is and any key the settings don't write back out is unknown.
+impl<'de, T> Deserialize<'de> for Stage<T>
+where
+    T: DeserializeOwned + Serialize,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut table = Map::<String, Value>::deserialize(deserializer)?;
+        let enabled = match table.remove("enabled") {
+            Some(value) => bool::deserialize(value).map_err(de::Error::custom)?,
+            None => enabled_by_default(),
+        };
+
+        let settings = T::deserialize(Value::Object(table.clone())).map_err(de::Error::custom)?;
+        let known = match serde_json::to_value(&settings).map_err(de::Error::custom)? {
+            Value::Object(fields) => fields,
+            _ => Map::new(),
+        };
+
+        if let Some(unknown) = table.keys().find(|key| !known.contains_key(*key)) {
+            let expected: Vec<String> = std::iter::once("enabled")
+                .chain(known.keys().map(String::as_str))
+                .map(|key| format!("`{}`", key))
+                .collect();
+            return Err(de::Error::custom(format!(
+                "unknown field `{}`, expected one of {}",
+                unknown,
+                expected.join(", ")
+            )));
+        }
+
+        Ok(Self { enabled, settings })
+    }
+}
+
+impl PipelineConfig {
+    /// Build a configuration with default stages for the given inputs.
+    pub fn new(main_cloud: &Path, asset_library: Option<&Path>, output_dir: Option<&Path>) -> Self {
+        Self {
+            input: InputConfig {
+                main_cloud: main_cloud.to_path_buf(),
+                asset_library: asset_library.map(Path::to_path_buf),
+            },
+            filters: FilterConfig::default(),
+            sampling: SamplingConfig::default(),
+            heightmap: HeightmapConfig::default(),
+            derivatives: DerivativesConfig::default(),
+            analysis: AnalysisConfig::default(),
+            output: OutputConfig {
+                directory: output_dir.map(Path::to_path_buf),
+            },
+        }
+    }
+
+    /// Load configuration from a `.toml` or `.json` file.
+    pub fn load(path: &Path) -> Result<Self, PreprocessError> {
+        let invalid = |e: &dyn std::fmt::Display| {
+            PreprocessError::Config(format!(
+                "Invalid pipeline config '{}': {}",
+                path.display(),
+                e
+            ))
+        };
+
+        let contents = fs::read_to_string(path).map_err(|e| {
+            PreprocessError::Config(format!(
+                "Failed to read pipeline config '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        let config = match extension.as_str() {
+            "toml" => toml::from_str(&contents).map_err(|e| invalid(&e))?,
+            "json" => serde_json::from_str(&contents).map_err(|e| invalid(&e))?,
+            _ => {
+                return Err(PreprocessError::Config(format!(
+                    "Pipeline config '{}' must have a .toml or .json extension",
+                    path.display()
+                )));
+            }
+        };
+
+        Ok(config)
+    }
+
+    /// Check inputs and stage parameters before any processing starts.
+    /// Reports every problem found rather than stopping at the first.
+    pub fn validate(&self) -> Result<(), PreprocessError> {
+        let mut problems = Vec::new();
+
+        if !self.input.main_cloud.is_file() {
+            problems.push(format!(
+                "input.main_cloud '{}' does not exist",
+                self.input.main_cloud.display()
+            ));
+        }
+
+        if let Some(asset_dir) = &self.input.asset_library
+            && !asset_dir.is_dir()
+        {
+            problems.push(format!(
+                "input.asset_library '{}' is not a directory",
+                asset_dir.display()
+            ));
+        }
+
+        if let Some(outliers) = self.filters.outliers.active() {
+            match outliers.method {
+                OutlierMethod::Statistical { k, std_ratio } => {
+                    if k == 0 {
+                        problems.push("filters.outliers.k must be at least 1".to_string());
+                    }
+                    if std_ratio <= 0.0 {
+                        problems.push(
+                            "filters.outliers.std_ratio must be greater than zero".to_string(),
+                        );
+                    }
+                }
+                OutlierMethod::Radius { radius, .. } => {
+                    if radius <= 0.0 {
+                        problems
+                            .push("filters.outliers.radius must be greater than zero".to_string());
+                    }
+                }
+            }
+        }
+
+        if self.sampling.colour_detection_sample_size == 0 {
+            problems.push("sampling.colour_detection_sample_size must be at least 1".to_string());
+        }
+
+        // Morton codes interleave 16 bits per axis into a u32.
+        let grid = self.sampling.grid_resolution;
+        if !grid.is_power_of_two() || !(2..=65_536).contains(&grid) {
+            problems.push(format!(
+                "sampling.grid_resolution must be a power of two between 2 and 65536 (got {})",
+                grid
+            ));
+        }
+
+        if self.heightmap.gaussian_sigma <= 0.0 {
+            problems.push("heightmap.gaussian_sigma must be greater than zero".to_string());
+        }
+
+        if self.heightmap.blend_radius <= 0.0 {
+            problems.push("heightmap.blend_radius must be greater than zero".to_string());
+        }
+
+        if let Some(segmentation) = self.derivatives.segmentation.active()
+            && segmentation.cluster_distance <= 0.0
+        {
+            problems.push(
+                "derivatives.segmentation.cluster_distance must be greater than zero".to_string(),
+            );
+        }
+
+        if let Some(normals) = self.derivatives.normals.active() {
+            if normals.k_neighbours < 3 {
+                problems.push("derivatives.normals.k_neighbours must be at least 3".to_string());
+            }
+            if normals.search_cell_size <= 0.0 {
+                problems.push(
+                    "derivatives.normals.search_cell_size must be greater than zero".to_string(),
+                );
+            }
+        }
+
+        if let Some(change) = self.derivatives.change.active() {
+            if !change.reference.is_file() {
+                problems.push(format!(
+                    "derivatives.change.reference '{}' does not exist",
+                    change.reference.display()
+                ));
+            }
+            for (name, value) in [
+                ("threshold", change.threshold),
+                ("search_cell_size", change.search_cell_size),
+            ] {
+                if value <= 0.0 {
+                    problems.push(format!(
+                        "derivatives.change.{} must be greater than zero",
+                        name
+                    ));
+                }
+            }
+            if change.max_distance < change.threshold {
+                problems.push(
+                    "derivatives.change.max_distance must not be less than threshold".to_string(),
+                );
+            }
+            if change.method == ChangeMethod::PointToSurface && change.k_neighbours < 3 {
+                problems.push(
+                    "derivatives.change.k_neighbours must be at least 3 for point_to_surface"
+                        .to_string(),
+                );
+            }
+        }
+
+        if let Some(sidewalk) = self.analysis.sidewalk_width.active() {
+            if sidewalk.sidewalk_classes.is_empty() {
+                problems.push(
+                    "analysis.sidewalk_width.sidewalk_classes must list at least one class"
+                        .to_string(),
+                );
+            }
+            for (name, value) in [
+                ("cell_size", sidewalk.cell_size),
+                ("station_spacing", sidewalk.station_spacing),
+                ("clearance_height", sidewalk.clearance_height),
+            ] {
+                if value <= 0.0 {
+                    problems.push(format!(
+                        "analysis.sidewalk_width.{} must be greater than zero",
+                        name
+                    ));
+                }
+            }
+            if sidewalk.minimum_width < 0.0 || sidewalk.min_branch_length < 0.0 {
+                problems.push(
+                    "analysis.sidewalk_width.minimum_width and min_branch_length must not be negative"
+                        .to_string(),
+                );
+            }
+        }
+
+        if let Some(kerbs) = self.analysis.kerbs.active() {
+            if kerbs.road_classes.is_empty() || kerbs.sidewalk_classes.is_empty() {
+                problems.push(
+                    "analysis.kerbs.road_classes and sidewalk_classes must each list a class"
+                        .to_string(),
+                );
+            }
+            for (name, value) in [
+                ("cell_size", kerbs.cell_size),
+                ("vertex_spacing", kerbs.vertex_spacing),
+                ("sample_offset", kerbs.sample_offset),
+            ] {
+                if value <= 0.0 {
+                    problems.push(format!("analysis.kerbs.{} must be greater than zero", name));
+                }
+            }
+            if kerbs.min_length < 0.0 {
+                problems.push("analysis.kerbs.min_length must not be negative".to_string());
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(PreprocessError::Config(format!(
+                "Invalid pipeline config:\n  - {}",
+                problems.join("\n  - ")
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Existing file standing in for the input cloud, so only stage checks fail.
+    const MAIN_CLOUD: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+
+    fn parse(stages: &str) -> Result<PipelineConfig, toml::de::Error> {
+        toml::from_str(&format!(
+            "[input]\nmain_cloud = {:?}\n\n{}",
+            MAIN_CLOUD, stages
+        ))
+    }
+
+    fn problems(config: &PipelineConfig) -> String {
+        match config.validate() {
+            Err(PreprocessError::Config(message)) => message,
+            other => panic!("expected config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn stage_settings_parse_alongside_enable_switch() {
+        let config = parse(
+            "[filters.outliers]\nmethod = \"radius\"\nradius = 0.3\nkeep_noise = true\n\n\
+             [derivatives.normals]\nenabled = false\nk_neighbours = 24\n",
+        )
+        .unwrap();
+
+        let outliers = config.filters.outliers.active().unwrap();
+        assert!(outliers.keep_noise);
+        assert!(matches!(
+            outliers.method,
+            OutlierMethod::Radius { radius, min_neighbours: 4 } if radius == 0.3
+        ));
+        assert!(config.derivatives.normals.active().is_none());
+        assert_eq!(config.derivatives.normals.settings.k_neighbours, 24);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn unknown_stage_keys_are_rejected() {
+        for (stages, key) in [
+            (
+                "[filters.outliers]\nmethod = \"statistical\"\nstd_ration = 2.0\n",
+                "std_ration",
+            ),
+            ("[derivatives.normals]\nk_neighbors = 16\n", "k_neighbors"),
+            ("[analysis.kerbs]\nramp_hieght = 0.04\n", "ramp_hieght"),
+            // Statistical parameters don't apply to the radius method.
+            ("[filters.outliers]\nmethod = \"radius\"\nk = 8\n", "`k`"),
+        ] {
+            let error = parse(stages).unwrap_err().to_string();
+            assert!(error.contains(key), "{} not reported in: {}", key, error);
+        }
+
+        let json = format!(
+            r#"{{"input": {{"main_cloud": {:?}}}, "derivatives": {{"normals": {{"k_neighbors": 16}}}}}}"#,
+            MAIN_CLOUD
+        );
+        let error = serde_json::from_str::<PipelineConfig>(&json).unwrap_err();
+        assert!(error.to_string().contains("k_neighbors"));
+    }
+
+    #[test]
+    fn unknown_top_level_keys_are_rejected() {
+        assert!(parse("[heightmap]\ngausian_sigma = 3.0\n").is_err());
+        assert!(parse("[derivatives.normal]\nk_neighbours = 16\n").is_err());
+    }
+
+    #[test]
+    fn validate_reports_every_out_of_range_value() {
+        let config = parse(
+            "[filters.outliers]\nmethod = \"statistical\"\nstd_ratio = 0.0\n\n\
+             [sampling]\ngrid_resolution = 1000\n\n\
+             [heightmap]\nblend_radius = -1.0\n\n\
+             [derivatives.normals]\nk_neighbours = 2\n",
+        )
+        .unwrap();
+
+        let message = problems(&config);
+        for expected in [
+            "filters.outliers.std_ratio",
+            "sampling.grid_resolution",
+            "heightmap.blend_radius",
+            "derivatives.normals.k_neighbours",
+        ] {
+            assert!(message.contains(expected), "missing {}", expected);
+        }
+    }
+
+    #[test]
+    fn disabled_stages_are_not_validated() {
+        let config = parse("[derivatives.normals]\nenabled = false\nk_neighbours = 0\n").unwrap();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn missing_inputs_fail_validation() {
+        let mut config = PipelineConfig::new(Path::new("missing.laz"), None, None);
+        config.derivatives.change = Stage::enabled();
+        let message = problems(&config);
+        assert!(message.contains("input.main_cloud"));
+        assert!(message.contains("derivatives.change.reference"));
+    }
+}
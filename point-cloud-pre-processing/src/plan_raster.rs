@@ -0,0 +1,345 @@
+/// Plan-view raster helpers shared by the ground analysis stages.
+use std::collections::HashSet;
+
+/// Upper bound on raster cells; larger scenes use a coarser cell size.
+const MAX_GRID_CELLS: usize = 64 * 1024 * 1024;
+
+/// Largest gap-closing radius in cells.
+const MAX_CLOSING_RADIUS: usize = 8;
+
+/// Empty cells around the raster extent.
+const GRID_MARGIN: usize = MAX_CLOSING_RADIUS + 1;
+
+/// Plan-view (world X, Z) cell layout with an empty border wide enough that
+/// gap closing never reaches the edge.
+pub(crate) struct PlanExtent {
+    pub(crate) origin: (f64, f64),
+    pub(crate) cell_size: f64,
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+}
+
+impl PlanExtent {
+    /// Extent covering the given plan positions. Very large scenes get a
+    /// coarser cell size rather than exhausting memory.
+    pub(crate) fn around(positions: &[(f64, f64)], cell_size: f64) -> Option<Self> {
+        if positions.is_empty() {
+            return None;
+        }
+
+        let (mut min_x, mut min_z) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_z) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for &(x, z) in positions {
+            min_x = min_x.min(x);
+            max_x = max_x.max(x);
+            min_z = min_z.min(z);
+            max_z = max_z.max(z);
+        }
+
+        let cells_along = |extent: f64, size: f64| (extent / size) as usize + 1 + 2 * GRID_MARGIN;
+        let mut cell_size = cell_size;
+        while cells_along(max_x - min_x, cell_size) * cells_along(max_z - min_z, cell_size)
+            > MAX_GRID_CELLS
+        {
+            cell_size *= 2.0;
+        }
+
+        let margin = GRID_MARGIN as f64 * cell_size;
+        Some(Self {
+            origin: (min_x - margin, min_z - margin),
+            cell_size,
+            width: cells_along(max_x - min_x, cell_size),
+            height: cells_along(max_z - min_z, cell_size),
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    pub(crate) fn cell_index(&self, x: f64, z: f64) -> Option<usize> {
+        let cx = ((x - self.origin.0) / self.cell_size).floor();
+        let cz = ((z - self.origin.1) / self.cell_size).floor();
+        if cx < 0.0 || cz < 0.0 || cx >= self.width as f64 || cz >= self.height as f64 {
+            return None;
+        }
+        Some(cz as usize * self.width + cx as usize)
+    }
+
+    pub(crate) fn cell_centre(&self, idx: usize) -> (f64, f64) {
+        let (cx, cz) = (idx % self.width, idx / self.width);
+        (
+            self.origin.0 + (cx as f64 + 0.5) * self.cell_size,
+            self.origin.1 + (cz as f64 + 0.5) * self.cell_size,
+        )
+    }
+
+    /// Close gaps between sampled points in `mask`. Spacing is estimated from
+    /// the area covered by one metre cells, so sparse sampling closes more.
+    pub(crate) fn close_gaps(&self, mask: &[bool], positions: &[(f64, f64)]) -> Vec<bool> {
+        let covered: HashSet<(i64, i64)> = positions
+            .iter()
+            .map(|&(x, z)| (x.floor() as i64, z.floor() as i64))
+            .collect();
+        let spacing = (covered.len() as f64 / positions.len().max(1) as f64).sqrt();
+        let radius = (spacing / self.cell_size)
+            .ceil()
+            .clamp(1.0, MAX_CLOSING_RADIUS as f64) as usize;
+
+        let mut closed = mask.to_vec();
+        for _ in 0..radius {
+            closed = dilate(&closed, self.width, self.height);
+        }
+        for _ in 0..radius {
+            closed = erode(&closed, self.width, self.height);
+        }
+        closed
+    }
+}
+
+pub(crate) fn polyline_length(line: &[(f64, f64)]) -> f64 {
+    line.windows(2)
+        .map(|w| ((w[1].0 - w[0].0).powi(2) + (w[1].1 - w[0].1).powi(2)).sqrt())
+        .sum()
+}
+
+/// Interpolated position at `distance` along the polyline.
+pub(crate) fn point_at(line: &[(f64, f64)], distance: f64) -> (f64, f64) {
+    let mut remaining = distance;
+    for w in line.windows(2) {
+        let segment = ((w[1].0 - w[0].0).powi(2) + (w[1].1 - w[0].1).powi(2)).sqrt();
+        if remaining <= segment && segment > 0.0 {
+            let t = remaining / segment;
+            return (
+                w[0].0 + (w[1].0 - w[0].0) * t,
+                w[0].1 + (w[1].1 - w[0].1) * t,
+            );
+        }
+        remaining -= segment;
+    }
+    *line.last().unwrap()
+}
+
+/// Convert world plan coordinates (X, Z) back to source LAS (X, Y).
+pub(crate) fn source_xy(position: (f64, f64)) -> [f64; 2] {
+    [position.0, -position.1]
+}
+
+/// Round metres to centimetres for output.
+pub(crate) fn round_cm(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+pub(crate) fn neighbours_8(idx: usize, width: usize) -> [usize; 8] {
+    [
+        idx - width - 1,
+        idx - width,
+        idx - width + 1,
+        idx - 1,
+        idx + 1,
+        idx + width - 1,
+        idx + width,
+        idx + width + 1,
+    ]
+}
+
+pub(crate) fn dilate(mask: &[bool], width: usize, height: usize) -> Vec<bool> {
+    let mut out = mask.to_vec();
+    for z in 1..height - 1 {
+        for x in 1..width - 1 {
+            let idx = z * width + x;
+            if !mask[idx] && neighbours_8(idx, width).iter().any(|&n| mask[n]) {
+                out[idx] = true;
+            }
+        }
+    }
+    out
+}
+
+fn erode(mask: &[bool], width: usize, height: usize) -> Vec<bool> {
+    let mut out = mask.to_vec();
+    for z in 0..height {
+        for x in 0..width {
+            let idx = z * width + x;
+            let border = x == 0 || z == 0 || x == width - 1 || z == height - 1;
+            if mask[idx] && (border || neighbours_8(idx, width).iter().any(|&n| !mask[n])) {
+                out[idx] = false;
+            }
+        }
+    }
+    out
+}
+
+/// Zhang-Suen thinning down to a one-cell-wide skeleton.
+/// Only cells still set are revisited, so cost scales with the masked area.
+pub(crate) fn thin(mask: &mut [bool], width: usize, height: usize) {
+    let mut active: Vec<usize> = (0..mask.len())
+        .filter(|&idx| {
+            let (x, z) = (idx % width, idx / width);
+            mask[idx] && x > 0 && z > 0 && x < width - 1 && z < height - 1
+        })
+        .collect();
+
+    loop {
+        let mut changed = false;
+
+        for pass in 0..2 {
+            let removable: Vec<usize> = active
+                .iter()
+                .copied()
+                .filter(|&idx| {
+                    // P2..P9 clockwise from north.
+                    let p = [
+                        mask[idx - width],
+                        mask[idx - width + 1],
+                        mask[idx + 1],
+                        mask[idx + width + 1],
+                        mask[idx + width],
+                        mask[idx + width - 1],
+                        mask[idx - 1],
+                        mask[idx - width - 1],
+                    ];
+                    let count = p.iter().filter(|&&v| v).count();
+                    let transitions = (0..8).filter(|&i| !p[i] && p[(i + 1) % 8]).count();
+                    if !(2..=6).contains(&count) || transitions != 1 {
+                        return false;
+                    }
+
+                    let (n, e, s, w) = (p[0], p[2], p[4], p[6]);
+                    // First pass removes south-east edges, second north-west.
+                    if pass == 0 {
+                        !(e && s && (n || w))
+                    } else {
+                        !(n && w && (e || s))
+                    }
+                })
+                .collect();
+
+            changed |= !removable.is_empty();
+            for idx in removable {
+                mask[idx] = false;
+            }
+            active.retain(|&idx| mask[idx]);
+        }
+
+        if !changed {
+            break;
+        }
+    }
+}
+
+/// Skeleton path between two graph nodes (ends or junctions).
+pub(crate) struct Trace {
+    pub(crate) cells: Vec<usize>,
+    /// Whether the first cell is a dead end rather than a junction.
+    pub(crate) open_start: bool,
+    /// Whether the last cell is a dead end rather than a junction.
+    pub(crate) open_end: bool,
+}
+
+/// Skeleton neighbours, preferring orthogonal steps so staircases do not
+/// register as junctions.
+fn skeleton_neighbours(skeleton: &[bool], idx: usize, width: usize) -> Vec<usize> {
+    let orthogonal = [idx - width, idx + 1, idx + width, idx - 1];
+    let mut out: Vec<usize> = orthogonal.into_iter().filter(|&n| skeleton[n]).collect();
+
+    let diagonals = [
+        (idx - width + 1, idx - width, idx + 1),
+        (idx + width + 1, idx + width, idx + 1),
+        (idx + width - 1, idx + width, idx - 1),
+        (idx - width - 1, idx - width, idx - 1),
+    ];
+    for (diagonal, a, b) in diagonals {
+        if skeleton[diagonal] && !skeleton[a] && !skeleton[b] {
+            out.push(diagonal);
+        }
+    }
+    out
+}
+
+/// Remove dead-end branches shorter than `min_cells` and re-thin, so the
+/// remaining paths are not split at the junctions the spurs left behind.
+pub(crate) fn prune_spurs(skeleton: &mut [bool], width: usize, height: usize, min_cells: usize) {
+    for trace in trace_skeleton(skeleton, width) {
+        if trace.open_start == trace.open_end || trace.cells.len() > min_cells {
+            continue;
+        }
+        // Keep the junction cell the spur hangs off.
+        let spur = if trace.open_start {
+            &trace.cells[..trace.cells.len() - 1]
+        } else {
+            &trace.cells[1..]
+        };
+        for &idx in spur {
+            skeleton[idx] = false;
+        }
+    }
+    thin(skeleton, width, height);
+}
+
+/// Split the skeleton into paths between nodes, then pick up closed loops.
+pub(crate) fn trace_skeleton(skeleton: &[bool], width: usize) -> Vec<Trace> {
+    let cells: Vec<usize> = (0..skeleton.len()).filter(|&idx| skeleton[idx]).collect();
+    let degree = |idx: usize| skeleton_neighbours(skeleton, idx, width).len();
+
+    let mut visited = vec![false; skeleton.len()];
+    let mut node_links: HashSet<(usize, usize)> = HashSet::new();
+    let mut traces = Vec::new();
+
+    let follow = |start: usize, first: usize, visited: &mut Vec<bool>| -> Vec<usize> {
+        let mut path = vec![start, first];
+        let (mut prev, mut current) = (start, first);
+        while degree(current) == 2 && !visited[current] {
+            visited[current] = true;
+            let Some(next) = skeleton_neighbours(skeleton, current, width)
+                .into_iter()
+                .find(|&n| n != prev)
+            else {
+                break;
+            };
+            path.push(next);
+            prev = current;
+            current = next;
+        }
+        path
+    };
+
+    for &node in cells.iter().filter(|&&idx| degree(idx) != 2) {
+        for next in skeleton_neighbours(skeleton, node, width) {
+            if degree(next) != 2 {
+                let link = (node.min(next), node.max(next));
+                if !node_links.insert(link) {
+                    continue;
+                }
+            } else if visited[next] {
+                continue;
+            }
+
+            let path = follow(node, next, &mut visited);
+            let end = *path.last().unwrap();
+            traces.push(Trace {
+                open_start: degree(node) <= 1,
+                open_end: degree(end) <= 1,
+                cells: path,
+            });
+        }
+    }
+
+    // Remaining unvisited cells belong to loops without junctions.
+    for &start in &cells {
+        if visited[start] || degree(start) != 2 {
+            continue;
+        }
+        visited[start] = true;
+        let first = skeleton_neighbours(skeleton, start, width)[0];
+        let mut path = follow(start, first, &mut visited);
+        path.push(start);
+        traces.push(Trace {
+            cells: path,
+            open_start: false,
+            open_end: false,
+        });
+    }
+
+    traces
+}
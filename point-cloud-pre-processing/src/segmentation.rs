@@ -0,0 +1,146 @@
+/// Per-class Euclidean clustering for automatic object ID assignment.
+use crate::logging::Reporter;
+use crate::spatial_hash::SpatialHash;
+use crate::spatial_layout::SpatialPoint;
+use indicatif::ProgressStyle;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+
+/// Clustering parameters for instance segmentation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SegmentationSettings {
+    /// Maximum distance in world units between neighbouring points of one object.
+    pub cluster_distance: f64,
+    /// Clusters with fewer points are left as object 0.
+    pub min_cluster_size: usize,
+}
+
+impl Default for SegmentationSettings {
+    fn default() -> Self {
+        Self {
+            cluster_distance: 0.5,
+            min_cluster_size: 50,
+        }
+    }
+}
+
+/// Summary of a segmentation pass for logging.
+pub struct SegmentationResult {
+    pub objects_created: u32,
+    pub unassigned_points: usize,
+}
+
+/// Check whether any point carries a non-zero object number from extra bytes.
+pub fn has_object_ids(points: &[SpatialPoint]) -> bool {
+    points.iter().any(|p| p.object_number != 0.0)
+}
+
+/// Assign object numbers by connected components within each classification.
+/// Points of the same class closer than `cluster_distance` are linked; every
+/// component of at least `min_cluster_size` points receives a scene-unique ID
+/// starting at 1. IDs are global so hover highlighting by object ID alone
+/// never matches objects of another class.
+pub fn segment_instances(
+    points: &mut [SpatialPoint],
+    settings: &SegmentationSettings,
+    reporter: &Reporter,
+) -> SegmentationResult {
+    // Group point indices per class in ascending class order for deterministic IDs.
+    let mut class_groups: BTreeMap<u8, Vec<usize>> = BTreeMap::new();
+    for (idx, point) in points.iter().enumerate() {
+        class_groups
+            .entry(point.classification)
+            .or_default()
+            .push(idx);
+    }
+
+    let pb = reporter.progress_bar(
+        "segmentation",
+        class_groups.len() as u64,
+        ProgressStyle::default_bar()
+            .template("[{bar:40.magenta/blue}] {pos}/{len} classes ({percent}%) {msg}")
+            .unwrap()
+            .progress_chars("▉▊▋▌▍▎▏ "),
+    );
+    pb.set_message("Clustering instances");
+
+    let groups: Vec<(u8, Vec<usize>)> = class_groups.into_iter().collect();
+    let points_ref: &[SpatialPoint] = points;
+
+    // Cluster each class independently; output keeps class order.
+    let class_clusters: Vec<Vec<Vec<usize>>> = groups
+        .par_iter()
+        .map(|(_, indices)| {
+            let clusters = cluster_class(points_ref, indices, settings.cluster_distance);
+            pb.inc(1);
+            clusters
+        })
+        .collect();
+
+    pb.finish_with_message("Clustering complete");
+
+    let mut next_id = 1u32;
+    let mut unassigned_points = 0;
+
+    for clusters in class_clusters {
+        for cluster in clusters {
+            let object_number = if cluster.len() >= settings.min_cluster_size {
+                let id = next_id;
+                next_id += 1;
+                id as f32
+            } else {
+                unassigned_points += cluster.len();
+                0.0
+            };
+
+            for idx in cluster {
+                points[idx].object_number = object_number;
+            }
+        }
+    }
+
+    SegmentationResult {
+        objects_created: next_id - 1,
+        unassigned_points,
+    }
+}
+
+/// Breadth-first connected components over a single class.
+/// Returns clusters as global point indices, ordered by their first member.
+fn cluster_class(points: &[SpatialPoint], indices: &[usize], distance: f64) -> Vec<Vec<usize>> {
+    let positions: Vec<(f64, f64, f64)> = indices.iter().map(|&i| points[i].world_pos).collect();
+    let hash = SpatialHash::new(&positions, distance);
+
+    let mut visited = vec![false; positions.len()];
+    let mut clusters = Vec::new();
+    let mut queue = VecDeque::new();
+    let mut neighbours = Vec::new();
+
+    for seed in 0..positions.len() {
+        if visited[seed] {
+            continue;
+        }
+
+        visited[seed] = true;
+        queue.push_back(seed);
+        let mut cluster = Vec::new();
+
+        while let Some(current) = queue.pop_front() {
+            cluster.push(indices[current]);
+
+            hash.within_radius(positions[current], distance, &mut neighbours);
+            for &next in &neighbours {
+                if !visited[next] {
+                    visited[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        clusters.push(cluster);
+    }
+
+    clusters
+}
@@ -0,0 +1,369 @@
+/// Sidewalk centreline extraction and clear-path width sampling.
+use crate::plan_raster::{
+    PlanExtent, neighbours_8, point_at, polyline_length, round_cm, source_xy, thin, trace_skeleton,
+};
+use crate::spatial_layout::SpatialPoint;
+use constants::class::SIDEWALK_CLASSIFICATIONS;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+/// Width analysis parameters. Distances are in world units (metres).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SidewalkWidthSettings {
+    /// Classes forming the walkable surface.
+    pub sidewalk_classes: Vec<u8>,
+    /// Classes that narrow the clear path, e.g. street furniture and vegetation.
+    pub obstruction_classes: Vec<u8>,
+    /// Plan-view raster cell size.
+    pub cell_size: f64,
+    /// Distance between width stations along the centreline.
+    pub station_spacing: f64,
+    /// Clear widths below this are reported as narrow segments.
+    pub minimum_width: f64,
+    /// Obstruction points higher than this above the walking surface are ignored.
+    pub clearance_height: f64,
+    /// Centreline spurs shorter than this are discarded.
+    pub min_branch_length: f64,
+}
+
+impl Default for SidewalkWidthSettings {
+    fn default() -> Self {
+        Self {
+            sidewalk_classes: SIDEWALK_CLASSIFICATIONS.to_vec(),
+            obstruction_classes: vec![3, 4, 5, 8],
+            cell_size: 0.1,
+            station_spacing: 1.0,
+            minimum_width: 1.5,
+            clearance_height: 2.2,
+            min_branch_length: 2.0,
+        }
+    }
+}
+
+/// Consecutive centreline run whose clear width stays below the minimum.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NarrowSegment {
+    pub centreline_id: usize,
+    pub start_chainage_m: f64,
+    pub end_chainage_m: f64,
+    pub min_clear_width_m: f64,
+}
+
+/// Scene-wide totals for the width analysis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SidewalkWidthSummary {
+    pub minimum_width_m: f64,
+    pub centreline_count: usize,
+    pub centreline_length_m: f64,
+    pub station_count: usize,
+    pub below_minimum_length_m: f64,
+    pub narrow_segments: Vec<NarrowSegment>,
+}
+
+/// GeoJSON features and summary produced by [`analyse_sidewalk_width`].
+pub struct SidewalkWidthResult {
+    pub geojson: Value,
+    pub summary: SidewalkWidthSummary,
+}
+
+/// Furthest a cross-section is followed from the centreline.
+const MAX_CROSS_SECTION_REACH: f64 = 25.0;
+
+/// Extract sidewalk centrelines and sample clear widths at regular stations.
+/// Centrelines are the thinned skeleton of the rasterised sidewalk surface;
+/// widths are measured across the centreline, with the clear width being the
+/// longest unobstructed run of the cross-section. Output coordinates are in
+/// the source cloud's horizontal CRS (LAS X, Y).
+pub fn analyse_sidewalk_width(
+    points: &[SpatialPoint],
+    settings: &SidewalkWidthSettings,
+) -> Option<SidewalkWidthResult> {
+    let mut grid = PlanGrid::from_points(points, settings)?;
+    grid.mark_obstructions(points, settings);
+
+    let mut skeleton = grid.walkable.clone();
+    thin(&mut skeleton, grid.extent.width, grid.extent.height);
+
+    let centrelines: Vec<Vec<(f64, f64)>> = trace_skeleton(&skeleton, grid.extent.width)
+        .into_iter()
+        .filter_map(|trace| {
+            let mut line: Vec<(f64, f64)> = trace
+                .cells
+                .iter()
+                .map(|&idx| grid.extent.cell_centre(idx))
+                .collect();
+            let is_spur = trace.open_start || trace.open_end;
+            if line.len() < 2 || (is_spur && polyline_length(&line) < settings.min_branch_length) {
+                return None;
+            }
+
+            // Thinning stops half a width short of dead ends; run them out to the edge.
+            if trace.open_start {
+                line.reverse();
+                grid.extend_to_edge(&mut line);
+                line.reverse();
+            }
+            if trace.open_end {
+                grid.extend_to_edge(&mut line);
+            }
+            Some(line)
+        })
+        .collect();
+
+    let mut features = Vec::new();
+    let mut summary = SidewalkWidthSummary {
+        minimum_width_m: settings.minimum_width,
+        centreline_count: centrelines.len(),
+        centreline_length_m: 0.0,
+        station_count: 0,
+        below_minimum_length_m: 0.0,
+        narrow_segments: Vec::new(),
+    };
+
+    for (centreline_id, line) in centrelines.iter().enumerate() {
+        let stations = sample_stations(&grid, line, settings.station_spacing);
+        summary.centreline_length_m += polyline_length(line);
+        summary.station_count += stations.len();
+
+        let mut narrow: Option<NarrowSegment> = None;
+        for pair in stations.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            let clear_width = a.clear_width.min(b.clear_width);
+            let sidewalk_width = a.sidewalk_width.min(b.sidewalk_width);
+            let below_minimum = clear_width < settings.minimum_width;
+            let length = b.chainage - a.chainage;
+
+            features.push(json!({
+                "type": "Feature",
+                "geometry": {
+                    "type": "LineString",
+                    "coordinates": [source_xy(a.position), source_xy(b.position)],
+                },
+                "properties": {
+                    "centreline_id": centreline_id,
+                    "chainage_m": round_cm(a.chainage),
+                    "length_m": round_cm(length),
+                    "clear_width_m": round_cm(clear_width),
+                    "sidewalk_width_m": round_cm(sidewalk_width),
+                    "below_minimum": below_minimum,
+                },
+            }));
+
+            if below_minimum {
+                summary.below_minimum_length_m += length;
+                let segment = narrow.get_or_insert(NarrowSegment {
+                    centreline_id,
+                    start_chainage_m: a.chainage,
+                    end_chainage_m: b.chainage,
+                    min_clear_width_m: clear_width,
+                });
+                segment.end_chainage_m = b.chainage;
+                segment.min_clear_width_m = segment.min_clear_width_m.min(clear_width);
+            } else if let Some(segment) = narrow.take() {
+                summary.narrow_segments.push(segment);
+            }
+        }
+        summary.narrow_segments.extend(narrow);
+    }
+
+    summary.centreline_length_m = round_cm(summary.centreline_length_m);
+    summary.below_minimum_length_m = round_cm(summary.below_minimum_length_m);
+    for segment in &mut summary.narrow_segments {
+        segment.start_chainage_m = round_cm(segment.start_chainage_m);
+        segment.end_chainage_m = round_cm(segment.end_chainage_m);
+        segment.min_clear_width_m = round_cm(segment.min_clear_width_m);
+    }
+
+    let geojson = json!({
+        "type": "FeatureCollection",
+        "features": features,
+    });
+
+    Some(SidewalkWidthResult { geojson, summary })
+}
+
+/// Sidewalk surface raster with obstruction flags.
+struct PlanGrid {
+    extent: PlanExtent,
+    walkable: Vec<bool>,
+    blocked: Vec<bool>,
+    /// Mean sidewalk elevation per cell, NaN where no points fell.
+    ground: Vec<f32>,
+}
+
+impl PlanGrid {
+    fn from_points(points: &[SpatialPoint], settings: &SidewalkWidthSettings) -> Option<Self> {
+        let sidewalk: Vec<&SpatialPoint> = points
+            .iter()
+            .filter(|p| settings.sidewalk_classes.contains(&p.classification))
+            .collect();
+        let plan: Vec<(f64, f64)> = sidewalk
+            .iter()
+            .map(|p| (p.world_pos.0, p.world_pos.2))
+            .collect();
+        let extent = PlanExtent::around(&plan, settings.cell_size)?;
+
+        let mut sums = vec![(0.0f64, 0u32); extent.len()];
+        for p in &sidewalk {
+            if let Some(idx) = extent.cell_index(p.world_pos.0, p.world_pos.2) {
+                sums[idx].0 += p.world_pos.1;
+                sums[idx].1 += 1;
+            }
+        }
+
+        let mut walkable = vec![false; extent.len()];
+        let mut ground = vec![f32::NAN; extent.len()];
+        for (idx, &(sum, count)) in sums.iter().enumerate() {
+            if count > 0 {
+                walkable[idx] = true;
+                ground[idx] = (sum / count as f64) as f32;
+            }
+        }
+
+        Some(Self {
+            walkable: extent.close_gaps(&walkable, &plan),
+            blocked: vec![false; extent.len()],
+            ground,
+            extent,
+        })
+    }
+
+    /// Flag walkable cells containing obstruction points below the clearance height.
+    fn mark_obstructions(&mut self, points: &[SpatialPoint], settings: &SidewalkWidthSettings) {
+        for p in points
+            .iter()
+            .filter(|p| settings.obstruction_classes.contains(&p.classification))
+        {
+            let Some(idx) = self.extent.cell_index(p.world_pos.0, p.world_pos.2) else {
+                continue;
+            };
+            if !self.walkable[idx] {
+                continue;
+            }
+
+            // Without a local ground estimate the obstruction is assumed to block.
+            let blocks = self
+                .local_ground(idx)
+                .is_none_or(|ground| p.world_pos.1 - (ground as f64) < settings.clearance_height);
+            if blocks {
+                self.blocked[idx] = true;
+            }
+        }
+    }
+
+    fn local_ground(&self, idx: usize) -> Option<f32> {
+        if !self.ground[idx].is_nan() {
+            return Some(self.ground[idx]);
+        }
+        neighbours_8(idx, self.extent.width)
+            .into_iter()
+            .map(|n| self.ground[n])
+            .find(|g| !g.is_nan())
+    }
+
+    /// Walkable and blocked flags at a world position.
+    fn sample(&self, x: f64, z: f64) -> Option<bool> {
+        let idx = self.extent.cell_index(x, z)?;
+        self.walkable[idx].then_some(self.blocked[idx])
+    }
+
+    /// Continue the line's final direction until it leaves the walkable area.
+    fn extend_to_edge(&self, line: &mut Vec<(f64, f64)>) {
+        let length = polyline_length(line);
+        let end = *line.last().unwrap();
+        let back = point_at(line, (length - self.extent.cell_size * 10.0).max(0.0));
+        let (dx, dz) = (end.0 - back.0, end.1 - back.1);
+        let norm = (dx * dx + dz * dz).sqrt();
+        if norm <= f64::EPSILON {
+            return;
+        }
+
+        let step = self.extent.cell_size * 0.5;
+        let max_steps = (MAX_CROSS_SECTION_REACH / step) as usize;
+        let reach = (1..=max_steps)
+            .take_while(|&i| {
+                let s = i as f64 * step;
+                self.sample(end.0 + dx / norm * s, end.1 + dz / norm * s)
+                    .is_some()
+            })
+            .last();
+
+        if let Some(i) = reach {
+            let s = i as f64 * step;
+            line.push((end.0 + dx / norm * s, end.1 + dz / norm * s));
+        }
+    }
+
+    /// Total and longest clear extent across the sidewalk at `centre`.
+    fn cross_section(&self, centre: (f64, f64), normal: (f64, f64)) -> (f64, f64) {
+        let step = self.extent.cell_size * 0.5;
+        let max_steps = (MAX_CROSS_SECTION_REACH / step) as usize;
+
+        let walk = |sign: f64| {
+            (1..=max_steps)
+                .map(|i| {
+                    let s = sign * i as f64 * step;
+                    self.sample(centre.0 + normal.0 * s, centre.1 + normal.1 * s)
+                })
+                .take_while(Option::is_some)
+                .flatten()
+                .collect::<Vec<bool>>()
+        };
+
+        let mut samples = walk(-1.0);
+        samples.reverse();
+        samples.push(self.sample(centre.0, centre.1).unwrap_or(true));
+        samples.extend(walk(1.0));
+
+        let mut longest = 0usize;
+        let mut run = 0usize;
+        for &blocked in &samples {
+            run = if blocked { 0 } else { run + 1 };
+            longest = longest.max(run);
+        }
+
+        (samples.len() as f64 * step, longest as f64 * step)
+    }
+}
+
+/// Width measurement at one centreline station.
+struct Station {
+    position: (f64, f64),
+    chainage: f64,
+    sidewalk_width: f64,
+    clear_width: f64,
+}
+
+/// Place stations every `spacing` along the line, plus one at its end.
+fn sample_stations(grid: &PlanGrid, line: &[(f64, f64)], spacing: f64) -> Vec<Station> {
+    let length = polyline_length(line);
+    // Tangents span several cells so the pixel staircase does not skew them.
+    let half_window = spacing.max(grid.extent.cell_size * 10.0) * 0.5;
+
+    let mut chainages: Vec<f64> = (0..)
+        .map(|i| i as f64 * spacing)
+        .take_while(|&d| d < length)
+        .collect();
+    chainages.push(length);
+
+    chainages
+        .into_iter()
+        .map(|chainage| {
+            let position = point_at(line, chainage);
+            let ahead = point_at(line, (chainage + half_window).min(length));
+            let behind = point_at(line, (chainage - half_window).max(0.0));
+            let (tx, tz) = (ahead.0 - behind.0, ahead.1 - behind.1);
+            let norm = (tx * tx + tz * tz).sqrt().max(f64::EPSILON);
+            let normal = (-tz / norm, tx / norm);
+
+            let (sidewalk_width, clear_width) = grid.cross_section(position, normal);
+            Station {
+                position,
+                chainage,
+                sidewalk_width,
+                clear_width,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,112 @@
+/// Uniform voxel hash for fixed-radius neighbour queries over world positions.
+use std::collections::HashMap;
+
+/// Voxel hash over a borrowed set of world positions.
+/// Cells are cubes of `cell_size`, so a radius query only visits the cells
+/// overlapping the query sphere's bounding box.
+pub struct SpatialHash<'a> {
+    positions: &'a [(f64, f64, f64)],
+    cell_size: f64,
+    cells: HashMap<(i64, i64, i64), Vec<usize>>,
+}
+
+impl<'a> SpatialHash<'a> {
+    /// Build hash from positions with the given cell edge length in world units.
+    pub fn new(positions: &'a [(f64, f64, f64)], cell_size: f64) -> Self {
+        let cell_size = cell_size.max(f64::EPSILON);
+        let mut cells: HashMap<(i64, i64, i64), Vec<usize>> = HashMap::new();
+
+        for (idx, &pos) in positions.iter().enumerate() {
+            cells.entry(cell_key(pos, cell_size)).or_default().push(idx);
+        }
+
+        Self {
+            positions,
+            cell_size,
+            cells,
+        }
+    }
+
+    /// Collect indices of all positions within `radius` of `pos` into `out`.
+    /// The output buffer is cleared first so callers can reuse it across queries.
+    pub fn within_radius(&self, pos: (f64, f64, f64), radius: f64, out: &mut Vec<usize>) {
+        out.clear();
+
+        let reach = (radius / self.cell_size).ceil() as i64;
+        let (cx, cy, cz) = cell_key(pos, self.cell_size);
+        let radius_sq = radius * radius;
+
+        for dx in -reach..=reach {
+            for dy in -reach..=reach {
+                for dz in -reach..=reach {
+                    let Some(bucket) = self.cells.get(&(cx + dx, cy + dy, cz + dz)) else {
+                        continue;
+                    };
+
+                    for &idx in bucket {
+                        if distance_sq(self.positions[idx], pos) <= radius_sq {
+                            out.push(idx);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Collect indices of the `k` nearest positions to `pos` into `out`, closest first.
+    /// Searches outward shell by shell and stops once the k-th candidate is closer
+    /// than any unvisited cell could be, or after `max_rings` shells in sparse areas.
+    pub fn nearest_k(&self, pos: (f64, f64, f64), k: usize, max_rings: i64, out: &mut Vec<usize>) {
+        out.clear();
+        let (cx, cy, cz) = cell_key(pos, self.cell_size);
+        let mut candidates: Vec<(f64, usize)> = Vec::new();
+
+        for ring in 0..=max_rings {
+            for dx in -ring..=ring {
+                for dy in -ring..=ring {
+                    for dz in -ring..=ring {
+                        // Only visit the outer shell of this ring.
+                        if dx.abs() != ring && dy.abs() != ring && dz.abs() != ring {
+                            continue;
+                        }
+
+                        let Some(bucket) = self.cells.get(&(cx + dx, cy + dy, cz + dz)) else {
+                            continue;
+                        };
+
+                        for &idx in bucket {
+                            candidates.push((distance_sq(self.positions[idx], pos), idx));
+                        }
+                    }
+                }
+            }
+
+            if candidates.len() >= k {
+                candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let covered = ring as f64 * self.cell_size;
+                if candidates[k - 1].0 <= covered * covered {
+                    break;
+                }
+            }
+        }
+
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+        out.extend(candidates.iter().take(k).map(|&(_, idx)| idx));
+    }
+}
+
+/// Squared Euclidean distance between two world positions.
+pub fn distance_sq(a: (f64, f64, f64), b: (f64, f64, f64)) -> f64 {
+    let dx = a.0 - b.0;
+    let dy = a.1 - b.1;
+    let dz = a.2 - b.2;
+    dx * dx + dy * dy + dz * dz
+}
+
+fn cell_key(pos: (f64, f64, f64), cell_size: f64) -> (i64, i64, i64) {
+    (
+        (pos.0 / cell_size).floor() as i64,
+        (pos.1 / cell_size).floor() as i64,
+        (pos.2 / cell_size).floor() as i64,
+    )
+}
@@ -1,5 +1,6 @@
 /// Simple Z-Order spatial layout for point cloud textures
 use crate::bounds::PointCloudBounds;
+use constants::class::encode_object_id;
 use constants::texture::TEXTURE_SIZE;
 
 /// Point with spatial metadata for texture generation
@@ -107,7 +108,7 @@ impl SpatialTextureGenerator {
                 position_data[base_idx] = point.norm_pos.0;
                 position_data[base_idx + 1] = point.norm_pos.1;
                 position_data[base_idx + 2] = point.norm_pos.2;
-                position_data[base_idx + 3] = point.object_number / 121.0;
+                position_data[base_idx + 3] = encode_object_id(point.object_number);
             }
         }
 
@@ -148,3 +149,18 @@ pub fn morton_encode_2d(x: u32, z: u32) -> u32 {
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use constants::class::decode_object_id;
+    use constants::texture::MAX_POINTS;
+
+    #[test]
+    fn every_object_id_survives_the_position_texture() {
+        // Segmentation numbers objects from 1, at most one per point.
+        for id in 0..=MAX_POINTS as u32 {
+            assert_eq!(decode_object_id(encode_object_id(id as f32)), id);
+        }
+    }
+}
@@ -9,15 +9,28 @@
 // Input Textures:
 //   - original_texture: Encoded RGB + original classification (A)
 //   - position_texture: Normalised world-space coordinates + connectivity class ID
-//   - spatial_index_texture: Precomputed morton codes for spatial acceleration
+//   - spatial_index_texture: Precomputed morton codes (morton debug render mode)
+//   - normal_texture: Estimated surface normal (XYZ) + curvature (W), or a 1x1 up-vector placeholder
+//   - change_texture: Signed distance to a reference epoch (X), changed flag (Y), reference class (Z), or a 1x1 placeholder
+//   - heightmap_texture: Normalised ground elevation (R) for ground-relative polygon height ranges
 //
 // Uniform Data:
-//   - compute_data: Packed structure with classification polygons, masks, modes
+//   - compute_data: Polygon/mask counts, render mode, selection and hover state
 //   - bounds: World-space bounding box for position denormalisation
 //
+// Storage Data (sized at runtime to the current polygon set):
+//   - polygon_points: User-placed polygon vertices (XZ) for all polygons, back to back
+//   - polygon_info: Per-polygon vertex, mask and edge bin ranges, target class, mode, XZ AABB and height range
+//   - polygon_masks: Per-polygon (class id, object id) mask entries
+//   - polygon_edge_bins: Per-polygon Z slab headers (offset, count) followed by edge indices
+//   - polygon_selections: Per-polygon sorted point texel indices from screen-space selections
+//   - selected_points: One bit per point texel for the pending selection highlight
+//
 // Features:
-//   - Supports two spatial filtering modes: AABB (default) and Morton (toggleable)
+//   - AABB broad phase, then an exact crossing test over the edges in the point's Z slab
 //   - Applies point-in-polygon masking and allows reclassification or hiding
+//   - Optional per-polygon height band (absolute or above ground) turns a footprint into a prism
+//   - Selection polygons match their listed texels instead of the footprint test
 //   - Avoids redundant operations on already-hidden points (modifier stack logic)
 //   - Outputs reclassified points and/or filtered ones to `output_texture`
 //   - Supports on mouse hover debug, spatial debug, morton debug, and multiple render modes
@@ -25,7 +38,7 @@
 // Notes:
 //   - Final output color encodes classification state for downstream shaders
 //   - Classification ID 254 is treated as "hidden" (non-destructive hide) (note this may need to be extended upon in the future)
-//   - Morton mode is optional but used for coarse-grained spatial filtering (note for long, thin polygons this is much more efficient than an AABB and in the future i would recommend a hybrid approach with some huristic)
+//   - Containment is exact for any polygon size; edge bins are rebuilt on the CPU each dispatch
 //
 // This pass sets up the modified textures used by all subsequent stages in the
 // render pipeline.
@@ -37,10 +50,6 @@
 @group(0) @binding(2) var spatial_index_texture: texture_2d<f32>;
 @group(0) @binding(3) var output_texture: texture_storage_2d<rgba32float, write>;
 
-const MAX_IGNORE_MASK_LENGTH: u32 = 512u;
-const MAXIMUM_POLYGON_POINTS: u32 = 2048u;
-const MAXIMUM_POLYGONS: u32 = 512u;
-
 struct ComputeUniformData {
     polygon_count: u32,        // 0
     total_points: u32,         // 4
@@ -52,11 +61,29 @@ struct ComputeUniformData {
 
     is_selecting: u32,         // 32–36
     hover_object_id: u32,      // 36–40
-    _padding: vec2<u32>,       // 40–48  (fills out to next 16-byte boundary)
+    mask_count: u32,           // 40–44
+    _padding: u32,             // 44–48  (fills out to next 16-byte boundary)
+}
 
-    point_data: array<vec4<f32>, MAXIMUM_POLYGON_POINTS>,  // starts @ 48 → aligned(16)
-    polygon_info: array<vec4<f32>, MAXIMUM_POLYGONS>,
-    ignore_masks: array<vec4<u32>, MAX_IGNORE_MASK_LENGTH>,
+// Mode 0 hides matching points, mode 1 reclassifies them to new_class.
+struct PolygonInfo {
+    point_start: u32,          // 0
+    point_count: u32,          // 4
+    new_class: u32,            // 8
+    mode: u32,                 // 12
+    mask_start: u32,           // 16
+    mask_count: u32,           // 20
+    bin_start: u32,            // 24
+    bin_count: u32,            // 28
+    aabb: vec4<f32>,           // 32–48  (min x, min z, max x, max z)
+    height_mode: u32,          // 48  (0 = full column, 1 = absolute, 2 = above ground)
+    height_min: f32,           // 52
+    height_max: f32,           // 56
+    selection_start: u32,      // 60
+    selection_count: u32,      // 64  (0 = footprint polygon)
+    _padding0: u32,            // 68
+    _padding1: u32,            // 72
+    _padding2: u32,            // 76–80
 }
 
 
@@ -71,9 +98,23 @@ struct BoundsData {
 
 @group(0) @binding(5) var<uniform> bounds: BoundsData;
 
-const GRID_RESOLUTION: u32 = 1024u;
-const MORTON_THRESHOLD = 500u; // Empirical threshold for Morton spatial distance.
-const USE_MORTON_SPATIAL: bool = false; // Toggle: true=Morton, false=AABB
+@group(0) @binding(6) var normal_texture: texture_2d<f32>;
+
+@group(0) @binding(7) var change_texture: texture_2d<f32>;
+
+@group(0) @binding(8) var<storage, read> polygon_points: array<vec2<f32>>;
+@group(0) @binding(9) var<storage, read> polygon_info: array<PolygonInfo>;
+@group(0) @binding(10) var<storage, read> polygon_masks: array<vec2<u32>>;
+@group(0) @binding(11) var<storage, read> polygon_edge_bins: array<u32>;
+
+@group(0) @binding(12) var heightmap_texture: texture_2d<f32>;
+
+@group(0) @binding(13) var<storage, read> polygon_selections: array<u32>;
+@group(0) @binding(14) var<storage, read> selected_points: array<u32>;
+
+const SELECTION_HIGHLIGHT: vec3<f32> = vec3<f32>(1.0, 0.85, 0.0);
+
+const CHANGE_RAMP_RANGE: f32 = 0.5; // Signed distance in metres mapped to full ramp colour.
 
 @compute @workgroup_size(8, 8, 1)
 fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
@@ -87,13 +128,14 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
     let original_sample = textureLoad(original_texture, coords, 0);
     let position_sample = textureLoad(position_texture, coords, 0);
 
-    // let point_connectivity_class_id = u32(position_sample.a * 255.0);
-    let point_connectivity_class_id = u32(position_sample.a * 121.0);
+    // Rounded like `decode_object_id`; truncating turns many IDs into their predecessor.
+    let point_connectivity_class_id = u32(round(position_sample.a * 121.0));
     let world_pos = bounds.min_bounds + position_sample.xyz * (bounds.max_bounds - bounds.min_bounds);
     let original_rgb = original_sample.rgb;
     let original_class = u32(original_sample.a * 255.0);
+    let texel = coords.y * texture_size.x + coords.x;
 
-    // Get Morton code for spatial optimisation when using Morton mode.
+    // Morton code for the debug render mode.
     let spatial_sample = textureLoad(spatial_index_texture, coords, 0);
     let morton_high = bitcast<u32>(spatial_sample.r);
     let morton_low = bitcast<u32>(spatial_sample.g);
@@ -104,160 +146,140 @@ fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
 
     for (var i: u32 = compute_data.polygon_count; i > 0u; i = i - 1u) {
         let real_index: u32 = i - 1u;
-        let poly_info = compute_data.polygon_info[real_index];
-        let start_idx = u32(poly_info.x);
-        let point_count = u32(poly_info.y);
-        let new_class = u32(poly_info.z);
+        let poly_info = polygon_info[real_index];
+        let new_class = poly_info.new_class;
 
         if compute_data.enable_spatial_opt == 1u {
-            // Compile-time spatial optimization method selection.
-            var should_test_polygon = false;
-
-            if USE_MORTON_SPATIAL {
-                // Morton code spatial filtering with centroid coverage.
-                should_test_polygon = is_point_near_polygon_morton(
-                    world_pos.xz, start_idx, point_count, bounds.min_bounds.xz, bounds.max_bounds.xz
-                );
-            } else {
-                // AABB spatial filtering for guaranteed coverage.
-                should_test_polygon = is_point_near_polygon_aabb(
-                    world_pos.xz, start_idx, point_count
-                );
-            }
+            // AABB broad phase before the exact edge test.
+            let should_test_polygon = is_point_near_polygon_aabb(world_pos.xz, poly_info);
 
             // since this is technically a procedural modifer stack - we should not bother performing additional hide or reclasiffy ops to a point that has been previously hidden
             // otherwise intersecting polygons will undo hide ops when we reclassify, regardless of mask ids
             if should_test_polygon && !found_hide_op && !found_reclassify_op {
                 // Here's where we check if the mask ids for the current polygon overlap AND it's inside the polygon
                 // effectivly this is our masking logic per polygon in the Reclassify polygon mode
-                if point_in_polygon(world_pos.xz, start_idx, point_count) {
+                if point_in_footprint(world_pos.xz, texel, poly_info) &&
+                   point_in_height_range(world_pos, poly_info) &&
+                   polygon_mask_matches(poly_info, original_class, point_connectivity_class_id) {
                     // update the final class for points inside the polygon, with it's masks considered for reclassification
-                    if contains_value(real_index, original_class, point_connectivity_class_id, 1u) {
+                    if poly_info.mode == 1u {
                         final_class = new_class;
                         found_reclassify_op = true;
-                    }
-
-                    // set the final class for points inside the polygon, with masks considered to a magic number that our fragment shader will ignore and discard 'hiding' non-destructivly
-                    if contains_value(real_index, original_class, point_connectivity_class_id, 0u) {
+                    } else {
+                        // set the final class for points inside the polygon, with masks considered to a magic number that our fragment shader will ignore and discard 'hiding' non-destructivly
                         found_hide_op = true;
                         final_class = 254u;
                         break;
                     }
-
                 }
             }
         } else {
             // Direct polygon testing without spatial optimization.
-            if point_in_polygon(world_pos.xz, start_idx, point_count) {
+            if point_in_footprint(world_pos.xz, texel, poly_info) {
                 final_class = new_class;
                 break;
             }
         }
     }
 
-    let final_color = apply_render_mode(original_rgb, original_class, final_class, world_pos, coords, morton_low, morton_high, point_connectivity_class_id);
+    var final_color = apply_render_mode(original_rgb, original_class, final_class, world_pos, coords, morton_low, morton_high, point_connectivity_class_id);
+    if is_point_selected(texel) {
+        final_color = vec4<f32>(mix(final_color.rgb, SELECTION_HIGHLIGHT, 0.6), final_color.a);
+    }
     textureStore(output_texture, coords, final_color);
 }
 
-// decode packed masks, index and mode and checks if both masks intersect (note mask id1 is the optional refinement)
-fn contains_value(poly_idx: u32, mask_id0: u32, mask_id1: u32, mode: u32) -> bool {
-    var index: u32 = 0u;
-    loop {
-        if (index >= MAX_IGNORE_MASK_LENGTH) {
-            break;
-        }
-
-        let mask_vec = compute_data.ignore_masks[index];
-        for (var j: u32 = 0u; j < 4u; j = j + 1u) {
-            let encoded: u32 = bitcast<u32>(mask_vec[j]);
-
-            let dec_mask_id0: u32 = encoded & 0x1FFu;
-            let dec_mask_id1: u32 = (encoded >> 9u) & 0x1FFu;
-            let dec_poly_idx: u32 = (encoded >> 18u) & 0x1FFu;
-            let dec_mode: u32     = (encoded >> 27u) & 0x1u;
+// Selection polygons list their texels; everything else uses the drawn footprint.
+fn point_in_footprint(point: vec2<f32>, texel: u32, info: PolygonInfo) -> bool {
+    if info.selection_count == 0u {
+        return point_in_polygon(point, info);
+    }
 
-            if (dec_poly_idx == poly_idx &&
-                dec_mask_id0 == mask_id0 &&
-                dec_mask_id1 == mask_id1 &&
-                dec_mode == mode) {
-                return true;
-            }
+    // Binary search over the polygon's sorted texel indices.
+    var low = info.selection_start;
+    var high = info.selection_start + info.selection_count;
+    while low < high {
+        let mid = (low + high) / 2u;
+        let value = polygon_selections[mid];
+        if value == texel {
+            return true;
+        }
+        if value < texel {
+            low = mid + 1u;
+        } else {
+            high = mid;
         }
-
-        index = index + 1u;
     }
-
     return false;
 }
 
-/// Morton-based spatial filtering with centroid coverage for large polygons.
-/// Provides fine-grained spatial discrimination but requires Morton encoding overhead.
-fn is_point_near_polygon_morton(
-    point: vec2<f32>,
-    start_idx: u32,
-    point_count: u32,
-    bounds_min: vec2<f32>,
-    bounds_max: vec2<f32>
-) -> bool {
-    if point_count == 0u { return false; }
-
-    let query_morton = encode_morton_2d_current(point, bounds_min, bounds_max);
-
-    // Calculate polygon centroid for interior coverage.
-    // Addresses Morton spatial gaps in large polygon interiors.
-    var centroid = vec2<f32>(0.0, 0.0);
-    for (var i = 0u; i < point_count; i++) {
-        centroid += compute_data.point_data[start_idx + i].xy;
+// Pending selection bitset; an empty selection binds a single zero word.
+fn is_point_selected(texel: u32) -> bool {
+    let word = texel / 32u;
+    if word >= arrayLength(&selected_points) {
+        return false;
     }
-    centroid /= f32(point_count);
+    return (selected_points[word] & (1u << (texel % 32u))) != 0u;
+}
 
-    // Priority check: centroid Morton distance for interior points.
-    let centroid_morton = encode_morton_2d_current(centroid, bounds_min, bounds_max);
-    let centroid_diff = abs(i32(query_morton) - i32(centroid_morton));
-    if centroid_diff < i32(MORTON_THRESHOLD) {
-        return true;
+// checks the polygon's (class id, object id) masks against the point (note object id is the optional refinement)
+fn polygon_mask_matches(info: PolygonInfo, class_id: u32, object_id: u32) -> bool {
+    for (var i = 0u; i < info.mask_count; i++) {
+        let mask = polygon_masks[info.mask_start + i];
+        if mask.x == class_id && mask.y == object_id {
+            return true;
+        }
     }
+    return false;
+}
 
-    // Fallback: boundary vertex Morton distance checks.
-    for (var i = 0u; i < point_count; i++) {
-        let poly_point = compute_data.point_data[start_idx + i].xy;
-        let poly_morton = encode_morton_2d_current(poly_point, bounds_min, bounds_max);
-        let morton_diff = abs(i32(query_morton) - i32(poly_morton));
-        if morton_diff < i32(MORTON_THRESHOLD) {
+// Vertical band of the polygon prism; mode 0 keeps the full column.
+fn point_in_height_range(world_pos: vec3<f32>, info: PolygonInfo) -> bool {
+    switch info.height_mode {
+        case 1u: {
+            return world_pos.y >= info.height_min && world_pos.y <= info.height_max;
+        }
+        case 2u: {
+            let ground = ground_height(world_pos.xz);
+            return world_pos.y >= ground + info.height_min && world_pos.y <= ground + info.height_max;
+        }
+        default: {
             return true;
         }
     }
+}
 
-    return false;
+// Bilinear heightmap sample at a world XZ position, denormalised to world Y.
+fn ground_height(point: vec2<f32>) -> f32 {
+    let dims = textureDimensions(heightmap_texture);
+    let last = dims - vec2<u32>(1u, 1u);
+    let norm = (point - bounds.min_bounds.xz) / (bounds.max_bounds.xz - bounds.min_bounds.xz);
+    let pixel = norm * vec2<f32>(last);
+
+    let p0 = min(vec2<u32>(max(floor(pixel), vec2<f32>(0.0))), last);
+    let p1 = min(p0 + vec2<u32>(1u, 1u), last);
+    let weight = pixel - vec2<f32>(p0);
+
+    let h00 = textureLoad(heightmap_texture, vec2<u32>(p0.x, p0.y), 0).r;
+    let h10 = textureLoad(heightmap_texture, vec2<u32>(p1.x, p0.y), 0).r;
+    let h01 = textureLoad(heightmap_texture, vec2<u32>(p0.x, p1.y), 0).r;
+    let h11 = textureLoad(heightmap_texture, vec2<u32>(p1.x, p1.y), 0).r;
+
+    let h_top = h00 * (1.0 - weight.x) + h10 * weight.x;
+    let h_bottom = h01 * (1.0 - weight.x) + h11 * weight.x;
+    let normalised = h_top * (1.0 - weight.y) + h_bottom * weight.y;
+    return bounds.min_bounds.y + normalised * (bounds.max_bounds.y - bounds.min_bounds.y);
 }
 
 /// AABB-based spatial filtering with guaranteed coverage and no false negatives.
-/// Simpler computation with O(n) vertex iteration for bounding box calculation.
-fn is_point_near_polygon_aabb(
-    point: vec2<f32>,
-    start_idx: u32,
-    point_count: u32
-) -> bool {
-    if point_count == 0u { return false; }
-
-    // Calculate axis-aligned bounding box from polygon vertices.
-    var min_x = compute_data.point_data[start_idx].x;
-    var max_x = min_x;
-    var min_z = compute_data.point_data[start_idx].y;
-    var max_z = min_z;
-
-    for (var i = 1u; i < point_count; i++) {
-        let pt = compute_data.point_data[start_idx + i].xy;
-        min_x = min(min_x, pt.x);
-        max_x = max(max_x, pt.x);
-        min_z = min(min_z, pt.y);
-        max_z = max(max_z, pt.y);
-    }
+/// The bounding box is precomputed on the CPU per polygon.
+fn is_point_near_polygon_aabb(point: vec2<f32>, info: PolygonInfo) -> bool {
+    if info.point_count == 0u { return false; }
 
     // Spatial margin to account for point cloud sampling density.
     let margin = 1.0;
-    return point.x >= (min_x - margin) && point.x <= (max_x + margin) &&
-           point.y >= (min_z - margin) && point.y <= (max_z + margin);
+    return point.x >= (info.aabb.x - margin) && point.x <= (info.aabb.z + margin) &&
+           point.y >= (info.aabb.y - margin) && point.y <= (info.aabb.w + margin);
 }
 
 fn apply_render_mode(original_rgb: vec3<f32>, original_class: u32, final_class: u32, world_pos: vec3<f32>, coords: vec2<u32>, morton_low: u32, morton_high: u32, point_connectivity_class_id: u32) -> vec4<f32> {
@@ -282,21 +304,8 @@ fn apply_render_mode(original_rgb: vec3<f32>, original_class: u32, final_class:
         case 4u: { // Spatial Debug - show which points were considered for processing
             var was_considered = 0.0;
             for (var i = 0u; i < compute_data.polygon_count; i++) {
-                let poly_info = compute_data.polygon_info[i];
-                let start_idx = u32(poly_info.x);
-                let point_count = u32(poly_info.y);
-
-                // Use same spatial filtering method as main processing loop.
-                var should_test = false;
-                if USE_MORTON_SPATIAL {
-                    should_test = is_point_near_polygon_morton(
-                        world_pos.xz, start_idx, point_count, bounds.min_bounds.xz, bounds.max_bounds.xz
-                    );
-                } else {
-                    should_test = is_point_near_polygon_aabb(world_pos.xz, start_idx, point_count);
-                }
-
-                if should_test {
+                // Same broad phase as the main processing loop.
+                if is_point_near_polygon_aabb(world_pos.xz, polygon_info[i]) {
                     was_considered = 1.0;
                     break;
                 }
@@ -311,29 +320,103 @@ fn apply_render_mode(original_rgb: vec3<f32>, original_class: u32, final_class:
         case 5u: {
             return vec4<f32>(classification_to_random_color(point_connectivity_class_id), f32(point_connectivity_class_id));
         }
+        case 7u: { // Normal shaded - lambert lighting over the modified classification colour
+            let normal = load_normal(coords).xyz;
+            let light_dir = normalize(vec3<f32>(0.4, 0.8, 0.3));
+            let diffuse = abs(dot(normal, light_dir));
+            let shade = 0.35 + 0.65 * diffuse;
+            return vec4<f32>(classification_to_color(final_class) * shade, f32(point_connectivity_class_id));
+        }
+        case 8u: { // Verticality - blue for horizontal surfaces through to red for vertical faces
+            let normal = load_normal(coords).xyz;
+            let verticality = clamp(1.0 - abs(normal.y), 0.0, 1.0);
+            return vec4<f32>(verticality_ramp(verticality), f32(point_connectivity_class_id));
+        }
+        case 9u: { // Change - red above / in front of the earlier epoch, blue below / behind, grey unchanged
+            let change = load_change(coords);
+            if change.y < 0.5 {
+                let grey = dot(original_rgb, vec3<f32>(0.299, 0.587, 0.114)) * 0.5;
+                return vec4<f32>(vec3<f32>(grey), f32(point_connectivity_class_id));
+            }
+            return vec4<f32>(change_ramp(change.x / CHANGE_RAMP_RANGE), f32(point_connectivity_class_id));
+        }
         default: {
             return vec4<f32>(original_rgb, f32(point_connectivity_class_id));
         }
     }
 }
 
-fn point_in_polygon(point: vec2<f32>, start_idx: u32, point_count: u32) -> bool {
-    var inside = false;
-    var j = point_count - 1u;
+// Placeholder textures are 1x1, so clamp the lookup to stay in range.
+fn load_normal(coords: vec2<u32>) -> vec4<f32> {
+    let dims = textureDimensions(normal_texture);
+    let clamped = min(coords, dims - vec2<u32>(1u, 1u));
+    return textureLoad(normal_texture, clamped, 0);
+}
+
+// Placeholder textures are 1x1, so clamp the lookup to stay in range.
+fn load_change(coords: vec2<u32>) -> vec4<f32> {
+    let dims = textureDimensions(change_texture);
+    let clamped = min(coords, dims - vec2<u32>(1u, 1u));
+    return textureLoad(change_texture, clamped, 0);
+}
+
+// Diverging ramp over [-1, 1]: blue for removed material, white near zero, red for added.
+fn change_ramp(t: f32) -> vec3<f32> {
+    let lowered = vec3<f32>(0.1, 0.3, 0.95);
+    let neutral = vec3<f32>(0.95, 0.95, 0.95);
+    let raised = vec3<f32>(0.95, 0.15, 0.1);
+    let s = clamp(t, -1.0, 1.0);
+    if s < 0.0 {
+        return mix(neutral, lowered, -s);
+    }
+    return mix(neutral, raised, s);
+}
 
-    for (var i = 0u; i < point_count; i++) {
-        let curr_pt = compute_data.point_data[start_idx + i].xy;
-        let prev_pt = compute_data.point_data[start_idx + j].xy;
+fn verticality_ramp(t: f32) -> vec3<f32> {
+    let horizontal = vec3<f32>(0.1, 0.3, 0.9);
+    let sloped = vec3<f32>(0.95, 0.9, 0.2);
+    let vertical = vec3<f32>(0.9, 0.1, 0.1);
+    if t < 0.5 {
+        return mix(horizontal, sloped, t * 2.0);
+    }
+    return mix(sloped, vertical, (t - 0.5) * 2.0);
+}
+
+// Crossing test over the edges in the point's Z slab; exact for any polygon size.
+// Edge e joins vertex e to vertex e + 1 (wrapping).
+fn point_in_polygon(point: vec2<f32>, info: PolygonInfo) -> bool {
+    if info.bin_count == 0u || point.y < info.aabb.y || point.y > info.aabb.w {
+        return false;
+    }
+
+    let header = info.bin_start + edge_bin_index(point.y, info) * 2u;
+    let edge_offset = polygon_edge_bins[header];
+    let edge_count = polygon_edge_bins[header + 1u];
+
+    var inside = false;
+    for (var i = 0u; i < edge_count; i++) {
+        let edge = polygon_edge_bins[edge_offset + i];
+        let next = select(edge + 1u, 0u, edge + 1u == info.point_count);
+        let curr_pt = polygon_points[info.point_start + edge];
+        let prev_pt = polygon_points[info.point_start + next];
 
         if ((curr_pt.y > point.y) != (prev_pt.y > point.y)) &&
            (point.x < (prev_pt.x - curr_pt.x) * (point.y - curr_pt.y) / (prev_pt.y - curr_pt.y) + curr_pt.x) {
             inside = !inside;
         }
-        j = i;
     }
     return inside;
 }
 
+// Equal Z slabs across the polygon AABB; must match `edge_bin_index` on the CPU.
+fn edge_bin_index(z: f32, info: PolygonInfo) -> u32 {
+    if info.bin_count <= 1u {
+        return 0u;
+    }
+    let last = f32(info.bin_count - 1u);
+    return u32(clamp((z - info.aabb.y) / (info.aabb.w - info.aabb.y) * f32(info.bin_count), 0.0, last));
+}
+
 fn classification_to_random_color(classification: u32) -> vec3<f32> {
     let c = classification & 255u;
     let hash1 = (c * 73u) % 255u;
@@ -356,6 +439,7 @@ fn classification_to_color(classification: u32) -> vec3<f32> {
         case 4u: { return vec3<f32>(0.0, 0.8, 0.0); }        // medium vegetation
         case 5u: { return vec3<f32>(0.0, 0.6, 0.0); }        // high vegetation
         case 6u: { return vec3<f32>(0.92, 1.0, 0.0); }       // buildings
+        case 7u: { return vec3<f32>(1.0, 0.0, 1.0); }        // noise
         case 8u: { return vec3<f32>(0.2, 0.0, 1.0); }        // street furniture
         case 10u: { return vec3<f32>(1.0, 1.0, 1.0); }       // street markings
         case 11u: { return vec3<f32>(0.18, 0.18, 0.18); }    // street surface
@@ -376,29 +460,3 @@ fn morton_to_debug_color_blended(morton_high: u32, morton_low: u32) -> vec3<f32>
 
     return vec3<f32>(r, g, b);
 }
-
-// Helper function for Morton encoding
-fn morton_part_1by1(n: u32) -> u32 {
-    var result = n & 0x0000FFFFu;
-    result = (result ^ (result << 8u)) & 0x00FF00FFu;
-    result = (result ^ (result << 4u)) & 0x0F0F0F0Fu;
-    result = (result ^ (result << 2u)) & 0x33333333u;
-    result = (result ^ (result << 1u)) & 0x55555555u;
-    return result;
-}
-
-fn morton_encode_2d_optimized(x: u32, z: u32) -> u32 {
-    return morton_part_1by1(x) | (morton_part_1by1(z) << 1u);
-}
-
-fn encode_morton_2d_current(point: vec2<f32>, bounds_min: vec2<f32>, bounds_max: vec2<f32>) -> u32 {
-    // Normalise coordinates to match bounds normalisation logic.
-    let norm_x = (point.x - bounds_min.x) / (bounds_max.x - bounds_min.x);
-    let norm_z = (point.y - bounds_min.y) / (bounds_max.y - bounds_min.y);
-
-    // Calculate grid coordinates for Morton encoding.
-    let grid_x = u32(clamp(norm_x * f32(GRID_RESOLUTION - 1u), 0.0, f32(GRID_RESOLUTION - 1u)));
-    let grid_z = u32(clamp(norm_z * f32(GRID_RESOLUTION - 1u), 0.0, f32(GRID_RESOLUTION - 1u)));
-
-    return morton_encode_2d_optimized(grid_x, grid_z);
-}
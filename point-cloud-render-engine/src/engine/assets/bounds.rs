@@ -56,6 +56,15 @@ impl PointCloudBounds {
         )
     }
 
+    /// Minimum corner; normalised texture positions are offsets from it scaled by `size`.
+    pub fn min_corner(&self) -> Vec3 {
+        Vec3::new(
+            self.bounds.min_x as f32,
+            self.bounds.min_y as f32,
+            self.bounds.min_z as f32,
+        )
+    }
+
     /// Get ground height for camera collision and terrain placement.
     pub fn ground_height(&self) -> f32 {
         self.bounds.min_y as f32
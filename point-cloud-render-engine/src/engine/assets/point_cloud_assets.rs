@@ -13,6 +13,8 @@ pub fn create_point_cloud_assets(manifest: Option<Handle<SceneManifest>>) -> Poi
         result_texture: Handle::default(),
         depth_texture: Handle::default(),
         heightmap_texture: Handle::default(),
+        normal_texture: Handle::default(),
+        change_texture: Handle::default(),
         asset_position_texture: Handle::default(),
         asset_colour_class_texture: Handle::default(),
         manifest,
@@ -29,6 +31,8 @@ pub struct PointCloudAssets {
     pub colour_class_texture: Handle<Image>, // RGBA32F: RGB + classification.
     pub spatial_index_texture: Handle<Image>, // RG32Uint: spatial data.
     pub heightmap_texture: Handle<Image>, // R32F: elevation.
+    pub normal_texture: Handle<Image>, // RGBA32F: XYZ normal + curvature (1x1 placeholder if absent).
+    pub change_texture: Handle<Image>, // RGBA32F: signed distance + changed flag + reference class (1x1 placeholder if absent).
 
     // Asset atlas textures
     pub asset_position_texture: Handle<Image>,
@@ -52,3 +56,13 @@ impl PointCloudAssets {
         Some(manifest.to_point_cloud_bounds())
     }
 }
+
+/// Read one RGBA32F texel from raw little-endian texture bytes.
+pub(crate) fn read_texel(data: &[u8], index: usize) -> Option<[f32; 4]> {
+    let bytes = data.get(index * 16..index * 16 + 16)?;
+    let mut texel = [0.0; 4];
+    for (value, chunk) in texel.iter_mut().zip(bytes.chunks_exact(4)) {
+        *value = f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    Some(texel)
+}
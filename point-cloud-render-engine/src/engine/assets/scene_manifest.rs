@@ -39,6 +39,33 @@ pub struct AssetAtlasData {
     pub atlas_config: AtlasConfig,
 }
 
+/// Single kerb polyline vertex relative to the terrain origin, at the sidewalk top.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KerbVertexData {
+    pub position: [f32; 3],
+    pub height_m: f32,
+    pub ramp: bool,
+}
+
+/// Kerb polyline with per-vertex heights and ramp flags.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KerbLineData {
+    pub vertices: Vec<KerbVertexData>,
+}
+
+/// Kerb lines detected between road and sidewalk during preprocessing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KerbData {
+    pub lines: Vec<KerbLineData>,
+}
+
+/// Optional ground analysis results. Only the parts the viewer draws are mirrored here.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnalysisData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kerbs: Option<KerbData>,
+}
+
 /// Complete scene manifest as a Bevy asset. Mirrors JSON structure exactly.
 /// Contains both terrain point cloud data and optional asset atlas information.
 #[derive(Asset, Debug, Clone, Serialize, Deserialize, TypePath, Resource, ExtractResource)]
@@ -52,6 +79,9 @@ pub struct SceneManifest {
     // pub scene_bounds: BoundsData,
     /// Describes the class types and object id's found in the specific dataset
     pub classes: ClassificationInfo,
+    /// Ground analysis results such as kerb lines, when those stages ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub analysis: Option<AnalysisData>,
 }
 
 impl SceneManifest {
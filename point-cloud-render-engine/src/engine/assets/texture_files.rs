@@ -9,6 +9,12 @@ pub struct TerrainTextureFiles {
     pub colour_class: String,
     pub spatial_index: String,
     pub heightmap: String,
+    /// Optional RGBA32F normal texture (XYZ normal + curvature); older manifests omit it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normal: Option<String>,
+    /// Optional RGBA32F change texture (signed distance, changed flag, reference class).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change: Option<String>,
 }
 
 /// DDS texture file paths for asset atlas data.
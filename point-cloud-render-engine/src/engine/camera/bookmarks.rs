@@ -0,0 +1,460 @@
+use crate::engine::assets::bounds::PointCloudBounds;
+use crate::engine::assets::point_cloud_assets::{PointCloudAssets, read_texel};
+use crate::engine::assets::scene_manifest::SceneManifest;
+use crate::engine::camera::viewport_camera::{CameraView, ViewportCamera, camera_controller};
+use crate::engine::compute::classification_reference::object_id_of;
+use crate::engine::core::app_state::AppState;
+use crate::rpc::web_rpc::WebRpcInterface;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use bevy::render::camera::ScalingMode;
+use std::f32::consts::PI;
+
+/// Fly-to duration when none is requested.
+const DEFAULT_FLIGHT_SECONDS: f32 = 1.2;
+/// Long flights rise by this fraction of the distance covered, peaking halfway.
+const FLIGHT_ARC: f32 = 0.25;
+/// Objects are framed from this multiple of their largest extent.
+const OBJECT_FRAMING: f32 = 1.5;
+const MIN_OBJECT_HEIGHT: f32 = 5.0;
+
+/// A named, saved viewpoint.
+#[derive(Debug, Clone)]
+pub struct CameraBookmark {
+    pub name: String,
+    pub view: CameraView,
+}
+
+/// Saved viewpoints in the order they were created.
+#[derive(Resource, Default)]
+pub struct CameraBookmarks {
+    pub items: Vec<CameraBookmark>,
+    /// Bookmark the native F3 shortcut flies to next.
+    next: usize,
+}
+
+impl CameraBookmarks {
+    /// Save `view` under `name`, replacing a bookmark of the same name.
+    pub fn save(&mut self, name: &str, view: CameraView) {
+        match self.items.iter_mut().find(|b| b.name == name) {
+            Some(bookmark) => bookmark.view = view,
+            None => self.items.push(CameraBookmark {
+                name: name.to_string(),
+                view,
+            }),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CameraBookmark> {
+        self.items.iter().find(|b| b.name == name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.items.len();
+        self.items.retain(|b| b.name != name);
+        self.items.len() != before
+    }
+
+    /// First free `View N` name.
+    fn unused_name(&self) -> String {
+        (1..)
+            .map(|n| format!("View {}", n))
+            .find(|name| self.get(name).is_none())
+            .unwrap_or_default()
+    }
+
+    /// RPC payload with focus points in absolute world coordinates.
+    pub fn summaries(&self, origin: DVec3) -> serde_json::Value {
+        self.items
+            .iter()
+            .map(|b| {
+                serde_json::json!({
+                    "name": b.name,
+                    "focus_point": (b.view.focus_point.as_dvec3() + origin).to_array(),
+                    "height": b.view.height,
+                    "yaw": b.view.yaw,
+                    "pitch": b.view.pitch,
+                    "top_view": b.view.top_down,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Animated transition between two views.
+#[derive(Debug, Clone, Copy)]
+pub struct Flight {
+    pub from: CameraView,
+    pub to: CameraView,
+    pub elapsed: f32,
+    pub duration: f32,
+}
+
+impl Flight {
+    /// View at `elapsed`, eased in and out and lifted over long distances.
+    pub fn view(&self) -> CameraView {
+        let t = (self.elapsed / self.duration.max(f32::EPSILON)).clamp(0.0, 1.0);
+        if t >= 1.0 {
+            return self.to;
+        }
+        let eased = t * t * (3.0 - 2.0 * t);
+        let mut view = self.from.lerp(&self.to, eased);
+        let distance = self.from.focus_point.distance(self.to.focus_point);
+        view.height += distance * FLIGHT_ARC * (PI * eased).sin();
+        view
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+/// Flight in progress, if any. Clicking in the viewport cancels it.
+#[derive(Resource, Default)]
+pub struct CameraFlight(pub Option<Flight>);
+
+/// Camera requests from RPC or keyboard.
+#[derive(Event, Debug, Clone)]
+pub struct CameraEvent {
+    pub action: CameraAction,
+}
+
+#[derive(Debug, Clone)]
+pub enum CameraAction {
+    SaveBookmark {
+        name: Option<String>, // `None` picks the next free `View N`.
+    },
+    GoToBookmark {
+        name: String,
+        duration: Option<f32>,
+    },
+    NextBookmark, // Cycle through bookmarks in order.
+    DeleteBookmark {
+        name: String,
+    },
+    FlyTo {
+        target: FlyToTarget,
+        height: Option<f32>,
+        duration: Option<f32>,
+    },
+    SetTopView(Option<bool>), // `None` toggles.
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum FlyToTarget {
+    World(DVec3), // Absolute world position, as in session files.
+    Object(u32),  // Connectivity object ID, framed by its points.
+}
+
+pub struct CameraBookmarkPlugin;
+impl Plugin for CameraBookmarkPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraBookmarks>()
+            .init_resource::<CameraFlight>()
+            .add_event::<CameraEvent>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        handle_camera_events,
+                        animate_camera_flight,
+                        notify_bookmark_changes,
+                    )
+                        .chain()
+                        .before(camera_controller),
+                    sync_camera_projection.after(camera_controller),
+                )
+                    .run_if(in_state(AppState::Running)),
+            );
+    }
+}
+
+/// Applies camera requests. Native builds also save a bookmark with F2, fly to the
+/// next bookmark with F3 and toggle the top view with F4.
+pub fn handle_camera_events(
+    mut events: EventReader<CameraEvent>,
+    #[cfg(not(target_arch = "wasm32"))] keyboard: Res<ButtonInput<KeyCode>>,
+    viewport_camera: Option<ResMut<ViewportCamera>>,
+    mut bookmarks: ResMut<CameraBookmarks>,
+    mut flight: ResMut<CameraFlight>,
+    images: Res<Assets<Image>>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    let mut actions: Vec<CameraAction> = events.read().map(|e| e.action.clone()).collect();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if keyboard.just_pressed(KeyCode::F2) {
+            actions.push(CameraAction::SaveBookmark { name: None });
+        }
+        if keyboard.just_pressed(KeyCode::F3) {
+            actions.push(CameraAction::NextBookmark);
+        }
+        if keyboard.just_pressed(KeyCode::F4) {
+            actions.push(CameraAction::SetTopView(None));
+        }
+    }
+
+    if actions.is_empty() {
+        return;
+    }
+    let Some(mut viewport_camera) = viewport_camera else {
+        return;
+    };
+    let Some(bounds) = assets.get_bounds(&manifests) else {
+        return;
+    };
+    let (x, y, z) = bounds.bounds.origin;
+    let origin = DVec3::new(x, y, z);
+
+    for action in actions {
+        let (target, duration) = match action {
+            CameraAction::SaveBookmark { name } => {
+                let name = name.unwrap_or_else(|| bookmarks.unused_name());
+                bookmarks.save(&name, viewport_camera.view());
+                info!("Saved camera bookmark '{}'", name);
+                continue;
+            }
+            CameraAction::DeleteBookmark { name } => {
+                if !bookmarks.remove(&name) {
+                    send_camera_error(
+                        &mut rpc_interface,
+                        &format!("Bookmark '{}' not found", name),
+                    );
+                }
+                continue;
+            }
+            CameraAction::GoToBookmark { name, duration } => match bookmarks.get(&name) {
+                Some(bookmark) => (bookmark.view, duration),
+                None => {
+                    send_camera_error(
+                        &mut rpc_interface,
+                        &format!("Bookmark '{}' not found", name),
+                    );
+                    continue;
+                }
+            },
+            CameraAction::NextBookmark => {
+                if bookmarks.items.is_empty() {
+                    continue;
+                }
+                let index = bookmarks.next % bookmarks.items.len();
+                // Cycling isn't a change the frontend needs to hear about
+                bookmarks.bypass_change_detection().next = index + 1;
+                (bookmarks.items[index].view, None)
+            }
+            CameraAction::FlyTo {
+                target,
+                height,
+                duration,
+            } => {
+                let current = viewport_camera.view();
+                let (focus_point, framed_height) = match target {
+                    FlyToTarget::World(position) => ((position - origin).as_vec3(), current.height),
+                    FlyToTarget::Object(object_id) => {
+                        match object_bounds(&images, &assets, &bounds, object_id) {
+                            Some((min, max)) => (
+                                (min + max) * 0.5,
+                                ((max - min).max_element() * OBJECT_FRAMING).max(MIN_OBJECT_HEIGHT),
+                            ),
+                            None => {
+                                send_camera_error(
+                                    &mut rpc_interface,
+                                    &format!("Object {} not found", object_id),
+                                );
+                                continue;
+                            }
+                        }
+                    }
+                };
+                let view = CameraView {
+                    focus_point,
+                    height: height.unwrap_or(framed_height),
+                    ..current
+                };
+                (view, duration)
+            }
+            CameraAction::SetTopView(enabled) => {
+                flight.0 = None;
+                let enabled = enabled.unwrap_or(!viewport_camera.top_down);
+                viewport_camera.set_top_down(enabled);
+                info!("Top view {}", if enabled { "on" } else { "off" });
+                rpc_interface.send_notification(
+                    "camera_top_view_changed",
+                    serde_json::json!({ "enabled": enabled }),
+                );
+                continue;
+            }
+        };
+
+        let duration = duration.unwrap_or(DEFAULT_FLIGHT_SECONDS).max(0.0);
+        flight.0 = Some(Flight {
+            from: viewport_camera.view(),
+            to: target,
+            elapsed: 0.0,
+            duration,
+        });
+        rpc_interface.send_notification(
+            "camera_flight_started",
+            serde_json::json!({
+                "focus_point": (target.focus_point.as_dvec3() + origin).to_array(),
+                "height": target.height,
+                "duration": duration,
+            }),
+        );
+    }
+}
+
+/// Steps the current flight; the camera controller eases the transform as usual.
+pub fn animate_camera_flight(
+    time: Res<Time>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut flight: ResMut<CameraFlight>,
+    viewport_camera: Option<ResMut<ViewportCamera>>,
+) {
+    let Some(mut viewport_camera) = viewport_camera else {
+        return;
+    };
+    let Some(current) = flight.0.as_mut() else {
+        return;
+    };
+    if mouse.get_just_pressed().next().is_some() {
+        flight.0 = None;
+        return;
+    }
+
+    current.elapsed += time.delta_secs();
+    viewport_camera.set_view(&current.view());
+    if current.is_finished() {
+        flight.0 = None;
+    }
+}
+
+/// Switches the camera between perspective and the orthographic top view, and keeps
+/// the orthographic scale matched to the orbit height so zooming works the same.
+pub fn sync_camera_projection(
+    viewport_camera: Option<Res<ViewportCamera>>,
+    mut projections: Query<&mut Projection, With<Camera3d>>,
+) {
+    let Some(viewport_camera) = viewport_camera else {
+        return;
+    };
+    if !viewport_camera.is_changed() {
+        return;
+    }
+    let Ok(mut projection) = projections.single_mut() else {
+        return;
+    };
+
+    match (viewport_camera.top_down, projection.as_ref()) {
+        (true, _) => *projection = top_down_projection(viewport_camera.height),
+        (false, Projection::Orthographic(_)) => {
+            *projection = Projection::Perspective(PerspectiveProjection::default());
+        }
+        (false, _) => {}
+    }
+}
+
+/// Orthographic projection showing as much ground as the perspective view does at
+/// the focus point from `height`.
+fn top_down_projection(height: f32) -> Projection {
+    let fov = PerspectiveProjection::default().fov;
+    Projection::Orthographic(OrthographicProjection {
+        scaling_mode: ScalingMode::FixedVertical {
+            viewport_height: 2.0 * height * (fov * 0.5).tan(),
+        },
+        // Keep poles and canopies taller than the camera height in view.
+        near: -height,
+        far: height * 4.0,
+        ..OrthographicProjection::default_3d()
+    })
+}
+
+/// Render-space bounding box of the points carrying `object_id`.
+fn object_bounds(
+    images: &Assets<Image>,
+    assets: &PointCloudAssets,
+    bounds: &PointCloudBounds,
+    object_id: u32,
+) -> Option<(Vec3, Vec3)> {
+    let position_data = images
+        .get(&assets.position_texture)
+        .and_then(|image| image.data.as_deref())?;
+    let min = bounds.min_corner();
+    let extent = bounds.size();
+
+    (0..bounds.loaded_points)
+        .filter_map(|index| read_texel(position_data, index))
+        .filter(|&texel| object_id_of(texel) == object_id)
+        .map(|texel| min + Vec3::new(texel[0], texel[1], texel[2]) * extent)
+        .fold(None, |acc, p| match acc {
+            Some((low, high)) => Some((p.min(low), p.max(high))),
+            None => Some((p, p)),
+        })
+}
+
+/// Sends the bookmark list whenever it changes, including on session load.
+pub fn notify_bookmark_changes(
+    bookmarks: Res<CameraBookmarks>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    if !bookmarks.is_changed() {
+        return;
+    }
+    let Some(bounds) = assets.get_bounds(&manifests) else {
+        return;
+    };
+    let (x, y, z) = bounds.bounds.origin;
+    rpc_interface.send_notification(
+        "camera_bookmarks_changed",
+        serde_json::json!({ "bookmarks": bookmarks.summaries(DVec3::new(x, y, z)) }),
+    );
+}
+
+fn send_camera_error(rpc_interface: &mut WebRpcInterface, error: &str) {
+    warn!("Camera: {}", error);
+    rpc_interface.send_notification("camera_error", serde_json::json!({ "error": error }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(x: f32, yaw: f32, top_down: bool) -> CameraView {
+        CameraView {
+            focus_point: Vec3::new(x, 0.0, 0.0),
+            height: 10.0,
+            yaw,
+            pitch: -0.8,
+            top_down,
+        }
+    }
+
+    #[test]
+    fn flights_turn_the_short_way_and_land_on_target() {
+        let mut flight = Flight {
+            from: view(0.0, 3.0, false),
+            to: view(40.0, -3.0, true),
+            elapsed: 0.0,
+            duration: 2.0,
+        };
+
+        flight.elapsed = 1.0;
+        let halfway = flight.view();
+        // 3.0 to -3.0 is shorter through ±π than through zero
+        assert!(halfway.yaw.abs() > 3.0);
+        assert!((halfway.height - (10.0 + 40.0 * FLIGHT_ARC)).abs() < 1e-4);
+        assert!(!halfway.top_down);
+
+        flight.elapsed = 2.0;
+        let landed = flight.view();
+        assert!(flight.is_finished());
+        assert_eq!(landed.focus_point, Vec3::new(40.0, 0.0, 0.0));
+        assert_eq!(landed.height, 10.0);
+        assert!(landed.top_down);
+    }
+}
@@ -1,7 +1,18 @@
 //! Viewport camera system for point cloud scene navigation.
 //!
 //! Provides orbit camera controls with heightmap-aware ground plane intersection,
-//! smooth interpolation, and keyboard/mouse input handling.
+//! smooth interpolation, and keyboard/mouse input handling, plus named view
+//! bookmarks, animated fly-to transitions, an orthographic top view, a
+//! first-person walk mode at pedestrian eye height and keyframed camera paths.
+
+/// Camera bookmarks, animated fly-to and the orthographic top view toggle.
+pub mod bookmarks;
+
+/// Keyframed camera paths recorded from navigation or built from bookmarks.
+pub mod path;
 
 /// Viewport camera resource and controller system for scene navigation.
 pub mod viewport_camera;
+
+/// First-person walk mode that follows the ground at a configurable eye height.
+pub mod walk;
@@ -0,0 +1,492 @@
+use crate::engine::assets::point_cloud_assets::PointCloudAssets;
+use crate::engine::assets::scene_manifest::SceneManifest;
+use crate::engine::camera::bookmarks::{CameraBookmarks, CameraFlight};
+use crate::engine::camera::viewport_camera::{CameraView, ViewportCamera, camera_controller};
+use crate::engine::core::app_state::AppState;
+use crate::engine::render::frame_export::{FrameExport, FrameExportRequest};
+use crate::rpc::web_rpc::WebRpcInterface;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use std::f32::consts::{PI, TAU};
+
+/// Live navigation is sampled into a keyframe this often while recording.
+const RECORD_INTERVAL: f32 = 0.5;
+/// Gap between keyframes built from bookmarks or added without a time.
+const DEFAULT_SEGMENT_SECONDS: f32 = 3.0;
+const MIN_HEIGHT: f32 = 0.5;
+
+/// A view the camera passes through `time` seconds into the path.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraKeyframe {
+    pub time: f32,
+    pub view: CameraView,
+}
+
+/// Keyframed camera path, interpolated with a Catmull-Rom spline through the
+/// keyframes at their own times.
+#[derive(Resource, Default)]
+pub struct CameraPath {
+    /// Sorted by time.
+    pub keyframes: Vec<CameraKeyframe>,
+}
+
+impl CameraPath {
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |k| k.time)
+    }
+
+    /// Add `view` at `time`, or one segment after the last keyframe. A keyframe at
+    /// the same time is replaced.
+    pub fn insert(&mut self, view: CameraView, time: Option<f32>) {
+        let time = time.unwrap_or_else(|| match self.keyframes.last() {
+            Some(last) => last.time + DEFAULT_SEGMENT_SECONDS,
+            None => 0.0,
+        });
+        let index = self.keyframes.partition_point(|k| k.time < time);
+        match self.keyframes.get_mut(index) {
+            Some(existing) if existing.time == time => existing.view = view,
+            _ => self.keyframes.insert(index, CameraKeyframe { time, view }),
+        }
+    }
+
+    /// One keyframe per view, `seconds_per_view` apart.
+    pub fn from_views(views: impl IntoIterator<Item = CameraView>, seconds_per_view: f32) -> Self {
+        Self {
+            keyframes: views
+                .into_iter()
+                .enumerate()
+                .map(|(i, view)| CameraKeyframe {
+                    time: i as f32 * seconds_per_view,
+                    view,
+                })
+                .collect(),
+        }
+    }
+
+    /// Stretch or squash the keyframe times so the path lasts `duration` seconds.
+    pub fn retime(&mut self, duration: f32) {
+        let start = self.keyframes.first().map_or(0.0, |k| k.time);
+        let span = self.duration() - start;
+        if span <= 0.0 {
+            return;
+        }
+        for keyframe in &mut self.keyframes {
+            keyframe.time = (keyframe.time - start) / span * duration;
+        }
+    }
+
+    /// Interpolated view `time` seconds in, clamped to the ends of the path.
+    pub fn sample(&self, time: f32) -> Option<CameraView> {
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+        if self.keyframes.len() == 1 || time <= first.time {
+            return Some(first.view);
+        }
+        if time >= last.time {
+            return Some(last.view);
+        }
+
+        // Unwrap yaw so every segment turns the short way round
+        let mut controls: Vec<(f32, [f32; 6])> = Vec::with_capacity(self.keyframes.len());
+        for keyframe in &self.keyframes {
+            let view = &keyframe.view;
+            let yaw = match controls.last() {
+                Some((_, previous)) => {
+                    previous[4] + (view.yaw - previous[4] + PI).rem_euclid(TAU) - PI
+                }
+                None => view.yaw,
+            };
+            let p = view.focus_point;
+            let values = [p.x, p.y, p.z, view.height, yaw, view.effective_pitch()];
+            controls.push((keyframe.time, values));
+        }
+
+        let i = self.keyframes.partition_point(|k| k.time <= time) - 1;
+        let (t0, p0) = controls[i];
+        let (t1, p1) = controls[i + 1];
+        let m0 = tangent(&controls, i);
+        let m1 = tangent(&controls, i + 1);
+        let h = t1 - t0;
+        let s = if h > 0.0 { (time - t0) / h } else { 1.0 };
+
+        // Cubic Hermite basis
+        let s2 = s * s;
+        let s3 = s2 * s;
+        let h00 = 2.0 * s3 - 3.0 * s2 + 1.0;
+        let h10 = s3 - 2.0 * s2 + s;
+        let h01 = -2.0 * s3 + 3.0 * s2;
+        let h11 = s3 - s2;
+        let v: [f32; 6] =
+            std::array::from_fn(|c| h00 * p0[c] + h10 * h * m0[c] + h01 * p1[c] + h11 * h * m1[c]);
+
+        Some(CameraView {
+            focus_point: Vec3::new(v[0], v[1], v[2]),
+            height: v[3].max(MIN_HEIGHT),
+            yaw: v[4],
+            pitch: v[5],
+            top_down: self.keyframes[i].view.top_down && self.keyframes[i + 1].view.top_down,
+        })
+    }
+
+    /// RPC payload with focus points in absolute world coordinates.
+    pub fn summaries(&self, origin: DVec3) -> serde_json::Value {
+        self.keyframes
+            .iter()
+            .map(|k| {
+                serde_json::json!({
+                    "time": k.time,
+                    "focus_point": (k.view.focus_point.as_dvec3() + origin).to_array(),
+                    "height": k.view.height,
+                    "yaw": k.view.yaw,
+                    "pitch": k.view.pitch,
+                    "top_view": k.view.top_down,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Catmull-Rom tangent: the slope between the neighbouring keyframes, one-sided at
+/// the ends.
+fn tangent(controls: &[(f32, [f32; 6])], i: usize) -> [f32; 6] {
+    let before = controls[i.saturating_sub(1)];
+    let after = controls[(i + 1).min(controls.len() - 1)];
+    let dt = after.0 - before.0;
+    std::array::from_fn(|c| {
+        if dt > 0.0 {
+            (after.1[c] - before.1[c]) / dt
+        } else {
+            0.0
+        }
+    })
+}
+
+/// What the camera path is currently doing.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PathMode {
+    #[default]
+    Idle,
+    Recording {
+        elapsed: f32,
+        since_keyframe: f32,
+    },
+    Playing {
+        elapsed: f32,
+    },
+}
+
+impl PathMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Recording { .. } => "recording",
+            Self::Playing { .. } => "playing",
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct CameraPathState {
+    pub mode: PathMode,
+}
+
+/// Camera path requests from RPC or keyboard.
+#[derive(Event, Debug, Clone)]
+pub struct CameraPathEvent {
+    pub action: CameraPathAction,
+}
+
+#[derive(Debug, Clone)]
+pub enum CameraPathAction {
+    Record(Option<bool>), // Start or stop sampling live navigation; `None` toggles.
+    FromBookmarks {
+        names: Option<Vec<String>>, // `None` uses every bookmark in order.
+        seconds_per_view: Option<f32>,
+    },
+    AddKeyframe {
+        time: Option<f32>, // `None` appends one segment after the last keyframe.
+    },
+    SetDuration(f32),
+    Play,
+    Stop, // Stop recording, playback or a frame export.
+    Clear,
+    Export(FrameExportRequest),
+}
+
+pub struct CameraPathPlugin;
+impl Plugin for CameraPathPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraPath>()
+            .init_resource::<CameraPathState>()
+            .add_event::<CameraPathEvent>()
+            .add_systems(
+                Update,
+                (
+                    handle_camera_path_events,
+                    advance_camera_path,
+                    notify_camera_path_changes,
+                )
+                    .chain()
+                    .before(camera_controller)
+                    .run_if(in_state(AppState::Running)),
+            );
+    }
+}
+
+/// Applies camera path requests. Native builds also start and stop recording with F6
+/// and play the path with F7.
+pub fn handle_camera_path_events(
+    mut events: EventReader<CameraPathEvent>,
+    #[cfg(not(target_arch = "wasm32"))] keyboard: Res<ButtonInput<KeyCode>>,
+    mut path: ResMut<CameraPath>,
+    mut state: ResMut<CameraPathState>,
+    mut export: ResMut<FrameExport>,
+    mut flight: ResMut<CameraFlight>,
+    bookmarks: Res<CameraBookmarks>,
+    viewport_camera: Option<Res<ViewportCamera>>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    let mut actions: Vec<CameraPathAction> = events.read().map(|e| e.action.clone()).collect();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if keyboard.just_pressed(KeyCode::F6) {
+            actions.push(CameraPathAction::Record(None));
+        }
+        if keyboard.just_pressed(KeyCode::F7) {
+            actions.push(CameraPathAction::Play);
+        }
+    }
+
+    let Some(viewport_camera) = viewport_camera else {
+        return;
+    };
+
+    for action in actions {
+        match action {
+            CameraPathAction::Record(enabled) => {
+                let recording = matches!(state.mode, PathMode::Recording { .. });
+                let enabled = enabled.unwrap_or(!recording);
+                if enabled && !recording {
+                    path.keyframes.clear();
+                    path.insert(viewport_camera.view(), Some(0.0));
+                    state.mode = PathMode::Recording {
+                        elapsed: 0.0,
+                        since_keyframe: 0.0,
+                    };
+                    info!("Recording camera path");
+                } else if !enabled && recording {
+                    stop_recording(&mut path, &mut state, &viewport_camera);
+                }
+            }
+            CameraPathAction::FromBookmarks {
+                names,
+                seconds_per_view,
+            } => {
+                let views = match names {
+                    Some(names) => {
+                        match names
+                            .iter()
+                            .map(|name| bookmarks.get(name).map(|b| b.view).ok_or(name))
+                            .collect::<Result<Vec<_>, _>>()
+                        {
+                            Ok(views) => views,
+                            Err(name) => {
+                                send_path_error(
+                                    &mut rpc_interface,
+                                    &format!("Bookmark '{}' not found", name),
+                                );
+                                continue;
+                            }
+                        }
+                    }
+                    None => bookmarks.items.iter().map(|b| b.view).collect(),
+                };
+                if views.len() < 2 {
+                    send_path_error(&mut rpc_interface, "A path needs at least two bookmarks");
+                    continue;
+                }
+                state.mode = PathMode::Idle;
+                *path = CameraPath::from_views(
+                    views,
+                    seconds_per_view.unwrap_or(DEFAULT_SEGMENT_SECONDS),
+                );
+            }
+            CameraPathAction::AddKeyframe { time } => {
+                path.insert(viewport_camera.view(), time);
+            }
+            CameraPathAction::SetDuration(duration) => {
+                if path.keyframes.len() < 2 {
+                    send_path_error(&mut rpc_interface, "The path has no keyframes to retime");
+                    continue;
+                }
+                path.retime(duration);
+            }
+            CameraPathAction::Play => {
+                if matches!(state.mode, PathMode::Recording { .. }) {
+                    stop_recording(&mut path, &mut state, &viewport_camera);
+                }
+                if path.keyframes.len() < 2 {
+                    send_path_error(&mut rpc_interface, "The path needs at least two keyframes");
+                    continue;
+                }
+                flight.0 = None;
+                state.mode = PathMode::Playing { elapsed: 0.0 };
+            }
+            CameraPathAction::Stop => {
+                if matches!(state.mode, PathMode::Recording { .. }) {
+                    stop_recording(&mut path, &mut state, &viewport_camera);
+                }
+                state.mode = PathMode::Idle;
+                export.cancel();
+            }
+            CameraPathAction::Clear => {
+                state.mode = PathMode::Idle;
+                path.keyframes.clear();
+            }
+            CameraPathAction::Export(request) => {
+                if path.keyframes.len() < 2 {
+                    send_path_error(&mut rpc_interface, "The path needs at least two keyframes");
+                    continue;
+                }
+                if export.is_running() {
+                    send_path_error(&mut rpc_interface, "A frame export is already running");
+                    continue;
+                }
+                state.mode = PathMode::Idle;
+                export.start(request, path.duration());
+            }
+        }
+    }
+}
+
+/// Keep the last stretch of the recording even if it is shorter than an interval.
+fn stop_recording(path: &mut CameraPath, state: &mut CameraPathState, camera: &ViewportCamera) {
+    if let PathMode::Recording { elapsed, .. } = state.mode
+        && elapsed > path.duration()
+    {
+        path.insert(camera.view(), Some(elapsed));
+    }
+    state.mode = PathMode::Idle;
+    info!(
+        "Recorded camera path: {} keyframes over {:.1} s",
+        path.keyframes.len(),
+        path.duration()
+    );
+}
+
+/// Samples live navigation while recording and moves the camera along the path while
+/// playing. Clicking in the viewport stops playback.
+pub fn advance_camera_path(
+    time: Res<Time>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut path: ResMut<CameraPath>,
+    mut state: ResMut<CameraPathState>,
+    viewport_camera: Option<ResMut<ViewportCamera>>,
+) {
+    let Some(mut viewport_camera) = viewport_camera else {
+        return;
+    };
+    let dt = time.delta_secs();
+
+    match state.mode {
+        PathMode::Idle => {}
+        PathMode::Recording {
+            elapsed,
+            since_keyframe,
+        } => {
+            let elapsed = elapsed + dt;
+            let mut since_keyframe = since_keyframe + dt;
+            if since_keyframe >= RECORD_INTERVAL {
+                path.insert(viewport_camera.view(), Some(elapsed));
+                since_keyframe = 0.0;
+            }
+            // The clock ticks every frame; only keyframes are news to the frontend
+            state.bypass_change_detection().mode = PathMode::Recording {
+                elapsed,
+                since_keyframe,
+            };
+        }
+        PathMode::Playing { elapsed } => {
+            if mouse.get_just_pressed().next().is_some() {
+                state.mode = PathMode::Idle;
+                return;
+            }
+            let elapsed = elapsed + dt;
+            if let Some(view) = path.sample(elapsed) {
+                viewport_camera.set_view(&view);
+            }
+            if elapsed >= path.duration() {
+                state.mode = PathMode::Idle;
+            } else {
+                state.bypass_change_detection().mode = PathMode::Playing { elapsed };
+            }
+        }
+    }
+}
+
+/// Sends the keyframes and mode whenever either changes.
+pub fn notify_camera_path_changes(
+    path: Res<CameraPath>,
+    state: Res<CameraPathState>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    if !path.is_changed() && !state.is_changed() {
+        return;
+    }
+    let Some(bounds) = assets.get_bounds(&manifests) else {
+        return;
+    };
+    let (x, y, z) = bounds.bounds.origin;
+    rpc_interface.send_notification(
+        "camera_path_changed",
+        serde_json::json!({
+            "keyframes": path.summaries(DVec3::new(x, y, z)),
+            "duration": path.duration(),
+            "state": state.mode.as_str(),
+        }),
+    );
+}
+
+fn send_path_error(rpc_interface: &mut WebRpcInterface, error: &str) {
+    warn!("Camera path: {}", error);
+    rpc_interface.send_notification("camera_error", serde_json::json!({ "error": error }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(x: f32, yaw: f32) -> CameraView {
+        CameraView {
+            focus_point: Vec3::new(x, 0.0, 0.0),
+            height: 10.0,
+            yaw,
+            pitch: -0.8,
+            top_down: false,
+        }
+    }
+
+    #[test]
+    fn spline_passes_through_keyframes_at_their_times() {
+        let mut path = CameraPath::from_views([view(0.0, 3.0), view(10.0, -3.0)], 2.0);
+        path.insert(view(30.0, -2.5), Some(6.0));
+
+        for keyframe in &path.keyframes {
+            let sampled = path.sample(keyframe.time).unwrap();
+            assert!((sampled.focus_point - keyframe.view.focus_point).length() < 1e-4);
+        }
+        // Between 3.0 and -3.0 the camera turns through ±π rather than through zero
+        assert!(path.sample(1.0).unwrap().yaw.abs() > 3.0);
+        // Keyframes are unevenly spaced, so speed follows the timing
+        let early = path.sample(1.0).unwrap().focus_point.x;
+        assert!(early > 0.0 && early < 10.0);
+
+        path.retime(3.0);
+        assert_eq!(path.duration(), 3.0);
+        assert_eq!(path.keyframes[1].time, 1.0);
+        assert_eq!(path.sample(10.0).unwrap().focus_point.x, 30.0);
+    }
+}
@@ -7,6 +7,11 @@ use bevy::{
     input::mouse::{MouseMotion, MouseWheel},
     prelude::*,
 };
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+/// Pitch range of the perspective orbit; only the top view looks straight down.
+pub const MIN_ORBIT_PITCH: f32 = -1.4;
+pub const MAX_ORBIT_PITCH: f32 = -0.1;
 
 #[derive(Resource)]
 pub struct ViewportCamera {
@@ -19,6 +24,58 @@ pub struct ViewportCamera {
     // Add smoothing for intersection
     pub last_intersection: Option<Vec3>,
     pub intersection_smooth_factor: f32,
+    /// Straight-down orthographic view for plan-style drawing; `pitch` is kept for
+    /// when the perspective view returns.
+    pub top_down: bool,
+}
+
+/// Orbit parameters that fully describe a viewpoint, in render space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CameraView {
+    pub focus_point: Vec3,
+    pub height: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub top_down: bool,
+}
+
+impl CameraView {
+    /// Interpolate towards `to`, turning the short way round. Transitions use the
+    /// perspective view unless both ends are top-down.
+    pub fn lerp(&self, to: &CameraView, t: f32) -> CameraView {
+        let yaw_delta = (to.yaw - self.yaw + PI).rem_euclid(TAU) - PI;
+        CameraView {
+            focus_point: self.focus_point.lerp(to.focus_point, t),
+            height: lerp(self.height, to.height, t),
+            yaw: self.yaw + yaw_delta * t,
+            pitch: lerp(self.effective_pitch(), to.effective_pitch(), t),
+            top_down: if t < 1.0 {
+                self.top_down && to.top_down
+            } else {
+                to.top_down
+            },
+        }
+    }
+
+    /// Pitch the camera actually uses; the top view always looks straight down.
+    pub fn effective_pitch(&self) -> f32 {
+        if self.top_down {
+            -FRAC_PI_2
+        } else {
+            self.pitch
+        }
+    }
+
+    /// Camera transform for this view, orbiting `height` away from the focus point.
+    pub fn transform(&self) -> Transform {
+        // Yaw around the Y axis, then pitch around the local X axis. The orbit rotation
+        // already faces the focus point, and unlike `looking_at` it stays well defined
+        // when looking straight down
+        let rotation =
+            Quat::from_rotation_y(self.yaw) * Quat::from_rotation_x(self.effective_pitch());
+        let offset = rotation * Vec3::new(0.0, 0.0, self.height);
+        Transform::from_translation(self.focus_point + offset).with_rotation(rotation)
+    }
 }
 
 impl ViewportCamera {
@@ -37,6 +94,36 @@ impl ViewportCamera {
             pitch: -0.785,
             last_intersection: None,
             intersection_smooth_factor: 0.3,
+            top_down: false,
+        }
+    }
+
+    pub fn view(&self) -> CameraView {
+        CameraView {
+            focus_point: self.focus_point,
+            height: self.height,
+            yaw: self.yaw,
+            pitch: self.pitch,
+            top_down: self.top_down,
+        }
+    }
+
+    /// Jump to `view`; the controller still eases the transform towards it.
+    pub fn set_view(&mut self, view: &CameraView) {
+        self.focus_point = view.focus_point;
+        self.height = view.height;
+        self.yaw = view.yaw;
+        self.pitch = view.pitch.clamp(-FRAC_PI_2, MAX_ORBIT_PITCH);
+        self.top_down = view.top_down;
+        self.last_intersection = None;
+    }
+
+    /// Switch the top view on or off. Leaving it brings the kept pitch back into the
+    /// orbit range.
+    pub fn set_top_down(&mut self, enabled: bool) {
+        self.top_down = enabled;
+        if !enabled {
+            self.pitch = self.pitch.clamp(MIN_ORBIT_PITCH, MAX_ORBIT_PITCH);
         }
     }
 
@@ -268,15 +355,18 @@ pub fn camera_controller(
             return;
         };
 
-        // Control yaw
-        if keyboard.pressed(KeyCode::PageDown) || keyboard.pressed(KeyCode::KeyF) {
+        // Control yaw (pitch is fixed straight down in the top view)
+        let can_pitch = !viewport_camera.top_down;
+        if can_pitch && (keyboard.pressed(KeyCode::PageDown) || keyboard.pressed(KeyCode::KeyF)) {
             let rotation_speed = 1.0 * time.delta_secs();
-            viewport_camera.pitch = (viewport_camera.pitch + rotation_speed).clamp(-1.4, -0.1);
+            viewport_camera.pitch =
+                (viewport_camera.pitch + rotation_speed).clamp(MIN_ORBIT_PITCH, MAX_ORBIT_PITCH);
         }
 
-        if keyboard.pressed(KeyCode::PageUp) || keyboard.pressed(KeyCode::KeyR) {
+        if can_pitch && (keyboard.pressed(KeyCode::PageUp) || keyboard.pressed(KeyCode::KeyR)) {
             let rotation_speed = 1.0 * time.delta_secs();
-            viewport_camera.pitch = (viewport_camera.pitch - rotation_speed).clamp(-1.4, -0.1);
+            viewport_camera.pitch =
+                (viewport_camera.pitch - rotation_speed).clamp(MIN_ORBIT_PITCH, MAX_ORBIT_PITCH);
         }
 
         // WASD camera focus point update
@@ -332,16 +422,7 @@ pub fn camera_controller(
         }
 
         // Simple camera positioning
-        let yaw_rot = Quat::from_rotation_y(viewport_camera.yaw);
-        let pitch_rot = Quat::from_rotation_x(viewport_camera.pitch);
-        // Combine rotations: yaw around Y axis, then pitch around local X axis
-        let combined_rot = yaw_rot * pitch_rot;
-
-        let offset = combined_rot * Vec3::new(0.0, 0.0, viewport_camera.height);
-        let target_pos = viewport_camera.focus_point + offset;
-
-        let target_transform = Transform::from_translation(target_pos)
-            .looking_at(viewport_camera.focus_point, Vec3::Y);
+        let target_transform = viewport_camera.view().transform();
 
         // Smooth interpolation
         let lerp_speed = 24.0 * time.delta_secs();
@@ -0,0 +1,399 @@
+use crate::engine::assets::bounds::PointCloudBounds;
+use crate::engine::assets::point_cloud_assets::PointCloudAssets;
+use crate::engine::assets::scene_manifest::SceneManifest;
+use crate::engine::camera::bookmarks::CameraFlight;
+use crate::engine::camera::path::{CameraPathState, PathMode};
+use crate::engine::camera::viewport_camera::ViewportCamera;
+use crate::engine::core::app_state::AppState;
+use crate::engine::scene::heightmap::ground_below;
+use crate::rpc::web_rpc::WebRpcInterface;
+use crate::tools::point_pick::PointPicker;
+use bevy::input::mouse::MouseMotion;
+use bevy::prelude::*;
+use constants::class::BUILDING_CLASSIFICATIONS;
+
+/// Standing eye height of an adult pedestrian, in metres.
+pub const PEDESTRIAN_EYE_HEIGHT: f32 = 1.6;
+/// Seated eye height of a wheelchair user, in metres.
+pub const WHEELCHAIR_EYE_HEIGHT: f32 = 1.2;
+/// Typical walking pace, in metres per second.
+const DEFAULT_WALK_SPEED: f32 = 1.4;
+/// Holding Shift moves this many times faster.
+const RUN_MULTIPLIER: f32 = 3.0;
+/// Horizontal clearance kept from building points.
+const BODY_RADIUS: f32 = 0.3;
+/// Building points lower than this above the ground, such as plinths, don't block.
+const STEP_HEIGHT: f32 = 0.3;
+/// Radians of turn per pixel of mouse movement.
+const LOOK_SENSITIVITY: f32 = 0.003;
+const PITCH_LIMIT: f32 = 1.4;
+/// The eye follows the ground at this rate, smoothing kerb steps.
+const HEIGHT_FOLLOW_RATE: f32 = 10.0;
+
+/// Named eye heights; setting an explicit height switches to `Custom`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EyeHeightPreset {
+    Pedestrian,
+    Wheelchair,
+    Custom,
+}
+
+impl EyeHeightPreset {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "pedestrian" => Some(Self::Pedestrian),
+            "wheelchair" => Some(Self::Wheelchair),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pedestrian => "pedestrian",
+            Self::Wheelchair => "wheelchair",
+            Self::Custom => "custom",
+        }
+    }
+
+    pub fn eye_height(&self) -> Option<f32> {
+        match self {
+            Self::Pedestrian => Some(PEDESTRIAN_EYE_HEIGHT),
+            Self::Wheelchair => Some(WHEELCHAIR_EYE_HEIGHT),
+            Self::Custom => None,
+        }
+    }
+}
+
+/// First-person walk mode. While active the orbit controller is paused and the camera
+/// stays `eye_height` above the heightmap.
+#[derive(Resource)]
+pub struct WalkMode {
+    pub active: bool,
+    pub preset: EyeHeightPreset,
+    pub eye_height: f32,
+    /// Walking speed in metres per second.
+    pub speed: f32,
+    /// Ground point under the walker, in render space.
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    /// Eye elevation, eased towards the ground plus `eye_height`.
+    eye_y: f32,
+}
+
+impl Default for WalkMode {
+    fn default() -> Self {
+        Self {
+            active: false,
+            preset: EyeHeightPreset::Pedestrian,
+            eye_height: PEDESTRIAN_EYE_HEIGHT,
+            speed: DEFAULT_WALK_SPEED,
+            position: Vec3::ZERO,
+            yaw: 0.0,
+            pitch: 0.0,
+            eye_y: 0.0,
+        }
+    }
+}
+
+impl WalkMode {
+    /// Settings summary for the frontend.
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "enabled": self.active,
+            "preset": self.preset.as_str(),
+            "eye_height": self.eye_height,
+            "speed": self.speed,
+        })
+    }
+}
+
+/// Run condition for the orbit controller.
+pub fn walk_inactive(walk: Res<WalkMode>) -> bool {
+    !walk.active
+}
+
+/// Walk mode requests from RPC or keyboard.
+#[derive(Event, Debug, Clone)]
+pub struct WalkModeEvent {
+    pub action: WalkAction,
+}
+
+#[derive(Debug, Clone)]
+pub enum WalkAction {
+    Toggle,
+    /// Fields left as `None` keep their current value; `eye_height` overrides `preset`.
+    Set {
+        enabled: Option<bool>,
+        preset: Option<EyeHeightPreset>,
+        eye_height: Option<f32>,
+        speed: Option<f32>,
+    },
+}
+
+pub struct WalkModePlugin;
+impl Plugin for WalkModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WalkMode>()
+            .add_event::<WalkModeEvent>()
+            .add_systems(
+                Update,
+                (handle_walk_events, walk_controller)
+                    .chain()
+                    .run_if(in_state(AppState::Running)),
+            );
+    }
+}
+
+/// Applies walk mode requests. Native builds also toggle walking with H and switch
+/// between the pedestrian and wheelchair eye heights with Shift+H.
+pub fn handle_walk_events(
+    mut events: EventReader<WalkModeEvent>,
+    #[cfg(not(target_arch = "wasm32"))] keyboard: Res<ButtonInput<KeyCode>>,
+    mut walk: ResMut<WalkMode>,
+    viewport_camera: Option<ResMut<ViewportCamera>>,
+    mut flight: ResMut<CameraFlight>,
+    path_state: Res<CameraPathState>,
+    images: Res<Assets<Image>>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    let mut actions: Vec<WalkAction> = events.read().map(|e| e.action.clone()).collect();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if keyboard.just_pressed(KeyCode::KeyH) {
+            if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+                let preset = match walk.preset {
+                    EyeHeightPreset::Wheelchair => EyeHeightPreset::Pedestrian,
+                    _ => EyeHeightPreset::Wheelchair,
+                };
+                actions.push(WalkAction::Set {
+                    enabled: None,
+                    preset: Some(preset),
+                    eye_height: None,
+                    speed: None,
+                });
+            } else {
+                actions.push(WalkAction::Toggle);
+            }
+        }
+    }
+
+    // Flying somewhere, playing a camera path or switching to the top view hands control
+    // back to the orbit camera
+    let top_down = viewport_camera
+        .as_ref()
+        .is_some_and(|camera| camera.top_down);
+    let playing = matches!(path_state.mode, PathMode::Playing { .. });
+    if walk.active && (flight.0.is_some() || playing || top_down) {
+        actions.push(WalkAction::Set {
+            enabled: Some(false),
+            preset: None,
+            eye_height: None,
+            speed: None,
+        });
+    }
+
+    if actions.is_empty() {
+        return;
+    }
+    let Some(mut viewport_camera) = viewport_camera else {
+        return;
+    };
+    let Some(bounds) = assets.get_bounds(&manifests) else {
+        return;
+    };
+    let heightmap = images.get(&assets.heightmap_texture);
+
+    for action in actions {
+        let enabled = match action {
+            WalkAction::Toggle => !walk.active,
+            WalkAction::Set {
+                enabled,
+                preset,
+                eye_height,
+                speed,
+            } => {
+                if let Some(preset) = preset
+                    && let Some(height) = preset.eye_height()
+                {
+                    walk.preset = preset;
+                    walk.eye_height = height;
+                }
+                if let Some(height) = eye_height {
+                    walk.preset = EyeHeightPreset::Custom;
+                    walk.eye_height = height;
+                }
+                if let Some(speed) = speed {
+                    walk.speed = speed;
+                }
+                enabled.unwrap_or(walk.active)
+            }
+        };
+
+        if enabled && !walk.active {
+            // Start on the ground at the orbit focus, facing the way the orbit camera did
+            let focus = viewport_camera.focus_point;
+            let ground = ground_below(focus, heightmap, &bounds);
+            walk.position = Vec3::new(focus.x, ground, focus.z);
+            walk.eye_y = ground + walk.eye_height;
+            walk.yaw = viewport_camera.yaw;
+            walk.pitch = 0.0;
+            flight.0 = None;
+            if viewport_camera.top_down {
+                viewport_camera.set_top_down(false);
+                rpc_interface.send_notification(
+                    "camera_top_view_changed",
+                    serde_json::json!({ "enabled": false }),
+                );
+            }
+        } else if !enabled && walk.active {
+            // Hand back to the orbit camera centred on where the walk ended
+            viewport_camera.focus_point = walk.position;
+            viewport_camera.yaw = walk.yaw;
+            viewport_camera.last_intersection = None;
+        }
+        walk.active = enabled;
+
+        info!(
+            "Walk mode {} ({}, eye height {:.2} m, {:.1} m/s)",
+            if walk.active { "on" } else { "off" },
+            walk.preset.as_str(),
+            walk.eye_height,
+            walk.speed
+        );
+        rpc_interface.send_notification("walk_mode_changed", walk.summary());
+    }
+}
+
+/// First-person movement: WASD walks, Q/E and right-drag turn, Shift runs. Steps that
+/// would bring the body within `BODY_RADIUS` of a building point slide along it instead.
+pub fn walk_controller(
+    mut walk: ResMut<WalkMode>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    time: Res<Time>,
+    mut cameras: Query<&mut Transform, With<Camera3d>>,
+    picker: Res<PointPicker>,
+    images: Res<Assets<Image>>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+) {
+    // Drain motion while inactive so turning doesn't jump on entry
+    let motion: Vec2 = mouse_motion.read().map(|m| m.delta).sum();
+    if !walk.active {
+        return;
+    }
+    let Ok(mut transform) = cameras.single_mut() else {
+        return;
+    };
+    let Some(bounds) = assets.get_bounds(&manifests) else {
+        return;
+    };
+    let heightmap = images.get(&assets.heightmap_texture);
+    let dt = time.delta_secs();
+
+    if mouse_button.pressed(MouseButton::Right) {
+        walk.yaw -= motion.x * LOOK_SENSITIVITY;
+        walk.pitch = (walk.pitch - motion.y * LOOK_SENSITIVITY).clamp(-PITCH_LIMIT, PITCH_LIMIT);
+    }
+    if keyboard.pressed(KeyCode::KeyQ) {
+        walk.yaw += dt;
+    }
+    if keyboard.pressed(KeyCode::KeyE) {
+        walk.yaw -= dt;
+    }
+
+    let yaw_rot = Quat::from_rotation_y(walk.yaw);
+    let forward = yaw_rot * Vec3::NEG_Z;
+    let right = yaw_rot * Vec3::X;
+    let mut direction = Vec3::ZERO;
+    for (key, axis) in [
+        (KeyCode::KeyW, forward),
+        (KeyCode::KeyS, -forward),
+        (KeyCode::KeyD, right),
+        (KeyCode::KeyA, -right),
+    ] {
+        if keyboard.pressed(key) {
+            direction += axis;
+        }
+    }
+
+    if direction != Vec3::ZERO {
+        let running = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        let speed = walk.speed * if running { RUN_MULTIPLIER } else { 1.0 };
+        let step = direction.normalize() * speed * dt;
+        let eye_height = walk.eye_height;
+        // Building points within the body, from step height up to the eye
+        let blocked = |position: Vec3| {
+            let ground = ground_below(position, heightmap, &bounds);
+            let reach = Vec3::new(BODY_RADIUS, 0.0, BODY_RADIUS);
+            picker.any_in_box(
+                Vec3::new(position.x, ground + STEP_HEIGHT, position.z) - reach,
+                Vec3::new(position.x, ground + eye_height, position.z) + reach,
+                &images,
+                &assets,
+                &bounds,
+                |class| BUILDING_CLASSIFICATIONS.iter().any(|&c| c as u32 == class),
+            )
+        };
+
+        // Don't trap a walker who starts inside a building
+        let start = walk.position;
+        let stuck = blocked(start);
+        let candidates = [
+            step,
+            Vec3::new(step.x, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, step.z),
+        ];
+        if let Some(next) = candidates
+            .into_iter()
+            .map(|offset| clamp_to_bounds(start + offset, &bounds))
+            .find(|&next| stuck || !blocked(next))
+        {
+            walk.position = next;
+        }
+    }
+
+    let ground = ground_below(walk.position, heightmap, &bounds);
+    walk.position.y = ground;
+    let target_eye = ground + walk.eye_height;
+    let follow = 1.0 - (-HEIGHT_FOLLOW_RATE * dt).exp();
+    walk.eye_y += (target_eye - walk.eye_y) * follow;
+
+    transform.translation = Vec3::new(walk.position.x, walk.eye_y, walk.position.z);
+    transform.rotation = yaw_rot * Quat::from_rotation_x(walk.pitch);
+}
+
+fn clamp_to_bounds(position: Vec3, bounds: &PointCloudBounds) -> Vec3 {
+    Vec3::new(
+        position
+            .x
+            .clamp(bounds.min_x() as f32, bounds.max_x() as f32),
+        position.y,
+        position
+            .z
+            .clamp(bounds.min_z() as f32, bounds.max_z() as f32),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_parse_and_only_named_ones_carry_a_height() {
+        assert_eq!(
+            EyeHeightPreset::from_string("Wheelchair"),
+            Some(EyeHeightPreset::Wheelchair)
+        );
+        assert_eq!(EyeHeightPreset::from_string("custom"), None);
+        for preset in [EyeHeightPreset::Pedestrian, EyeHeightPreset::Wheelchair] {
+            assert_eq!(EyeHeightPreset::from_string(preset.as_str()), Some(preset));
+        }
+        assert_eq!(EyeHeightPreset::Custom.eye_height(), None);
+    }
+}
@@ -0,0 +1,1010 @@
+use crate::engine::assets::bounds::BoundsData;
+use crate::engine::assets::point_cloud_assets::read_texel;
+use crate::engine::compute::compute_classification::{
+    GpuPolygonInfo, PackedPolygons, edge_bin_index, pack_polygons,
+};
+use crate::tools::polygon::ClassificationPolygon;
+use bevy::prelude::*;
+use constants::class::decode_object_id;
+
+// Mirrors of the constants in `modified_classification.wgsl`.
+const AABB_MARGIN: f32 = 1.0;
+/// Final class written for hidden points.
+pub const HIDDEN_CLASS: u32 = 254;
+
+/// CPU implementation of the hide/reclassify pass in `modified_classification.wgsl`.
+///
+/// Polygons are packed with the same `pack_polygons` layout that is uploaded to the GPU
+/// and evaluated with the same f32 arithmetic, so results match the shader's
+/// modified classification output point for point. `matches_shader_on_gpu` checks this
+/// against the real shader when a GPU adapter is available.
+pub struct ClassificationReference {
+    packed: PackedPolygons,
+    bounds_min: Vec3,
+    bounds_max: Vec3,
+    heightmap: Vec<u8>, // R32F normalised ground elevation, `heightmap_size` texels square.
+    heightmap_size: u32,
+}
+
+impl ClassificationReference {
+    pub fn new(polygons: &[ClassificationPolygon], bounds: &BoundsData) -> Self {
+        Self {
+            packed: pack_polygons(polygons, bounds),
+            bounds_min: Vec3::new(
+                bounds.min_x as f32,
+                bounds.min_y as f32,
+                bounds.min_z as f32,
+            ),
+            bounds_max: Vec3::new(
+                bounds.max_x as f32,
+                bounds.max_y as f32,
+                bounds.max_z as f32,
+            ),
+            heightmap: Vec::new(),
+            heightmap_size: 1,
+        }
+    }
+
+    /// Ground heightmap for ground-relative height ranges; without one the ground is
+    /// flat at the bottom of the bounds.
+    pub fn with_heightmap(mut self, heightmap: &[u8], size: u32) -> Self {
+        self.heightmap = heightmap.to_vec();
+        self.heightmap_size = size.max(1);
+        self
+    }
+
+    pub fn has_heightmap(&self) -> bool {
+        !self.heightmap.is_empty()
+    }
+
+    /// Final class for one point from its texel index and its position and colour texels;
+    /// `254` when hidden. The index is only used by screen-space selection polygons.
+    pub fn classify(&self, texel: u32, position: [f32; 4], colour: [f32; 4]) -> u32 {
+        let object_id = object_id_of(position);
+        let original_class = (colour[3] * 255.0) as u32;
+        let normalised = Vec3::new(position[0], position[1], position[2]);
+        let world = self.bounds_min + normalised * (self.bounds_max - self.bounds_min);
+        let point = world.xz();
+
+        // Later polygons take priority; the first match ends the modifier stack.
+        for info in self.packed.info.iter().rev() {
+            if point_near_aabb(point, info)
+                && self.point_in_footprint(point, texel, info)
+                && self.point_in_height_range(world, info)
+                && self.mask_matches(info, original_class, object_id)
+            {
+                return if info.mode == 1 {
+                    info.new_class
+                } else {
+                    HIDDEN_CLASS
+                };
+            }
+        }
+
+        original_class
+    }
+
+    /// Classify the first `point_count` texels of RGBA32F position and colour data.
+    /// Stops early if either buffer runs out.
+    pub fn classify_texels(
+        &self,
+        position_data: &[u8],
+        colour_data: &[u8],
+        point_count: usize,
+    ) -> Vec<u32> {
+        (0..point_count)
+            .map_while(|index| {
+                let position = read_texel(position_data, index)?;
+                let colour = read_texel(colour_data, index)?;
+                Some(self.classify(index as u32, position, colour))
+            })
+            .collect()
+    }
+
+    fn mask_matches(&self, info: &GpuPolygonInfo, class_id: u32, object_id: u32) -> bool {
+        let start = info.mask_start as usize;
+        self.packed.masks[start..start + info.mask_count as usize]
+            .iter()
+            .any(|&[mask_class, mask_object]| mask_class == class_id && mask_object == object_id)
+    }
+
+    /// Vertical band of the polygon prism; mode 0 keeps the full column.
+    fn point_in_height_range(&self, world: Vec3, info: &GpuPolygonInfo) -> bool {
+        match info.height_mode {
+            1 => world.y >= info.height_min && world.y <= info.height_max,
+            2 => {
+                let ground = self.ground_height(world.xz());
+                world.y >= ground + info.height_min && world.y <= ground + info.height_max
+            }
+            _ => true,
+        }
+    }
+
+    /// Bilinear heightmap sample at a world XZ position, denormalised to world Y.
+    fn ground_height(&self, point: Vec2) -> f32 {
+        let last = self.heightmap_size - 1;
+        let norm = (point - self.bounds_min.xz()) / (self.bounds_max.xz() - self.bounds_min.xz());
+        let pixel = norm * last as f32;
+
+        let p0 = pixel
+            .floor()
+            .max(Vec2::ZERO)
+            .as_uvec2()
+            .min(UVec2::splat(last));
+        let p1 = (p0 + UVec2::ONE).min(UVec2::splat(last));
+        let weight = pixel - p0.as_vec2();
+
+        let h00 = self.height_texel(p0.x, p0.y);
+        let h10 = self.height_texel(p1.x, p0.y);
+        let h01 = self.height_texel(p0.x, p1.y);
+        let h11 = self.height_texel(p1.x, p1.y);
+
+        let h_top = h00 * (1.0 - weight.x) + h10 * weight.x;
+        let h_bottom = h01 * (1.0 - weight.x) + h11 * weight.x;
+        let normalised = h_top * (1.0 - weight.y) + h_bottom * weight.y;
+        self.bounds_min.y + normalised * (self.bounds_max.y - self.bounds_min.y)
+    }
+
+    fn height_texel(&self, x: u32, z: u32) -> f32 {
+        let index = (z * self.heightmap_size + x) as usize * 4;
+        self.heightmap.get(index..index + 4).map_or(0.0, |bytes| {
+            f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+        })
+    }
+
+    /// Selection polygons list their texels; everything else uses the drawn footprint.
+    fn point_in_footprint(&self, point: Vec2, texel: u32, info: &GpuPolygonInfo) -> bool {
+        if info.selection_count == 0 {
+            return self.point_in_polygon(point, info);
+        }
+        let start = info.selection_start as usize;
+        self.packed.selections[start..start + info.selection_count as usize]
+            .binary_search(&texel)
+            .is_ok()
+    }
+
+    /// Crossing test over the edges in the point's Z slab, in the shader's operation order.
+    fn point_in_polygon(&self, point: Vec2, info: &GpuPolygonInfo) -> bool {
+        let [_, min_z, _, max_z] = info.aabb;
+        if info.bin_count == 0 || point.y < min_z || point.y > max_z {
+            return false;
+        }
+
+        let header =
+            (info.bin_start + edge_bin_index(point.y, min_z, max_z, info.bin_count) * 2) as usize;
+        let edge_offset = self.packed.edge_bins[header] as usize;
+        let edge_count = self.packed.edge_bins[header + 1] as usize;
+
+        let mut inside = false;
+        for &edge in &self.packed.edge_bins[edge_offset..edge_offset + edge_count] {
+            let next = if edge + 1 == info.point_count {
+                0
+            } else {
+                edge + 1
+            };
+            let current = self.packed.points[(info.point_start + edge) as usize];
+            let previous = self.packed.points[(info.point_start + next) as usize];
+
+            if ((current[1] > point.y) != (previous[1] > point.y))
+                && (point.x
+                    < (previous[0] - current[0]) * (point.y - current[1])
+                        / (previous[1] - current[1])
+                        + current[0])
+            {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+}
+
+/// Connectivity object ID packed into a position texel's alpha channel.
+pub fn object_id_of(position: [f32; 4]) -> u32 {
+    decode_object_id(position[3])
+}
+
+fn point_near_aabb(point: Vec2, info: &GpuPolygonInfo) -> bool {
+    if info.point_count == 0 {
+        return false;
+    }
+    let [min_x, min_z, max_x, max_z] = info.aabb;
+    point.x >= min_x - AABB_MARGIN
+        && point.x <= max_x + AABB_MARGIN
+        && point.y >= min_z - AABB_MARGIN
+        && point.y <= max_z + AABB_MARGIN
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::polygon::{
+        HeightReference, PolygonMode, VerticalRange, resample_polygon_uniform,
+    };
+    use constants::class::encode_object_id;
+
+    const UNCLASSIFIED: u32 = 1;
+    const SIDEWALK: u32 = 2;
+    const CARS: u32 = 15;
+
+    fn bounds() -> BoundsData {
+        BoundsData {
+            origin: (0.0, 0.0, 0.0),
+            min_x: 0.0,
+            max_x: 100.0,
+            min_y: 0.0,
+            max_y: 10.0,
+            min_z: 0.0,
+            max_z: 100.0,
+        }
+    }
+
+    fn square(
+        id: u32,
+        min: f32,
+        max: f32,
+        mode: PolygonMode,
+        new_class: u32,
+    ) -> ClassificationPolygon {
+        let vertices = vec![
+            Vec3::new(min, 0.0, min),
+            Vec3::new(max, 0.0, min),
+            Vec3::new(max, 0.0, max),
+            Vec3::new(min, 0.0, max),
+        ];
+        ClassificationPolygon {
+            id,
+            points: vertices.clone(),
+            vertices,
+            new_class,
+            masks: vec![(UNCLASSIFIED, 0), (SIDEWALK, 0)],
+            mode,
+            vertical_range: None,
+            selection: Vec::new(),
+        }
+    }
+
+    /// Texels for a point at world XZ with the given class and object id.
+    fn texels(x: f32, z: f32, class_id: u32, object_id: u32) -> ([f32; 4], [f32; 4]) {
+        (
+            [
+                x / 100.0,
+                0.5,
+                z / 100.0,
+                encode_object_id(object_id as f32),
+            ],
+            [0.5, 0.5, 0.5, class_id as f32 / 255.0],
+        )
+    }
+
+    fn classify_at(reference: &ClassificationReference, x: f32, z: f32, class_id: u32) -> u32 {
+        let (position, colour) = texels(x, z, class_id, 0);
+        reference.classify(0, position, colour)
+    }
+
+    /// Class of an unclassified point at world height `y` (bounds span 0 to 10).
+    fn classify_at_height(reference: &ClassificationReference, x: f32, y: f32, z: f32) -> u32 {
+        let (mut position, colour) = texels(x, z, UNCLASSIFIED, 0);
+        position[1] = y / 10.0;
+        reference.classify(0, position, colour)
+    }
+
+    #[test]
+    fn reclassifies_masked_points_inside_polygon() {
+        let polygons = [square(1, 10.0, 20.0, PolygonMode::Reclassify, CARS)];
+        let reference = ClassificationReference::new(&polygons, &bounds());
+
+        assert_eq!(classify_at(&reference, 15.0, 15.0, UNCLASSIFIED), CARS);
+        assert_eq!(classify_at(&reference, 15.0, 15.0, SIDEWALK), CARS);
+        assert_eq!(
+            classify_at(&reference, 25.0, 15.0, UNCLASSIFIED),
+            UNCLASSIFIED
+        );
+    }
+
+    #[test]
+    fn unmasked_class_or_object_is_left_unchanged() {
+        let polygons = [square(1, 10.0, 20.0, PolygonMode::Hide, 0)];
+        let reference = ClassificationReference::new(&polygons, &bounds());
+
+        assert_eq!(classify_at(&reference, 15.0, 15.0, CARS), CARS);
+        let (position, colour) = texels(15.0, 15.0, UNCLASSIFIED, 7);
+        assert_eq!(reference.classify(0, position, colour), UNCLASSIFIED);
+        // Object 1 must not decode as the unsegmented object 0.
+        let (position, colour) = texels(15.0, 15.0, UNCLASSIFIED, 1);
+        assert_eq!(object_id_of(position), 1);
+        assert_eq!(reference.classify(0, position, colour), UNCLASSIFIED);
+        assert_eq!(
+            classify_at(&reference, 15.0, 15.0, UNCLASSIFIED),
+            HIDDEN_CLASS
+        );
+    }
+
+    #[test]
+    fn later_polygon_takes_priority() {
+        let polygons = [
+            square(1, 10.0, 30.0, PolygonMode::Reclassify, CARS),
+            square(2, 20.0, 40.0, PolygonMode::Reclassify, SIDEWALK),
+        ];
+        let reference = ClassificationReference::new(&polygons, &bounds());
+
+        assert_eq!(classify_at(&reference, 25.0, 25.0, UNCLASSIFIED), SIDEWALK);
+        assert_eq!(classify_at(&reference, 15.0, 15.0, UNCLASSIFIED), CARS);
+    }
+
+    #[test]
+    fn reclassify_stops_earlier_hide() {
+        let polygons = [
+            square(1, 10.0, 30.0, PolygonMode::Hide, 0),
+            square(2, 20.0, 40.0, PolygonMode::Reclassify, CARS),
+        ];
+        let reference = ClassificationReference::new(&polygons, &bounds());
+
+        assert_eq!(classify_at(&reference, 25.0, 25.0, UNCLASSIFIED), CARS);
+        assert_eq!(
+            classify_at(&reference, 15.0, 15.0, UNCLASSIFIED),
+            HIDDEN_CLASS
+        );
+    }
+
+    #[test]
+    fn later_hide_overrides_earlier_reclassify() {
+        let polygons = [
+            square(1, 10.0, 30.0, PolygonMode::Reclassify, CARS),
+            square(2, 20.0, 40.0, PolygonMode::Hide, 0),
+        ];
+        let reference = ClassificationReference::new(&polygons, &bounds());
+
+        assert_eq!(
+            classify_at(&reference, 25.0, 25.0, UNCLASSIFIED),
+            HIDDEN_CLASS
+        );
+    }
+
+    #[test]
+    fn concave_polygon_excludes_notch() {
+        let vertices = vec![
+            Vec3::new(10.0, 0.0, 10.0),
+            Vec3::new(30.0, 0.0, 10.0),
+            Vec3::new(30.0, 0.0, 30.0),
+            Vec3::new(20.0, 0.0, 20.0),
+            Vec3::new(10.0, 0.0, 30.0),
+        ];
+        let polygons = [ClassificationPolygon {
+            id: 1,
+            points: vertices.clone(),
+            vertices,
+            new_class: CARS,
+            masks: vec![(UNCLASSIFIED, 0)],
+            mode: PolygonMode::Reclassify,
+            vertical_range: None,
+            selection: Vec::new(),
+        }];
+        let reference = ClassificationReference::new(&polygons, &bounds());
+
+        assert_eq!(classify_at(&reference, 20.0, 15.0, UNCLASSIFIED), CARS);
+        assert_eq!(
+            classify_at(&reference, 20.0, 27.0, UNCLASSIFIED),
+            UNCLASSIFIED
+        );
+    }
+
+    #[test]
+    fn aabb_margin_matches_shader() {
+        let info = pack_polygons(&[square(1, 10.0, 20.0, PolygonMode::Hide, 0)], &bounds()).info[0];
+
+        assert!(point_near_aabb(Vec2::new(9.0, 15.0), &info));
+        assert!(point_near_aabb(Vec2::new(21.0, 21.0), &info));
+        assert!(!point_near_aabb(Vec2::new(8.9, 15.0), &info));
+        assert!(!point_near_aabb(Vec2::new(15.0, 21.1), &info));
+    }
+
+    #[test]
+    fn resampled_outline_classifies_like_its_corners() {
+        let corners = vec![
+            Vec3::new(12.0, 0.0, 8.0),
+            Vec3::new(61.0, 0.0, 17.0),
+            Vec3::new(48.0, 0.0, 70.0),
+            Vec3::new(30.0, 0.0, 35.0),
+            Vec3::new(9.0, 0.0, 52.0),
+        ];
+        let polygon = |vertices: Vec<Vec3>| ClassificationPolygon {
+            id: 1,
+            points: vertices.clone(),
+            vertices,
+            new_class: CARS,
+            masks: vec![(UNCLASSIFIED, 0)],
+            mode: PolygonMode::Reclassify,
+            vertical_range: None,
+            selection: Vec::new(),
+        };
+        let resampled = resample_polygon_uniform(&corners, 0.5);
+        assert!(resampled.len() > 100);
+
+        let exact = ClassificationReference::new(&[polygon(corners.clone())], &bounds());
+        let dense = ClassificationReference::new(&[polygon(resampled)], &bounds());
+        assert_eq!(dense.packed.info[0].bin_count, 64);
+
+        let edges: Vec<(Vec2, Vec2)> = (0..corners.len())
+            .map(|i| (corners[i].xz(), corners[(i + 1) % corners.len()].xz()))
+            .collect();
+        let mut inside = 0;
+        for x in 0..140 {
+            for z in 0..160 {
+                let point = Vec2::new(x as f32 * 0.5 + 0.25, z as f32 * 0.5 + 0.25);
+                // Resampled points are only collinear with the corners to within rounding.
+                let near_edge = edges.iter().any(|&(a, b)| {
+                    let t = ((point - a).dot(b - a) / (b - a).length_squared()).clamp(0.0, 1.0);
+                    point.distance(a + (b - a) * t) < 0.01
+                });
+                if near_edge {
+                    continue;
+                }
+                let expected = classify_at(&exact, point.x, point.y, UNCLASSIFIED);
+                assert_eq!(
+                    classify_at(&dense, point.x, point.y, UNCLASSIFIED),
+                    expected,
+                    "{point}"
+                );
+                inside += usize::from(expected == CARS);
+            }
+        }
+        assert!(inside > 1000);
+    }
+
+    #[test]
+    fn large_polygon_interior_is_contained() {
+        let polygons = [square(1, 1.0, 99.0, PolygonMode::Hide, 0)];
+        let reference = ClassificationReference::new(&polygons, &bounds());
+
+        assert_eq!(
+            classify_at(&reference, 50.0, 50.0, UNCLASSIFIED),
+            HIDDEN_CLASS
+        );
+        assert_eq!(
+            classify_at(&reference, 98.9, 1.1, UNCLASSIFIED),
+            HIDDEN_CLASS
+        );
+    }
+
+    #[test]
+    fn edge_bins_cover_every_crossing_edge() {
+        let vertices: Vec<Vec2> = (0..40)
+            .map(|i| {
+                let angle = i as f32 / 40.0 * std::f32::consts::TAU;
+                Vec2::new(
+                    50.0 + 30.0 * angle.cos(),
+                    50.0 + (20.0 + (i % 3) as f32) * angle.sin(),
+                )
+            })
+            .collect();
+        let packed = pack_polygons(
+            &[ClassificationPolygon {
+                id: 1,
+                vertices: vertices.iter().map(|v| Vec3::new(v.x, 0.0, v.y)).collect(),
+                points: Vec::new(),
+                new_class: 0,
+                masks: Vec::new(),
+                mode: PolygonMode::Hide,
+                vertical_range: None,
+                selection: Vec::new(),
+            }],
+            &bounds(),
+        );
+        let info = packed.info[0];
+        let [_, min_z, _, max_z] = info.aabb;
+
+        for step in 0..=1000 {
+            let z = min_z + (max_z - min_z) * step as f32 / 1000.0;
+            let header =
+                (info.bin_start + edge_bin_index(z, min_z, max_z, info.bin_count) * 2) as usize;
+            let offset = packed.edge_bins[header] as usize;
+            let bin = &packed.edge_bins[offset..offset + packed.edge_bins[header + 1] as usize];
+            for (index, start) in vertices.iter().enumerate() {
+                let end = vertices[(index + 1) % vertices.len()];
+                if (start.y > z) != (end.y > z) {
+                    assert!(
+                        bin.contains(&(index as u32)),
+                        "edge {index} missing at z {z}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn absolute_range_limits_selection_to_band() {
+        let mut polygon = square(1, 10.0, 20.0, PolygonMode::Reclassify, CARS);
+        polygon.vertical_range = Some(VerticalRange {
+            reference: HeightReference::Absolute,
+            min: 2.0,
+            max: 4.0,
+        });
+        let polygons = [polygon];
+        let reference = ClassificationReference::new(&polygons, &bounds());
+
+        assert_eq!(classify_at_height(&reference, 15.0, 3.0, 15.0), CARS);
+        assert_eq!(
+            classify_at_height(&reference, 15.0, 1.0, 15.0),
+            UNCLASSIFIED
+        );
+        assert_eq!(
+            classify_at_height(&reference, 15.0, 6.0, 15.0),
+            UNCLASSIFIED
+        );
+    }
+
+    #[test]
+    fn absolute_range_is_in_world_heights() {
+        // Render Y 3 is world height 103 once the origin is removed.
+        let mut polygon = square(1, 10.0, 20.0, PolygonMode::Reclassify, CARS);
+        polygon.vertical_range = Some(VerticalRange {
+            reference: HeightReference::Absolute,
+            min: 102.0,
+            max: 104.0,
+        });
+        let polygons = [polygon];
+        let bounds = BoundsData {
+            origin: (0.0, 100.0, 0.0),
+            ..bounds()
+        };
+        let reference = ClassificationReference::new(&polygons, &bounds);
+
+        assert_eq!(classify_at_height(&reference, 15.0, 3.0, 15.0), CARS);
+        assert_eq!(
+            classify_at_height(&reference, 15.0, 1.0, 15.0),
+            UNCLASSIFIED
+        );
+    }
+
+    #[test]
+    fn ground_range_follows_heightmap() {
+        // 2x2 heightmap sloping from 0 m at x = 0 to 5 m at x = 100.
+        let heightmap: Vec<u8> = [0.0f32, 0.5, 0.0, 0.5]
+            .into_iter()
+            .flat_map(f32::to_le_bytes)
+            .collect();
+        let mut polygon = square(1, 0.0, 100.0, PolygonMode::Hide, 0);
+        polygon.vertical_range = Some(VerticalRange {
+            reference: HeightReference::Ground,
+            min: 0.5,
+            max: 2.0,
+        });
+        let polygons = [polygon];
+        let reference =
+            ClassificationReference::new(&polygons, &bounds()).with_heightmap(&heightmap, 2);
+
+        // Ground is 0.5 m at x = 10 and 4.5 m at x = 90.
+        assert_eq!(
+            classify_at_height(&reference, 10.0, 1.5, 50.0),
+            HIDDEN_CLASS
+        );
+        assert_eq!(
+            classify_at_height(&reference, 90.0, 1.5, 50.0),
+            UNCLASSIFIED
+        );
+        assert_eq!(
+            classify_at_height(&reference, 90.0, 5.5, 50.0),
+            HIDDEN_CLASS
+        );
+        assert_eq!(
+            classify_at_height(&reference, 90.0, 4.7, 50.0),
+            UNCLASSIFIED
+        );
+    }
+
+    #[test]
+    fn classify_texels_decodes_texture_bytes() {
+        let polygons = [square(1, 10.0, 20.0, PolygonMode::Hide, 0)];
+        let reference = ClassificationReference::new(&polygons, &bounds());
+
+        let points = [
+            texels(15.0, 15.0, UNCLASSIFIED, 0),
+            texels(50.0, 50.0, UNCLASSIFIED, 0),
+            texels(12.0, 18.0, CARS, 0),
+        ];
+        let to_bytes = |texel: [f32; 4]| texel.into_iter().flat_map(f32::to_le_bytes);
+        let position_data: Vec<u8> = points.iter().flat_map(|(p, _)| to_bytes(*p)).collect();
+        let colour_data: Vec<u8> = points.iter().flat_map(|(_, c)| to_bytes(*c)).collect();
+
+        assert_eq!(
+            reference.classify_texels(&position_data, &colour_data, 10),
+            vec![HIDDEN_CLASS, UNCLASSIFIED, CARS]
+        );
+    }
+
+    #[test]
+    fn selection_polygon_matches_listed_texels_only() {
+        let mut polygon = square(1, 10.0, 20.0, PolygonMode::Reclassify, CARS);
+        polygon.selection = vec![2, 5, 9];
+        let polygons = [polygon];
+        let reference = ClassificationReference::new(&polygons, &bounds());
+
+        let (position, colour) = texels(15.0, 15.0, UNCLASSIFIED, 0);
+        assert_eq!(reference.classify(5, position, colour), CARS);
+        assert_eq!(reference.classify(9, position, colour), CARS);
+        assert_eq!(reference.classify(4, position, colour), UNCLASSIFIED);
+        // Masks still apply to selected points.
+        let (position, colour) = texels(15.0, 15.0, UNCLASSIFIED, 7);
+        assert_eq!(reference.classify(2, position, colour), UNCLASSIFIED);
+    }
+
+    /// Run `modified_classification.wgsl` over square RGBA32F textures in the modified
+    /// classification render mode, which writes each point's final class to alpha.
+    /// `None` when no GPU adapter is available.
+    fn classify_on_gpu(
+        polygons: &[ClassificationPolygon],
+        bounds: &BoundsData,
+        side: u32,
+        position_data: &[u8],
+        colour_data: &[u8],
+        heightmap: (&[u8], u32),
+    ) -> Option<Vec<u32>> {
+        use bytemuck::Zeroable;
+        use wgpu::util::DeviceExt;
+
+        let instance = wgpu::Instance::default();
+        let adapter = bevy::tasks::block_on(
+            instance.request_adapter(&wgpu::RequestAdapterOptions::default()),
+        )?;
+        let (device, queue) =
+            bevy::tasks::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None))
+                .ok()?;
+
+        let texture = |size: u32, format, usage, data: &[u8]| {
+            let descriptor = wgpu::TextureDescriptor {
+                label: None,
+                size: wgpu::Extent3d {
+                    width: size,
+                    height: size,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage,
+                view_formats: &[],
+            };
+            device
+                .create_texture_with_data(
+                    &queue,
+                    &descriptor,
+                    wgpu::util::TextureDataOrder::LayerMajor,
+                    data,
+                )
+                .create_view(&Default::default())
+        };
+        let rgba = wgpu::TextureFormat::Rgba32Float;
+        let input = wgpu::TextureUsages::TEXTURE_BINDING;
+        let zero_texel = [0u8; 16];
+        let colour = texture(side, rgba, input, colour_data);
+        let position = texture(side, rgba, input, position_data);
+        let spatial = texture(side, rgba, input, &vec![0; position_data.len()]);
+        let normal = texture(1, rgba, input, &zero_texel);
+        let change = texture(1, rgba, input, &zero_texel);
+        let ground = texture(
+            heightmap.1,
+            wgpu::TextureFormat::R32Float,
+            input,
+            heightmap.0,
+        );
+        let output_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: side,
+                height: side,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: rgba,
+            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let output = output_texture.create_view(&Default::default());
+
+        // Zero-sized storage bindings are invalid, so empty sets get one unused element.
+        let mut packed = pack_polygons(polygons, bounds);
+        if packed.masks.is_empty() {
+            packed.masks.push([0; 2]);
+        }
+        if packed.selections.is_empty() {
+            packed.selections.push(0);
+        }
+        if packed.info.is_empty() {
+            packed.info.push(GpuPolygonInfo::zeroed());
+        }
+        let buffer = |contents: &[u8], usage| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: None,
+                contents,
+                usage,
+            })
+        };
+        let storage = wgpu::BufferUsages::STORAGE;
+        // `ComputeUniformData`: render mode 1, spatial filtering on, no hovered object.
+        let compute: [u32; 12] = [
+            polygons.len() as u32,
+            side * side,
+            1,
+            1,
+            0,
+            0,
+            0,
+            0,
+            0,
+            u32::MAX,
+            packed.masks.len() as u32,
+            0,
+        ];
+        let terrain: [f32; 8] = [
+            bounds.min_x as f32,
+            bounds.min_y as f32,
+            bounds.min_z as f32,
+            0.0,
+            bounds.max_x as f32,
+            bounds.max_y as f32,
+            bounds.max_z as f32,
+            0.0,
+        ];
+        let compute = buffer(bytemuck::cast_slice(&compute), wgpu::BufferUsages::UNIFORM);
+        let terrain = buffer(bytemuck::cast_slice(&terrain), wgpu::BufferUsages::UNIFORM);
+        let points = buffer(bytemuck::cast_slice(&packed.points), storage);
+        let info = buffer(bytemuck::cast_slice(&packed.info), storage);
+        let masks = buffer(bytemuck::cast_slice(&packed.masks), storage);
+        let edge_bins = buffer(bytemuck::cast_slice(&packed.edge_bins), storage);
+        let selections = buffer(bytemuck::cast_slice(&packed.selections), storage);
+        let selected = buffer(bytemuck::cast_slice(&[0u32]), storage);
+
+        let texture_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        };
+        let buffer_entry = |binding, ty| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let read_only = wgpu::BufferBindingType::Storage { read_only: true };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: None,
+            entries: &[
+                texture_entry(0),
+                texture_entry(1),
+                texture_entry(2),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: rgba,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                buffer_entry(4, wgpu::BufferBindingType::Uniform),
+                buffer_entry(5, wgpu::BufferBindingType::Uniform),
+                texture_entry(6),
+                texture_entry(7),
+                buffer_entry(8, read_only),
+                buffer_entry(9, read_only),
+                buffer_entry(10, read_only),
+                buffer_entry(11, read_only),
+                texture_entry(12),
+                buffer_entry(13, read_only),
+                buffer_entry(14, read_only),
+            ],
+        });
+        let views = [
+            (0, &colour),
+            (1, &position),
+            (2, &spatial),
+            (3, &output),
+            (6, &normal),
+            (7, &change),
+            (12, &ground),
+        ];
+        let buffers = [
+            (4, &compute),
+            (5, &terrain),
+            (8, &points),
+            (9, &info),
+            (10, &masks),
+            (11, &edge_bins),
+            (13, &selections),
+            (14, &selected),
+        ];
+        let entries: Vec<wgpu::BindGroupEntry> = views
+            .iter()
+            .map(|&(binding, view)| wgpu::BindGroupEntry {
+                binding,
+                resource: wgpu::BindingResource::TextureView(view),
+            })
+            .chain(
+                buffers
+                    .iter()
+                    .map(|&(binding, buffer)| wgpu::BindGroupEntry {
+                        binding,
+                        resource: buffer.as_entire_binding(),
+                    }),
+            )
+            .collect();
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &layout,
+            entries: &entries,
+        });
+
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("modified_classification"),
+            source: wgpu::ShaderSource::Wgsl(
+                include_str!("../../../assets/shaders/modified_classification.wgsl").into(),
+            ),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: None,
+            layout: Some(
+                &device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: None,
+                    bind_group_layouts: &[&layout],
+                    push_constant_ranges: &[],
+                }),
+            ),
+            module: &shader,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+
+        // Rows are already 256-byte aligned for sides that are a multiple of 16.
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (side * side * 16) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&Default::default());
+            pass.set_pipeline(&pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(side.div_ceil(8), side.div_ceil(8), 1);
+        }
+        encoder.copy_texture_to_buffer(
+            output_texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(side * 16),
+                    rows_per_image: Some(side),
+                },
+            },
+            output_texture.size(),
+        );
+        queue.submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        device.poll(wgpu::Maintain::Wait);
+        let data = slice.get_mapped_range();
+        Some(
+            (0..(side * side) as usize)
+                .map_while(|index| read_texel(&data, index))
+                .map(|texel| texel[3] as u32)
+                .collect(),
+        )
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn matches_shader_on_gpu() {
+        const SIDE: u32 = 64;
+
+        // Scattered points with a mix of classes, object ids and heights.
+        let mut seed = 0x2545_f491_u32;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            (seed >> 8) as f32 / (1u32 << 24) as f32
+        };
+        let mut position_data = Vec::new();
+        let mut colour_data = Vec::new();
+        for _ in 0..SIDE * SIDE {
+            let class_id = [UNCLASSIFIED, SIDEWALK, CARS][(next() * 3.0) as usize];
+            // 1, 2 and 61 read back as their predecessor when truncated.
+            let object_id = [0, 0, 1, 2, 7, 61][(next() * 6.0) as usize];
+            let (mut position, colour) =
+                texels(next() * 100.0, next() * 100.0, class_id, object_id);
+            position[1] = next();
+            position_data.extend(position.into_iter().flat_map(f32::to_le_bytes));
+            colour_data.extend(colour.into_iter().flat_map(f32::to_le_bytes));
+        }
+        let heightmap: Vec<u8> = (0..16 * 16)
+            .map(|i| (i % 16) as f32 / 30.0)
+            .flat_map(f32::to_le_bytes)
+            .collect();
+
+        let concave = vec![
+            Vec3::new(10.0, 0.0, 10.0),
+            Vec3::new(70.0, 0.0, 10.0),
+            Vec3::new(70.0, 0.0, 70.0),
+            Vec3::new(40.0, 0.0, 35.0),
+            Vec3::new(10.0, 0.0, 70.0),
+        ];
+        let mut absolute = square(2, 30.0, 90.0, PolygonMode::Hide, 0);
+        absolute.vertical_range = Some(VerticalRange {
+            reference: HeightReference::Absolute,
+            min: 2.0,
+            max: 6.0,
+        });
+        let mut ground = square(3, 5.0, 60.0, PolygonMode::Reclassify, SIDEWALK);
+        ground.vertical_range = Some(VerticalRange {
+            reference: HeightReference::Ground,
+            min: 0.5,
+            max: 3.0,
+        });
+        ground.masks.extend([(CARS, 7), (SIDEWALK, 2)]);
+        let mut selection = square(4, 0.0, 100.0, PolygonMode::Hide, 0);
+        selection.selection = (0..SIDE * SIDE).step_by(5).collect();
+        let polygons = [
+            ClassificationPolygon {
+                id: 1,
+                points: concave.clone(),
+                vertices: concave,
+                new_class: CARS,
+                masks: vec![
+                    (UNCLASSIFIED, 0),
+                    (SIDEWALK, 7),
+                    (CARS, 1),
+                    (UNCLASSIFIED, 61),
+                ],
+                mode: PolygonMode::Reclassify,
+                vertical_range: None,
+                selection: Vec::new(),
+            },
+            absolute,
+            ground,
+            selection,
+        ];
+
+        let Some(gpu) = classify_on_gpu(
+            &polygons,
+            &bounds(),
+            SIDE,
+            &position_data,
+            &colour_data,
+            (&heightmap, 16),
+        ) else {
+            eprintln!("No GPU adapter; skipping shader parity check");
+            return;
+        };
+        let cpu = ClassificationReference::new(&polygons, &bounds())
+            .with_heightmap(&heightmap, 16)
+            .classify_texels(&position_data, &colour_data, (SIDE * SIDE) as usize);
+
+        assert_eq!(gpu.len(), cpu.len());
+        for (texel, (gpu, cpu)) in gpu.iter().zip(&cpu).enumerate() {
+            assert_eq!(gpu, cpu, "texel {texel}");
+        }
+        for class_id in [UNCLASSIFIED, SIDEWALK, CARS, HIDDEN_CLASS] {
+            assert!(
+                cpu.contains(&class_id),
+                "no points end up as class {class_id}"
+            );
+        }
+    }
+}
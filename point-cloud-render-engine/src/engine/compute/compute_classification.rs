@@ -4,10 +4,10 @@ use crate::engine::assets::scene_manifest::SceneManifest;
 use crate::engine::systems::render_mode::RenderMode;
 use crate::engine::systems::render_mode::{MouseEnterObjectState, RenderModeState};
 use crate::tools::class_selection::ClassSelectionState;
-use crate::tools::polygon::{ClassificationPolygon, PolygonClassificationData};
-use constants::procedural_shader::{
-    MAX_IGNORE_MASK_LENGTH, MAXIMUM_POLYGON_POINTS, MAXIMUM_POLYGONS,
+use crate::tools::polygon::{
+    ClassificationPolygon, HeightReference, PolygonClassificationData, VerticalRange,
 };
+use crate::tools::screen_selection::PointSelection;
 
 use bevy::prelude::*;
 use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
@@ -22,6 +22,7 @@ use bevy::render::{
     renderer::{RenderDevice, RenderQueue},
     texture::GpuImage,
 };
+use bytemuck::{Pod, Zeroable};
 pub struct ComputeClassificationPlugin;
 
 /// Runtime state for controlling classification compute pipeline execution.
@@ -44,6 +45,7 @@ pub struct ComputeClassificationState {
 impl Plugin for ComputeClassificationPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<ComputeClassificationState>()
+            .add_systems(Startup, apply_device_polygon_limits)
             .add_systems(Update, trigger_classification_compute)
             .add_plugins(ExtractResourcePlugin::<ComputeClassificationState>::default())
             .add_plugins(ExtractResourcePlugin::<PolygonClassificationData>::default())
@@ -51,12 +53,52 @@ impl Plugin for ComputeClassificationPlugin {
     }
 }
 
+/// Byte sizes of one element in each polygon storage buffer; must match the WGSL layouts.
+const POLYGON_POINT_SIZE: usize = std::mem::size_of::<[f32; 2]>();
+const POLYGON_INFO_SIZE: usize = std::mem::size_of::<GpuPolygonInfo>();
+const POLYGON_MASK_SIZE: usize = std::mem::size_of::<[u32; 2]>();
+const POLYGON_EDGE_BIN_SIZE: usize = std::mem::size_of::<u32>();
+const POLYGON_SELECTION_SIZE: usize = std::mem::size_of::<u32>();
+
+/// Upper bound on Z bins per polygon. Polygons get one bin per edge up to this count.
+const MAX_EDGE_BINS: u32 = 64;
+
+/// Clamps the polygon limits so each storage buffer fits the device's binding size.
+///
+/// The `procedural_shader` constants are upper bounds; WebGPU guarantees 128 MiB
+/// per storage binding, so they are rarely reduced. The clamped limits are what
+/// the tools enforce and what `get_polygon_limits` reports.
+fn apply_device_polygon_limits(
+    render_device: Option<Res<RenderDevice>>,
+    mut classification_data: ResMut<PolygonClassificationData>,
+) {
+    let Some(render_device) = render_device else {
+        return;
+    };
+    let max_binding = render_device.limits().max_storage_buffer_binding_size as usize;
+
+    let limits = &mut classification_data.limits;
+    limits.max_polygons = limits.max_polygons.min(max_binding / POLYGON_INFO_SIZE);
+    limits.max_points = limits.max_points.min(max_binding / POLYGON_POINT_SIZE);
+    // Each vertex adds at most one edge to every bin plus one bin header.
+    let edge_bin_entries_per_point = MAX_EDGE_BINS as usize + 2;
+    limits.max_points = limits
+        .max_points
+        .min(max_binding / (POLYGON_EDGE_BIN_SIZE * edge_bin_entries_per_point));
+    limits.max_masks = limits.max_masks.min(max_binding / POLYGON_MASK_SIZE);
+    limits.max_selected_points = limits
+        .max_selected_points
+        .min(max_binding / POLYGON_SELECTION_SIZE);
+    info!("Polygon limits: {:?}", limits);
+}
+
 /// System that flags when the classification compute pipeline should re-run.
 ///
 /// It checks for changes in:
 /// - Polygon classification data
 /// - Render mode (RGB | Original | Modified | Connectivity)
 /// - Mouse hover object selection for use feedback
+/// - The pending screen-space point selection
 ///
 /// If any of these change, `should_recompute` is set to `true` in the shared state.
 ///
@@ -66,10 +108,12 @@ pub fn trigger_classification_compute(
     classification_data: Res<PolygonClassificationData>,
     render_mode: Res<RenderModeState>,
     mouse_enter_object_id: Res<MouseEnterObjectState>,
+    point_selection: Res<PointSelection>,
 ) {
     if classification_data.is_changed()
         || render_mode.is_changed()
         || mouse_enter_object_id.is_changed()
+        || point_selection.is_changed()
     {
         state.should_recompute = true;
     }
@@ -80,7 +124,8 @@ pub fn trigger_classification_compute(
 /// This function:
 /// - Lazily initialises the compute pipeline if needed
 /// - Retrieves necessary GPU textures
-/// - Creates uniform buffers for polygons and terrain bounds
+/// - Creates uniform buffers for compute state and terrain bounds, and storage
+///   buffers sized to the current polygon points, metadata and masks
 /// - Dispatches the WGSL compute shader
 ///
 /// ### WGSL expectations:
@@ -88,17 +133,31 @@ pub fn trigger_classification_compute(
 /// - 3 input textures: colour class, position, and spatial index
 /// - 1 writable output texture
 /// - 2 uniform buffers:
-///   - Polygon + mask metadata
+///   - Polygon/mask counts, render mode, selection and hover state
 ///   - Scene bounds
+/// - 1 normal texture (XYZ normal + curvature, or a 1x1 placeholder)
+/// - 1 change texture (signed distance + changed flag + reference class, or a 1x1 placeholder)
+/// - 4 read-only storage buffers: polygon vertices, per-polygon info, masks and edge bins
+/// - 1 heightmap texture (R32F normalised ground elevation) for ground-relative ranges
+/// - 2 read-only storage buffers: per-polygon selected texels and the pending selection bitset
 ///
-/// These are mapped to bindings 0–5 in the WGSL:
+/// These are mapped to bindings 0–14 in the WGSL:
 /// ```wgsl
 /// @group(0) @binding(0) var colour_texture: texture_2d<f32>;
 /// @group(0) @binding(1) var position_texture: texture_2d<f32>;
 /// @group(0) @binding(2) var spatial_index_texture: texture_2d<f32>;
 /// @group(0) @binding(3) var result_texture: texture_storage_2d<rgba32float, write>;
-/// @group(0) @binding(4) var<uniform> polygon_data: PolygonUniform;
+/// @group(0) @binding(4) var<uniform> compute_data: ComputeUniformData;
 /// @group(0) @binding(5) var<uniform> terrain_bounds: TerrainBounds;
+/// @group(0) @binding(6) var normal_texture: texture_2d<f32>;
+/// @group(0) @binding(7) var change_texture: texture_2d<f32>;
+/// @group(0) @binding(8) var<storage, read> polygon_points: array<vec2<f32>>;
+/// @group(0) @binding(9) var<storage, read> polygon_info: array<PolygonInfo>;
+/// @group(0) @binding(10) var<storage, read> polygon_masks: array<vec2<u32>>;
+/// @group(0) @binding(11) var<storage, read> polygon_edge_bins: array<u32>;
+/// @group(0) @binding(12) var heightmap_texture: texture_2d<f32>;
+/// @group(0) @binding(13) var<storage, read> polygon_selections: array<u32>;
+/// @group(0) @binding(14) var<storage, read> selected_points: array<u32>;
 /// ```
 pub fn run_classification_compute(
     mut state: ResMut<ComputeClassificationState>,
@@ -106,6 +165,7 @@ pub fn run_classification_compute(
     selection_state: Res<ClassSelectionState>,
     render_mode: Res<RenderModeState>,
     mouse_enter_object_id: Res<MouseEnterObjectState>,
+    point_selection: Res<PointSelection>,
     render_device: Res<RenderDevice>,
     mut render_queue: ResMut<RenderQueue>,
     pipeline_cache: Res<PipelineCache>,
@@ -118,7 +178,8 @@ pub fn run_classification_compute(
         || render_mode.is_changed()
         || mouse_enter_object_id.is_changed()
         || state.should_recompute
-        || selection_state.is_changed();
+        || selection_state.is_changed()
+        || point_selection.is_changed();
 
     if !should_update {
         return;
@@ -147,6 +208,15 @@ pub fn run_classification_compute(
     let Some(spatial_gpu) = gpu_images.get(&assets.spatial_index_texture) else {
         return;
     };
+    let Some(normal_gpu) = gpu_images.get(&assets.normal_texture) else {
+        return;
+    };
+    let Some(change_gpu) = gpu_images.get(&assets.change_texture) else {
+        return;
+    };
+    let Some(heightmap_gpu) = gpu_images.get(&assets.heightmap_texture) else {
+        return;
+    };
 
     let Some(final_gpu) = gpu_images.get(&assets.result_texture) else {
         return;
@@ -160,9 +230,13 @@ pub fn run_classification_compute(
         original_gpu,
         position_gpu,
         spatial_gpu,
+        normal_gpu,
+        change_gpu,
+        heightmap_gpu,
         final_gpu,
         &classification_data.polygons,
         &selection_state,
+        &point_selection,
         manifest.terrain_bounds(), // Pass terrain bounds directly from manifest.
         render_mode.current_mode,
         mouse_enter_object_id.object_id,
@@ -176,7 +250,12 @@ pub fn run_classification_compute(
 /// Sets up the following bindings:
 /// 0–2: Input textures (read-only)
 /// 3:   Output texture (write-only)
-/// 4–5: Uniform buffers (polygon data + terrain bounds)
+/// 4–5: Uniform buffers (compute state + terrain bounds)
+/// 6:   Normal texture (read-only)
+/// 7:   Change texture (read-only)
+/// 8–11: Storage buffers (polygon vertices, info, masks and edge bins; read-only)
+/// 12:  Heightmap texture (read-only)
+/// 13–14: Storage buffers (polygon selected texels and pending selection bitset; read-only)
 ///
 /// Expects the shader to be located at `shaders/modified_classification.wgsl`.
 fn initialise_compute_pipeline(
@@ -248,6 +327,96 @@ fn initialise_compute_pipeline(
                 },
                 count: None,
             },
+            BindGroupLayoutEntry {
+                binding: 6,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Texture {
+                    sample_type: TextureSampleType::Float { filterable: false },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 7,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Texture {
+                    sample_type: TextureSampleType::Float { filterable: false },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 8,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 9,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 10,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 11,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 12,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Texture {
+                    sample_type: TextureSampleType::Float { filterable: false },
+                    view_dimension: TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 13,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            BindGroupLayoutEntry {
+                binding: 14,
+                visibility: ShaderStages::COMPUTE,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Storage { read_only: true },
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
         ],
     );
 
@@ -274,17 +443,23 @@ fn execute_compute_shader(
     original_gpu: &GpuImage,
     position_gpu: &GpuImage,
     spatial_gpu: &GpuImage,
+    normal_gpu: &GpuImage,
+    change_gpu: &GpuImage,
+    heightmap_gpu: &GpuImage,
     final_gpu: &GpuImage,
     polygons: &[ClassificationPolygon],
     selection_state: &ClassSelectionState,
+    point_selection: &PointSelection,
     terrain_bounds: &BoundsData, // Accept terrain bounds directly.
     current_mode: RenderMode,
     mouse_enter_object_id: Option<u32>,
 ) {
-    let compute_buffer = create_compute_buffer(
+    let buffers = create_polygon_buffers(
         render_device,
         polygons,
+        terrain_bounds,
         selection_state,
+        point_selection,
         current_mode,
         mouse_enter_object_id,
     );
@@ -312,12 +487,48 @@ fn execute_compute_shader(
             },
             BindGroupEntry {
                 binding: 4,
-                resource: compute_buffer.as_entire_binding(),
+                resource: buffers.compute.as_entire_binding(),
             },
             BindGroupEntry {
                 binding: 5,
                 resource: bounds_buffer.as_entire_binding(),
             },
+            BindGroupEntry {
+                binding: 6,
+                resource: BindingResource::TextureView(&normal_gpu.texture_view),
+            },
+            BindGroupEntry {
+                binding: 7,
+                resource: BindingResource::TextureView(&change_gpu.texture_view),
+            },
+            BindGroupEntry {
+                binding: 8,
+                resource: buffers.points.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 9,
+                resource: buffers.info.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 10,
+                resource: buffers.masks.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 11,
+                resource: buffers.edge_bins.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 12,
+                resource: BindingResource::TextureView(&heightmap_gpu.texture_view),
+            },
+            BindGroupEntry {
+                binding: 13,
+                resource: buffers.selections.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: 14,
+                resource: buffers.selected.as_entire_binding(),
+            },
         ],
     );
 
@@ -334,123 +545,270 @@ fn execute_compute_shader(
     render_queue.submit([encoder.finish()]);
 }
 
-/// encode a polygon tool operation ```polygon | class | object_id | mode```
-/// this will be decoded on the gpu to perform procedural non-destructive modifications
-fn encode_mask(poly_idx: u32, mask_id0: u32, mask_id1: u32, mode: u32) -> u32 {
-    assert!(
-        poly_idx < MAXIMUM_POLYGONS as u32,
-        "poly_idx must be < MAXIMUM_POLYGONS"
-    );
-    assert!(
-        mask_id0 < MAX_IGNORE_MASK_LENGTH as u32,
-        "mask_id.0 must be < MAX_IGNORE_MASK_LENGTH"
-    );
-    assert!(
-        mask_id1 < MAX_IGNORE_MASK_LENGTH as u32,
-        "mask_id.1 must be < MAX_IGNORE_MASK_LENGTH"
-    );
-    assert!(mode <= 1, "mode must be 0 or 1");
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct ComputeUniformData {
+    polygon_count: u32,      // 0
+    total_points: u32,       // 4
+    render_mode: u32,        // 8
+    enable_spatial_opt: u32, // 12
+
+    selection_point: [f32; 4], // 16 (vec3 + pad)
+    is_selecting: u32,         // 32
+    hover_object_id: u32,      // 36
+    mask_count: u32,           // 40
+    _padding: u32,             // 44 (→ 48)
+}
 
-    let encoded: u32 = (mask_id0 & 0x1FF) |              // bits 0–8
-        ((mask_id1 & 0x1FF) << 9) |       // bits 9–17
-        ((poly_idx & 0x1FF) << 18) |      // bits 18–26
-        ((mode & 0x1) << 27); // bit 27
+/// Per-polygon metadata; `PolygonInfo` in the WGSL.
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub(crate) struct GpuPolygonInfo {
+    pub point_start: u32,     // 0
+    pub point_count: u32,     // 4
+    pub new_class: u32,       // 8
+    pub mode: u32,            // 12 (0 = hide, 1 = reclassify)
+    pub mask_start: u32,      // 16
+    pub mask_count: u32,      // 20
+    pub bin_start: u32,       // 24
+    pub bin_count: u32,       // 28
+    pub aabb: [f32; 4],       // 32 (min x, min z, max x, max z)
+    pub height_mode: u32,     // 48 (0 = full column, 1 = absolute, 2 = above ground)
+    pub height_min: f32,      // 52
+    pub height_max: f32,      // 56
+    pub selection_start: u32, // 60
+    pub selection_count: u32, // 64 (0 = footprint polygon)
+    _padding: [u32; 3],       // 68 (→ 80)
+}
 
-    encoded
+/// Polygon storage buffer contents in the layout the WGSL reads.
+///
+/// `edge_bins` holds, per polygon, `bin_count` headers of (offset, count) starting at
+/// `bin_start`, each pointing at a run of edge indices in the same array. Edge `e` joins
+/// vertex `e` to vertex `e + 1` (wrapping). `selections` holds each selection polygon's
+/// sorted texel indices from `selection_start`.
+pub(crate) struct PackedPolygons {
+    pub points: Vec<[f32; 2]>,
+    pub info: Vec<GpuPolygonInfo>,
+    pub masks: Vec<[u32; 2]>,
+    pub edge_bins: Vec<u32>,
+    pub selections: Vec<u32>,
 }
 
-fn create_compute_buffer(
-    render_device: &RenderDevice,
+/// Lay polygons out back to back, with each polygon's vertex, mask and edge bin ranges
+/// in its `GpuPolygonInfo`. Shared by the GPU upload and the CPU classification reference.
+///
+/// Containment is tested against the user-placed vertices rather than the resampled
+/// outline, so results do not depend on `target_point_spacing`. Absolute height bands
+/// arrive in world heights and are shifted by the bounds origin into render space.
+pub(crate) fn pack_polygons(
     polygons: &[ClassificationPolygon],
-    selection_state: &ClassSelectionState,
-    current_mode: RenderMode,
-    mouse_enter_object_id: Option<u32>,
-) -> bevy::render::render_resource::Buffer {
-    use bytemuck::{Pod, Zeroable};
+    bounds: &BoundsData,
+) -> PackedPolygons {
+    let mut points: Vec<[f32; 2]> = Vec::new();
+    let mut masks: Vec<[u32; 2]> = Vec::new();
+    let mut edge_bins: Vec<u32> = Vec::new();
+    let mut selections: Vec<u32> = Vec::new();
+    let mut info: Vec<GpuPolygonInfo> = Vec::with_capacity(polygons.len());
+
+    for polygon in polygons {
+        let vertices: Vec<Vec2> = polygon.vertices.iter().map(|v| v.xz()).collect();
+        let (min, max) = vertices.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), &v| (min.min(v), max.max(v)),
+        );
+        let bins = build_edge_bins(&vertices, min.y, max.y);
+        let vertical_range = polygon
+            .vertical_range
+            .map(|range| range.offset(-bounds.origin.1));
+
+        info.push(GpuPolygonInfo {
+            point_start: points.len() as u32,
+            point_count: vertices.len() as u32,
+            new_class: polygon.new_class,
+            mode: polygon.mode.clone() as u32,
+            mask_start: masks.len() as u32,
+            mask_count: polygon.masks.len() as u32,
+            bin_start: edge_bins.len() as u32,
+            bin_count: bins.len() as u32,
+            aabb: [min.x, min.y, max.x, max.y],
+            height_mode: height_mode(vertical_range.as_ref()),
+            height_min: vertical_range.map_or(0.0, |range| range.min),
+            height_max: vertical_range.map_or(0.0, |range| range.max),
+            selection_start: selections.len() as u32,
+            selection_count: polygon.selection.len() as u32,
+            _padding: [0; 3],
+        });
+        selections.extend(&polygon.selection);
+        points.extend(vertices.iter().map(|v| [v.x, v.y]));
+        masks.extend(
+            polygon
+                .masks
+                .iter()
+                .map(|&(class_id, object_id)| [class_id, object_id]),
+        );
+
+        let header_start = edge_bins.len();
+        edge_bins.resize(header_start + bins.len() * 2, 0);
+        for (bin_index, edges) in bins.iter().enumerate() {
+            edge_bins[header_start + bin_index * 2] = edge_bins.len() as u32;
+            edge_bins[header_start + bin_index * 2 + 1] = edges.len() as u32;
+            edge_bins.extend(edges);
+        }
+    }
 
-    #[repr(C)]
-    #[derive(Clone, Copy, Pod, Zeroable)]
-    pub struct ComputeUniformData {
-        pub polygon_count: u32,      // 0
-        pub total_points: u32,       // 4
-        pub render_mode: u32,        // 8
-        pub enable_spatial_opt: u32, // 12
-
-        pub selection_point: [f32; 4], // 16 (vec3 + pad)
-        pub is_selecting: u32,         // 32
-        pub hover_object_id: u32,      // 36
-        pub _padding: [u32; 2],        // 40 (8 bytes → next @48)
-
-        pub point_data: [[f32; 4]; MAXIMUM_POLYGON_POINTS], // 48, stride 16
-        pub polygon_info: [[f32; 4]; MAXIMUM_POLYGONS],     // after that
-        pub ignore_masks: [[u32; 4]; MAX_IGNORE_MASK_LENGTH],
+    PackedPolygons {
+        points,
+        info,
+        masks,
+        edge_bins,
+        selections,
     }
+}
 
-    let mut uniform = ComputeUniformData::zeroed();
+/// `height_mode` in the WGSL `PolygonInfo`.
+fn height_mode(vertical_range: Option<&VerticalRange>) -> u32 {
+    match vertical_range.map(|range| range.reference) {
+        None => 0,
+        Some(HeightReference::Absolute) => 1,
+        Some(HeightReference::Ground) => 2,
+    }
+}
 
-    if let Some(id) = mouse_enter_object_id {
-        uniform.hover_object_id = id as u32;
+/// Bucket polygon edges into equal Z slabs across the polygon's AABB.
+///
+/// A point only needs the edges crossing its own Z for the crossing test, so it reads
+/// one bin. Each edge is also added to the neighbouring bins so GPU rounding at a
+/// slab boundary cannot drop an edge; extra edges don't change the crossing count.
+fn build_edge_bins(vertices: &[Vec2], min_z: f32, max_z: f32) -> Vec<Vec<u32>> {
+    if vertices.is_empty() {
+        return Vec::new();
     }
+    let bin_count = if max_z > min_z {
+        (vertices.len() as u32).clamp(1, MAX_EDGE_BINS)
+    } else {
+        1
+    };
 
-    uniform.polygon_count = polygons.len() as u32;
-    uniform.render_mode = current_mode as u32;
-    uniform.enable_spatial_opt = 1;
-
-    // Encode our mask id's along with the polygon index and polygon mode (hide or reclassify)
-    // so we can decode these complicated operations and relationships on the GPU in a per-point context
-    let mut mask_offset = 0;
-    for (poly_idx, polygon) in polygons.iter().enumerate().take(MAXIMUM_POLYGONS) {
-        for (mask_idx, &(mask_id0, mask_id1)) in polygon.masks.iter().enumerate() {
-            let encoded = encode_mask(
-                poly_idx as u32,
-                mask_id0,
-                mask_id1,
-                polygon.mode.clone() as u32,
-            );
-
-            let i = mask_offset + mask_idx;
-            assert!(
-                (i as usize) < MAX_IGNORE_MASK_LENGTH * 4,
-                "mask array overflow: i={} capacity={}",
-                i,
-                MAX_IGNORE_MASK_LENGTH * 4
-            );
-            uniform.ignore_masks[i / 4][i % 4] = encoded;
+    let mut bins = vec![Vec::new(); bin_count as usize];
+    for (index, &start) in vertices.iter().enumerate() {
+        let end = vertices[(index + 1) % vertices.len()];
+        // Horizontal edges never satisfy the crossing test.
+        if start.y == end.y {
+            continue;
+        }
+        let low = edge_bin_index(start.y.min(end.y), min_z, max_z, bin_count).saturating_sub(1);
+        let high =
+            (edge_bin_index(start.y.max(end.y), min_z, max_z, bin_count) + 1).min(bin_count - 1);
+        for bin in &mut bins[low as usize..=high as usize] {
+            bin.push(index as u32);
         }
-        mask_offset += polygon.masks.len();
     }
+    bins
+}
 
-    uniform.selection_point = selection_state
-        .selection_point
-        .map(|p| [p.x, p.y, p.z, 0.0])
-        .unwrap_or([0.0, 0.0, 0.0, 0.0]);
-
-    uniform.is_selecting = if selection_state.is_selecting { 1 } else { 0 };
-
-    let mut point_offset = 0;
-    for (i, polygon) in polygons.iter().take(64).enumerate() {
-        uniform.polygon_info[i] = [
-            point_offset as f32,
-            polygon.points.len() as f32,
-            polygon.new_class as f32,
-            0.0,
-        ];
-
-        for point in &polygon.points {
-            if point_offset < MAXIMUM_POLYGON_POINTS {
-                uniform.point_data[point_offset] = [point.x, point.z, 0.0, 0.0];
-                point_offset += 1;
-            }
-        }
+/// Z slab holding `z`; `edge_bin_index` in the WGSL.
+pub(crate) fn edge_bin_index(z: f32, min_z: f32, max_z: f32, bin_count: u32) -> u32 {
+    if bin_count <= 1 {
+        return 0;
     }
+    let last = (bin_count - 1) as f32;
+    ((z - min_z) / (max_z - min_z) * bin_count as f32).clamp(0.0, last) as u32
+}
 
-    uniform.total_points = point_offset as u32;
+struct PolygonBuffers {
+    compute: bevy::render::render_resource::Buffer,
+    points: bevy::render::render_resource::Buffer,
+    info: bevy::render::render_resource::Buffer,
+    masks: bevy::render::render_resource::Buffer,
+    edge_bins: bevy::render::render_resource::Buffer,
+    selections: bevy::render::render_resource::Buffer,
+    selected: bevy::render::render_resource::Buffer,
+}
 
-    render_device.create_buffer_with_data(&bevy::render::render_resource::BufferInitDescriptor {
-        label: Some("polygon_data"),
-        contents: bytemuck::cast_slice(&[uniform]),
-        usage: BufferUsages::UNIFORM,
-    })
+/// Packs polygons into storage buffers sized to the current polygon set.
+///
+/// Layout comes from `pack_polygons`. Capacity is enforced by `PolygonLimits` when
+/// polygons are added, so nothing is truncated here.
+fn create_polygon_buffers(
+    render_device: &RenderDevice,
+    polygons: &[ClassificationPolygon],
+    terrain_bounds: &BoundsData,
+    selection_state: &ClassSelectionState,
+    point_selection: &PointSelection,
+    current_mode: RenderMode,
+    mouse_enter_object_id: Option<u32>,
+) -> PolygonBuffers {
+    let PackedPolygons {
+        mut points,
+        mut info,
+        mut masks,
+        mut edge_bins,
+        mut selections,
+    } = pack_polygons(polygons, terrain_bounds);
+
+    let compute = ComputeUniformData {
+        polygon_count: polygons.len() as u32,
+        total_points: points.len() as u32,
+        render_mode: current_mode as u32,
+        enable_spatial_opt: 1,
+        selection_point: selection_state
+            .selection_point
+            .map(|p| [p.x, p.y, p.z, 0.0])
+            .unwrap_or([0.0, 0.0, 0.0, 0.0]),
+        is_selecting: if selection_state.is_selecting { 1 } else { 0 },
+        hover_object_id: mouse_enter_object_id.unwrap_or(0),
+        mask_count: masks.len() as u32,
+        _padding: 0,
+    };
+
+    // Zero-sized storage bindings are invalid, so empty sets get one unused element.
+    if points.is_empty() {
+        points.push([0.0; 2]);
+    }
+    if info.is_empty() {
+        info.push(GpuPolygonInfo::zeroed());
+    }
+    if masks.is_empty() {
+        masks.push([0; 2]);
+    }
+    if edge_bins.is_empty() {
+        edge_bins.push(0);
+    }
+    if selections.is_empty() {
+        selections.push(0);
+    }
+    let selected: &[u32] = if point_selection.mask.is_empty() {
+        &[0]
+    } else {
+        &point_selection.mask
+    };
+
+    let storage = |label: &'static str, contents: &[u8]| {
+        render_device.create_buffer_with_data(
+            &bevy::render::render_resource::BufferInitDescriptor {
+                label: Some(label),
+                contents,
+                usage: BufferUsages::STORAGE,
+            },
+        )
+    };
+
+    PolygonBuffers {
+        compute: render_device.create_buffer_with_data(
+            &bevy::render::render_resource::BufferInitDescriptor {
+                label: Some("polygon_compute_data"),
+                contents: bytemuck::cast_slice(&[compute]),
+                usage: BufferUsages::UNIFORM,
+            },
+        ),
+        points: storage("polygon_points", bytemuck::cast_slice(&points)),
+        info: storage("polygon_info", bytemuck::cast_slice(&info)),
+        masks: storage("polygon_masks", bytemuck::cast_slice(&masks)),
+        edge_bins: storage("polygon_edge_bins", bytemuck::cast_slice(&edge_bins)),
+        selections: storage("polygon_selections", bytemuck::cast_slice(&selections)),
+        selected: storage("selected_points", bytemuck::cast_slice(selected)),
+    }
 }
 
 /// Create uniform buffer from terrain bounds data without legacy conversion.
@@ -458,8 +816,6 @@ fn create_terrain_bounds_buffer(
     render_device: &RenderDevice,
     terrain_bounds: &BoundsData,
 ) -> bevy::render::render_resource::Buffer {
-    use bytemuck::{Pod, Zeroable};
-
     #[repr(C)]
     #[derive(Pod, Zeroable, Copy, Clone)]
     struct TerrainBoundsUniform {
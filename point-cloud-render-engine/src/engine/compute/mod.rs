@@ -11,13 +11,13 @@
 //! **Input textures:**
 //! - `colour_class_texture`: RGB + classification (alpha channel)
 //! - `position_texture`: Normalised XYZ + connectivity class ID
-//! - `spatial_index_texture`: Morton codes for spatial acceleration
+//! - `spatial_index_texture`: Morton codes for the spatial debug render mode
 //!
 //! **Output:**
 //! - `result_texture`: Reclassified points with updated classification values
 //!
 //! **Features:**
-//! - Polygon-based point selection with spatial AABB/Morton filtering
+//! - Exact polygon containment with an AABB broad phase and per-polygon edge bins
 //! - Hide/reclassify operations with mask-based ignore rules
 //! - Hover/selection highlighting for user feedback
 //! - Hidden points marked with classification `254`
@@ -32,6 +32,12 @@
 //! **Output:**
 //! - `depth_texture`: R32F depth buffer for EDL processing
 //!
+//! ### CPU Reference (`classification_reference`)
+//! Mirrors the Phase 1 hide/reclassify rules in Rust over the CPU copies of the position and
+//! colour textures, using the same polygon buffer layout. Used for GPU-free tests and for
+//! classification statistics; `cargo test -- --ignored` compares it with the shader on any
+//! available GPU adapter.
+//!
 //! ## WGSL Shader Bindings
 //!
 //! Classification compute shader expects:
@@ -40,10 +46,23 @@
 //! @group(0) @binding(1) var position_texture: texture_2d<f32>;    // Input: XYZ + ID
 //! @group(0) @binding(2) var spatial_index_texture: texture_2d<f32>; // Input: Morton codes
 //! @group(0) @binding(3) var result_texture: texture_storage_2d<rgba32float, write>; // Output
-//! @group(0) @binding(4) var<uniform> polygon_data: PolygonUniform; // Polygon definitions
+//! @group(0) @binding(4) var<uniform> compute_data: ComputeUniformData; // Counts, mode, hover
 //! @group(0) @binding(5) var<uniform> terrain_bounds: TerrainBounds; // World bounds
+//! @group(0) @binding(6) var normal_texture: texture_2d<f32>;      // Input: normal + curvature
+//! @group(0) @binding(7) var change_texture: texture_2d<f32>;      // Input: epoch change
+//! @group(0) @binding(8) var<storage, read> polygon_points: array<vec2<f32>>; // Polygon XZ points
+//! @group(0) @binding(9) var<storage, read> polygon_info: array<PolygonInfo>; // Ranges, class, AABB
+//! @group(0) @binding(10) var<storage, read> polygon_masks: array<vec2<u32>>; // Class/object masks
+//! @group(0) @binding(11) var<storage, read> polygon_edge_bins: array<u32>; // Z slab edge lists
+//! @group(0) @binding(12) var heightmap_texture: texture_2d<f32>;  // Input: ground heights
 //! ```
 //!
+//! Polygon storage buffers are rebuilt at the size of the current polygon set on each
+//! dispatch; capacity is bounded by `PolygonLimits`, not by the shader. Each polygon's
+//! edges are bucketed into equal Z slabs across its AABB, so a point runs the crossing
+//! test over only the edges in its slab. Containment uses the user-placed vertices and is
+//! exact regardless of polygon size or outline resampling.
+//!
 //! Depth compute shader expects:
 //! ```wgsl
 //! @group(0) @binding(0) var position_texture: texture_2d<f32>;    // Input: world positions
@@ -55,8 +74,8 @@
 //! ## Performance Considerations
 //!
 //! - Spatial filtering significantly reduces cost on large point clouds
-//! - Morton filtering provides granular culling but higher compute overhead
-//! - AABB mode offers faster broad-phase rejection for simple polygons
+//! - AABB broad phase rejects points away from each polygon before any edge is read
+//! - Edge bins keep the per-point edge count low for polygons with many vertices
 //! - Compute shaders only execute when state changes (trigger systems)
 
 /// Procedural point classification using polygon masks and spatial filtering.
@@ -64,6 +83,11 @@
 /// Non-destructive reclassification with hide/show operations and user feedback highlighting.
 pub mod compute_classification;
 
+/// CPU reference for the classification compute pass.
+///
+/// Applies the shader's hide/reclassify rules to decoded texture data for tests and statistics.
+pub mod classification_reference;
+
 /// Depth buffer computation for eye-dome lighting (EDL) shader effects.
 ///
 /// Generates camera-space depth from world positions for post-processing shading.
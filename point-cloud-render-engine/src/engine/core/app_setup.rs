@@ -7,11 +7,17 @@ use bevy::render::extract_resource::ExtractResourcePlugin;
 use bevy::render::view::RenderLayers;
 use bevy_common_assets::json::JsonAssetPlugin;
 // Crate engine modules
+use crate::engine::camera::bookmarks::CameraBookmarkPlugin;
+use crate::engine::camera::path::CameraPathPlugin;
 use crate::engine::camera::viewport_camera::camera_controller;
+use crate::engine::camera::walk::{WalkModePlugin, walk_inactive};
 use crate::engine::loading::point_cloud_creator::create_point_cloud_when_ready;
 use crate::engine::loading::texture_config::configure_loaded_textures;
 use crate::engine::scene::gizmos::{update_direction_gizmo, update_mouse_intersection_gizmo};
 use crate::engine::scene::grid::GridCreated;
+use crate::engine::scene::kerbs::{
+    KerbOverlay, create_kerb_overlay, toggle_kerb_overlay, update_kerb_overlay_visibility,
+};
 use crate::engine::systems::debug_pipeline::debug_pipeline_state;
 use crate::engine::systems::fps_tracking::fps_notification_system;
 use crate::engine::{
@@ -20,7 +26,9 @@ use crate::engine::{
     },
     compute::edl_compute_depth::{EDLComputePlugin, EDLRenderState, run_edl_compute},
     render::edl_post_processing::EDLPostProcessPlugin,
+    render::frame_export::FrameExportPlugin,
     render::pipeline::point_cloud_render_pipeline::{PointCloudRenderPlugin, PointCloudRenderable},
+    render::screenshot::ScreenshotPlugin,
     systems::render_mode::{MouseEnterObjectState, RenderModeState, render_mode_system},
 };
 // Crate tools modules
@@ -32,16 +40,26 @@ use crate::tools::{
     class_selection::{
         ClassSelectionState, SelectionBuffer, handle_class_selection, update_selection_buffer,
     },
-    measure::{MeasureTool, measure_tool_system, update_measure_render},
+    history::HistoryPlugin,
+    measure::{
+        MeasureActionEvent, MeasureTool, Measurements, measure_keyboard_shortcuts,
+        measure_tool_system, notify_measurement_changes, process_measure_actions,
+        update_measure_render, update_measurement_labels,
+    },
+    point_pick::PointPickPlugin,
     polygon::{
         PolygonClassificationData, PolygonCounter, PolygonHideRequestEvent, PolygonTool,
-        PolygonToolPlugin, polygon_tool_system, update_polygon_classification_shader,
-        update_polygon_preview, update_polygon_render,
+        PolygonToolPlugin, polygon_tool_system, update_polygon_preview, update_polygon_render,
     },
+    polygon_edit::PolygonEditPlugin,
+    profile::{ProfileTool, profile_tool_system, update_profile_render},
+    screen_selection::ScreenSelectionPlugin,
+    session::SessionPlugin,
     tool_manager::{
-        AssetPlacementEvent, ClearToolEvent, PolygonActionEvent, ToolManager, ToolSelectionEvent,
-        handle_asset_placement_events, handle_clear_tool_events, handle_polygon_action_events,
-        handle_tool_keyboard_shortcuts, handle_tool_selection_events,
+        AssetPlacementEvent, ClearToolEvent, PolygonActionEvent, ProfileActionEvent, ToolManager,
+        ToolSelectionEvent, handle_asset_placement_events, handle_clear_tool_events,
+        handle_polygon_action_events, handle_profile_action_events, handle_tool_keyboard_shortcuts,
+        handle_tool_selection_events,
     },
 };
 // Create Web RPC modules
@@ -100,8 +118,26 @@ pub fn create_app() -> App {
     // Plugin for asset manager UI panel
     app.add_plugins(AssetManagerPlugin);
 
-    // Plugin for Polygon
-    app.add_plugins(PolygonToolPlugin);
+    // Plugins for Polygon drawing and editing, screen-space point selection and point picking
+    app.add_plugins((
+        PolygonToolPlugin,
+        PolygonEditPlugin,
+        ScreenSelectionPlugin,
+        PointPickPlugin,
+    ));
+
+    // Plugins for undo/redo history and session save/load
+    app.add_plugins((HistoryPlugin, SessionPlugin));
+
+    // Plugins for camera bookmarks, fly-to, the top view, first-person walking,
+    // camera paths and their frame export, and screenshots
+    app.add_plugins((
+        CameraBookmarkPlugin,
+        WalkModePlugin,
+        CameraPathPlugin,
+        FrameExportPlugin,
+        ScreenshotPlugin,
+    ));
 
     // Initialise resources early
     app.init_resource::<LoadingProgress>()
@@ -112,14 +148,19 @@ pub fn create_app() -> App {
         .init_resource::<PolygonCounter>()
         .init_resource::<PolygonTool>()
         .init_resource::<MeasureTool>()
+        .init_resource::<Measurements>()
+        .init_resource::<ProfileTool>()
         .init_resource::<RenderModeState>()
         .init_resource::<MouseEnterObjectState>()
         .init_resource::<PlacedAssetInstances>()
         .init_resource::<GridCreated>()
+        .init_resource::<KerbOverlay>()
         .init_resource::<ToolManager>()
         .add_event::<ToolSelectionEvent>()
         .add_event::<PolygonActionEvent>()
         .add_event::<AssetPlacementEvent>()
+        .add_event::<ProfileActionEvent>()
+        .add_event::<MeasureActionEvent>()
         .add_event::<ClearToolEvent>()
         .add_event::<PolygonHideRequestEvent>()
         .add_event::<PolygonHideRequestEvent>()
@@ -192,7 +233,7 @@ pub fn create_app() -> App {
         // Runtime systems - only run when everything is ready
         handle_class_selection,
         fps_notification_system,
-        camera_controller,
+        camera_controller.run_if(walk_inactive),
         update_direction_gizmo,
         update_mouse_intersection_gizmo,
         // Tool management systems
@@ -205,12 +246,30 @@ pub fn create_app() -> App {
         polygon_tool_system,
         update_polygon_preview,
         update_polygon_render,
-        measure_tool_system,
-        update_measure_render,
+        (
+            measure_keyboard_shortcuts,
+            process_measure_actions,
+            measure_tool_system,
+            update_measure_render,
+            update_measurement_labels,
+            notify_measurement_changes,
+        )
+            .chain(),
+        (
+            handle_profile_action_events,
+            profile_tool_system,
+            update_profile_render,
+        )
+            .chain(),
         // Other systems
         render_mode_system,
+        (
+            create_kerb_overlay,
+            toggle_kerb_overlay,
+            update_kerb_overlay_visibility,
+        )
+            .chain(),
         update_selection_buffer,
-        update_polygon_classification_shader,
     );
 
     // Add fps_text_update_system only for native builds.
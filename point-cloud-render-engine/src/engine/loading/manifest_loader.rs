@@ -44,7 +44,7 @@ pub fn load_bounds_system(
             commands.insert_resource(vp_camera);
 
             // Start loading textures now that we have bounds
-            load_unified_textures(&asset_server, &mut assets);
+            load_unified_textures(&asset_server, &mut assets, &manifest.terrain.texture_files);
         }
     }
 }
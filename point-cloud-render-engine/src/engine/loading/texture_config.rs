@@ -60,11 +60,54 @@ pub fn configure_loaded_textures(
         assets.result_texture = images.add(result_image);
         assets.depth_texture = images.add(depth_image);
 
+        // Manifests without normals get a single upward-facing texel so the
+        // classification compute bind group layout stays fixed.
+        if assets.normal_texture == Handle::default() {
+            assets.normal_texture = images.add(create_placeholder_normal_image());
+        }
+
+        // Likewise a single unchanged texel when no reference epoch was compared.
+        if assets.change_texture == Handle::default() {
+            assets.change_texture = images.add(create_placeholder_change_image());
+        }
+
         println!("✓ Compute-ready textures created with proper formats");
         loading_progress.textures_configured = true;
     }
 }
 
+/// 1x1 RGBA32F texture holding an up vector with zero curvature.
+fn create_placeholder_normal_image() -> Image {
+    let texel: [f32; 4] = [0.0, 1.0, 0.0, 0.0];
+    Image::new(
+        bevy::render::render_resource::Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        bevy::render::render_resource::TextureDimension::D2,
+        bytemuck::cast_slice(&texel).to_vec(),
+        bevy::render::render_resource::TextureFormat::Rgba32Float,
+        bevy::asset::RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+/// 1x1 RGBA32F texture with zero distance, unchanged and no reference class.
+fn create_placeholder_change_image() -> Image {
+    let texel: [f32; 4] = [0.0, 0.0, -1.0, 0.0];
+    Image::new(
+        bevy::render::render_resource::Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        bevy::render::render_resource::TextureDimension::D2,
+        bytemuck::cast_slice(&texel).to_vec(),
+        bevy::render::render_resource::TextureFormat::Rgba32Float,
+        bevy::asset::RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
 // Abstracted texture configuration function
 fn configure_texture_sampling(images: &mut ResMut<Assets<Image>>, assets: &PointCloudAssets) {
     use bevy::image::{ImageFilterMode, ImageSampler, ImageSamplerDescriptor};
@@ -79,6 +122,8 @@ fn configure_texture_sampling(images: &mut ResMut<Assets<Image>>, assets: &Point
         &assets.position_texture,
         &assets.colour_class_texture,
         &assets.spatial_index_texture,
+        &assets.normal_texture,
+        &assets.change_texture,
         &assets.result_texture,
         &assets.depth_texture,
     ] {
@@ -29,18 +29,40 @@ pub fn check_texture_loading(
         Some(bevy::asset::LoadState::Loaded)
     );
 
+    // Normals are optional, an unset handle means the manifest has none.
+    let normals_requested = assets.normal_texture != Handle::default();
+    let normal_loaded = !normals_requested
+        || matches!(
+            asset_server.get_load_state(&assets.normal_texture),
+            Some(bevy::asset::LoadState::Loaded)
+        );
+
+    // Change texture is only present for scenes compared against an earlier epoch.
+    let change_requested = assets.change_texture != Handle::default();
+    let change_loaded = !change_requested
+        || matches!(
+            asset_server.get_load_state(&assets.change_texture),
+            Some(bevy::asset::LoadState::Loaded)
+        );
+
     //update the progress so we can send states to the frontend.
     //TODO: real percentages 
 
-    let progress = vec![
+    let mut progress = vec![
         (String::from("Position texture"), i32::from(pos_loaded)),
         (String::from("Colour texture"), i32::from(colour_class_loaded)),
         (String::from("Heightmap"), i32::from(heightmap_loaded)),
         (String::from("Spatial index"), i32::from(spatial_loaded)),
     ];
+    if normals_requested {
+        progress.push((String::from("Normals"), i32::from(normal_loaded)));
+    }
+    if change_requested {
+        progress.push((String::from("Change"), i32::from(change_loaded)));
+    }
     loading_progress.textures_loading_states = progress;
     
-    if pos_loaded && colour_class_loaded && spatial_loaded && heightmap_loaded {
+    if pos_loaded && colour_class_loaded && spatial_loaded && heightmap_loaded && normal_loaded && change_loaded {
         println!("✓ All DDS textures loaded successfully");
         loading_progress.textures_loaded = true;
     }
@@ -38,7 +38,10 @@
 //!
 //! ### Camera Control (`camera`)
 //! Viewport camera with orbit controls, heightmap-aware ground plane intersection,
-//! and smooth interpolation for navigation.
+//! and smooth interpolation for navigation. Named bookmarks and fly-to requests
+//! animate between views; an orthographic top view supports plan-style drawing.
+//! Walk mode moves a first-person camera at eye height over the ground, and
+//! keyframed camera paths replay recorded or bookmarked views along a spline.
 //!
 //! ### GPU Compute (`compute`)
 //! Non-destructive point classification via polygon masks and spatial filtering.
@@ -59,6 +62,8 @@
 //! ### Rendering (`render`)
 //! Custom render pipelines for point clouds and instanced assets with specialised
 //! material bindings, EDL post-processing, and resource extraction systems.
+//! Camera paths are exported offline as PNG sequences through an offscreen target,
+//! and screenshots are rendered in tiles at multiples of the window resolution.
 //!
 //! ### Scene Utilities (`scene`)
 //! Heightmap sampling, heightfield-aware grid generation, and interactive
@@ -103,7 +108,7 @@
 /// Asset loading and management for scene manifests and texture references.
 pub mod assets;
 
-/// Viewport camera with orbit controls and heightmap-aware intersection.
+/// Viewport camera with orbit controls, bookmarks and heightmap-aware intersection.
 pub mod camera;
 
 /// GPU compute shaders for point classification and depth buffer generation.
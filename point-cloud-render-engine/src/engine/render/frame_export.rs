@@ -0,0 +1,286 @@
+use crate::engine::camera::bookmarks::sync_camera_projection;
+use crate::engine::camera::path::CameraPath;
+use crate::engine::camera::viewport_camera::{ViewportCamera, camera_controller};
+use crate::engine::camera::walk::walk_controller;
+use crate::engine::core::app_state::AppState;
+use crate::engine::render::screenshot::ScreenshotCapture;
+use crate::rpc::web_rpc::WebRpcInterface;
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
+use bevy::render::view::screenshot::{Screenshot, ScreenshotCaptured};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Frames rendered at the first view before capture starts, so the new render target
+/// and anything loading for it have settled.
+pub const WARMUP_FRAMES: u32 = 8;
+
+/// Frames go here when no directory is requested.
+pub const DEFAULT_FRAME_DIRECTORY: &str = "camera_path_frames";
+pub const DEFAULT_EXPORT_FPS: u32 = 30;
+pub const MAX_EXPORT_FPS: u32 = 120;
+pub const DEFAULT_EXPORT_SIZE: (u32, u32) = (1920, 1080);
+/// Largest offscreen render target, within the texture limit of common GPUs.
+pub const MAX_RENDER_SIZE: u32 = 8192;
+
+/// Where, how fast and how large to write a camera path as a PNG sequence.
+#[derive(Debug, Clone)]
+pub struct FrameExportRequest {
+    pub directory: PathBuf,
+    pub fps: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Offline frame export. The camera path is stepped one frame per update at a fixed
+/// frame rate, so the output doesn't depend on how fast frames actually render.
+#[derive(Resource, Default)]
+pub struct FrameExport {
+    pending: Option<(FrameExportRequest, f32)>,
+    job: Option<ExportJob>,
+    cancelled: bool,
+}
+
+struct ExportJob {
+    request: FrameExportRequest,
+    frame_count: u32,
+    next_frame: u32,
+    warmup: u32,
+    target: Handle<Image>,
+    previous_target: RenderTarget,
+    /// Updated by screenshot observers as frames reach disk.
+    written: Arc<AtomicU32>,
+    failed: Arc<AtomicU32>,
+}
+
+impl FrameExport {
+    /// Queue an export of a path lasting `duration` seconds.
+    pub fn start(&mut self, request: FrameExportRequest, duration: f32) {
+        self.pending = Some((request, duration));
+        self.cancelled = false;
+    }
+
+    pub fn cancel(&mut self) {
+        self.pending = None;
+        if self.job.is_some() {
+            self.cancelled = true;
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.pending.is_some() || self.job.is_some()
+    }
+}
+
+pub struct FrameExportPlugin;
+impl Plugin for FrameExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FrameExport>().add_systems(
+            Update,
+            run_frame_export
+                .after(camera_controller)
+                .after(walk_controller)
+                .before(sync_camera_projection)
+                .run_if(in_state(AppState::Running)),
+        );
+    }
+}
+
+/// Frames needed to cover `duration` seconds at `fps`, including both ends.
+pub fn frame_count(duration: f32, fps: u32) -> u32 {
+    (duration * fps as f32).ceil() as u32 + 1
+}
+
+/// Blank colour image the camera can render into and screenshots can read back.
+pub fn render_target_image(width: u32, height: u32) -> Image {
+    let size = Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::bevy_default(),
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage = TextureUsages::TEXTURE_BINDING
+        | TextureUsages::COPY_SRC
+        | TextureUsages::COPY_DST
+        | TextureUsages::RENDER_ATTACHMENT;
+    image
+}
+
+/// Redirects the camera into an offscreen image at the requested resolution, places it
+/// exactly on the path for each frame and captures every rendered frame, EDL included,
+/// to `frame_00000.png` onwards. The camera returns to the window once all frames are
+/// on disk.
+pub fn run_frame_export(
+    mut commands: Commands,
+    mut export: ResMut<FrameExport>,
+    capture: Res<ScreenshotCapture>,
+    path: Res<CameraPath>,
+    viewport_camera: Option<ResMut<ViewportCamera>>,
+    mut cameras: Query<(&mut Camera, &mut Transform), With<Camera3d>>,
+    mut images: ResMut<Assets<Image>>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    if !export.is_running() {
+        return;
+    }
+    let Some(mut viewport_camera) = viewport_camera else {
+        return;
+    };
+    let Ok((mut camera, mut transform)) = cameras.single_mut() else {
+        return;
+    };
+
+    // Both redirect the camera, so an export waits for a screenshot to finish
+    if export.pending.is_some() && capture.is_running() {
+        return;
+    }
+    if let Some((request, duration)) = export.pending.take() {
+        if let Err(error) = prepare_export_directory(&request.directory) {
+            send_export_error(&mut rpc_interface, &error);
+            return;
+        }
+        let frame_count = frame_count(duration, request.fps);
+        let target = images.add(render_target_image(request.width, request.height));
+        let previous_target = std::mem::replace(&mut camera.target, target.clone().into());
+
+        info!(
+            "Exporting {} frames at {}x{} to {}",
+            frame_count,
+            request.width,
+            request.height,
+            request.directory.display()
+        );
+        rpc_interface.send_notification(
+            "camera_path_export_started",
+            serde_json::json!({
+                "directory": request.directory.display().to_string(),
+                "frames": frame_count,
+                "fps": request.fps,
+                "width": request.width,
+                "height": request.height,
+            }),
+        );
+        export.job = Some(ExportJob {
+            request,
+            frame_count,
+            next_frame: 0,
+            warmup: WARMUP_FRAMES,
+            target,
+            previous_target,
+            written: Arc::new(AtomicU32::new(0)),
+            failed: Arc::new(AtomicU32::new(0)),
+        });
+    }
+
+    let cancelled = export.cancelled;
+    let Some(job) = export.job.as_mut() else {
+        return;
+    };
+    let written = job.written.load(Ordering::Relaxed);
+    let failed = job.failed.load(Ordering::Relaxed);
+
+    if !cancelled && job.next_frame < job.frame_count {
+        let time = job.next_frame as f32 / job.request.fps as f32;
+        if let Some(view) = path.sample(time) {
+            // Place the camera exactly; the orbit controller would still be easing
+            viewport_camera.set_view(&view);
+            *transform = view.transform();
+        }
+
+        if job.warmup > 0 {
+            job.warmup -= 1;
+            return;
+        }
+
+        let file = job
+            .request
+            .directory
+            .join(format!("frame_{:05}.png", job.next_frame));
+        let (written, failed) = (job.written.clone(), job.failed.clone());
+        commands
+            .spawn(Screenshot::image(job.target.clone()))
+            .observe(move |trigger: Trigger<ScreenshotCaptured>| {
+                match save_frame(&trigger.event().0, &file) {
+                    Ok(()) => written.fetch_add(1, Ordering::Relaxed),
+                    Err(error) => {
+                        warn!("Frame export: {}", error);
+                        failed.fetch_add(1, Ordering::Relaxed)
+                    }
+                };
+            });
+        job.next_frame += 1;
+
+        if job.next_frame % job.request.fps == 0 {
+            rpc_interface.send_notification(
+                "camera_path_export_progress",
+                serde_json::json!({ "frame": job.next_frame, "frames": job.frame_count }),
+            );
+        }
+        return;
+    }
+
+    // Wait for frames still being read back before handing the camera back
+    if !cancelled && written + failed < job.next_frame {
+        return;
+    }
+
+    camera.target = job.previous_target.clone();
+    images.remove(&job.target);
+    info!(
+        "Frame export {}: {} frames written to {}",
+        if cancelled { "cancelled" } else { "finished" },
+        written,
+        job.request.directory.display()
+    );
+    rpc_interface.send_notification(
+        "camera_path_export_finished",
+        serde_json::json!({
+            "directory": job.request.directory.display().to_string(),
+            "frames": written,
+            "failed": failed,
+            "cancelled": cancelled,
+        }),
+    );
+    export.job = None;
+    export.cancelled = false;
+}
+
+fn save_frame(image: &Image, file: &Path) -> Result<(), String> {
+    let frame = image
+        .clone()
+        .try_into_dynamic()
+        .map_err(|e| format!("Unreadable frame {}: {}", file.display(), e))?;
+    // Drop alpha so frames composite the same in every viewer
+    frame
+        .to_rgb8()
+        .save(file)
+        .map_err(|e| format!("Failed to write {}: {}", file.display(), e))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn prepare_export_directory(directory: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(directory)
+        .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn prepare_export_directory(directory: &Path) -> Result<(), String> {
+    Err(format!(
+        "Cannot write frames to {} in the browser; export from the native build",
+        directory.display()
+    ))
+}
+
+fn send_export_error(rpc_interface: &mut WebRpcInterface, error: &str) {
+    warn!("Frame export: {}", error);
+    rpc_interface.send_notification("camera_error", serde_json::json!({ "error": error }));
+}
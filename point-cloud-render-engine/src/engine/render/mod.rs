@@ -13,6 +13,16 @@ pub mod edl_post_processing;
 /// Synchronises camera data, bounds, application state, and scene manifests each frame.
 pub mod extraction;
 
+/// Offline export of camera paths as PNG frame sequences.
+///
+/// Renders each frame into an offscreen target at a fixed frame rate and chosen resolution.
+pub mod frame_export;
+
+/// High-resolution screenshots of the current view with optional overlays.
+///
+/// Renders window-sized tiles of a larger frustum offscreen and stitches them into one PNG.
+pub mod screenshot;
+
 /// Instanced asset rendering pipeline for GPU-efficient object placement.
 ///
 /// Renders multiple asset instances using per-instance vertex buffers and atlas texture sampling.
@@ -0,0 +1,351 @@
+use crate::engine::camera::bookmarks::sync_camera_projection;
+use crate::engine::camera::viewport_camera::camera_controller;
+use crate::engine::camera::walk::walk_controller;
+use crate::engine::core::app_state::AppState;
+use crate::engine::render::frame_export::{FrameExport, WARMUP_FRAMES, render_target_image};
+use crate::engine::scene::gizmos::{DirectionGizmo, MouseIntersectionGizmo};
+use crate::engine::scene::grid::GroundGrid;
+use crate::rpc::web_rpc::WebRpcInterface;
+use crate::tools::measure::{CompletedMeasurementTag, MeasurePreview};
+use crate::tools::polygon::{
+    CompletedPolygon, PolygonFill, PolygonLines, PolygonPoints, PolygonPreview,
+};
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageFormat;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::render::camera::{RenderTarget, SubCameraView};
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::render::view::VisibilitySystems;
+use bevy::render::view::screenshot::{Screenshot, ScreenshotCaptured};
+use bevy::window::PrimaryWindow;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Largest multiple of the window resolution; 4× a 1080p window is already 7680×4320.
+pub const MAX_SCREENSHOT_SCALE: u32 = 4;
+
+/// Overlay layers drawn on top of the point cloud that a screenshot can leave out.
+#[derive(Debug, Clone, Copy)]
+pub struct OverlayLayers {
+    pub polygons: bool,
+    pub measurements: bool,
+    pub grid: bool,
+}
+
+impl Default for OverlayLayers {
+    fn default() -> Self {
+        Self {
+            polygons: true,
+            measurements: true,
+            grid: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScreenshotRequest {
+    /// Output is this many times the window resolution in each direction.
+    pub scale: u32,
+    pub overlays: OverlayLayers,
+    /// Native builds only; defaults to a timestamped file in the working directory.
+    pub path: Option<PathBuf>,
+}
+
+/// Screenshot requests from RPC or keyboard.
+#[derive(Event, Debug, Clone)]
+pub struct ScreenshotEvent {
+    pub request: ScreenshotRequest,
+}
+
+/// Screenshot in progress. The view is rendered offscreen in window-sized tiles, each
+/// a sub-view of the full frustum, so large multiples stay within GPU texture limits.
+#[derive(Resource, Default)]
+pub struct ScreenshotCapture {
+    job: Option<CaptureJob>,
+    /// Visibility of overlay entities hidden for the capture, restored afterwards.
+    hidden: HashMap<Entity, Visibility>,
+}
+
+struct CaptureJob {
+    request: ScreenshotRequest,
+    tile_size: UVec2,
+    next_tile: u32,
+    warmup: u32,
+    /// Camera pose held for every tile, so tiles line up even if the camera is easing.
+    transform: Transform,
+    target: Handle<Image>,
+    previous_target: RenderTarget,
+    previous_sub_view: Option<SubCameraView>,
+    /// Filled in by screenshot observers as tiles are read back.
+    tiles: Arc<Mutex<Vec<Option<Image>>>>,
+}
+
+impl ScreenshotCapture {
+    pub fn is_running(&self) -> bool {
+        self.job.is_some()
+    }
+}
+
+impl CaptureJob {
+    fn tile_count(&self) -> u32 {
+        self.request.scale * self.request.scale
+    }
+}
+
+pub struct ScreenshotPlugin;
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenshotCapture>()
+            .add_event::<ScreenshotEvent>()
+            .add_systems(
+                Update,
+                run_screenshot_capture
+                    .after(camera_controller)
+                    .after(walk_controller)
+                    .before(sync_camera_projection)
+                    .run_if(in_state(AppState::Running)),
+            )
+            .add_systems(
+                PostUpdate,
+                hide_capture_overlays.before(VisibilitySystems::VisibilityPropagate),
+            );
+    }
+}
+
+/// Starts queued screenshots, renders one tile per frame and delivers the stitched
+/// PNG once every tile is back. Native builds also capture the view with F8.
+pub fn run_screenshot_capture(
+    mut commands: Commands,
+    mut events: EventReader<ScreenshotEvent>,
+    #[cfg(not(target_arch = "wasm32"))] keyboard: Res<ButtonInput<KeyCode>>,
+    mut capture: ResMut<ScreenshotCapture>,
+    export: Res<FrameExport>,
+    mut cameras: Query<(&mut Camera, &mut Transform), With<Camera3d>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut images: ResMut<Assets<Image>>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    let mut requests: Vec<ScreenshotRequest> = events.read().map(|e| e.request.clone()).collect();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if keyboard.just_pressed(KeyCode::F8) {
+            requests.push(ScreenshotRequest {
+                scale: 1,
+                overlays: OverlayLayers::default(),
+                path: None,
+            });
+        }
+    }
+
+    let Ok((mut camera, mut transform)) = cameras.single_mut() else {
+        return;
+    };
+
+    if let Some(request) = requests.into_iter().next() {
+        if capture.is_running() || export.is_running() {
+            send_screenshot_error(
+                &mut rpc_interface,
+                "Another capture is running; try again when it finishes",
+            );
+        } else if let Ok(window) = windows.single() {
+            let tile_size = UVec2::new(window.physical_width(), window.physical_height());
+            // A minimised window has no pixels to render tiles into.
+            if tile_size.x == 0 || tile_size.y == 0 {
+                send_screenshot_error(&mut rpc_interface, "The window has no visible area");
+                return;
+            }
+            let tile_count = (request.scale * request.scale) as usize;
+            let target = images.add(render_target_image(tile_size.x, tile_size.y));
+            let previous_target = std::mem::replace(&mut camera.target, target.clone().into());
+            capture.job = Some(CaptureJob {
+                request,
+                tile_size,
+                next_tile: 0,
+                warmup: WARMUP_FRAMES,
+                transform: *transform,
+                target,
+                previous_target,
+                previous_sub_view: camera.sub_camera_view,
+                tiles: Arc::new(Mutex::new(vec![None; tile_count])),
+            });
+        }
+    }
+
+    let Some(job) = capture.job.as_mut() else {
+        return;
+    };
+    *transform = job.transform;
+    let scale = job.request.scale;
+
+    if job.next_tile < job.tile_count() {
+        // Tiles are taken row by row from the top left
+        let column = job.next_tile % scale;
+        let row = job.next_tile / scale;
+        camera.sub_camera_view = (scale > 1).then(|| SubCameraView {
+            full_size: job.tile_size * scale,
+            offset: (UVec2::new(column, row) * job.tile_size).as_vec2(),
+            size: job.tile_size,
+        });
+
+        if job.warmup > 0 {
+            job.warmup -= 1;
+            return;
+        }
+
+        let (tiles, index) = (job.tiles.clone(), job.next_tile as usize);
+        commands
+            .spawn(Screenshot::image(job.target.clone()))
+            .observe(move |trigger: Trigger<ScreenshotCaptured>| {
+                if let Ok(mut tiles) = tiles.lock() {
+                    tiles[index] = Some(trigger.event().0.clone());
+                }
+            });
+        job.next_tile += 1;
+        return;
+    }
+
+    let tiles: Vec<Image> = match job.tiles.lock() {
+        Ok(tiles) if tiles.iter().all(Option::is_some) => tiles.iter().flatten().cloned().collect(),
+        _ => return,
+    };
+    let Some(job) = capture.job.take() else {
+        return;
+    };
+    camera.target = job.previous_target.clone();
+    camera.sub_camera_view = job.previous_sub_view;
+    images.remove(&job.target);
+
+    let size = job.tile_size * scale;
+    match stitch_tiles(&tiles, job.tile_size, scale).and_then(|png| deliver_screenshot(&job, png)) {
+        Ok(mut result) => {
+            info!("Captured {}x{} screenshot", size.x, size.y);
+            result["width"] = size.x.into();
+            result["height"] = size.y.into();
+            rpc_interface.send_notification("screenshot_captured", result);
+        }
+        Err(error) => send_screenshot_error(&mut rpc_interface, &error),
+    }
+}
+
+/// Grid, measurement, polygon and cursor gizmo markers of an entity.
+type OverlayMarkers = (
+    Has<GroundGrid>,
+    (Has<MeasurePreview>, Has<CompletedMeasurementTag>),
+    (
+        Has<PolygonPoints>,
+        Has<PolygonLines>,
+        Has<PolygonFill>,
+        Has<PolygonPreview>,
+        Has<CompletedPolygon>,
+    ),
+    (Has<DirectionGizmo>, Has<MouseIntersectionGizmo>),
+);
+
+/// Hides the overlay layers a capture leaves out, plus the cursor gizmos, every frame
+/// while it runs, and puts their visibility back once it's done.
+pub fn hide_capture_overlays(
+    mut capture: ResMut<ScreenshotCapture>,
+    mut overlays: Query<(Entity, &mut Visibility, OverlayMarkers)>,
+) {
+    let Some(layers) = capture.job.as_ref().map(|job| job.request.overlays) else {
+        if !capture.hidden.is_empty() {
+            for (entity, visibility) in capture.hidden.drain() {
+                if let Ok((_, mut current, ..)) = overlays.get_mut(entity) {
+                    *current = visibility;
+                }
+            }
+        }
+        return;
+    };
+
+    for (entity, mut visibility, (grid, measurement, polygon, gizmo)) in &mut overlays {
+        let measurement = measurement.0 || measurement.1;
+        let polygon = polygon.0 || polygon.1 || polygon.2 || polygon.3 || polygon.4;
+        let hide = (grid && !layers.grid)
+            || (measurement && !layers.measurements)
+            || (polygon && !layers.polygons)
+            || gizmo.0
+            || gizmo.1;
+        if hide && *visibility != Visibility::Hidden {
+            capture.hidden.entry(entity).or_insert(*visibility);
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+/// Assemble row-major tiles into one image and encode it as PNG, without alpha.
+fn stitch_tiles(tiles: &[Image], tile_size: UVec2, scale: u32) -> Result<Vec<u8>, String> {
+    let size = tile_size * scale;
+    let tile_row_bytes = tile_size.x as usize * 4;
+    let row_bytes = size.x as usize * 4;
+    let mut pixels = vec![0u8; row_bytes * size.y as usize];
+
+    for (index, tile) in tiles.iter().enumerate() {
+        let rgba = tile
+            .clone()
+            .try_into_dynamic()
+            .map_err(|e| format!("Unreadable screenshot tile: {}", e))?
+            .to_rgba8();
+        let column = index % scale as usize;
+        let row = index / scale as usize;
+        for (y, line) in rgba.as_raw().chunks_exact(tile_row_bytes).enumerate() {
+            let start = (row * tile_size.y as usize + y) * row_bytes + column * tile_row_bytes;
+            pixels[start..start + tile_row_bytes].copy_from_slice(line);
+        }
+    }
+
+    let image = Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        pixels,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::MAIN_WORLD,
+    );
+    let format = ImageFormat::Png
+        .as_image_crate_format()
+        .ok_or("PNG encoding is not available in this build")?;
+    let mut png = Cursor::new(Vec::new());
+    image
+        .try_into_dynamic()
+        .map_err(|e| format!("Failed to assemble screenshot: {}", e))?
+        .to_rgb8()
+        .write_to(&mut png, format)
+        .map_err(|e| format!("Failed to encode screenshot: {}", e))?;
+    Ok(png.into_inner())
+}
+
+/// Native builds write the PNG to disk and report where.
+#[cfg(not(target_arch = "wasm32"))]
+fn deliver_screenshot(job: &CaptureJob, png: Vec<u8>) -> Result<serde_json::Value, String> {
+    let path = job.request.path.clone().unwrap_or_else(|| {
+        let seconds = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        PathBuf::from(format!("screenshot_{}.png", seconds))
+    });
+    std::fs::write(&path, png).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(serde_json::json!({ "path": path.display().to_string() }))
+}
+
+/// The browser can't write files, so the PNG travels back over postMessage as base64.
+#[cfg(target_arch = "wasm32")]
+fn deliver_screenshot(_job: &CaptureJob, png: Vec<u8>) -> Result<serde_json::Value, String> {
+    use base64::Engine;
+    Ok(serde_json::json!({
+        "filename": "screenshot.png",
+        "mime_type": "image/png",
+        "data": base64::engine::general_purpose::STANDARD.encode(png),
+    }))
+}
+
+fn send_screenshot_error(rpc_interface: &mut WebRpcInterface, error: &str) {
+    warn!("Screenshot: {}", error);
+    rpc_interface.send_notification("screenshot_error", serde_json::json!({ "error": error }));
+}
@@ -49,6 +49,7 @@ pub fn update_direction_gizmo(
             tm.is_tool_active(ToolType::Measure)
                 || tm.is_tool_active(ToolType::Polygon)
                 || tm.is_tool_active(ToolType::AssetPlacement)
+                || tm.is_tool_active(ToolType::Selection)
         });
 
         if hide_gizmos {
@@ -111,6 +112,7 @@ pub fn update_mouse_intersection_gizmo(
             tm.is_tool_active(ToolType::Measure)
                 || tm.is_tool_active(ToolType::Polygon)
                 || tm.is_tool_active(ToolType::AssetPlacement)
+                || tm.is_tool_active(ToolType::Selection)
         });
 
         if hide_gizmos {
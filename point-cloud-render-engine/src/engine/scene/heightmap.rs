@@ -45,6 +45,18 @@ pub fn sample_heightmap_bilinear(
     bounds.min_y() as f32 + normalized_height * (bounds.max_y() - bounds.min_y()) as f32
 }
 
+/// Heightmap elevation below `point`, or the scene ground height outside the heightmap.
+pub fn ground_below(point: Vec3, heightmap: Option<&Image>, bounds: &PointCloudBounds) -> f32 {
+    let norm_x = (point.x - bounds.min_x() as f32) / (bounds.max_x() - bounds.min_x()) as f32;
+    let norm_z = (point.z - bounds.min_z() as f32) / (bounds.max_z() - bounds.min_z()) as f32;
+    match heightmap {
+        Some(image) if (0.0..=1.0).contains(&norm_x) && (0.0..=1.0).contains(&norm_z) => {
+            sample_heightmap_bilinear(image, norm_x, norm_z, bounds)
+        }
+        _ => bounds.ground_height(),
+    }
+}
+
 /// Sample height at specific pixel coordinates
 fn sample_height_at_pixel(data: &[u8], x: usize, z: usize) -> f32 {
     // Check if coordinates are within texture bounds
@@ -0,0 +1,142 @@
+/// Kerb line overlay built from the manifest's ground analysis results
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use bevy::render::mesh::PrimitiveTopology;
+use bevy::render::view::NoFrustumCulling;
+
+use crate::engine::assets::point_cloud_assets::PointCloudAssets;
+use crate::engine::assets::scene_manifest::{KerbLineData, SceneManifest};
+
+/// Lift above the sidewalk top so lines are not hidden inside the points.
+const OVERLAY_LIFT: f32 = 0.03;
+
+/// Kerb height at which the colour ramp reaches full green.
+const FULL_KERB_HEIGHT: f32 = 0.15;
+
+#[derive(Component)]
+pub struct KerbOverlayLine;
+
+#[derive(Resource)]
+pub struct KerbOverlay {
+    pub visible: bool,
+    pub created: bool,
+}
+
+impl Default for KerbOverlay {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            created: false,
+        }
+    }
+}
+
+/// Spawn one line mesh per kerb once the manifest is available.
+/// Vertices are coloured by kerb height, with ramps drawn in red.
+pub fn create_kerb_overlay(
+    mut commands: Commands,
+    mut overlay: ResMut<KerbOverlay>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+) {
+    if overlay.created {
+        return;
+    }
+    let Some(manifest) = assets.manifest.as_ref().and_then(|h| manifests.get(h)) else {
+        return;
+    };
+    overlay.created = true;
+
+    let Some(kerbs) = manifest.analysis.as_ref().and_then(|a| a.kerbs.as_ref()) else {
+        return;
+    };
+
+    // Vertex colours carry the kerb state; the material only disables lighting.
+    let material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        unlit: true,
+        ..default()
+    });
+
+    let visibility = if overlay.visible {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+
+    for line in kerbs.lines.iter().filter(|line| line.vertices.len() >= 2) {
+        commands.spawn((
+            Mesh3d(meshes.add(create_kerb_line_mesh(line))),
+            MeshMaterial3d(material.clone()),
+            visibility,
+            NoFrustumCulling,
+            Transform::IDENTITY,
+            KerbOverlayLine,
+        ));
+    }
+
+    println!("Kerb overlay created with {} lines", kerbs.lines.len());
+}
+
+/// Toggle the overlay with G on native builds.
+pub fn toggle_kerb_overlay(
+    mut overlay: ResMut<KerbOverlay>,
+    #[cfg(not(target_arch = "wasm32"))] keyboard: Res<ButtonInput<KeyCode>>,
+) {
+    #[cfg(not(target_arch = "wasm32"))]
+    if keyboard.just_pressed(KeyCode::KeyG) {
+        overlay.visible = !overlay.visible;
+    }
+}
+
+/// Apply overlay visibility changes from the keyboard or RPC.
+pub fn update_kerb_overlay_visibility(
+    overlay: Res<KerbOverlay>,
+    mut lines: Query<&mut Visibility, With<KerbOverlayLine>>,
+) {
+    if !overlay.is_changed() {
+        return;
+    }
+    let visibility = if overlay.visible {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+    for mut line_visibility in &mut lines {
+        *line_visibility = visibility;
+    }
+}
+
+fn create_kerb_line_mesh(line: &KerbLineData) -> Mesh {
+    let positions: Vec<[f32; 3]> = line
+        .vertices
+        .iter()
+        .map(|v| [v.position[0], v.position[1] + OVERLAY_LIFT, v.position[2]])
+        .collect();
+    let colours: Vec<[f32; 4]> = line
+        .vertices
+        .iter()
+        .map(|v| kerb_colour(v.height_m, v.ramp))
+        .collect();
+
+    let indices = (0..positions.len() as u32 - 1)
+        .flat_map(|i| [i, i + 1])
+        .collect();
+
+    let mut mesh = Mesh::new(PrimitiveTopology::LineList, RenderAssetUsages::RENDER_WORLD);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colours);
+    mesh.insert_indices(bevy::render::mesh::Indices::U32(indices));
+    mesh
+}
+
+/// Red for ramps, otherwise yellow through green as the kerb gets taller.
+fn kerb_colour(height: f32, ramp: bool) -> [f32; 4] {
+    if ramp {
+        return [1.0, 0.1, 0.1, 1.0];
+    }
+    let t = (height / FULL_KERB_HEIGHT).clamp(0.0, 1.0);
+    [1.0 - t, 1.0, 0.1, 1.0]
+}
@@ -13,6 +13,11 @@ pub mod gizmos;
 /// Creates adaptive grid lines that conform to heightmap data for spatial reference.
 pub mod grid;
 
+/// Kerb line overlay from preprocessing analysis results.
+///
+/// Draws detected kerbs coloured by height, with ramps highlighted, and toggles their visibility.
+pub mod kerbs;
+
 /// Heightmap sampling utilities for terrain intersection queries.
 ///
 /// Bilinear interpolation of heightmap textures for smooth elevation sampling.
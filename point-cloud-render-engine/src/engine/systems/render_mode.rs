@@ -39,6 +39,9 @@ pub enum RenderMode {
     PerformanceDebug = 4,
     ClassSelection = 5,
     ConnectivityClass = 6,
+    NormalShaded = 7,
+    Verticality = 8,
+    Change = 9,
 }
 
 /// Handle render mode switching via keyboard input.
@@ -54,7 +57,15 @@ pub fn render_mode_system(
         let mut mode_changed = false;
         let mut new_mode = render_state.current_mode;
 
-        if keyboard.just_pressed(KeyCode::KeyZ) {
+        // Ctrl/Cmd+Z is undo, not a render mode switch.
+        let modifier = keyboard.any_pressed([
+            KeyCode::ControlLeft,
+            KeyCode::ControlRight,
+            KeyCode::SuperLeft,
+            KeyCode::SuperRight,
+        ]);
+
+        if keyboard.just_pressed(KeyCode::KeyZ) && !modifier {
             new_mode = RenderMode::RgbColour;
             mode_changed = true;
             println!("Render mode: RGB Colour");
@@ -96,6 +107,24 @@ pub fn render_mode_system(
             println!("Render mode: Connectivity Class");
         }
 
+        if keyboard.just_pressed(KeyCode::KeyK) {
+            new_mode = RenderMode::NormalShaded;
+            mode_changed = true;
+            println!("Render mode: Normal Shaded");
+        }
+
+        if keyboard.just_pressed(KeyCode::KeyL) {
+            new_mode = RenderMode::Verticality;
+            mode_changed = true;
+            println!("Render mode: Verticality");
+        }
+
+        if keyboard.just_pressed(KeyCode::KeyJ) {
+            new_mode = RenderMode::Change;
+            mode_changed = true;
+            println!("Render mode: Change");
+        }
+
         if mode_changed {
             render_state.current_mode = new_mode;
             // Resource change detection triggers compute shader recomputation.
@@ -4,6 +4,7 @@ mod rpc;
 mod tools;
 
 use crate::engine::assets::point_cloud_assets::PointCloudAssets;
+use crate::engine::assets::texture_files::TerrainTextureFiles;
 use crate::engine::core::app_setup::create_app;
 use crate::engine::systems::render_mode::RenderModeState;
 use constants::path::{
@@ -27,7 +28,11 @@ fn main() {
 }
 
 // Abstracted texture loading function
-fn load_unified_textures(asset_server: &AssetServer, assets: &mut PointCloudAssets) {
+fn load_unified_textures(
+    asset_server: &AssetServer,
+    assets: &mut PointCloudAssets,
+    texture_files: &TerrainTextureFiles,
+) {
     let position_texture_path = format!(
         "{}{}{}/position.dds",
         RELATIVE_MANIFEST_PATH, TERRAIN_PATH, TEXTURE_RESOLUTION_FILE_PATH
@@ -60,6 +65,24 @@ fn load_unified_textures(asset_server: &AssetServer, assets: &mut PointCloudAsse
     assets.colour_class_texture = asset_server.load(&colour_class_texture_path);
     assets.spatial_index_texture = asset_server.load(&spatial_index_texture_path);
     assets.heightmap_texture = asset_server.load(&heightmap_texture_path);
+
+    // Normal texture is optional; a placeholder is created during texture configuration.
+    if texture_files.normal.is_some() {
+        let normal_texture_path = format!(
+            "{}{}{}/normal.dds",
+            RELATIVE_MANIFEST_PATH, TERRAIN_PATH, TEXTURE_RESOLUTION_FILE_PATH
+        );
+        assets.normal_texture = asset_server.load(&normal_texture_path);
+    }
+
+    // Change texture only exists when the scene was compared against an earlier epoch.
+    if texture_files.change.is_some() {
+        let change_texture_path = format!(
+            "{}{}{}/change.dds",
+            RELATIVE_MANIFEST_PATH, TERRAIN_PATH, TEXTURE_RESOLUTION_FILE_PATH
+        );
+        assets.change_texture = asset_server.load(&change_texture_path);
+    }
     assets.asset_position_texture = asset_server.load(&atlas_position_texture_path);
     assets.asset_colour_class_texture = asset_server.load(&atlas_colourclass_texture_path);
 }
@@ -108,7 +108,7 @@
 //! ## Existing Methods
 //!
 //! ### Tool Management
-//! - `tool_selection`: Activate polygon/measure/asset tools
+//! - `tool_selection`: Activate polygon/measure/profile/selection/asset tools
 //! - `clear_tool`: Deactivate current tool
 //!
 //! ### Asset Operations
@@ -119,15 +119,65 @@
 //!
 //! ### Polygon Operations
 //! - `get_classification_categories`: Get point cloud class types
-//! - `hide_points_in_polygon`: Queue hide operation with mask filters
-//! - `reclassify_points_in_polygon`: Queue reclassification with target class
+//! - `hide_points_in_polygon`: Queue hide operation with mask filters and optional height band
+//! - `reclassify_points_in_polygon`: Queue reclassification with target class and optional height band
+//! - `list_polygons`: List completed polygons in evaluation order (later overrides earlier)
+//! - `update_polygon`: Change a polygon's mode, target class, masks, vertices or height band
+//! - `delete_polygon` / `reorder_polygon`: Remove a polygon or move it in the evaluation order
+//! - `set_polygon_edit_mode`: Toggle in-viewport vertex dragging for completed polygons
+//! - `get_polygon_limits`: Polygon, point and mask capacity and current usage
+//! - `get_classification_statistics`: Point counts per class after polygon edits, computed on the CPU
+//! - `pick_point`: Front-most visible point at a window position or the cursor, with class, object ID and colour
 //! - `set_hover_object_id`: Update hover highlight for object ID
 //!
+//! ### Measure Operations
+//! - `set_measure_snap`: Set the point/ground/vertex/midpoint snap mode and toggle snapping
+//! - `set_measure_mode`: Pick distance/polyline/area/height/slope/angle measurements
+//! - `measure_action`: Complete, remove the last point of, or cancel the measurement being drawn
+//! - `list_measurements`: Retrieve completed measurements with their derived values
+//! - `rename_measurement` / `delete_measurement`: Rename or remove a measurement by ID
+//! - `export_measurements`: Export all measurements as `csv` or `json` in world coordinates
+//!
+//! ### Profile Operations
+//! - `profile_action`: Complete, clear or export (`csv`/`svg`) the current profile
+//! - `set_profile_corridor`: Set corridor half-width and ground-only filtering
+//!
+//! ### Selection Operations
+//! - `set_selection_mode`: Set the rectangle/lasso/brush shape, through or front-most depth, operation and brush radius
+//! - `clear_selection`: Drop the pending screen-space selection
+//!
+//! ### History Operations
+//! - `undo` / `redo`: Revert or reapply the last polygon, asset or measurement edit
+//! - `get_history`: Retrieve the undo and redo stacks as edit labels
+//!
+//! ### Camera Operations
+//! - `save_camera_bookmark` / `delete_camera_bookmark`: Save the current view under a name, or remove one
+//! - `go_to_camera_bookmark`: Animate the camera to a saved view
+//! - `list_camera_bookmarks`: Retrieve bookmarks with world-space focus points
+//! - `fly_to`: Animate the camera to a world position or to frame an object ID
+//! - `set_top_view`: Set or toggle the orthographic top-down view
+//! - `set_walk_mode`: Enter or leave first-person walk mode and set eye height and speed
+//!
+//! ### Camera Path Operations
+//! - `record_camera_path`: Start or stop sampling live navigation into keyframes
+//! - `camera_path_from_bookmarks`: Build a path through bookmarks in order
+//! - `add_camera_path_keyframe` / `set_camera_path_duration`: Edit keyframes and timing
+//! - `play_camera_path` / `stop_camera_path` / `clear_camera_path`: Preview, stop or discard the path
+//! - `get_camera_path`: Retrieve keyframes with world-space focus points
+//! - `export_camera_path_frames`: Write the path as a PNG sequence at a fixed frame rate (native only)
+//!
+//! ### Screenshot Operations
+//! - `capture_screenshot`: Render the current view to PNG at 1-4x window resolution, with optional overlays
+//!
+//! ### Session Operations
+//! - `save_session`: Serialise polygons, assets, measurements, camera and bookmarks to a project file
+//! - `load_session`: Restore a project file from `content` (or a native `path`)
+//!
 //! ### Diagnostics
 //! - `get_fps`: Retrieve current frame rate
 //!
 //! ### Render Control
-//! - `render_mode_changed`: Switch between RGB/Original/Modified/Connectivity views
+//! - `render_mode_changed`: Switch between RGB/Original/Modified/Connectivity/Normals/Verticality/Change views
 
 /// JSON-RPC 2.0 bidirectional communication system for React integration.
 ///
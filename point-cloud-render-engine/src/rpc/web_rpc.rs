@@ -1,17 +1,79 @@
 use crate::engine::assets::point_cloud_assets::PointCloudAssets;
 use crate::engine::assets::scene_manifest::SceneManifest;
+use crate::engine::camera::bookmarks::{CameraAction, CameraBookmarks, CameraEvent, FlyToTarget};
+use crate::engine::camera::path::{CameraPath, CameraPathAction, CameraPathEvent, CameraPathState};
+use crate::engine::camera::walk::{EyeHeightPreset, WalkAction, WalkModeEvent};
+use crate::engine::compute::classification_reference::{ClassificationReference, HIDDEN_CLASS};
+use crate::engine::render::frame_export::{
+    DEFAULT_EXPORT_FPS, DEFAULT_EXPORT_SIZE, DEFAULT_FRAME_DIRECTORY, FrameExportRequest,
+    MAX_EXPORT_FPS, MAX_RENDER_SIZE, frame_count,
+};
+use crate::engine::render::screenshot::{
+    MAX_SCREENSHOT_SCALE, OverlayLayers, ScreenshotEvent, ScreenshotRequest,
+};
+use crate::engine::scene::kerbs::KerbOverlay;
 use crate::engine::systems::render_mode::{MouseEnterObjectState, RenderMode, RenderModeState};
 use crate::tools::asset_manager::PlaceAssetBoundState;
-use crate::tools::polygon::{PolygonHideRequestEvent, PolygonReclassifyRequestEvent};
+use crate::tools::history::{EditHistory, HistoryAction, HistoryEvent};
+use crate::tools::measure::{
+    MeasureAction, MeasureActionEvent, MeasureExportFormat, MeasureMode, Measurements,
+};
+use crate::tools::point_pick::PointPicker;
+use crate::tools::polygon::{
+    PolygonClassificationData, PolygonHideRequestEvent, PolygonMode, PolygonReclassifyRequestEvent,
+    VerticalRange,
+};
+use crate::tools::polygon_edit::{PolygonEditAction, PolygonEditEvent, polygon_summaries};
+use crate::tools::profile::ProfileExportFormat;
+use crate::tools::screen_selection::{
+    SelectionAction, SelectionActionEvent, SelectionDepth, SelectionOperation, SelectionShape,
+};
+use crate::tools::session::{SessionAction, SessionEvent};
+use crate::tools::snap::SnapMode;
 use crate::tools::tool_manager::{
-    AssetPlacementAction, AssetPlacementEvent, ClearToolEvent, ToolSelectionEvent,
-    ToolSelectionSource, ToolType,
+    AssetPlacementAction, AssetPlacementEvent, ClearToolEvent, ProfileAction, ProfileActionEvent,
+    ToolSelectionEvent, ToolSelectionSource, ToolType,
 };
 use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::ecs::system::SystemParam;
+use bevy::math::DVec3;
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 
+/// Event writers and state for tool-level RPC methods, grouped to stay within
+/// Bevy's system parameter limit.
+#[derive(SystemParam)]
+struct ToolRpcParams<'w, 's> {
+    profile_actions: EventWriter<'w, ProfileActionEvent>,
+    selection_actions: EventWriter<'w, SelectionActionEvent>,
+    measure_actions: EventWriter<'w, MeasureActionEvent>,
+    measurements: Res<'w, Measurements>,
+    sessions: EventWriter<'w, SessionEvent>,
+    history_events: EventWriter<'w, HistoryEvent>,
+    history: Res<'w, EditHistory>,
+    polygon_edits: EventWriter<'w, PolygonEditEvent>,
+    polygons: Res<'w, PolygonClassificationData>,
+    images: Res<'w, Assets<Image>>,
+    picker: Res<'w, PointPicker>,
+    cameras: Query<'w, 's, (&'static GlobalTransform, &'static Camera), With<Camera3d>>,
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    camera: CameraRpcParams<'w>,
+    screenshots: EventWriter<'w, ScreenshotEvent>,
+}
+
+/// Camera, walk mode and camera path requests and state, nested in `ToolRpcParams`.
+#[derive(SystemParam)]
+struct CameraRpcParams<'w> {
+    events: EventWriter<'w, CameraEvent>,
+    bookmarks: Res<'w, CameraBookmarks>,
+    walk_events: EventWriter<'w, WalkModeEvent>,
+    path_events: EventWriter<'w, CameraPathEvent>,
+    path: Res<'w, CameraPath>,
+    path_state: Res<'w, CameraPathState>,
+}
+
 #[derive(Debug, Deserialize)]
 struct SourceItem {
     class_id: u32,
@@ -22,6 +84,8 @@ struct SourceItem {
 struct HideParams {
     #[serde(default)]
     masked_classes: Vec<SourceItem>,
+    #[serde(default)]
+    vertical_range: Option<VerticalRange>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -30,6 +94,59 @@ struct ReclassifyParams {
     masked_classes: Vec<SourceItem>,
     target_class_id: u32,
     target_object_id: u32,
+    #[serde(default)]
+    vertical_range: Option<VerticalRange>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolygonIdParams {
+    id: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdatePolygonParams {
+    id: u32,
+    #[serde(default)]
+    mode: Option<String>,
+    #[serde(default)]
+    target_class_id: Option<u32>,
+    #[serde(default)]
+    masked_classes: Option<Vec<SourceItem>>,
+    #[serde(default)]
+    vertices: Option<Vec<[f32; 3]>>,
+    /// Absent keeps the current range; `null` removes it.
+    #[serde(default, deserialize_with = "deserialize_present")]
+    vertical_range: Option<Option<VerticalRange>>,
+}
+
+/// Wrap a present field in `Some` so an explicit `null` is distinct from an absent field.
+fn deserialize_present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// Reject inverted or non-finite height bands.
+fn validate_vertical_range(range: Option<&VerticalRange>) -> Result<(), RpcError> {
+    if range.is_some_and(|range| !range.is_valid()) {
+        return Err(RpcError::invalid_params(
+            "vertical_range needs finite min <= max",
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+struct ReorderPolygonParams {
+    id: u32,
+    index: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct PolygonEditModeParams {
+    enabled: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,6 +155,180 @@ struct OnHoverParams {
     object_id: u32,
 }
 
+#[derive(Debug, Deserialize)]
+struct ProfileActionParams {
+    action: String,
+    #[serde(default)]
+    format: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileCorridorParams {
+    half_width: f32,
+    #[serde(default)]
+    ground_only: bool,
+}
+
+/// Window position in logical pixels; omitted coordinates use the cursor.
+#[derive(Debug, Default, Deserialize)]
+struct PickPointParams {
+    #[serde(default)]
+    x: Option<f32>,
+    #[serde(default)]
+    y: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SelectionModeParams {
+    #[serde(default)]
+    shape: Option<String>,
+    #[serde(default)]
+    depth: Option<String>,
+    #[serde(default)]
+    operation: Option<String>,
+    #[serde(default)]
+    brush_radius: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MeasureSnapParams {
+    #[serde(default)]
+    mode: Option<String>,
+    #[serde(default)]
+    enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MeasureModeParams {
+    mode: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MeasureActionParams {
+    action: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MeasurementIdParams {
+    id: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct RenameMeasurementParams {
+    id: u32,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportMeasurementsParams {
+    #[serde(default)]
+    format: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct BookmarkParams {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    duration: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FlyToParams {
+    #[serde(default)]
+    position: Option<[f64; 3]>,
+    #[serde(default)]
+    object_id: Option<u32>,
+    #[serde(default)]
+    height: Option<f32>,
+    #[serde(default)]
+    duration: Option<f32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TopViewParams {
+    #[serde(default)]
+    enabled: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RecordPathParams {
+    #[serde(default)]
+    enabled: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PathFromBookmarksParams {
+    #[serde(default)]
+    names: Option<Vec<String>>,
+    #[serde(default)]
+    seconds_per_view: Option<f32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PathKeyframeParams {
+    #[serde(default)]
+    time: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PathDurationParams {
+    duration: f32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FrameExportParams {
+    #[serde(default)]
+    directory: Option<String>,
+    #[serde(default)]
+    fps: Option<u32>,
+    #[serde(default)]
+    width: Option<u32>,
+    #[serde(default)]
+    height: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ScreenshotParams {
+    #[serde(default)]
+    scale: Option<u32>,
+    #[serde(default)]
+    overlays: ScreenshotOverlayParams,
+    #[serde(default)]
+    path: Option<String>,
+}
+
+/// Overlay layers to draw; any left out are included.
+#[derive(Debug, Default, Deserialize)]
+struct ScreenshotOverlayParams {
+    #[serde(default)]
+    polygons: Option<bool>,
+    #[serde(default)]
+    measurements: Option<bool>,
+    #[serde(default)]
+    grid: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct WalkModeParams {
+    #[serde(default)]
+    enabled: Option<bool>,
+    #[serde(default)]
+    preset: Option<String>,
+    #[serde(default)]
+    eye_height: Option<f32>,
+    #[serde(default)]
+    speed: Option<f32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SessionParams {
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    content: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 struct PolygonOperationResult {
     success: bool,
@@ -225,6 +516,8 @@ fn handle_rpc_messages(
     mut polygon_hide_events: EventWriter<PolygonHideRequestEvent>, // Polygon event writer to handle Hide request
     mut polygon_reclassify_events: EventWriter<PolygonReclassifyRequestEvent>, // Polygon event writer to handle Reclassify request
     mut current_mouse_enter_object_id: ResMut<MouseEnterObjectState>,
+    mut kerb_overlay: ResMut<KerbOverlay>,
+    mut tool_rpc: ToolRpcParams,
 ) {
     for event in events.read() {
         // Parse as generic JSON first to check for 'id' field
@@ -245,6 +538,7 @@ fn handle_rpc_messages(
                         &mut polygon_hide_events, // Polygon Hide event writer
                         &mut polygon_reclassify_events, // Polygon Reclassify writer
                         &mut current_mouse_enter_object_id, // on hover mouse events highlighting of clown pass objects
+                        &mut tool_rpc,
                     ) {
                         rpc_interface.queue_response(response);
                     }
@@ -254,7 +548,7 @@ fn handle_rpc_messages(
             } else {
                 // No ID field - try parsing as notification
                 if let Ok(notification) = serde_json::from_str::<RpcNotification>(&event.content) {
-                    handle_rpc_notification(&notification, &mut render_state);
+                    handle_rpc_notification(&notification, &mut render_state, &mut kerb_overlay);
                 } else {
                     warn!("Failed to parse as RPC notification: {}", event.content);
                 }
@@ -284,6 +578,7 @@ fn handle_rpc_request(
     polygon_hide_events: &mut EventWriter<PolygonHideRequestEvent>, // Accept Polygon Hide writer
     polygon_reclassify_events: &mut EventWriter<PolygonReclassifyRequestEvent>, // Accept Polygon Reclassify writer
     current_mouse_enter_object_id: &mut ResMut<MouseEnterObjectState>,
+    tool_rpc: &mut ToolRpcParams,
 ) -> Option<RpcResponse> {
     // Only generate responses for requests with IDs (notifications have no ID).
     let id = request.id.clone()?;
@@ -312,6 +607,75 @@ fn handle_rpc_request(
         "reclassify_points_in_polygon" => {
             handle_reclassify_points_in_polygon(&request.params, polygon_reclassify_events)
         }
+        "list_polygons" => Ok(json!({
+            "polygons": polygon_summaries(&tool_rpc.polygons),
+            "limits": tool_rpc.polygons.limits_summary(),
+        })),
+        "get_polygon_limits" => Ok(tool_rpc.polygons.limits_summary()),
+        "get_classification_statistics" => {
+            handle_get_classification_statistics(assets, manifests, tool_rpc)
+        }
+        "pick_point" => handle_pick_point(&request.params, assets, manifests, tool_rpc),
+        "delete_polygon" => handle_delete_polygon(&request.params, tool_rpc),
+        "update_polygon" => handle_update_polygon(&request.params, tool_rpc),
+        "reorder_polygon" => handle_reorder_polygon(&request.params, tool_rpc),
+        "set_polygon_edit_mode" => handle_set_polygon_edit_mode(&request.params, tool_rpc),
+        // Profile rpc
+        "profile_action" => handle_profile_action(&request.params, &mut tool_rpc.profile_actions),
+        "set_profile_corridor" => {
+            handle_set_profile_corridor(&request.params, &mut tool_rpc.profile_actions)
+        }
+        // Measure rpc
+        "set_measure_snap" => {
+            handle_set_measure_snap(&request.params, &mut tool_rpc.measure_actions)
+        }
+        "set_measure_mode" => handle_set_measure_mode(&request.params, tool_rpc),
+        "measure_action" => handle_measure_action(&request.params, tool_rpc),
+        "list_measurements" => Ok(json!({
+            "measurements": tool_rpc.measurements.summaries()
+        })),
+        "delete_measurement" => handle_delete_measurement(&request.params, tool_rpc),
+        "rename_measurement" => handle_rename_measurement(&request.params, tool_rpc),
+        "export_measurements" => handle_export_measurements(&request.params, tool_rpc),
+        // Selection rpc
+        "set_selection_mode" => {
+            handle_set_selection_mode(&request.params, &mut tool_rpc.selection_actions)
+        }
+        "clear_selection" => {
+            tool_rpc.selection_actions.write(SelectionActionEvent {
+                action: SelectionAction::Clear,
+            });
+            Ok(json!({ "success": true }))
+        }
+        // Camera rpc
+        "save_camera_bookmark" => handle_save_camera_bookmark(&request.params, tool_rpc),
+        "go_to_camera_bookmark" => handle_go_to_camera_bookmark(&request.params, tool_rpc),
+        "delete_camera_bookmark" => handle_delete_camera_bookmark(&request.params, tool_rpc),
+        "list_camera_bookmarks" => handle_list_camera_bookmarks(assets, manifests, tool_rpc),
+        "fly_to" => handle_fly_to(&request.params, tool_rpc),
+        "set_top_view" => handle_set_top_view(&request.params, tool_rpc),
+        "set_walk_mode" => handle_set_walk_mode(&request.params, tool_rpc),
+        // Camera path rpc
+        "record_camera_path" => handle_record_camera_path(&request.params, tool_rpc),
+        "camera_path_from_bookmarks" => {
+            handle_camera_path_from_bookmarks(&request.params, tool_rpc)
+        }
+        "add_camera_path_keyframe" => handle_add_camera_path_keyframe(&request.params, tool_rpc),
+        "set_camera_path_duration" => handle_set_camera_path_duration(&request.params, tool_rpc),
+        "play_camera_path" => handle_camera_path_action(CameraPathAction::Play, tool_rpc),
+        "stop_camera_path" => handle_camera_path_action(CameraPathAction::Stop, tool_rpc),
+        "clear_camera_path" => handle_camera_path_action(CameraPathAction::Clear, tool_rpc),
+        "get_camera_path" => handle_get_camera_path(assets, manifests, tool_rpc),
+        "export_camera_path_frames" => handle_export_camera_path_frames(&request.params, tool_rpc),
+        // Screenshot rpc
+        "capture_screenshot" => handle_capture_screenshot(&request.params, tool_rpc),
+        // Session rpc
+        "save_session" => handle_save_session(&request.params, &mut tool_rpc.sessions),
+        "load_session" => handle_load_session(&request.params, &mut tool_rpc.sessions),
+        // History rpc
+        "undo" => handle_history_action(HistoryAction::Undo, tool_rpc),
+        "redo" => handle_history_action(HistoryAction::Redo, tool_rpc),
+        "get_history" => Ok(tool_rpc.history.summary()),
         "set_hover_object_id" => {
             info!(
                 "set_hover_object_id request params incomming: {:?}",
@@ -719,6 +1083,7 @@ impl RpcError {
 fn handle_rpc_notification(
     notification: &RpcNotification,
     render_state: &mut ResMut<RenderModeState>,
+    kerb_overlay: &mut ResMut<KerbOverlay>,
 ) {
     match notification.method.as_str() {
         "render_mode_changed" => {
@@ -728,6 +1093,9 @@ fn handle_rpc_notification(
                     "modified" => RenderMode::ModifiedClassification,
                     "connectivity" => RenderMode::ClassSelection,
                     "RGB" => RenderMode::RgbColour,
+                    "normals" => RenderMode::NormalShaded,
+                    "verticality" => RenderMode::Verticality,
+                    "change" => RenderMode::Change,
                     _ => {
                         warn!("Unknown render mode: {}", mode_str);
                         return;
@@ -736,6 +1104,14 @@ fn handle_rpc_notification(
                 render_state.current_mode = new_mode;
             }
         }
+        "overlay_visibility" => {
+            let layer = notification.params.get("layer").and_then(|v| v.as_str());
+            let visible = notification.params.get("visible").and_then(|v| v.as_bool());
+            match (layer, visible) {
+                (Some("kerbs"), Some(visible)) => kerb_overlay.visible = visible,
+                _ => warn!("Invalid overlay_visibility params: {}", notification.params),
+            }
+        }
         _ => {
             warn!("Unknown RPC notification method: {}", notification.method);
         }
@@ -777,8 +1153,11 @@ fn handle_hide_points_in_polygon(
     );
 
     let p: HideParams = serde_json::from_value(params.clone()).map_err(|_| {
-        RpcError::invalid_params("Expected { source_items?: [{class_id, object_id}] }")
+        RpcError::invalid_params(
+            "Expected { source_items?: [{class_id, object_id}], vertical_range?: {reference, min, max} }",
+        )
     })?;
+    validate_vertical_range(p.vertical_range.as_ref())?;
 
     polygon_hide_events.write(PolygonHideRequestEvent {
         source_items: p
@@ -786,6 +1165,7 @@ fn handle_hide_points_in_polygon(
             .into_iter()
             .map(|s| (s.class_id, s.object_id))
             .collect(),
+        vertical_range: p.vertical_range,
     });
 
     let result = PolygonOperationResult {
@@ -809,9 +1189,10 @@ fn handle_reclassify_points_in_polygon(
 
     let p: ReclassifyParams = serde_json::from_value(params.clone()).map_err(|_| {
         RpcError::invalid_params(
-            "Expected { source_items?:[], target_category_id, target_item_id }",
+            "Expected { source_items?:[], target_category_id, target_item_id, vertical_range? }",
         )
     })?;
+    validate_vertical_range(p.vertical_range.as_ref())?;
 
     info!(
         "[RPC] reclassify_points_in_polygon queued; filters={}, target=({},{})",
@@ -827,6 +1208,7 @@ fn handle_reclassify_points_in_polygon(
             .map(|s| (s.class_id, s.object_id))
             .collect(),
         target: (p.target_class_id, p.target_object_id),
+        vertical_range: p.vertical_range,
     });
 
     Ok(json!(PolygonOperationResult {
@@ -836,6 +1218,954 @@ fn handle_reclassify_points_in_polygon(
     }))
 }
 
+/// Count points per modified class by running the classification rules on the CPU.
+fn handle_get_classification_statistics(
+    assets: &Res<PointCloudAssets>,
+    manifests: &Res<Assets<SceneManifest>>,
+    tool_rpc: &ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let bounds = assets
+        .get_bounds(manifests)
+        .ok_or_else(|| RpcError::internal_error("Scene manifest not available"))?;
+    let position_data = tool_rpc
+        .images
+        .get(&assets.position_texture)
+        .and_then(|image| image.data.as_deref());
+    let colour_data = tool_rpc
+        .images
+        .get(&assets.colour_class_texture)
+        .and_then(|image| image.data.as_deref());
+    let (Some(position_data), Some(colour_data)) = (position_data, colour_data) else {
+        return Err(RpcError::internal_error("Point data not available"));
+    };
+
+    let mut reference = ClassificationReference::new(&tool_rpc.polygons.polygons, &bounds.bounds);
+    let heightmap = tool_rpc
+        .images
+        .get(&assets.heightmap_texture)
+        .and_then(|image| Some((image.data.as_deref()?, image.width())));
+    if let Some((data, size)) = heightmap {
+        reference = reference.with_heightmap(data, size);
+    }
+    let classes = reference.classify_texels(position_data, colour_data, bounds.loaded_points);
+
+    let mut counts = std::collections::BTreeMap::new();
+    for class_id in &classes {
+        *counts.entry(*class_id).or_insert(0usize) += 1;
+    }
+    let hidden = counts.remove(&HIDDEN_CLASS).unwrap_or(0);
+
+    Ok(json!({
+        "point_count": classes.len(),
+        "hidden": hidden,
+        "classes": counts
+            .into_iter()
+            .map(|(class_id, count)| json!({ "class_id": class_id, "count": count }))
+            .collect::<Vec<_>>(),
+    }))
+}
+
+/// Pick the front-most visible point at a window position (or the cursor).
+/// Returns `point: null` when nothing is under it.
+fn handle_pick_point(
+    params: &Value,
+    assets: &Res<PointCloudAssets>,
+    manifests: &Res<Assets<SceneManifest>>,
+    tool_rpc: &ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: PickPointParams = if params.is_null() {
+        PickPointParams::default()
+    } else {
+        serde_json::from_value(params.clone())
+            .map_err(|_| RpcError::invalid_params("Expected { x?, y? } in window pixels"))?
+    };
+
+    if !tool_rpc.picker.is_ready() {
+        return Err(RpcError::internal_error("Point pick index not ready"));
+    }
+    let bounds = assets
+        .get_bounds(manifests)
+        .ok_or_else(|| RpcError::internal_error("Scene manifest not available"))?;
+    let (camera_transform, camera) = tool_rpc
+        .cameras
+        .single()
+        .map_err(|_| RpcError::internal_error("Camera not available"))?;
+
+    let cursor = tool_rpc
+        .windows
+        .single()
+        .ok()
+        .and_then(|window| window.cursor_position());
+    let position = match (p.x, p.y) {
+        (Some(x), Some(y)) => Vec2::new(x, y),
+        (None, None) => cursor.ok_or_else(|| {
+            RpcError::invalid_params("Cursor is outside the window; pass x and y")
+        })?,
+        _ => return Err(RpcError::invalid_params("Pass both x and y, or neither")),
+    };
+
+    let picked = tool_rpc.picker.pick(
+        position,
+        camera,
+        camera_transform,
+        &tool_rpc.images,
+        assets,
+        &bounds,
+    );
+
+    Ok(json!({
+        "point": picked.map(|point| point.payload(&bounds)),
+    }))
+}
+
+/// Reject ids that are not in the current polygon list.
+fn require_polygon(tool_rpc: &ToolRpcParams, id: u32) -> Result<(), RpcError> {
+    if tool_rpc.polygons.polygons.iter().any(|p| p.id == id) {
+        Ok(())
+    } else {
+        Err(RpcError::invalid_params(&format!(
+            "Polygon {} not found",
+            id
+        )))
+    }
+}
+
+/// Queue deletion of a single polygon; the new list arrives in `polygons_changed`.
+fn handle_delete_polygon(params: &Value, tool_rpc: &mut ToolRpcParams) -> Result<Value, RpcError> {
+    let p: PolygonIdParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { id }"))?;
+    require_polygon(tool_rpc, p.id)?;
+
+    tool_rpc.polygon_edits.write(PolygonEditEvent {
+        action: PolygonEditAction::Delete { id: p.id },
+    });
+
+    Ok(json!({
+        "success": true,
+        "id": p.id
+    }))
+}
+
+/// Parse + queue a partial polygon update; omitted fields are left unchanged.
+fn handle_update_polygon(params: &Value, tool_rpc: &mut ToolRpcParams) -> Result<Value, RpcError> {
+    let p: UpdatePolygonParams = serde_json::from_value(params.clone()).map_err(|_| {
+        RpcError::invalid_params(
+            "Expected { id, mode?, target_class_id?, masked_classes?, vertices?: [[x, y, z]], vertical_range? }",
+        )
+    })?;
+    require_polygon(tool_rpc, p.id)?;
+
+    let mode = match p.mode.as_deref() {
+        Some(mode) => Some(
+            PolygonMode::from_string(mode)
+                .ok_or_else(|| RpcError::invalid_params("Expected mode 'hide' or 'reclassify'"))?,
+        ),
+        None => None,
+    };
+    if p.vertices.as_ref().is_some_and(|v| v.len() < 3) {
+        return Err(RpcError::invalid_params(
+            "A polygon needs at least 3 vertices",
+        ));
+    }
+    validate_vertical_range(p.vertical_range.flatten().as_ref())?;
+
+    tool_rpc.polygon_edits.write(PolygonEditEvent {
+        action: PolygonEditAction::Update {
+            id: p.id,
+            mode,
+            new_class: p.target_class_id,
+            masks: p.masked_classes.map(|items| {
+                items
+                    .into_iter()
+                    .map(|s| (s.class_id, s.object_id))
+                    .collect()
+            }),
+            vertices: p
+                .vertices
+                .map(|v| v.into_iter().map(Vec3::from_array).collect()),
+            vertical_range: p.vertical_range,
+        },
+    });
+
+    Ok(json!({
+        "success": true,
+        "id": p.id
+    }))
+}
+
+/// Queue a move in the evaluation order; later polygons override earlier ones.
+fn handle_reorder_polygon(params: &Value, tool_rpc: &mut ToolRpcParams) -> Result<Value, RpcError> {
+    let p: ReorderPolygonParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { id, index }"))?;
+    require_polygon(tool_rpc, p.id)?;
+
+    if p.index >= tool_rpc.polygons.polygons.len() {
+        return Err(RpcError::invalid_params(&format!(
+            "index must be less than {}",
+            tool_rpc.polygons.polygons.len()
+        )));
+    }
+
+    tool_rpc.polygon_edits.write(PolygonEditEvent {
+        action: PolygonEditAction::Reorder {
+            id: p.id,
+            index: p.index,
+        },
+    });
+
+    Ok(json!({
+        "success": true,
+        "id": p.id,
+        "index": p.index
+    }))
+}
+
+/// Toggle in-viewport vertex dragging for completed polygons.
+fn handle_set_polygon_edit_mode(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: PolygonEditModeParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { enabled }"))?;
+
+    tool_rpc.polygon_edits.write(PolygonEditEvent {
+        action: PolygonEditAction::SetEditMode(p.enabled),
+    });
+
+    Ok(json!({
+        "success": true,
+        "enabled": p.enabled
+    }))
+}
+
+/// Parse + queue a profile completion, clear or export.
+fn handle_profile_action(
+    params: &Value,
+    profile_action_events: &mut EventWriter<ProfileActionEvent>,
+) -> Result<Value, RpcError> {
+    let p: ProfileActionParams = serde_json::from_value(params.clone()).map_err(|_| {
+        RpcError::invalid_params("Expected { action: 'complete' | 'clear' | 'export', format? }")
+    })?;
+
+    let action = match p.action.as_str() {
+        "complete" => ProfileAction::Complete,
+        "clear" => ProfileAction::Clear,
+        "export" => {
+            let format = p
+                .format
+                .as_deref()
+                .and_then(ProfileExportFormat::from_string)
+                .ok_or_else(|| RpcError::invalid_params("Expected format 'csv' or 'svg'"))?;
+            ProfileAction::Export(format)
+        }
+        other => {
+            return Err(RpcError::invalid_params(&format!(
+                "Unknown profile action: {}",
+                other
+            )));
+        }
+    };
+
+    profile_action_events.write(ProfileActionEvent { action });
+
+    Ok(json!({
+        "success": true,
+        "action": p.action
+    }))
+}
+
+/// Parse + queue corridor settings for the profile tool.
+fn handle_set_profile_corridor(
+    params: &Value,
+    profile_action_events: &mut EventWriter<ProfileActionEvent>,
+) -> Result<Value, RpcError> {
+    let p: ProfileCorridorParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { half_width, ground_only? }"))?;
+
+    if !p.half_width.is_finite() || p.half_width <= 0.0 {
+        return Err(RpcError::invalid_params(
+            "half_width must be greater than 0",
+        ));
+    }
+
+    profile_action_events.write(ProfileActionEvent {
+        action: ProfileAction::SetCorridor {
+            half_width: p.half_width,
+            ground_only: p.ground_only,
+        },
+    });
+
+    Ok(json!({
+        "success": true,
+        "half_width": p.half_width,
+        "ground_only": p.ground_only
+    }))
+}
+
+/// Parse + queue measure snap settings; omitted fields keep their current value.
+fn handle_set_measure_snap(
+    params: &Value,
+    measure_action_events: &mut EventWriter<MeasureActionEvent>,
+) -> Result<Value, RpcError> {
+    let p: MeasureSnapParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { mode?, enabled? }"))?;
+
+    let mode = p
+        .mode
+        .as_deref()
+        .map(|s| {
+            SnapMode::from_string(s).ok_or_else(|| {
+                RpcError::invalid_params("Expected mode 'point', 'ground', 'vertex' or 'midpoint'")
+            })
+        })
+        .transpose()?;
+
+    measure_action_events.write(MeasureActionEvent {
+        action: MeasureAction::SetSnap {
+            mode,
+            enabled: p.enabled,
+        },
+    });
+
+    Ok(json!({
+        "success": true,
+        "mode": mode.map(|m| m.as_str()),
+        "enabled": p.enabled
+    }))
+}
+
+fn handle_set_measure_mode(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: MeasureModeParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { mode }"))?;
+    let mode = MeasureMode::from_string(&p.mode).ok_or_else(|| {
+        RpcError::invalid_params(
+            "Expected mode 'distance', 'polyline', 'area', 'height', 'slope' or 'angle'",
+        )
+    })?;
+
+    tool_rpc.measure_actions.write(MeasureActionEvent {
+        action: MeasureAction::SetMode(mode),
+    });
+
+    Ok(json!({
+        "success": true,
+        "mode": mode.as_str()
+    }))
+}
+
+fn handle_measure_action(params: &Value, tool_rpc: &mut ToolRpcParams) -> Result<Value, RpcError> {
+    let p: MeasureActionParams = serde_json::from_value(params.clone()).map_err(|_| {
+        RpcError::invalid_params("Expected { action: 'complete' | 'remove_last_point' | 'cancel' }")
+    })?;
+
+    let action = match p.action.as_str() {
+        "complete" => MeasureAction::Complete,
+        "remove_last_point" => MeasureAction::RemoveLastPoint,
+        "cancel" => MeasureAction::Cancel,
+        other => {
+            return Err(RpcError::invalid_params(&format!(
+                "Unknown measure action: {}",
+                other
+            )));
+        }
+    };
+    tool_rpc
+        .measure_actions
+        .write(MeasureActionEvent { action });
+
+    Ok(json!({
+        "success": true,
+        "action": p.action
+    }))
+}
+
+fn require_measurement(tool_rpc: &ToolRpcParams, id: u32) -> Result<(), RpcError> {
+    match tool_rpc.measurements.index_of(id) {
+        Some(_) => Ok(()),
+        None => Err(RpcError::invalid_params(&format!(
+            "Measurement {} not found",
+            id
+        ))),
+    }
+}
+
+/// Queue deletion of a measurement; the new list arrives in `measurements_changed`.
+fn handle_delete_measurement(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: MeasurementIdParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { id }"))?;
+    require_measurement(tool_rpc, p.id)?;
+
+    tool_rpc.measure_actions.write(MeasureActionEvent {
+        action: MeasureAction::Delete { id: p.id },
+    });
+
+    Ok(json!({
+        "success": true,
+        "id": p.id
+    }))
+}
+
+fn handle_rename_measurement(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: RenameMeasurementParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { id, name }"))?;
+    require_measurement(tool_rpc, p.id)?;
+    let name = p.name.trim();
+    if name.is_empty() {
+        return Err(RpcError::invalid_params("name must not be empty"));
+    }
+
+    tool_rpc.measure_actions.write(MeasureActionEvent {
+        action: MeasureAction::Rename {
+            id: p.id,
+            name: name.to_string(),
+        },
+    });
+
+    Ok(json!({
+        "success": true,
+        "id": p.id,
+        "name": name
+    }))
+}
+
+/// Queue an export; the file arrives in the `measurements_exported` notification.
+fn handle_export_measurements(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: ExportMeasurementsParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { format? }"))?;
+    let format = match p.format.as_deref() {
+        Some(format) => MeasureExportFormat::from_string(format)
+            .ok_or_else(|| RpcError::invalid_params("Expected format 'csv' or 'json'"))?,
+        None => MeasureExportFormat::Csv,
+    };
+
+    tool_rpc.measure_actions.write(MeasureActionEvent {
+        action: MeasureAction::Export(format),
+    });
+
+    Ok(json!({
+        "success": true,
+        "format": format.extension()
+    }))
+}
+
+/// Parse + queue selection tool settings; omitted fields keep their current value.
+fn handle_set_selection_mode(
+    params: &Value,
+    selection_action_events: &mut EventWriter<SelectionActionEvent>,
+) -> Result<Value, RpcError> {
+    let p: SelectionModeParams = serde_json::from_value(params.clone()).map_err(|_| {
+        RpcError::invalid_params("Expected { shape?, depth?, operation?, brush_radius? }")
+    })?;
+
+    let shape = p
+        .shape
+        .as_deref()
+        .map(|s| {
+            SelectionShape::from_string(s).ok_or_else(|| {
+                RpcError::invalid_params("Expected shape 'rectangle', 'lasso' or 'brush'")
+            })
+        })
+        .transpose()?;
+    let depth = p
+        .depth
+        .as_deref()
+        .map(|s| {
+            SelectionDepth::from_string(s)
+                .ok_or_else(|| RpcError::invalid_params("Expected depth 'through' or 'front'"))
+        })
+        .transpose()?;
+    let operation = p
+        .operation
+        .as_deref()
+        .map(|s| {
+            SelectionOperation::from_string(s).ok_or_else(|| {
+                RpcError::invalid_params("Expected operation 'replace', 'add' or 'subtract'")
+            })
+        })
+        .transpose()?;
+    if p.brush_radius
+        .is_some_and(|radius| !radius.is_finite() || radius <= 0.0)
+    {
+        return Err(RpcError::invalid_params(
+            "brush_radius must be greater than 0",
+        ));
+    }
+
+    selection_action_events.write(SelectionActionEvent {
+        action: SelectionAction::SetMode {
+            shape,
+            depth,
+            operation,
+            brush_radius: p.brush_radius,
+        },
+    });
+
+    Ok(json!({
+        "success": true,
+        "shape": shape.map(|s| s.as_str()),
+        "depth": depth.map(|d| d.as_str()),
+        "operation": operation.map(|o| o.as_str()),
+        "brush_radius": p.brush_radius
+    }))
+}
+
+/// Queue saving the current view; without a name the next free `View N` is used.
+fn handle_save_camera_bookmark(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: BookmarkParams = serde_json::from_value(params.clone()).unwrap_or_default();
+    let name = p.name.as_deref().map(str::trim);
+    if name.is_some_and(str::is_empty) {
+        return Err(RpcError::invalid_params("name must not be empty"));
+    }
+
+    tool_rpc.camera.events.write(CameraEvent {
+        action: CameraAction::SaveBookmark {
+            name: name.map(str::to_string),
+        },
+    });
+
+    Ok(json!({
+        "success": true,
+        "name": name
+    }))
+}
+
+/// Bookmark name from `params`, which must name an existing bookmark.
+fn require_bookmark(params: &Value, tool_rpc: &ToolRpcParams) -> Result<BookmarkParams, RpcError> {
+    let p: BookmarkParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { name }"))?;
+    let Some(name) = p.name.as_deref() else {
+        return Err(RpcError::invalid_params("Expected { name }"));
+    };
+    if tool_rpc.camera.bookmarks.get(name).is_none() {
+        return Err(RpcError::invalid_params(&format!(
+            "Bookmark '{}' not found",
+            name
+        )));
+    }
+    Ok(p)
+}
+
+/// Queue an animated flight to a saved bookmark.
+fn handle_go_to_camera_bookmark(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p = require_bookmark(params, tool_rpc)?;
+    let name = p.name.unwrap_or_default();
+
+    tool_rpc.camera.events.write(CameraEvent {
+        action: CameraAction::GoToBookmark {
+            name: name.clone(),
+            duration: p.duration,
+        },
+    });
+
+    Ok(json!({
+        "success": true,
+        "name": name
+    }))
+}
+
+fn handle_delete_camera_bookmark(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p = require_bookmark(params, tool_rpc)?;
+    let name = p.name.unwrap_or_default();
+
+    tool_rpc.camera.events.write(CameraEvent {
+        action: CameraAction::DeleteBookmark { name: name.clone() },
+    });
+
+    Ok(json!({
+        "success": true,
+        "name": name
+    }))
+}
+
+fn handle_list_camera_bookmarks(
+    assets: &PointCloudAssets,
+    manifests: &Assets<SceneManifest>,
+    tool_rpc: &ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let bounds = assets
+        .get_bounds(manifests)
+        .ok_or_else(|| RpcError::internal_error("Point cloud bounds not available"))?;
+    let (x, y, z) = bounds.bounds.origin;
+
+    Ok(json!({
+        "bookmarks": tool_rpc.camera.bookmarks.summaries(DVec3::new(x, y, z))
+    }))
+}
+
+/// Queue a flight to an absolute world position or to the points of an object ID.
+fn handle_fly_to(params: &Value, tool_rpc: &mut ToolRpcParams) -> Result<Value, RpcError> {
+    let p: FlyToParams = serde_json::from_value(params.clone()).map_err(|_| {
+        RpcError::invalid_params("Expected { position: [x, y, z] } or { object_id }")
+    })?;
+    let target = match (p.position, p.object_id) {
+        (Some(position), None) => FlyToTarget::World(DVec3::from_array(position)),
+        (None, Some(0)) => {
+            return Err(RpcError::invalid_params("object_id 0 is unassigned"));
+        }
+        (None, Some(object_id)) => FlyToTarget::Object(object_id),
+        _ => {
+            return Err(RpcError::invalid_params(
+                "Expected exactly one of position or object_id",
+            ));
+        }
+    };
+    if p.height
+        .is_some_and(|height| height <= 0.0 || !height.is_finite())
+    {
+        return Err(RpcError::invalid_params("height must be positive"));
+    }
+
+    tool_rpc.camera.events.write(CameraEvent {
+        action: CameraAction::FlyTo {
+            target,
+            height: p.height,
+            duration: p.duration,
+        },
+    });
+
+    Ok(json!({ "success": true }))
+}
+
+/// Set or, without `enabled`, toggle the orthographic top view.
+fn handle_set_top_view(params: &Value, tool_rpc: &mut ToolRpcParams) -> Result<Value, RpcError> {
+    let p: TopViewParams = serde_json::from_value(params.clone()).unwrap_or_default();
+
+    tool_rpc.camera.events.write(CameraEvent {
+        action: CameraAction::SetTopView(p.enabled),
+    });
+
+    Ok(json!({
+        "success": true,
+        "enabled": p.enabled
+    }))
+}
+
+/// Enter, leave or adjust first-person walk mode; the applied settings arrive in the
+/// `walk_mode_changed` notification.
+fn handle_set_walk_mode(params: &Value, tool_rpc: &mut ToolRpcParams) -> Result<Value, RpcError> {
+    let p: WalkModeParams = serde_json::from_value(params.clone()).map_err(|_| {
+        RpcError::invalid_params(
+            "Expected { enabled?, preset?: 'pedestrian' | 'wheelchair', eye_height?, speed? }",
+        )
+    })?;
+    let preset = match p.preset.as_deref() {
+        Some(name) => Some(EyeHeightPreset::from_string(name).ok_or_else(|| {
+            RpcError::invalid_params(&format!("Unknown eye height preset: {}", name))
+        })?),
+        None => None,
+    };
+    for (name, value) in [("eye_height", p.eye_height), ("speed", p.speed)] {
+        if value.is_some_and(|value| value <= 0.0 || !value.is_finite()) {
+            return Err(RpcError::invalid_params(&format!(
+                "{} must be positive",
+                name
+            )));
+        }
+    }
+
+    tool_rpc.camera.walk_events.write(WalkModeEvent {
+        action: WalkAction::Set {
+            enabled: p.enabled,
+            preset,
+            eye_height: p.eye_height,
+            speed: p.speed,
+        },
+    });
+
+    Ok(json!({ "success": true }))
+}
+
+/// Start or stop recording live navigation into the camera path; without `enabled`
+/// it toggles.
+fn handle_record_camera_path(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: RecordPathParams = serde_json::from_value(params.clone()).unwrap_or_default();
+
+    tool_rpc.camera.path_events.write(CameraPathEvent {
+        action: CameraPathAction::Record(p.enabled),
+    });
+
+    Ok(json!({ "success": true }))
+}
+
+/// Replace the camera path with the named bookmarks, or all of them, in order.
+fn handle_camera_path_from_bookmarks(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: PathFromBookmarksParams = serde_json::from_value(params.clone()).map_err(|_| {
+        RpcError::invalid_params("Expected { names?: string[], seconds_per_view?: number }")
+    })?;
+    if let Some(name) = p
+        .names
+        .iter()
+        .flatten()
+        .find(|name| tool_rpc.camera.bookmarks.get(name).is_none())
+    {
+        return Err(RpcError::invalid_params(&format!(
+            "Bookmark '{}' not found",
+            name
+        )));
+    }
+    if p.seconds_per_view
+        .is_some_and(|seconds| seconds <= 0.0 || !seconds.is_finite())
+    {
+        return Err(RpcError::invalid_params(
+            "seconds_per_view must be positive",
+        ));
+    }
+
+    tool_rpc.camera.path_events.write(CameraPathEvent {
+        action: CameraPathAction::FromBookmarks {
+            names: p.names,
+            seconds_per_view: p.seconds_per_view,
+        },
+    });
+
+    Ok(json!({ "success": true }))
+}
+
+/// Add the current view to the camera path at `time`, or after the last keyframe.
+fn handle_add_camera_path_keyframe(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: PathKeyframeParams = serde_json::from_value(params.clone()).unwrap_or_default();
+    if p.time.is_some_and(|time| time < 0.0 || !time.is_finite()) {
+        return Err(RpcError::invalid_params("time must not be negative"));
+    }
+
+    tool_rpc.camera.path_events.write(CameraPathEvent {
+        action: CameraPathAction::AddKeyframe { time: p.time },
+    });
+
+    Ok(json!({ "success": true }))
+}
+
+/// Rescale keyframe times so the camera path lasts `duration` seconds.
+fn handle_set_camera_path_duration(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: PathDurationParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { duration: number }"))?;
+    if p.duration <= 0.0 || !p.duration.is_finite() {
+        return Err(RpcError::invalid_params("duration must be positive"));
+    }
+
+    tool_rpc.camera.path_events.write(CameraPathEvent {
+        action: CameraPathAction::SetDuration(p.duration),
+    });
+
+    Ok(json!({ "success": true }))
+}
+
+fn handle_camera_path_action(
+    action: CameraPathAction,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    tool_rpc
+        .camera
+        .path_events
+        .write(CameraPathEvent { action });
+
+    Ok(json!({ "success": true }))
+}
+
+fn handle_get_camera_path(
+    assets: &PointCloudAssets,
+    manifests: &Assets<SceneManifest>,
+    tool_rpc: &ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let bounds = assets
+        .get_bounds(manifests)
+        .ok_or_else(|| RpcError::internal_error("Point cloud bounds not available"))?;
+    let (x, y, z) = bounds.bounds.origin;
+
+    Ok(json!({
+        "keyframes": tool_rpc.camera.path.summaries(DVec3::new(x, y, z)),
+        "duration": tool_rpc.camera.path.duration(),
+        "state": tool_rpc.camera.path_state.mode.as_str(),
+    }))
+}
+
+/// Queue an offline PNG sequence export of the camera path. Native builds only; progress
+/// arrives in `camera_path_export_progress` and the result in `camera_path_export_finished`.
+fn handle_export_camera_path_frames(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: FrameExportParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { directory?, fps?, width?, height? }"))?;
+    let fps = p.fps.unwrap_or(DEFAULT_EXPORT_FPS);
+    if !(1..=MAX_EXPORT_FPS).contains(&fps) {
+        return Err(RpcError::invalid_params(&format!(
+            "fps must be between 1 and {}",
+            MAX_EXPORT_FPS
+        )));
+    }
+    let width = p.width.unwrap_or(DEFAULT_EXPORT_SIZE.0);
+    let height = p.height.unwrap_or(DEFAULT_EXPORT_SIZE.1);
+    if !(1..=MAX_RENDER_SIZE).contains(&width) || !(1..=MAX_RENDER_SIZE).contains(&height) {
+        return Err(RpcError::invalid_params(&format!(
+            "width and height must be between 1 and {}",
+            MAX_RENDER_SIZE
+        )));
+    }
+    if tool_rpc.camera.path.keyframes.len() < 2 {
+        return Err(RpcError::invalid_params(
+            "The camera path needs at least two keyframes",
+        ));
+    }
+    let directory = p
+        .directory
+        .unwrap_or_else(|| DEFAULT_FRAME_DIRECTORY.to_string());
+
+    tool_rpc.camera.path_events.write(CameraPathEvent {
+        action: CameraPathAction::Export(FrameExportRequest {
+            directory: directory.clone().into(),
+            fps,
+            width,
+            height,
+        }),
+    });
+
+    Ok(json!({
+        "success": true,
+        "directory": directory,
+        "frames": frame_count(tool_rpc.camera.path.duration(), fps)
+    }))
+}
+
+/// Queue a screenshot of the current view. The PNG arrives in the `screenshot_captured`
+/// notification, as base64 on the web or a file path on native builds.
+fn handle_capture_screenshot(
+    params: &Value,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let p: ScreenshotParams = serde_json::from_value(params.clone()).map_err(|_| {
+        RpcError::invalid_params(
+            "Expected { scale?, overlays?: { polygons?, measurements?, grid? }, path? }",
+        )
+    })?;
+    let scale = p.scale.unwrap_or(1);
+    if !(1..=MAX_SCREENSHOT_SCALE).contains(&scale) {
+        return Err(RpcError::invalid_params(&format!(
+            "scale must be between 1 and {}",
+            MAX_SCREENSHOT_SCALE
+        )));
+    }
+    let overlays = OverlayLayers {
+        polygons: p.overlays.polygons.unwrap_or(true),
+        measurements: p.overlays.measurements.unwrap_or(true),
+        grid: p.overlays.grid.unwrap_or(true),
+    };
+
+    tool_rpc.screenshots.write(ScreenshotEvent {
+        request: ScreenshotRequest {
+            scale,
+            overlays,
+            path: p.path.map(Into::into),
+        },
+    });
+
+    Ok(json!({
+        "success": true,
+        "scale": scale
+    }))
+}
+
+/// Queue a session save; the project file arrives in the `session_saved` notification.
+fn handle_save_session(
+    params: &Value,
+    session_events: &mut EventWriter<SessionEvent>,
+) -> Result<Value, RpcError> {
+    // Params are optional for save, so a missing/null object means "return the content".
+    let p: SessionParams = serde_json::from_value(params.clone()).unwrap_or_default();
+
+    session_events.write(SessionEvent {
+        action: SessionAction::Save {
+            path: p.path.as_ref().map(Into::into),
+        },
+    });
+
+    Ok(json!({
+        "success": true,
+        "path": p.path
+    }))
+}
+
+/// Queue a session load from inline JSON content or a native file path.
+fn handle_load_session(
+    params: &Value,
+    session_events: &mut EventWriter<SessionEvent>,
+) -> Result<Value, RpcError> {
+    let p: SessionParams = serde_json::from_value(params.clone())
+        .map_err(|_| RpcError::invalid_params("Expected { content } or { path }"))?;
+
+    let action = match (p.content, p.path) {
+        (Some(content), _) => SessionAction::LoadContent(content),
+        (None, Some(path)) => SessionAction::LoadPath(path.into()),
+        (None, None) => {
+            return Err(RpcError::invalid_params("Expected { content } or { path }"));
+        }
+    };
+
+    session_events.write(SessionEvent { action });
+
+    Ok(json!({
+        "success": true,
+        "message": "Session load queued"
+    }))
+}
+
+/// Queue an undo or redo; the new stacks arrive in the `history_changed` notification.
+fn handle_history_action(
+    action: HistoryAction,
+    tool_rpc: &mut ToolRpcParams,
+) -> Result<Value, RpcError> {
+    let available = match action {
+        HistoryAction::Undo => tool_rpc.history.can_undo(),
+        HistoryAction::Redo => tool_rpc.history.can_redo(),
+    };
+    if !available {
+        return Err(RpcError::invalid_params(&format!(
+            "Nothing to {}",
+            action.as_str()
+        )));
+    }
+
+    tool_rpc.history_events.write(HistoryEvent { action });
+
+    Ok(json!({
+        "success": true,
+        "action": action.as_str()
+    }))
+}
+
 /// Handle RPC notifications
 fn handle_mouse_enter_object_id(
     params: &Value,
@@ -1,4 +1,5 @@
 use super::state::*;
+use crate::tools::history::{EditCommand, EditHistory};
 use bevy::prelude::*;
 
 #[derive(Resource, Default)]
@@ -60,17 +61,23 @@ pub fn clear_bounds_button_interaction(
         (Changed<Interaction>, With<Button>, With<ClearBoundsButton>),
     >,
     mut commands: Commands,
-    to_clear: Query<Entity, With<PlacedBounds>>,
+    to_clear: Query<(Entity, &PlacedAssetInstance), With<PlacedBounds>>,
     existing_instances: Query<
         Entity,
         With<crate::engine::render::instanced_render_plugin::InstancedAssetData>,
     >,
     mut placed_assets: ResMut<PlacedAssetInstances>,
+    mut history: ResMut<EditHistory>,
 ) {
     for (interaction, mut bg) in &mut q {
         match *interaction {
             Interaction::Pressed => {
-                for e in &to_clear {
+                if !to_clear.is_empty() {
+                    history.record(EditCommand::DeleteAssets(
+                        to_clear.iter().map(|(_, inst)| inst.clone()).collect(),
+                    ));
+                }
+                for (e, _) in &to_clear {
                     commands.entity(e).despawn();
                 }
                 for e in &existing_instances {
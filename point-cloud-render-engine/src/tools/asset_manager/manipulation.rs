@@ -6,6 +6,7 @@ use crate::engine::assets::point_cloud_assets::PointCloudAssets;
 use crate::engine::assets::scene_manifest::SceneManifest;
 use crate::engine::camera::viewport_camera::ViewportCamera;
 use crate::engine::render::instanced_render_plugin::{InstanceData, InstancedAssetData};
+use crate::tools::history::{EditCommand, EditHistory};
 use bevy::input::mouse::MouseWheel;
 use bevy::prelude::*;
 use bevy::render::alpha::AlphaMode;
@@ -35,7 +36,7 @@ pub fn handle_asset_click(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut events: EventWriter<RebuildInstancesEvent>,
-    mut placed_assets: ResMut<PlacedAssetInstances>,
+    (mut placed_assets, mut history): (ResMut<PlacedAssetInstances>, ResMut<EditHistory>),
     mut existing_instances: Query<&mut InstancedAssetData>,
     existing_preview: Query<Entity, With<AssetPreview>>,
 ) {
@@ -138,22 +139,23 @@ pub fn handle_asset_click(
             );
 
             let placed_instance = PlacedAssetInstance {
+                id: placed_assets.next_id,
                 asset_name: asset_meta.name.clone(),
                 transform,
                 uv_bounds,
             };
+            placed_assets.next_id += 1;
             placed_assets.instances.push(placed_instance.clone());
+            history.record(EditCommand::PlaceAsset(placed_instance.clone()));
 
             // Spawn the actual bounds entity (not selected, not preview)
-            commands.spawn((
-                create_wireframe_mesh_bundle(&mut meshes, &mut materials, size, center),
-                placed_instance.clone(),
-                PlacedBounds,
-                BoundsSize(size),
-                bevy::render::view::NoIndirectDrawing,
-                NoFrustumCulling,
-                Name::new(format!("{}_bounds_wire", asset_meta.name)),
-            ));
+            spawn_placed_bounds(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                placed_instance,
+                size,
+            );
 
             // Update instanced renderer
             if let Ok(mut data) = existing_instances.single_mut() {
@@ -369,10 +371,14 @@ pub fn deselect_on_escape(
 // Delete selected assets with Delete key
 pub fn delete_selected(
     keyboard: Res<ButtonInput<KeyCode>>,
-    q_bounds: Query<(Entity, &PlacedAssetInstance), (With<PlacedBounds>, With<Selected>)>,
+    q_bounds: Query<
+        (Entity, &PlacedAssetInstance, Option<&SelectionOrigin>),
+        (With<PlacedBounds>, With<Selected>),
+    >,
     q_all_placed: Query<&PlacedAssetInstance, (With<PlacedBounds>, Without<Selected>)>,
     mut commands: Commands,
     mut placed_assets: ResMut<PlacedAssetInstances>,
+    mut history: ResMut<EditHistory>,
     mut existing_instances: Query<(Entity, &mut InstancedAssetData)>,
     place: Res<PlaceAssetBoundState>,
     manifests: Res<Assets<SceneManifest>>,
@@ -382,7 +388,21 @@ pub fn delete_selected(
         return;
     }
 
-    let to_delete: Vec<_> = q_bounds.iter().map(|(e, inst)| (e, inst.clone())).collect();
+    let to_delete: Vec<_> = q_bounds
+        .iter()
+        .map(|(e, inst, _)| (e, inst.clone()))
+        .collect();
+
+    // Undo puts assets back where they were before this selection moved them
+    history.record(EditCommand::DeleteAssets(
+        q_bounds
+            .iter()
+            .map(|(_, inst, origin)| PlacedAssetInstance {
+                transform: origin.map_or(inst.transform, |o| o.0),
+                ..inst.clone()
+            })
+            .collect(),
+    ));
 
     // Despawn the bound entities
     for (entity, _) in &to_delete {
@@ -391,14 +411,9 @@ pub fn delete_selected(
 
     // Update the resource list (for save/load purposes)
     for (_, instance) in &to_delete {
-        placed_assets.instances.retain(|inst| {
-            !(inst.asset_name == instance.asset_name
-                && inst
-                    .transform
-                    .translation
-                    .distance(instance.transform.translation)
-                    < 0.1)
-        });
+        placed_assets
+            .instances
+            .retain(|inst| inst.id != instance.id);
     }
 
     // Rebuild instance data from remaining components (which excludes deleted ones after despawn)
@@ -455,6 +470,43 @@ fn rebuild_instances(
     }
 }
 
+// Remember where assets were when selected and record a move/rotate edit once released
+pub fn record_asset_transform_edits(
+    mut commands: Commands,
+    newly_selected: Query<(Entity, &PlacedAssetInstance), Added<Selected>>,
+    released: Query<(Entity, &PlacedAssetInstance, &SelectionOrigin), Without<Selected>>,
+    mut placed_assets: ResMut<PlacedAssetInstances>,
+    mut history: ResMut<EditHistory>,
+) {
+    for (entity, placed) in &newly_selected {
+        commands
+            .entity(entity)
+            .insert(SelectionOrigin(placed.transform));
+    }
+
+    for (entity, placed, SelectionOrigin(before)) in &released {
+        commands.entity(entity).remove::<SelectionOrigin>();
+
+        let moved = before.translation.distance(placed.transform.translation) > 0.001
+            || before.rotation.angle_between(placed.transform.rotation) > 0.001;
+        if !moved {
+            continue;
+        }
+
+        history.record(EditCommand::TransformAsset {
+            id: placed.id,
+            asset_name: placed.asset_name.clone(),
+            before: *before,
+            after: placed.transform,
+        });
+        for inst in placed_assets.instances.iter_mut() {
+            if inst.id == placed.id {
+                inst.transform = placed.transform;
+            }
+        }
+    }
+}
+
 fn select_asset(commands: &mut Commands, entity: Entity) {
     commands.entity(entity).insert(Selected);
     commands.entity(entity).insert(ActiveRotating);
@@ -482,7 +534,7 @@ fn deselect_all<'a>(
     }
 }
 
-fn calculate_asset_size(asset_meta: &AssetDefinition) -> Vec3 {
+pub(crate) fn calculate_asset_size(asset_meta: &AssetDefinition) -> Vec3 {
     let lb = &asset_meta.local_bounds;
     let mut sx = (lb.max_x - lb.min_x).max(0.001) as f32;
     let mut sy = (lb.max_y - lb.min_y).max(0.001) as f32;
@@ -559,17 +611,38 @@ fn create_wireframe_mesh_bundle(
     )
 }
 
-fn create_new_instanced_renderer(
+// Spawn the selectable wireframe bounds entity for a placed asset
+pub(crate) fn spawn_placed_bounds(
     commands: &mut Commands,
-    meshes: &mut Assets<Mesh>,
-    instances: &[PlacedAssetInstance],
-    asset_meta: &AssetDefinition,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    placed_instance: PlacedAssetInstance,
+    size: Vec3,
 ) {
-    if instances.is_empty() {
-        return;
-    }
+    let (mesh, material, _) = create_wireframe_mesh_bundle(
+        meshes,
+        materials,
+        size,
+        placed_instance.transform.translation,
+    );
+    let name = Name::new(format!("{}_bounds_wire", placed_instance.asset_name));
 
-    let instance_data: Vec<InstanceData> = instances
+    commands.spawn((
+        (mesh, material, placed_instance.transform),
+        placed_instance,
+        PlacedBounds,
+        BoundsSize(size),
+        bevy::render::view::NoIndirectDrawing,
+        NoFrustumCulling,
+        name,
+    ));
+}
+
+pub(crate) fn instance_data(
+    instances: &[PlacedAssetInstance],
+    asset_meta: &AssetDefinition,
+) -> Vec<InstanceData> {
+    instances
         .iter()
         .map(|placed| InstanceData {
             position: placed.transform.translation.to_array(),
@@ -584,13 +657,24 @@ fn create_new_instanced_renderer(
             point_count: asset_meta.point_count as f32,
             _padding2: [0.0; 3],
         })
-        .collect();
+        .collect()
+}
+
+pub(crate) fn create_new_instanced_renderer(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    instances: &[PlacedAssetInstance],
+    asset_meta: &AssetDefinition,
+) {
+    if instances.is_empty() {
+        return;
+    }
 
     commands.spawn((
         Mesh3d(meshes.add(
             crate::engine::mesh::point_index_mesh::create_point_index_mesh(asset_meta.point_count),
         )),
-        InstancedAssetData(instance_data),
+        InstancedAssetData(instance_data(instances, asset_meta)),
         Transform::IDENTITY,
         NoFrustumCulling,
         bevy::render::view::NoIndirectDrawing,
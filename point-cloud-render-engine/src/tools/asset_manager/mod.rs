@@ -86,7 +86,7 @@ use ui::{
 
 use manipulation::{
     RebuildInstancesEvent, delete_selected, deselect_on_escape, handle_asset_click,
-    manipulate_selected_asset, rebuild_instances_on_event,
+    manipulate_selected_asset, rebuild_instances_on_event, record_asset_transform_edits,
 };
 
 use interactions::{
@@ -116,6 +116,7 @@ impl Plugin for AssetManagerPlugin {
                     deselect_on_escape,
                     handle_asset_click,
                     rebuild_instances_on_event,
+                    record_asset_transform_edits,
                 ),
             );
 
@@ -37,6 +37,7 @@ impl Default for RotationSettings {
 #[derive(Resource, Clone, Default)]
 pub struct PlacedAssetInstances {
     pub instances: Vec<PlacedAssetInstance>,
+    pub next_id: u32,
 }
 
 // Components
@@ -66,10 +67,14 @@ pub struct ActiveRotating;
 pub struct Selected;
 #[derive(Component)]
 pub struct BoundsSize(pub Vec3);
+/// Transform an asset had when it was selected, used to record move/rotate edits.
+#[derive(Component)]
+pub struct SelectionOrigin(pub Transform);
 
 // Per-placed instance data
-#[derive(Component, Clone, ExtractComponent)]
+#[derive(Component, Clone, Debug, ExtractComponent)]
 pub struct PlacedAssetInstance {
+    pub id: u32, // Stable across undo/redo so history commands can find the entity.
     pub asset_name: String,
     pub transform: Transform,
     pub uv_bounds: Vec4,
@@ -1,6 +1,7 @@
 use crate::engine::assets::point_cloud_assets::PointCloudAssets;
 use crate::engine::assets::scene_manifest::SceneManifest;
 use crate::engine::camera::viewport_camera::ViewportCamera;
+use crate::tools::point_pick::PointPicker;
 use bevy::prelude::*;
 use bevy::render::extract_resource::ExtractResource;
 use bevy::window::PrimaryWindow;
@@ -16,37 +17,36 @@ pub struct ClassSelectionState {
     pub is_selecting: bool,
 }
 
-// Selection buffer system remains unchanged
+/// Toggles the object under the cursor in the selection buffer on click while selecting.
+///
+/// Unsegmented points (object 0) are ignored.
 pub fn update_selection_buffer(
     mut selection_buffer: ResMut<SelectionBuffer>,
+    selection_state: Res<ClassSelectionState>,
+    picker: Res<PointPicker>,
     mouse_button: Res<ButtonInput<MouseButton>>,
     keyboard: Res<ButtonInput<KeyCode>>,
 ) {
-    if mouse_button.just_pressed(MouseButton::Left) {
-        selection_buffer.selected_ids.push(2);
-    }
-
-    // Number keys 1-9 to set specific IDs
-    if keyboard.just_pressed(KeyCode::Digit1) {
-        selection_buffer.selected_ids.clear();
-        selection_buffer.selected_ids.push(10);
-    }
-    if keyboard.just_pressed(KeyCode::Digit2) {
-        selection_buffer.selected_ids.clear();
-        selection_buffer.selected_ids.push(11);
-    }
-    if keyboard.just_pressed(KeyCode::Digit3) {
+    // Clear all selections
+    if keyboard.just_pressed(KeyCode::Escape) {
         selection_buffer.selected_ids.clear();
-        selection_buffer.selected_ids.push(12);
     }
-    if keyboard.just_pressed(KeyCode::Digit4) {
-        selection_buffer.selected_ids.clear();
-        selection_buffer.selected_ids.push(13);
+
+    if !selection_state.is_selecting {
+        return;
     }
 
-    // Clear all selections
-    if keyboard.just_pressed(KeyCode::KeyC) {
-        selection_buffer.selected_ids.clear();
+    if mouse_button.just_pressed(MouseButton::Left)
+        && let Some(point) = picker.hovered.filter(|p| p.object_id != 0)
+    {
+        let ids = &mut selection_buffer.selected_ids;
+        match ids.iter().position(|&id| id == point.object_id) {
+            Some(existing) => {
+                ids.remove(existing);
+            }
+            None => ids.push(point.object_id),
+        }
+        println!("Selected objects: {:?}", ids);
     }
 }
 
@@ -60,6 +60,7 @@ pub fn handle_class_selection(
     assets: Res<PointCloudAssets>,
     images: Res<Assets<Image>>,
     manifests: Res<Assets<SceneManifest>>,
+    picker: Res<PointPicker>,
 ) {
     if keyboard.just_pressed(KeyCode::KeyS) {
         selection_state.is_selecting = true;
@@ -76,7 +77,14 @@ pub fn handle_class_selection(
     };
 
     if mouse_button.just_pressed(MouseButton::Left) && selection_state.is_selecting {
-        if let (Ok((camera_global_transform, camera)), Ok(window)) =
+        // Prefer the rendered point under the cursor; fall back to the ground plane.
+        if let Some(point) = picker.hovered {
+            selection_state.selection_point = Some(point.position);
+            println!(
+                "Selected point: {:?} (class {}, object {})",
+                point.position, point.class_id, point.object_id
+            );
+        } else if let (Ok((camera_global_transform, camera)), Ok(window)) =
             (camera_query.single(), windows.single())
         {
             if let Some(cursor_pos) = window.cursor_position() {
@@ -0,0 +1,566 @@
+use crate::engine::assets::point_cloud_assets::PointCloudAssets;
+use crate::engine::assets::scene_manifest::SceneManifest;
+use crate::engine::core::app_state::AppState;
+use crate::engine::render::instanced_render_plugin::InstancedAssetData;
+use crate::rpc::web_rpc::WebRpcInterface;
+use crate::tools::asset_manager::manipulation::{
+    calculate_asset_size, create_new_instanced_renderer, instance_data, spawn_placed_bounds,
+};
+use crate::tools::asset_manager::state::{PlacedAssetInstance, PlacedAssetInstances, PlacedBounds};
+use crate::tools::measure::{Measurement, Measurements};
+use crate::tools::polygon::{
+    ClassificationPolygon, CompletedPolygon, PolygonClassificationData, PolygonMode,
+};
+use crate::tools::polygon_edit::{
+    despawn_polygon_meshes, move_polygon, polygon_index, rebuild_polygon_meshes,
+};
+use bevy::prelude::*;
+
+/// Oldest commands are dropped once the undo stack grows past this.
+pub const MAX_HISTORY: usize = 100;
+
+/// A reversible edit. Each variant carries enough state to apply it in both directions.
+#[derive(Debug, Clone)]
+pub enum EditCommand {
+    AddPolygon(ClassificationPolygon),
+    ClearPolygons(Vec<ClassificationPolygon>),
+    UpdatePolygon {
+        before: ClassificationPolygon,
+        after: ClassificationPolygon,
+    },
+    DeletePolygon {
+        polygon: ClassificationPolygon,
+        index: usize,
+    },
+    ReorderPolygon {
+        id: u32,
+        from: usize,
+        to: usize,
+    },
+    PlaceAsset(PlacedAssetInstance),
+    TransformAsset {
+        id: u32,
+        asset_name: String,
+        before: Transform,
+        after: Transform,
+    },
+    DeleteAssets(Vec<PlacedAssetInstance>),
+    AddMeasurement(Measurement),
+    DeleteMeasurement {
+        measurement: Measurement,
+        index: usize,
+    },
+    RenameMeasurement {
+        id: u32,
+        before: String,
+        after: String,
+    },
+}
+
+impl EditCommand {
+    /// Short description shown in the frontend history list.
+    pub fn label(&self) -> String {
+        match self {
+            Self::AddPolygon(p) => match p.mode {
+                PolygonMode::Hide => format!("Hide polygon {}", p.id),
+                PolygonMode::Reclassify => {
+                    format!("Reclassify polygon {} to class {}", p.id, p.new_class)
+                }
+            },
+            Self::ClearPolygons(polygons) => format!("Clear {} polygons", polygons.len()),
+            Self::UpdatePolygon { after, .. } => format!("Edit polygon {}", after.id),
+            Self::DeletePolygon { polygon, .. } => format!("Delete polygon {}", polygon.id),
+            Self::ReorderPolygon { id, to, .. } => format!("Move polygon {} to {}", id, to),
+            Self::PlaceAsset(a) => format!("Place {}", a.asset_name),
+            Self::TransformAsset { asset_name, .. } => format!("Move {}", asset_name),
+            Self::DeleteAssets(assets) => match assets.as_slice() {
+                [a] => format!("Delete {}", a.asset_name),
+                _ => format!("Delete {} assets", assets.len()),
+            },
+            Self::AddMeasurement(m) => format!("Measure {} ({})", m.name, m.value_text()),
+            Self::DeleteMeasurement { measurement, .. } => {
+                format!("Delete {}", measurement.name)
+            }
+            Self::RenameMeasurement { before, after, .. } => {
+                format!("Rename {} to {}", before, after)
+            }
+        }
+    }
+}
+
+/// Undo and redo stacks. Recording a new edit discards the redo stack.
+#[derive(Resource, Default)]
+pub struct EditHistory {
+    undo: Vec<EditCommand>,
+    redo: Vec<EditCommand>,
+}
+
+impl EditHistory {
+    pub fn record(&mut self, command: EditCommand) {
+        self.undo.push(command);
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Stack labels for the frontend; `undo` is oldest first, `redo` is next-to-redo first.
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "undo": self.undo.iter().map(EditCommand::label).collect::<Vec<_>>(),
+            "redo": self.redo.iter().rev().map(EditCommand::label).collect::<Vec<_>>(),
+            "can_undo": self.can_undo(),
+            "can_redo": self.can_redo(),
+        })
+    }
+
+    fn take(&mut self, action: HistoryAction) -> Option<EditCommand> {
+        match action {
+            HistoryAction::Undo => self.undo.pop(),
+            HistoryAction::Redo => self.redo.pop(),
+        }
+    }
+
+    /// Move an applied command onto the opposite stack without clearing redo.
+    fn applied(&mut self, action: HistoryAction, command: EditCommand) {
+        match action {
+            HistoryAction::Undo => self.redo.push(command),
+            HistoryAction::Redo => self.undo.push(command),
+        }
+    }
+
+    /// Put a command back where it came from after a failed apply.
+    fn restore(&mut self, action: HistoryAction, command: EditCommand) {
+        match action {
+            HistoryAction::Undo => self.undo.push(command),
+            HistoryAction::Redo => self.redo.push(command),
+        }
+    }
+}
+
+/// Event fired when undo or redo is requested via RPC or keyboard.
+#[derive(Event, Debug, Clone)]
+pub struct HistoryEvent {
+    pub action: HistoryAction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryAction {
+    Undo,
+    Redo,
+}
+
+impl HistoryAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+        }
+    }
+}
+
+pub struct HistoryPlugin;
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EditHistory>()
+            .add_event::<HistoryEvent>()
+            .add_systems(
+                Update,
+                (history_keyboard_shortcuts, handle_history_events)
+                    .chain()
+                    .run_if(in_state(AppState::Running)),
+            );
+    }
+}
+
+/// Native Ctrl+Z / Ctrl+Shift+Z (Cmd on macOS); no-op on WASM where the frontend sends RPCs.
+pub fn history_keyboard_shortcuts(
+    #[cfg(not(target_arch = "wasm32"))] keyboard: Res<ButtonInput<KeyCode>>,
+    #[cfg(not(target_arch = "wasm32"))] mut events: EventWriter<HistoryEvent>,
+) {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let modifier = keyboard.any_pressed([
+            KeyCode::ControlLeft,
+            KeyCode::ControlRight,
+            KeyCode::SuperLeft,
+            KeyCode::SuperRight,
+        ]);
+        if modifier && keyboard.just_pressed(KeyCode::KeyZ) {
+            let action = if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+                HistoryAction::Redo
+            } else {
+                HistoryAction::Undo
+            };
+            events.write(HistoryEvent { action });
+        }
+    }
+}
+
+/// Applies undo/redo requests and reports stack changes to the frontend.
+pub fn handle_history_events(
+    mut events: EventReader<HistoryEvent>,
+    mut history: ResMut<EditHistory>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut classification_data: ResMut<PolygonClassificationData>,
+    mut placed_assets: ResMut<PlacedAssetInstances>,
+    mut measurements: ResMut<Measurements>,
+    completed_polygons: Query<(Entity, &CompletedPolygon)>,
+    mut placed_bounds: Query<
+        (Entity, &mut Transform, &mut PlacedAssetInstance),
+        With<PlacedBounds>,
+    >,
+    mut renderers: Query<(Entity, &mut InstancedAssetData)>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    for event in events.read() {
+        let action = event.action;
+        let Some(command) = history.take(action) else {
+            continue;
+        };
+        let undo = action == HistoryAction::Undo;
+
+        let Some(manifest) = assets.manifest.as_ref().and_then(|h| manifests.get(h)) else {
+            history.restore(action, command);
+            continue;
+        };
+        let ground_height = manifest.to_point_cloud_bounds().ground_height();
+
+        let result = match &command {
+            EditCommand::AddPolygon(polygon) => {
+                if undo {
+                    remove_polygons(
+                        &[polygon.id],
+                        &mut classification_data,
+                        &completed_polygons,
+                        &mut commands,
+                    );
+                    Ok(())
+                } else {
+                    restore_polygons(
+                        std::slice::from_ref(polygon),
+                        &mut classification_data,
+                        &completed_polygons,
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        ground_height,
+                    )
+                }
+            }
+            EditCommand::ClearPolygons(polygons) => {
+                if undo {
+                    restore_polygons(
+                        polygons,
+                        &mut classification_data,
+                        &completed_polygons,
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        ground_height,
+                    )
+                } else {
+                    let ids: Vec<u32> = polygons.iter().map(|p| p.id).collect();
+                    remove_polygons(
+                        &ids,
+                        &mut classification_data,
+                        &completed_polygons,
+                        &mut commands,
+                    );
+                    Ok(())
+                }
+            }
+            EditCommand::UpdatePolygon { before, after } => {
+                let target = if undo { before } else { after };
+                match polygon_index(&classification_data, target.id) {
+                    Some(index) => {
+                        classification_data.polygons[index] = target.clone();
+                        rebuild_polygon_meshes(
+                            target,
+                            &completed_polygons,
+                            &mut commands,
+                            &mut meshes,
+                            &mut materials,
+                            ground_height,
+                            classification_data.height_origin,
+                        );
+                        Ok(())
+                    }
+                    None => Err(format!("Polygon {} no longer exists", target.id)),
+                }
+            }
+            EditCommand::DeletePolygon { polygon, index } => {
+                if undo {
+                    // Restore at the end, then move back to its original place in the order.
+                    restore_polygons(
+                        std::slice::from_ref(polygon),
+                        &mut classification_data,
+                        &completed_polygons,
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        ground_height,
+                    )
+                    .map(|()| {
+                        move_polygon(&mut classification_data, polygon.id, *index);
+                    })
+                } else {
+                    remove_polygons(
+                        &[polygon.id],
+                        &mut classification_data,
+                        &completed_polygons,
+                        &mut commands,
+                    );
+                    Ok(())
+                }
+            }
+            EditCommand::ReorderPolygon { id, from, to } => {
+                let index = if undo { *from } else { *to };
+                move_polygon(&mut classification_data, *id, index)
+                    .map(|_| ())
+                    .ok_or_else(|| format!("Polygon {} no longer exists", id))
+            }
+            EditCommand::PlaceAsset(_)
+            | EditCommand::TransformAsset { .. }
+            | EditCommand::DeleteAssets(_) => {
+                // Work on a copy of the live instances; commands only apply at the next sync point.
+                let mut live: Vec<PlacedAssetInstance> =
+                    placed_bounds.iter().map(|(_, _, p)| p.clone()).collect();
+
+                match (&command, undo) {
+                    (EditCommand::PlaceAsset(a), true) => {
+                        despawn_assets(&[a.id], &placed_bounds, &mut live, &mut commands);
+                    }
+                    (EditCommand::PlaceAsset(a), false) => {
+                        spawn_assets(
+                            std::slice::from_ref(a),
+                            manifest,
+                            &mut live,
+                            &mut commands,
+                            &mut meshes,
+                            &mut materials,
+                        );
+                    }
+                    (EditCommand::DeleteAssets(deleted), true) => {
+                        spawn_assets(
+                            deleted,
+                            manifest,
+                            &mut live,
+                            &mut commands,
+                            &mut meshes,
+                            &mut materials,
+                        );
+                    }
+                    (EditCommand::DeleteAssets(deleted), false) => {
+                        let ids: Vec<u32> = deleted.iter().map(|a| a.id).collect();
+                        despawn_assets(&ids, &placed_bounds, &mut live, &mut commands);
+                    }
+                    (
+                        EditCommand::TransformAsset {
+                            id, before, after, ..
+                        },
+                        undo,
+                    ) => {
+                        let target = if undo { *before } else { *after };
+                        for (_, mut transform, mut placed) in &mut placed_bounds {
+                            if placed.id == *id {
+                                *transform = target;
+                                placed.transform = target;
+                            }
+                        }
+                        for placed in live.iter_mut().filter(|p| p.id == *id) {
+                            placed.transform = target;
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+
+                sync_instanced_renderer(
+                    &live,
+                    manifest,
+                    &mut renderers,
+                    &mut commands,
+                    &mut meshes,
+                );
+                placed_assets.instances = live;
+                Ok(())
+            }
+            EditCommand::AddMeasurement(measurement) => {
+                if undo {
+                    if let Some(index) = measurements.index_of(measurement.id) {
+                        measurements.items.remove(index);
+                    }
+                } else {
+                    let end = measurements.items.len();
+                    measurements.restore(measurement.clone(), end);
+                }
+                Ok(())
+            }
+            EditCommand::DeleteMeasurement { measurement, index } => {
+                if undo {
+                    measurements.restore(measurement.clone(), *index);
+                } else if let Some(index) = measurements.index_of(measurement.id) {
+                    measurements.items.remove(index);
+                }
+                Ok(())
+            }
+            EditCommand::RenameMeasurement { id, before, after } => {
+                match measurements.index_of(*id) {
+                    Some(index) => {
+                        measurements.items[index].name = if undo { before } else { after }.clone();
+                        Ok(())
+                    }
+                    None => Err(format!("Measurement {} no longer exists", id)),
+                }
+            }
+        };
+
+        match result {
+            Ok(()) => {
+                info!("{}: {}", action.as_str(), command.label());
+                history.applied(action, command);
+            }
+            Err(e) => {
+                warn!("Cannot {} '{}': {}", action.as_str(), command.label(), e);
+                rpc_interface.send_notification(
+                    "history_error",
+                    serde_json::json!({ "action": action.as_str(), "error": e }),
+                );
+                history.restore(action, command);
+            }
+        }
+    }
+
+    // Covers edits recorded by the tools as well as undo/redo applied above.
+    if history.is_changed() {
+        rpc_interface.send_notification("history_changed", history.summary());
+    }
+}
+
+fn remove_polygons(
+    ids: &[u32],
+    classification_data: &mut PolygonClassificationData,
+    completed_polygons: &Query<(Entity, &CompletedPolygon)>,
+    commands: &mut Commands,
+) {
+    classification_data
+        .polygons
+        .retain(|p| !ids.contains(&p.id));
+    for id in ids {
+        despawn_polygon_meshes(*id, completed_polygons, commands);
+    }
+}
+
+/// Append polygons in their recorded order; order matters since later polygons override earlier ones.
+fn restore_polygons(
+    polygons: &[ClassificationPolygon],
+    classification_data: &mut PolygonClassificationData,
+    completed_polygons: &Query<(Entity, &CompletedPolygon)>,
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    ground_height: f32,
+) -> Result<(), String> {
+    classification_data.check_limits(polygons, None)?;
+
+    for polygon in polygons {
+        classification_data.polygons.push(polygon.clone());
+        // Clearing leaves the meshes behind, so replace rather than duplicate them.
+        rebuild_polygon_meshes(
+            polygon,
+            completed_polygons,
+            commands,
+            meshes,
+            materials,
+            ground_height,
+            classification_data.height_origin,
+        );
+    }
+
+    Ok(())
+}
+
+fn spawn_assets(
+    instances: &[PlacedAssetInstance],
+    manifest: &SceneManifest,
+    live: &mut Vec<PlacedAssetInstance>,
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    for instance in instances {
+        let Some(asset_meta) = manifest
+            .asset_atlas
+            .as_ref()
+            .and_then(|aa| aa.assets.iter().find(|a| a.name == instance.asset_name))
+        else {
+            continue;
+        };
+        spawn_placed_bounds(
+            commands,
+            meshes,
+            materials,
+            instance.clone(),
+            calculate_asset_size(asset_meta),
+        );
+        live.push(instance.clone());
+    }
+}
+
+fn despawn_assets(
+    ids: &[u32],
+    placed_bounds: &Query<(Entity, &mut Transform, &mut PlacedAssetInstance), With<PlacedBounds>>,
+    live: &mut Vec<PlacedAssetInstance>,
+    commands: &mut Commands,
+) {
+    for (entity, _, placed) in placed_bounds {
+        if ids.contains(&placed.id) {
+            commands.entity(entity).despawn();
+        }
+    }
+    live.retain(|p| !ids.contains(&p.id));
+}
+
+/// Point the single instanced renderer at `instances`, creating or removing it as needed.
+fn sync_instanced_renderer(
+    instances: &[PlacedAssetInstance],
+    manifest: &SceneManifest,
+    renderers: &mut Query<(Entity, &mut InstancedAssetData)>,
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+) {
+    let Some(asset_meta) = manifest
+        .asset_atlas
+        .as_ref()
+        .and_then(|aa| aa.assets.first())
+    else {
+        return;
+    };
+
+    if instances.is_empty() {
+        for (entity, _) in renderers.iter() {
+            commands.entity(entity).despawn();
+        }
+    } else if let Ok((_, mut data)) = renderers.single_mut() {
+        data.0 = instance_data(instances, asset_meta);
+    } else {
+        create_new_instanced_renderer(commands, meshes, instances, asset_meta);
+    }
+}
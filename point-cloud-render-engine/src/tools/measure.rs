@@ -1,6 +1,9 @@
-use crate::engine::assets::point_cloud_assets::PointCloudAssets;
-use crate::engine::assets::scene_manifest::SceneManifest;
 use crate::engine::camera::viewport_camera::ViewportCamera;
+use crate::engine::scene::heightmap::ground_below;
+use crate::rpc::web_rpc::WebRpcInterface;
+use crate::tools::history::{EditCommand, EditHistory};
+use crate::tools::snap::{SnapMode, SnapParams, SnapSettings, SnapTarget};
+use bevy::math::DVec3;
 use bevy::prelude::*;
 use bevy::render::view::RenderLayers;
 use bevy::window::PrimaryWindow;
@@ -8,60 +11,608 @@ use constants::render_settings::{
     DRAW_LINE_WIDTH, DRAW_VERTEX_SIZE, MOUSE_RAYCAST_INTERSECTION_SPHERE_SIZE,
 };
 use serde::{Deserialize, Serialize};
+use std::fmt::Write;
 
+const LABEL_FONT_SIZE: f32 = 13.0;
+/// Labels sit this many pixels above and right of their anchor.
+const LABEL_OFFSET: Vec2 = Vec2::new(8.0, -24.0);
+/// Slope runs and angle legs shorter than this (metres) have no defined value.
+pub const MIN_MEASURE_SPAN: f32 = 0.01;
+
+/// What a measurement measures, and how many clicks it takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MeasureMode {
+    #[default]
+    Distance, // 3D, horizontal and vertical distance between two points.
+    Polyline, // Length along any number of points.
+    Area,     // Plan area and perimeter of a closed outline.
+    Height,   // Height of a point above the heightmap.
+    Slope,    // Rise over run between two points.
+    Angle,    // Angle at the middle of three points.
+}
+
+impl MeasureMode {
+    pub const ALL: [Self; 6] = [
+        Self::Distance,
+        Self::Polyline,
+        Self::Area,
+        Self::Height,
+        Self::Slope,
+        Self::Angle,
+    ];
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.as_str() == s.to_lowercase())
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Distance => "distance",
+            Self::Polyline => "polyline",
+            Self::Area => "area",
+            Self::Height => "height",
+            Self::Slope => "slope",
+            Self::Angle => "angle",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Distance => "Distance",
+            Self::Polyline => "Polyline",
+            Self::Area => "Area",
+            Self::Height => "Height",
+            Self::Slope => "Slope",
+            Self::Angle => "Angle",
+        }
+    }
+
+    /// Clicks that finish a measurement; `None` when it is finished with Enter.
+    pub fn clicks(&self) -> Option<usize> {
+        match self {
+            Self::Height => Some(1),
+            Self::Distance | Self::Slope => Some(2),
+            Self::Angle => Some(3),
+            Self::Polyline | Self::Area => None,
+        }
+    }
+
+    /// Fewest stored points a finished measurement has. A height measurement
+    /// stores its clicked point and the ground below it.
+    pub fn min_points(&self) -> usize {
+        match self {
+            Self::Distance | Self::Height | Self::Slope | Self::Polyline => 2,
+            Self::Angle | Self::Area => 3,
+        }
+    }
+}
+
+/// A named measurement with its render-space points.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Measurement {
     pub id: u32,
-    pub start: Vec3,
-    pub end: Vec3,
-    pub distance: f32,
+    pub name: String,
+    pub mode: MeasureMode,
+    pub points: Vec<Vec3>,
+}
+
+impl Measurement {
+    pub fn new(id: u32, mode: MeasureMode, points: Vec<Vec3>) -> Self {
+        Self {
+            id,
+            name: format!("{} {}", mode.title(), id),
+            mode,
+            points,
+        }
+    }
+
+    /// A slope without horizontal run or an angle with a zero-length leg.
+    pub fn is_degenerate(&self) -> bool {
+        let p = &self.points;
+        match self.mode {
+            MeasureMode::Slope if p.len() >= 2 => {
+                horizontal_distance(p[0], p[1]) < MIN_MEASURE_SPAN
+            }
+            MeasureMode::Angle if p.len() >= 3 => {
+                p[0].distance(p[1]) < MIN_MEASURE_SPAN || p[2].distance(p[1]) < MIN_MEASURE_SPAN
+            }
+            _ => false,
+        }
+    }
+
+    /// Derived quantities as `(name, value, unit)`; the first is the headline value.
+    /// Empty while there are too few points or the placement is degenerate.
+    pub fn values(&self) -> Vec<(&'static str, f32, &'static str)> {
+        let p = &self.points;
+        if p.len() < self.mode.min_points() || self.is_degenerate() {
+            return Vec::new();
+        }
+        match self.mode {
+            MeasureMode::Distance => vec![
+                ("distance", p[0].distance(p[1]), "m"),
+                ("horizontal", horizontal_distance(p[0], p[1]), "m"),
+                ("vertical", (p[1].y - p[0].y).abs(), "m"),
+            ],
+            MeasureMode::Polyline => vec![
+                ("length", path_length(p, Vec3::distance), "m"),
+                ("horizontal", path_length(p, horizontal_distance), "m"),
+            ],
+            MeasureMode::Area => {
+                let mut ring = p.clone();
+                ring.push(p[0]);
+                vec![
+                    ("area", plan_area(p), "m²"),
+                    ("perimeter", path_length(&ring, Vec3::distance), "m"),
+                ]
+            }
+            MeasureMode::Height => vec![("height", p[0].y - p[1].y, "m")],
+            MeasureMode::Slope => {
+                let run = horizontal_distance(p[0], p[1]);
+                let rise = p[1].y - p[0].y;
+                vec![
+                    ("slope", rise / run * 100.0, "%"),
+                    ("horizontal", run, "m"),
+                    ("rise", rise, "m"),
+                ]
+            }
+            MeasureMode::Angle => {
+                let angle = (p[0] - p[1]).angle_between(p[2] - p[1]);
+                vec![("angle", angle.to_degrees(), "°")]
+            }
+        }
+    }
+
+    /// Headline value with its unit, e.g. `3.42 m` or `87.5°`.
+    pub fn value_text(&self) -> String {
+        match self.values().first() {
+            Some((_, value, "°")) => format!("{:.1}°", value),
+            Some((_, value, "%")) => format!("{:.1} %", value),
+            Some((_, value, unit)) => format!("{:.2} {}", value, unit),
+            None => String::new(),
+        }
+    }
+
+    /// Point the on-screen label is attached to.
+    pub fn label_anchor(&self) -> Option<Vec3> {
+        let p = &self.points;
+        match self.mode {
+            MeasureMode::Height => p.first().copied(),
+            MeasureMode::Angle => p.get(1).copied(),
+            MeasureMode::Polyline => p.last().copied(),
+            MeasureMode::Distance | MeasureMode::Slope => Some(p.first()?.midpoint(*p.last()?)),
+            MeasureMode::Area => (!p.is_empty()).then(|| p.iter().sum::<Vec3>() / p.len() as f32),
+        }
+    }
+
+    /// Line segments to draw; area outlines are closed.
+    pub fn segments(&self) -> Vec<(Vec3, Vec3)> {
+        let mut segments: Vec<(Vec3, Vec3)> =
+            self.points.windows(2).map(|w| (w[0], w[1])).collect();
+        if self.mode == MeasureMode::Area && self.points.len() >= 3 {
+            segments.push((self.points[self.points.len() - 1], self.points[0]));
+        }
+        segments
+    }
+
+    /// RPC payload with render-space points.
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "mode": self.mode.as_str(),
+            "points": self.points.iter().map(|p| p.to_array()).collect::<Vec<_>>(),
+            "values": values_json(self),
+            "label": self.value_text(),
+        })
+    }
+}
+
+fn horizontal_distance(a: Vec3, b: Vec3) -> f32 {
+    a.xz().distance(b.xz())
+}
+
+fn path_length(points: &[Vec3], distance: fn(Vec3, Vec3) -> f32) -> f32 {
+    points.windows(2).map(|w| distance(w[0], w[1])).sum()
+}
+
+/// Shoelace area of the outline projected onto the ground plane.
+fn plan_area(points: &[Vec3]) -> f32 {
+    let twice: f32 = (0..points.len())
+        .map(|i| {
+            let a = points[i].xz();
+            let b = points[(i + 1) % points.len()].xz();
+            a.perp_dot(b)
+        })
+        .sum();
+    twice.abs() * 0.5
+}
+
+fn values_json(measurement: &Measurement) -> serde_json::Value {
+    measurement
+        .values()
+        .into_iter()
+        .map(|(name, value, _)| (name.to_string(), serde_json::json!(value)))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/// Completed measurements, kept until deleted.
+#[derive(Resource, Default)]
+pub struct Measurements {
+    pub items: Vec<Measurement>,
+    pub next_id: u32,
+}
+
+impl Measurements {
+    /// Store a new measurement under the next free id.
+    pub fn add(&mut self, mode: MeasureMode, points: Vec<Vec3>) -> Measurement {
+        self.next_id += 1;
+        let measurement = Measurement::new(self.next_id, mode, points);
+        self.items.push(measurement.clone());
+        measurement
+    }
+
+    pub fn index_of(&self, id: u32) -> Option<usize> {
+        self.items.iter().position(|m| m.id == id)
+    }
+
+    /// Re-insert a measurement, e.g. on undo, keeping ids unique.
+    pub fn restore(&mut self, measurement: Measurement, index: usize) {
+        self.next_id = self.next_id.max(measurement.id);
+        let index = index.min(self.items.len());
+        self.items.insert(index, measurement);
+    }
+
+    pub fn summaries(&self) -> serde_json::Value {
+        self.items.iter().map(Measurement::summary).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeasureExportFormat {
+    Csv,
+    Json,
+}
+
+impl MeasureExportFormat {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Export file contents; points are absolute world positions (render space plus `origin`).
+pub fn export_measurements(
+    measurements: &[Measurement],
+    origin: DVec3,
+    format: MeasureExportFormat,
+) -> String {
+    match format {
+        MeasureExportFormat::Csv => measurements_to_csv(measurements),
+        MeasureExportFormat::Json => measurements_to_json(measurements, origin),
+    }
+}
+
+/// One row per derived quantity.
+fn measurements_to_csv(measurements: &[Measurement]) -> String {
+    let mut csv = String::from("id,name,mode,quantity,value,unit\n");
+    for m in measurements {
+        for (quantity, value, unit) in m.values() {
+            let _ = writeln!(
+                csv,
+                "{},\"{}\",{},{},{:.3},{}",
+                m.id,
+                m.name.replace('"', "\"\""),
+                m.mode.as_str(),
+                quantity,
+                value,
+                unit
+            );
+        }
+    }
+    csv
+}
+
+fn measurements_to_json(measurements: &[Measurement], origin: DVec3) -> String {
+    let items: Vec<serde_json::Value> = measurements
+        .iter()
+        .map(|m| {
+            serde_json::json!({
+                "id": m.id,
+                "name": m.name,
+                "mode": m.mode.as_str(),
+                "values": values_json(m),
+                "points": m
+                    .points
+                    .iter()
+                    .map(|p| (p.as_dvec3() + origin).to_array())
+                    .collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&serde_json::json!({
+        "origin": origin.to_array(),
+        "measurements": items,
+    }))
+    .unwrap_or_default()
 }
 
 #[derive(Resource, Default)]
 pub struct MeasureTool {
     pub is_active: bool,
-    pub start_point: Option<Vec3>,
+    pub mode: MeasureMode,
+    /// Points placed for the measurement being drawn.
+    pub pending: Vec<Vec3>,
     pub preview_point: Option<Vec3>,
-    pub next_id: u32,
-    pub current: Option<Measurement>,
+    /// Measurement being drawn, including the cursor point.
+    pub live: Option<Measurement>,
+    pub snap: SnapSettings,
+    /// Feature `preview_point` snapped to; `None` when it is the ground-plane hit.
+    pub snap_target: Option<SnapTarget>,
 }
 
 impl MeasureTool {
     pub fn set_active(&mut self, active: bool) {
         self.is_active = active;
         if !active {
-            self.start_point = None;
+            self.pending.clear();
             self.preview_point = None;
-            self.current = None;
+            self.live = None;
+            self.snap_target = None;
         }
     }
     pub fn is_active(&self) -> bool {
         self.is_active
     }
+
+    /// Settings summary for the frontend.
+    pub fn state_summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "mode": self.mode.as_str(),
+            "snap": self.snap.summary(),
+        })
+    }
 }
 
-#[derive(Component)]
+/// Measure tool requests from RPC or keyboard.
+#[derive(Event, Debug, Clone)]
+pub struct MeasureActionEvent {
+    pub action: MeasureAction,
+}
+
+#[derive(Debug, Clone)]
+pub enum MeasureAction {
+    /// Fields left as `None` keep their current value.
+    SetSnap {
+        mode: Option<SnapMode>,
+        enabled: Option<bool>,
+    },
+    SetMode(MeasureMode),
+    Complete,        // Finish a polyline or area (equivalent to Enter).
+    RemoveLastPoint, // Equivalent to Backspace.
+    Cancel,          // Drop the measurement being drawn.
+    Delete {
+        id: u32,
+    },
+    Rename {
+        id: u32,
+        name: String,
+    },
+    Export(MeasureExportFormat),
+}
+
+#[derive(Component, Clone)]
 pub struct MeasurePreview;
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 pub struct CompletedMeasurementTag;
 
-// Input/logic: click to start, move to preview, click to finish
-// Starting new measurement deletes previous one
+/// On-screen label of a completed measurement, or of the live one when `None`.
+#[derive(Component)]
+pub struct MeasurementLabel(pub Option<u32>);
+
+/// Native shortcuts while the measure tool is active: Tab cycles the snap mode,
+/// 1–6 pick the measurement mode, Enter finishes and Backspace removes the last point.
+pub fn measure_keyboard_shortcuts(
+    #[cfg(not(target_arch = "wasm32"))] keyboard: Res<ButtonInput<KeyCode>>,
+    #[cfg(not(target_arch = "wasm32"))] measure_tool: Res<MeasureTool>,
+    #[cfg(not(target_arch = "wasm32"))] mut events: EventWriter<MeasureActionEvent>,
+) {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if !measure_tool.is_active() {
+            return;
+        }
+        let mut send = |action| {
+            events.write(MeasureActionEvent { action });
+        };
+
+        if keyboard.just_pressed(KeyCode::Tab) {
+            send(MeasureAction::SetSnap {
+                mode: Some(measure_tool.snap.mode.next()),
+                enabled: None,
+            });
+        }
+        let digits = [
+            KeyCode::Digit1,
+            KeyCode::Digit2,
+            KeyCode::Digit3,
+            KeyCode::Digit4,
+            KeyCode::Digit5,
+            KeyCode::Digit6,
+        ];
+        for (key, mode) in digits.into_iter().zip(MeasureMode::ALL) {
+            if keyboard.just_pressed(key) {
+                send(MeasureAction::SetMode(mode));
+            }
+        }
+        if keyboard.just_pressed(KeyCode::Enter) {
+            send(MeasureAction::Complete);
+        }
+        if keyboard.just_pressed(KeyCode::Backspace) {
+            send(MeasureAction::RemoveLastPoint);
+        }
+    }
+}
+
+/// Applies measure tool requests; mode and snap settings persist between activations.
+pub fn process_measure_actions(
+    mut events: EventReader<MeasureActionEvent>,
+    mut measure_tool: ResMut<MeasureTool>,
+    mut measurements: ResMut<Measurements>,
+    mut history: ResMut<EditHistory>,
+    snap: SnapParams,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    for event in events.read() {
+        match &event.action {
+            MeasureAction::SetSnap { mode, enabled } => {
+                if let Some(mode) = mode {
+                    measure_tool.snap.mode = *mode;
+                }
+                if let Some(enabled) = enabled {
+                    measure_tool.snap.enabled = *enabled;
+                }
+                info!(
+                    "Measure snap: {} ({})",
+                    measure_tool.snap.mode.as_str(),
+                    if measure_tool.snap.enabled {
+                        "on"
+                    } else {
+                        "off"
+                    }
+                );
+                rpc_interface
+                    .send_notification("measure_snap_changed", measure_tool.snap.summary());
+            }
+            MeasureAction::SetMode(mode) => {
+                measure_tool.mode = *mode;
+                measure_tool.pending.clear();
+                info!("Measure mode: {}", mode.as_str());
+                rpc_interface
+                    .send_notification("measure_mode_changed", measure_tool.state_summary());
+            }
+            MeasureAction::Complete => {
+                if let Err(e) = complete_measurement(
+                    &mut measure_tool,
+                    &mut measurements,
+                    &mut history,
+                    &mut rpc_interface,
+                    None,
+                ) {
+                    warn!("{}", e);
+                }
+            }
+            MeasureAction::RemoveLastPoint => {
+                measure_tool.pending.pop();
+            }
+            MeasureAction::Cancel => {
+                measure_tool.pending.clear();
+                rpc_interface.send_notification("measure_clear", serde_json::json!({}));
+            }
+            MeasureAction::Delete { id } => {
+                let Some(index) = measurements.index_of(*id) else {
+                    warn!("Measurement {} not found", id);
+                    continue;
+                };
+                let measurement = measurements.items.remove(index);
+                info!("Deleted {}", measurement.name);
+                history.record(EditCommand::DeleteMeasurement { measurement, index });
+            }
+            MeasureAction::Rename { id, name } => {
+                let Some(index) = measurements.index_of(*id) else {
+                    warn!("Measurement {} not found", id);
+                    continue;
+                };
+                let before = std::mem::replace(&mut measurements.items[index].name, name.clone());
+                history.record(EditCommand::RenameMeasurement {
+                    id: *id,
+                    before,
+                    after: name.clone(),
+                });
+            }
+            MeasureAction::Export(format) => {
+                let Some(bounds) = snap.assets.get_bounds(&snap.manifests) else {
+                    continue;
+                };
+                let (x, y, z) = bounds.bounds.origin;
+                rpc_interface.send_notification(
+                    "measurements_exported",
+                    serde_json::json!({
+                        "format": format.extension(),
+                        "filename": format!("measurements.{}", format.extension()),
+                        "content": export_measurements(
+                            &measurements.items,
+                            DVec3::new(x, y, z),
+                            *format,
+                        ),
+                    }),
+                );
+            }
+        }
+    }
+}
+
+/// Store the pending points as a measurement. A degenerate placement drops its
+/// last point so it can be placed again.
+fn complete_measurement(
+    measure_tool: &mut MeasureTool,
+    measurements: &mut Measurements,
+    history: &mut EditHistory,
+    rpc_interface: &mut WebRpcInterface,
+    snap_kind: Option<&str>,
+) -> Result<(), String> {
+    let mode = measure_tool.mode;
+    if measure_tool.pending.len() < mode.min_points() {
+        return Err(format!(
+            "Cannot complete {} measurement: need at least {} points",
+            mode.as_str(),
+            mode.min_points()
+        ));
+    }
+    if Measurement::new(0, mode, measure_tool.pending.clone()).is_degenerate() {
+        measure_tool.pending.pop();
+        return Err(format!(
+            "Cannot complete {} measurement: points are less than {} m apart",
+            mode.as_str(),
+            MIN_MEASURE_SPAN
+        ));
+    }
+    let points = std::mem::take(&mut measure_tool.pending);
+    let measurement = measurements.add(measure_tool.mode, points);
+    history.record(EditCommand::AddMeasurement(measurement.clone()));
+
+    let mut payload = measurement.summary();
+    payload["snap"] = serde_json::json!(snap_kind);
+    rpc_interface.send_notification("measure_completed", payload);
+    Ok(())
+}
+
+// Input/logic: clicks place points, the cursor previews the next one, and the
+// measurement is stored once the mode has enough clicks (or on Enter)
 pub fn measure_tool_system(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
     mut measure_tool: ResMut<MeasureTool>,
+    mut measurements: ResMut<Measurements>,
     mouse: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window, With<PrimaryWindow>>,
     cameras: Query<(&GlobalTransform, &Camera), With<Camera3d>>,
     viewport_camera: Option<ResMut<ViewportCamera>>,
-    images: Res<Assets<Image>>,
-    assets: Res<PointCloudAssets>,
-    manifests: Res<Assets<SceneManifest>>,
-    mut rpc_interface: ResMut<crate::rpc::web_rpc::WebRpcInterface>,
-    existing_preview: Query<Entity, With<MeasurePreview>>,
+    snap: SnapParams,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+    mut history: ResMut<EditHistory>,
 ) {
     if !measure_tool.is_active() {
         return;
@@ -70,13 +621,7 @@ pub fn measure_tool_system(
     let Ok(window) = windows.single() else {
         return;
     };
-    let Some(scene_bounds) = assets.get_bounds(&manifests) else {
-        return;
-    };
-    let Some(height_img) = images.get(&assets.heightmap_texture) else {
-        return;
-    };
-    let Some(cursor_pos) = window.cursor_position() else {
+    let Some(scene_bounds) = snap.assets.get_bounds(&snap.manifests) else {
         return;
     };
     let Some(mut viewport_camera) = viewport_camera else {
@@ -85,116 +630,111 @@ pub fn measure_tool_system(
     let Ok((cam_xform, camera)) = cameras.single() else {
         return;
     };
-
-    // Raycast from mouse to ground plane
-    let hit = viewport_camera.mouse_to_ground_plane(
-        cursor_pos,
-        camera,
-        cam_xform,
-        Some(height_img),
-        &scene_bounds,
-    );
-    let Some(hit) = hit else {
+    let Some(cursor_pos) = window.cursor_position() else {
+        measure_tool.preview_point = None;
+        measure_tool.snap_target = None;
+        measure_tool.live = None;
         return;
     };
+    let heightmap = snap.images.get(&snap.assets.heightmap_texture);
 
-    // Clean up existing preview entities to prevent accumulation.
-    for entity in existing_preview.iter() {
-        commands.entity(entity).despawn();
-    }
+    // Snap to a point or drawn feature, falling back to the ground plane under the cursor
+    let mut lines: Vec<Vec<Vec3>> = measurements
+        .items
+        .iter()
+        .map(|m| {
+            let mut line = m.points.clone();
+            if m.mode == MeasureMode::Area {
+                line.extend(m.points.first());
+            }
+            line
+        })
+        .collect();
+    lines.push(measure_tool.pending.clone());
+    let lines: Vec<&[Vec3]> = lines.iter().map(Vec::as_slice).collect();
+    let snap_target = snap
+        .active_mode(&measure_tool.snap)
+        .and_then(|mode| snap.resolve(mode, cursor_pos, camera, cam_xform, &lines));
+    measure_tool.snap_target = snap_target;
+    measure_tool.preview_point = match snap_target {
+        Some(target) => Some(target.position),
+        None => viewport_camera.mouse_to_ground_plane(
+            cursor_pos,
+            camera,
+            cam_xform,
+            heightmap,
+            &scene_bounds,
+        ),
+    };
+    let snap_kind = snap_target.map(|target| target.kind.as_str());
 
-    // mouse ray case visualisation
-    commands.spawn((
-        Mesh3d(meshes.add(Sphere::new(MOUSE_RAYCAST_INTERSECTION_SPHERE_SIZE))),
-        MeshMaterial3d(materials.add(StandardMaterial {
-            base_color: Color::hsv(0., 1., 1.),
-            emissive: LinearRgba::new(1., 1., 1., 1.),
-            depth_bias: 0.0,
-            unlit: true,
-            ..default()
-        })),
-        Transform::from_translation(hit),
-        MeasurePreview,
-        RenderLayers::layer(1),
-    ));
-
-    // Update cursor projection each frame for live preview
-    let Some(bounds) = assets.get_bounds(&manifests) else {
-        return;
+    // A height measurement is the clicked point and the ground below it
+    let mode = measure_tool.mode;
+    let with_ground = |point: Vec3| match mode {
+        MeasureMode::Height => vec![
+            point,
+            Vec3::new(
+                point.x,
+                ground_below(point, heightmap, &scene_bounds),
+                point.z,
+            ),
+        ],
+        _ => vec![point],
     };
-    if let (Ok((cam_tf, cam)), Ok(window)) = (cameras.single(), windows.single()) {
-        if let Some(cursor) = window.cursor_position() {
-            measure_tool.preview_point = viewport_camera.mouse_to_ground_plane(
-                cursor,
-                cam,
-                cam_tf,
-                images.get(&assets.heightmap_texture),
-                &bounds,
+
+    if mouse.just_pressed(MouseButton::Left)
+        && let Some(point) = measure_tool.preview_point
+    {
+        if measure_tool.pending.is_empty() {
+            rpc_interface.send_notification(
+                "measure_started",
+                serde_json::json!({
+                    "position": point.to_array(),
+                    "mode": mode.as_str(),
+                    "snap": snap_kind,
+                }),
             );
-        } else {
-            measure_tool.preview_point = None;
         }
-    }
-
-    // FIRST CLICK: start and clear any existing completed measurement
-    if mouse.just_pressed(MouseButton::Left) {
-        if let Some(preview) = measure_tool.preview_point {
-            match measure_tool.start_point {
-                None => {
-                    // If a previous completed measurement exists, drop it and clear UI
-                    if measure_tool.current.take().is_some() {
-                        rpc_interface.send_notification("measure_clear", serde_json::json!({}));
-                    }
+        measure_tool.pending.extend(with_ground(point));
 
-                    // Begin a new measurement
-                    measure_tool.start_point = Some(preview);
-                    rpc_interface.send_notification(
-                        "measure_started",
-                        serde_json::json!({ "position": [preview.x, preview.y, preview.z] }),
-                    );
-                }
-                Some(start) => {
-                    // SECOND CLICK: finalise the measurement
-                    let end = preview;
-                    let dist = start.distance(end);
-                    let m = Measurement {
-                        id: measure_tool.next_id,
-                        start,
-                        end,
-                        distance: dist,
-                    };
-                    measure_tool.next_id += 1;
-                    measure_tool.current = Some(m.clone());
-
-                    rpc_interface.send_notification(
-                        "measure_completed",
-                        serde_json::json!({
-                            "id": m.id,
-                            "start": [m.start.x, m.start.y, m.start.z],
-                            "end": [m.end.x, m.end.y, m.end.z],
-                            "distance": m.distance,
-                        }),
-                    );
-
-                    // Reset for next line
-                    measure_tool.start_point = None;
-                    measure_tool.preview_point = None;
-                }
-            }
+        let clicks = match mode {
+            MeasureMode::Height => 1,
+            _ => measure_tool.pending.len(),
+        };
+        if mode.clicks() == Some(clicks)
+            && let Err(e) = complete_measurement(
+                &mut measure_tool,
+                &mut measurements,
+                &mut history,
+                &mut rpc_interface,
+                snap_kind,
+            )
+        {
+            warn!("{}", e);
         }
     }
 
-    // Live measurement update
-    if let (Some(start), Some(preview)) = (measure_tool.start_point, measure_tool.preview_point) {
-        let dist = start.distance(preview);
-        rpc_interface.send_notification(
-            "measure_updated",
-            serde_json::json!({
-                "start": [start.x, start.y, start.z],
-                "end": [preview.x, preview.y, preview.z],
-                "distance": dist,
-            }),
-        );
+    // Live measurement including the cursor point
+    measure_tool.live = match measure_tool.preview_point {
+        Some(preview) if !measure_tool.pending.is_empty() || mode == MeasureMode::Height => {
+            let mut points = measure_tool.pending.clone();
+            points.extend(with_ground(preview));
+            Some(Measurement {
+                id: 0,
+                name: mode.title().to_string(),
+                mode,
+                points,
+            })
+        }
+        _ => None,
+    };
+
+    if !measure_tool.pending.is_empty()
+        && let Some(live) = &measure_tool.live
+    {
+        let mut payload = live.summary();
+        payload["snap"] = serde_json::json!(snap_kind);
+        rpc_interface.send_notification("measure_updated", payload);
     }
 }
 
@@ -202,6 +742,7 @@ pub fn measure_tool_system(
 pub fn update_measure_render(
     mut commands: Commands,
     measure_tool: Res<MeasureTool>,
+    measurements: Res<Measurements>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     existing_preview: Query<Entity, With<MeasurePreview>>,
@@ -216,61 +757,197 @@ pub fn update_measure_render(
         commands.entity(e).despawn();
     }
 
-    // Preview measurement before completion
-    if let (Some(start), Some(preview)) = (measure_tool.start_point, measure_tool.preview_point) {
+    let mut material = |colour: Color| {
+        materials.add(StandardMaterial {
+            base_color: colour,
+            emissive: colour.to_linear(),
+            unlit: true,
+            ..default()
+        })
+    };
+
+    // Cursor marker, coloured and enlarged when snapped
+    if let Some(preview) = measure_tool.preview_point {
+        let (colour, size) = match measure_tool.snap_target {
+            Some(target) => (
+                target.kind.colour(),
+                MOUSE_RAYCAST_INTERSECTION_SPHERE_SIZE * 1.5,
+            ),
+            None if !measure_tool.pending.is_empty() => (
+                Color::srgb(1.0, 1.0, 0.2),
+                MOUSE_RAYCAST_INTERSECTION_SPHERE_SIZE,
+            ),
+            None => (
+                Color::hsv(0., 1., 1.),
+                MOUSE_RAYCAST_INTERSECTION_SPHERE_SIZE,
+            ),
+        };
         commands.spawn((
-            Mesh3d(meshes.add(Sphere::new(MOUSE_RAYCAST_INTERSECTION_SPHERE_SIZE))),
-            MeshMaterial3d(materials.add(StandardMaterial {
-                base_color: Color::srgb(1.0, 1.0, 0.2),
-                emissive: LinearRgba::new(1., 1., 0.2, 1.),
-                unlit: true,
-                ..default()
-            })),
+            Mesh3d(meshes.add(Sphere::new(size))),
+            MeshMaterial3d(material(colour)),
             Transform::from_translation(preview),
             MeasurePreview,
             RenderLayers::layer(1),
         ));
+    }
 
-        let dir = preview - start;
-        let dist = dir.length();
-        if dist > 0.02 {
-            let midpoint = (start + preview) * 0.5;
-            let rot = Quat::from_rotation_arc(Vec3::X, dir.normalize());
-            commands.spawn((
-                Mesh3d(meshes.add(Cuboid::new(dist, DRAW_LINE_WIDTH, DRAW_LINE_WIDTH))),
-                MeshMaterial3d(materials.add(StandardMaterial {
-                    base_color: Color::srgb(1.0, 1.0, 0.2),
-                    emissive: LinearRgba::new(1., 1., 0.2, 1.),
-                    unlit: true,
-                    ..default()
-                })),
-                Transform::from_translation(midpoint).with_rotation(rot),
-                MeasurePreview,
-                RenderLayers::layer(1),
-            ));
+    // Measurement being drawn
+    if let Some(live) = &measure_tool.live {
+        let live_material = material(Color::srgb(1.0, 1.0, 0.2));
+        spawn_measurement(
+            &mut commands,
+            &mut meshes,
+            live,
+            live_material,
+            MeasurePreview,
+        );
+    }
+
+    // Completed measurements
+    if !measurements.items.is_empty() {
+        let completed_material = material(Color::srgb(1.0, 0.27, 0.0));
+        for m in &measurements.items {
+            spawn_measurement(
+                &mut commands,
+                &mut meshes,
+                m,
+                completed_material.clone(),
+                CompletedMeasurementTag,
+            );
         }
     }
+}
 
-    // Completed measurement
-    if let Some(m) = &measure_tool.current {
-        let dir = m.end - m.start;
+/// Spawn a measurement's vertices and segments with `tag`.
+fn spawn_measurement(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    measurement: &Measurement,
+    material: Handle<StandardMaterial>,
+    tag: impl Component + Clone,
+) {
+    for &point in &measurement.points {
+        commands.spawn((
+            Mesh3d(meshes.add(Sphere::new(DRAW_VERTEX_SIZE))),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(point),
+            tag.clone(),
+            RenderLayers::layer(1),
+        ));
+    }
+    for (a, b) in measurement.segments() {
+        let dir = b - a;
         let dist = dir.length();
-        if dist > 0.02 {
-            let midpoint = (m.start + m.end) * 0.5;
-            let rot = Quat::from_rotation_arc(Vec3::X, dir.normalize());
-            commands.spawn((
-                Mesh3d(meshes.add(Cuboid::new(dist, DRAW_LINE_WIDTH, DRAW_LINE_WIDTH))),
-                MeshMaterial3d(materials.add(StandardMaterial {
-                    base_color: Color::srgb(1.0, 0.27, 0.0),
-                    emissive: LinearRgba::new(1., 0.5, 0., 1.),
-                    unlit: true,
-                    ..default()
-                })),
-                Transform::from_translation(midpoint).with_rotation(rot),
-                CompletedMeasurementTag,
-                RenderLayers::layer(1),
-            ));
+        if dist <= 0.02 {
+            continue;
+        }
+        let rot = Quat::from_rotation_arc(Vec3::X, dir / dist);
+        commands.spawn((
+            Mesh3d(meshes.add(Cuboid::new(dist, DRAW_LINE_WIDTH, DRAW_LINE_WIDTH))),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(a.midpoint(b)).with_rotation(rot),
+            tag.clone(),
+            RenderLayers::layer(1),
+        ));
+    }
+}
+
+/// Keeps one screen-space text label per measurement, plus one for the live measurement,
+/// positioned over its anchor each frame.
+pub fn update_measurement_labels(
+    mut commands: Commands,
+    measure_tool: Res<MeasureTool>,
+    measurements: Res<Measurements>,
+    cameras: Query<(&GlobalTransform, &Camera), With<Camera3d>>,
+    mut labels: Query<(Entity, &MeasurementLabel, &mut Text, &mut Node)>,
+) {
+    let Ok((camera_transform, camera)) = cameras.single() else {
+        return;
+    };
+
+    let wanted: Vec<(Option<u32>, String, Option<Vec3>)> = measurements
+        .items
+        .iter()
+        .map(|m| {
+            (
+                Some(m.id),
+                format!("{}: {}", m.name, m.value_text()),
+                m.label_anchor(),
+            )
+        })
+        .chain(
+            measure_tool
+                .live
+                .iter()
+                .map(|m| (None, m.value_text(), m.label_anchor())),
+        )
+        .collect();
+    let screen_position = |anchor: Option<Vec3>| {
+        anchor
+            .and_then(|a| camera.world_to_viewport(camera_transform, a).ok())
+            .map(|p| p + LABEL_OFFSET)
+    };
+
+    let mut existing = Vec::new();
+    for (entity, label, mut text, mut node) in &mut labels {
+        let Some((_, content, anchor)) = wanted.iter().find(|(key, ..)| *key == label.0) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+        existing.push(label.0);
+        if text.0 != *content {
+            text.0.clone_from(content);
+        }
+        match screen_position(*anchor) {
+            Some(position) => {
+                node.display = Display::Flex;
+                node.left = Val::Px(position.x);
+                node.top = Val::Px(position.y);
+            }
+            None => node.display = Display::None,
+        }
+    }
+
+    for (key, content, anchor) in wanted {
+        if existing.contains(&key) {
+            continue;
         }
+        let position = screen_position(anchor);
+        commands.spawn((
+            Text::new(content),
+            TextFont {
+                font_size: LABEL_FONT_SIZE,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(position.map_or(0.0, |p| p.x)),
+                top: Val::Px(position.map_or(0.0, |p| p.y)),
+                padding: UiRect::axes(Val::Px(4.0), Val::Px(2.0)),
+                display: if position.is_some() {
+                    Display::Flex
+                } else {
+                    Display::None
+                },
+                ..default()
+            },
+            MeasurementLabel(key),
+        ));
+    }
+}
+
+/// Sends the measurement list after every change.
+pub fn notify_measurement_changes(
+    measurements: Res<Measurements>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    if measurements.is_changed() && !measurements.is_added() {
+        rpc_interface.send_notification(
+            "measurements_changed",
+            serde_json::json!({ "measurements": measurements.summaries() }),
+        );
     }
 }
 
@@ -278,6 +955,96 @@ pub struct MeasureToolPlugin;
 impl Plugin for MeasureToolPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<MeasureTool>()
-            .add_systems(Update, (measure_tool_system, update_measure_render));
+            .init_resource::<Measurements>()
+            .add_event::<MeasureActionEvent>()
+            .add_systems(
+                Update,
+                (
+                    measure_keyboard_shortcuts,
+                    process_measure_actions,
+                    measure_tool_system,
+                    update_measure_render,
+                    update_measurement_labels,
+                    notify_measurement_changes,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(measurement: &Measurement, name: &str) -> f32 {
+        measurement
+            .values()
+            .into_iter()
+            .find(|(n, ..)| *n == name)
+            .map(|(_, v, _)| v)
+            .unwrap()
+    }
+
+    #[test]
+    fn derived_values_per_mode() {
+        let a = Vec3::new(0.0, 0.0, 0.0);
+        let b = Vec3::new(3.0, 1.0, 4.0);
+
+        let distance = Measurement::new(1, MeasureMode::Distance, vec![a, b]);
+        assert!((value(&distance, "horizontal") - 5.0).abs() < 1e-5);
+        assert!((value(&distance, "vertical") - 1.0).abs() < 1e-5);
+        assert!((value(&distance, "distance") - 26f32.sqrt()).abs() < 1e-5);
+
+        let slope = Measurement::new(2, MeasureMode::Slope, vec![a, b]);
+        assert!((value(&slope, "slope") - 20.0).abs() < 1e-4);
+
+        let square = vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.5, 0.0),
+            Vec3::new(2.0, 0.0, 3.0),
+            Vec3::new(0.0, 0.0, 3.0),
+        ];
+        let area = Measurement::new(3, MeasureMode::Area, square);
+        assert!((value(&area, "area") - 6.0).abs() < 1e-5);
+        assert_eq!(area.segments().len(), 4);
+
+        let corner = vec![Vec3::X, Vec3::ZERO, Vec3::Z];
+        let angle = Measurement::new(4, MeasureMode::Angle, corner);
+        assert!((value(&angle, "angle") - 90.0).abs() < 1e-4);
+        assert_eq!(angle.value_text(), "90.0°");
+
+        let height = Measurement::new(5, MeasureMode::Height, vec![b, Vec3::new(3.0, -1.5, 4.0)]);
+        assert!((value(&height, "height") - 2.5).abs() < 1e-5);
+        assert_eq!(height.name, "Height 5");
+    }
+
+    #[test]
+    fn too_few_points_have_no_values() {
+        let polyline = Measurement::new(1, MeasureMode::Polyline, vec![Vec3::ZERO]);
+        assert!(polyline.values().is_empty());
+        assert_eq!(polyline.value_text(), "");
+    }
+
+    #[test]
+    fn degenerate_slope_and_angle_have_no_values() {
+        let vertical = vec![Vec3::ZERO, Vec3::new(0.0, 2.0, 0.0)];
+        let slope = Measurement::new(1, MeasureMode::Slope, vertical);
+        assert!(slope.is_degenerate());
+        assert!(slope.values().is_empty());
+        assert_eq!(slope.value_text(), "");
+
+        let collapsed = vec![Vec3::X, Vec3::X, Vec3::Z];
+        let angle = Measurement::new(2, MeasureMode::Angle, collapsed);
+        assert!(angle.is_degenerate());
+        assert!(angle.values().is_empty());
+        assert_eq!(
+            measurements_to_csv(&[slope, angle]),
+            "id,name,mode,quantity,value,unit\n"
+        );
+
+        let steep = vec![Vec3::ZERO, Vec3::new(0.02, 2.0, 0.0)];
+        let slope = Measurement::new(3, MeasureMode::Slope, steep);
+        assert!(!slope.is_degenerate());
+        assert!(value(&slope, "slope").is_finite());
     }
 }
@@ -1,7 +1,7 @@
 //! Interactive tools for point cloud editing and measurement.
 //!
 //! Provides polygon-based reclassification, asset placement with instancing,
-//! distance measurement, and point selection tools with unified tool manager
+//! distance measurement, elevation profiles, and point selection tools with unified tool manager
 //! coordination and RPC integration for frontend control.
 //!
 //! ## Tool Manager Architecture
@@ -30,13 +30,20 @@
 //! - **Workflow**:
 //!   1. Left click adds polygon vertices with heightmap intersection
 //!   2. Shift key (native) or RPC completion event finalises polygon
-//!   3. Polygon resampled to uniform point spacing for GPU performance
-//!   4. Compute shader applies classification changes within polygon bounds
+//!   3. Polygon outline resampled to uniform point spacing for display
+//!   4. Compute shader applies classification changes to points inside the vertices
 //! - **Features**:
 //!   - Mask filtering: Only affect specific class/object ID combinations
 //!   - Hide mode: Set points to classification 254 (discarded in rendering)
 //!   - Reclassify mode: Change points to target classification ID
 //!   - Visual feedback with emissive vertex markers and edge lines
+//! - **Editing**: `Tab` (native) or `set_polygon_edit_mode` RPC toggles vertex dragging on
+//!   completed polygons; `update_polygon`, `delete_polygon` and `reorder_polygon` change
+//!   individual polygons. Polygons are evaluated in list order, so later ones override
+//!   earlier ones, and `polygons_changed` carries the list after every change
+//! - **Limits**: Polygon vertices, metadata, masks and edge bins live in storage buffers
+//!   sized per dispatch; `PolygonLimits` caps polygons, vertices and masks (clamped to the
+//!   device's storage binding size) and is reported via `get_polygon_limits`
 //!
 //! ### Asset Placement Tool (`ToolType::AssetPlacement`)
 //! - **Activation**: `A` key (native) or `tool_selection` RPC with `"assets"`
@@ -55,22 +62,65 @@
 //!
 //! ### Measure Tool (`ToolType::Measure`)
 //! - **Activation**: `M` key (native) or `tool_selection` RPC with `"measure"`
-//! - **Purpose**: Measure distances between two points on terrain
+//! - **Purpose**: Persistent, named measurements in six modes: distance (3D, horizontal
+//!   and vertical), polyline length, plan area and perimeter, height above ground,
+//!   slope percentage and three-point angle
 //! - **Workflow**:
-//!   1. First click sets start point
-//!   2. Mouse movement shows live preview with distance
-//!   3. Second click completes measurement
-//!   4. New measurement clears previous one
+//!   1. `1`–`6` (native) or `set_measure_mode` RPC picks the mode
+//!   2. Clicks place points; mouse movement shows a live preview with its value
+//!   3. Distance, slope and angle finish after two or three clicks, height after one;
+//!      polylines and areas finish with Enter or `measure_action` RPC `"complete"`
+//!   4. Completed measurements are kept in `Measurements` until deleted
 //! - **Features**:
-//!   - Live distance updates sent to frontend via RPC
-//!   - Heightmap-aware measurement along terrain surface
-//!   - Visual feedback with coloured line segments
+//!   - Floating value labels on every measurement
+//!   - `list_measurements`, `rename_measurement`, `delete_measurement` and
+//!     `export_measurements` (CSV or JSON) RPC methods; `measurements_changed` notification
+//!   - Snapping to the nearest point, nearest ground point, or polygon/measurement
+//!     vertices and edge midpoints; the ground plane is used when nothing is in reach
+//!   - `Tab` (native) or `set_measure_snap` RPC picks the snap mode; holding `Alt`
+//!     inverts snapping for the current click
+//!   - Visual feedback with coloured line segments and a snap-coloured cursor marker
+//!
+//! ### Profile Tool (`ToolType::Profile`)
+//! - **Activation**: `T` key (native) or `tool_selection` RPC with `"profile"`
+//! - **Purpose**: Elevation profiles and cross-sections along a polyline
+//! - **Workflow**:
+//!   1. Left click adds polyline vertices with heightmap intersection
+//!   2. Enter (native) or `profile_action` RPC with `"complete"` extracts the profile
+//!   3. Points within the corridor half-width are projected onto the line's chainage axis
+//!   4. Next click after completion starts a new profile
+//! - **Features**:
+//!   - Adjustable corridor half-width and ground-only class filter via `set_profile_corridor`
+//!   - Chart samples sent to frontend via `profile_completed` notification
+//!   - CSV and SVG export via `profile_action` RPC with `"export"`
+//!
+//! ### Undo/Redo
+//! - **Shortcuts**: `Ctrl+Z` undoes and `Ctrl+Shift+Z` redoes (native), or
+//!   `undo` / `redo` RPC methods; `get_history` returns both stacks
+//! - **Recorded edits**: Polygon hide/reclassify, edit, delete, reorder and clear-all, asset place, move/rotate
+//!   (recorded when the asset is deselected) and delete, and measurement add, rename and delete
+//! - **Notifications**: `history_changed` carries the stack labels after every change
+//!
+//! ### Sessions
+//! - **Shortcuts**: `F5` saves and `F9` loads `session.json` (native), or
+//!   `save_session` / `load_session` RPC methods
+//! - **Format**: Versioned JSON with absolute world coordinates and the manifest origin
+//! - **Loading**: Rebuilds completed polygon meshes, asset bounds and the instanced
+//!   renderer, restores the measurements, camera and camera bookmarks; compute classification
+//!   reruns from the restored `PolygonClassificationData`; the undo history is cleared
 //!
 //! ### Class Selection Tool
 //! - **Activation**: `S` key (native only, no RPC integration)
 //! - **Purpose**: Select individual points by connectivity class ID
+//! - **Workflow**: Clicks pick the rendered point under the cursor and toggle its object ID
 //! - **Status**: Legacy tool, primarily for debugging connectivity data
 //!
+//! ### Point Picking
+//! - **Index**: `PointPicker` buckets the position texture's CPU copy into a uniform grid
+//!   once the dataset is loaded
+//! - **Hover**: The front-most visible point under the cursor drives `MouseEnterObjectState`
+//! - **RPC**: `pick_point` returns world position, class, object ID and colour
+//!
 //! ## Cross-Platform Considerations
 //!
 //! ### Native Builds
@@ -89,24 +139,64 @@
 //! - `ClearToolEvent`: Deactivate current tool and reset state
 //! - `PolygonActionEvent`: Complete, clear, or clear all polygons
 //! - `AssetPlacementEvent`: Select asset, place at position, toggle mode
+//! - `MeasureActionEvent`: Set the measure or snap mode, complete, cancel, rename, delete or export
+//! - `ProfileActionEvent`: Complete, clear, set corridor or export the profile
+//! - `HistoryEvent`: Undo or redo the last recorded edit
+//! - `SessionEvent`: Save or load a session project file
 //! - `PolygonHideRequestEvent`: Queue hide operation with mask filters
 //! - `PolygonReclassifyRequestEvent`: Queue reclassification with target class
+//! - `PolygonEditEvent`: Delete, update or reorder a completed polygon, or toggle edit mode
 
 /// Point selection tool for connectivity class ID queries (native only).
 ///
 /// Legacy debugging tool for inspecting connectivity data via mouse clicks.
 pub mod class_selection;
 
-/// Distance measurement tool with heightmap-aware terrain following.
+/// Command-based undo/redo history for classification, asset and measurement edits.
+///
+/// Tools record reversible commands; undo and redo rebuild meshes, instances and compute state.
+pub mod history;
+
+/// Multi-mode measurement tool with point and feature snapping.
 ///
-/// Two-point measurement with live preview and RPC distance notifications.
+/// Distance, polyline, area, height, slope and angle measurements with labels and export.
 pub mod measure;
 
+/// Picking of rendered points under the cursor through a CPU grid over the position texture.
+///
+/// Skips hidden points and drives object hover highlighting.
+pub mod point_pick;
+
 /// Polygon-based point reclassification and hiding operations.
 ///
 /// Interactive polygon drawing with compute shader integration for classification changes.
 pub mod polygon;
 
+/// Listing, editing, reordering and deleting completed classification polygons.
+///
+/// Includes an in-viewport vertex drag mode; every change reruns classification compute.
+pub mod polygon_edit;
+
+/// Polyline elevation profiles with corridor extraction and CSV/SVG export.
+///
+/// Projects points near the line onto its chainage axis using CPU copies of the point textures.
+pub mod profile;
+
+/// Screen-space rectangle, lasso and brush point selection.
+///
+/// Selected points feed the polygon hide and reclassify operations in place of a drawn polygon.
+pub mod screen_selection;
+
+/// Cursor snapping to rendered points and to drawn polygon and measurement features.
+///
+/// Resolves snap targets in screen space and through the point picker.
+pub mod snap;
+
+/// Versioned project files for saving and restoring editing sessions.
+///
+/// Serialises polygons, placed assets, measurements, camera state and bookmarks in world coordinates.
+pub mod session;
+
 /// Unified tool manager coordinating exclusive tool activation and state.
 ///
 /// Handles tool selection events from keyboard shortcuts and RPC with frontend notifications.
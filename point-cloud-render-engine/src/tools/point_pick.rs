@@ -0,0 +1,734 @@
+use crate::engine::assets::bounds::PointCloudBounds;
+use crate::engine::assets::point_cloud_assets::{PointCloudAssets, read_texel};
+use crate::engine::assets::scene_manifest::SceneManifest;
+use crate::engine::compute::classification_reference::{
+    ClassificationReference, HIDDEN_CLASS, object_id_of,
+};
+use crate::engine::core::app_state::AppState;
+use crate::engine::systems::render_mode::MouseEnterObjectState;
+use crate::tools::polygon::PolygonClassificationData;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use constants::class::ROAD_CLASSIFICATIONS;
+use std::collections::HashSet;
+
+/// Points within this many logical pixels of the cursor can be picked.
+const PICK_RADIUS_PX: f32 = 6.0;
+/// Average number of points per occupied grid cell the index aims for.
+const TARGET_POINTS_PER_CELL: f32 = 32.0;
+const MIN_CELL_SIZE: f32 = 0.1;
+/// Cap on grid cells so the cell table stays small for sparse, wide scenes.
+const MAX_GRID_CELLS: u64 = 1 << 24;
+/// Orthographic picks put the cone apex this many scene diagonals behind the ray origin.
+const ORTHOGRAPHIC_APEX_SCALE: f32 = 100.0;
+
+/// Cursor radius and class filter for a pick.
+#[derive(Debug, Clone, Copy)]
+pub struct PickOptions {
+    /// Points within this many logical pixels of the cursor can be picked.
+    pub radius_px: f32,
+    /// Only accept points whose current class is a ground class.
+    pub ground_only: bool,
+}
+
+impl Default for PickOptions {
+    fn default() -> Self {
+        Self {
+            radius_px: PICK_RADIUS_PX,
+            ground_only: false,
+        }
+    }
+}
+
+/// Rendered point under the cursor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PickedPoint {
+    /// Point texel index in the position texture.
+    pub index: u32,
+    /// Render-space position.
+    pub position: Vec3,
+    /// Class after polygon edits.
+    pub class_id: u32,
+    pub original_class_id: u32,
+    pub object_id: u32,
+    /// sRGB colour, 0–255.
+    pub colour: [u8; 3],
+}
+
+impl PickedPoint {
+    /// RPC payload with the render-space position and the absolute world position.
+    pub fn payload(&self, bounds: &PointCloudBounds) -> serde_json::Value {
+        let (x, y, z) = bounds.bounds.origin;
+        serde_json::json!({
+            "index": self.index,
+            "position": self.position.to_array(),
+            "world_position": [
+                self.position.x as f64 + x,
+                self.position.y as f64 + y,
+                self.position.z as f64 + z,
+            ],
+            "class_id": self.class_id,
+            "original_class_id": self.original_class_id,
+            "object_id": self.object_id,
+            "colour": self.colour,
+        })
+    }
+}
+
+/// Uniform 3D grid of point texel indices, bucketed by world position.
+///
+/// Cells are stored as a counting-sorted index list; `cell_start[c]..cell_start[c + 1]`
+/// is the run of texels in cell `c`.
+pub struct PointGrid {
+    min: Vec3,
+    cell_size: f32,
+    dims: UVec3,
+    cell_start: Vec<u32>,
+    indices: Vec<u32>,
+}
+
+impl PointGrid {
+    /// Bucket `point_count` points spanning `extent` from `min`; `position_of` returns a
+    /// texel's world position, or `None` to skip it.
+    pub fn build(
+        point_count: usize,
+        min: Vec3,
+        extent: Vec3,
+        position_of: impl Fn(u32) -> Option<Vec3>,
+    ) -> Self {
+        // Street scans are close to 2.5D, so size cells from the ground area.
+        let target_cells = (point_count as f32 / TARGET_POINTS_PER_CELL).max(1.0);
+        let mut cell_size = ((extent.x * extent.z).max(0.0) / target_cells)
+            .sqrt()
+            .max(MIN_CELL_SIZE);
+        let mut dims = grid_dims(extent, cell_size);
+        while dims.x as u64 * dims.y as u64 * dims.z as u64 > MAX_GRID_CELLS {
+            cell_size *= 1.25;
+            dims = grid_dims(extent, cell_size);
+        }
+
+        let mut grid = Self {
+            min,
+            cell_size,
+            dims,
+            cell_start: vec![0; (dims.x * dims.y * dims.z) as usize + 1],
+            indices: Vec::new(),
+        };
+
+        let cells: Vec<Option<usize>> = (0..point_count as u32)
+            .map(|index| position_of(index).map(|p| grid.cell_of(p)))
+            .collect();
+        for &cell in cells.iter().flatten() {
+            grid.cell_start[cell + 1] += 1;
+        }
+        for cell in 1..grid.cell_start.len() {
+            grid.cell_start[cell] += grid.cell_start[cell - 1];
+        }
+
+        let mut next = grid.cell_start.clone();
+        grid.indices = vec![0; *grid.cell_start.last().unwrap_or(&0) as usize];
+        for (index, cell) in cells.into_iter().enumerate() {
+            if let Some(cell) = cell {
+                grid.indices[next[cell] as usize] = index as u32;
+                next[cell] += 1;
+            }
+        }
+        grid
+    }
+
+    pub fn point_count(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn cell_count(&self) -> usize {
+        self.cell_start.len() - 1
+    }
+
+    /// Nearest accepted point along `ray` inside a cone of half-angle tangent `tan_radius`.
+    ///
+    /// Walks the ray in steps no shorter than half a cell, testing every cell that the
+    /// cone can reach within the step, and stops once the walk passes the best hit.
+    /// Returns the texel index and its distance along the ray.
+    pub fn pick(
+        &self,
+        ray: Ray3d,
+        tan_radius: f32,
+        position_of: impl Fn(u32) -> Option<Vec3>,
+        mut accept: impl FnMut(u32) -> bool,
+    ) -> Option<(u32, f32)> {
+        let direction = *ray.direction;
+        let max = self.min + self.dims.as_vec3() * self.cell_size;
+        let (enter, exit) = ray_box(ray.origin, direction, self.min, max)?;
+
+        let mut best: Option<(u32, f32)> = None;
+        let mut visited = HashSet::new();
+        let mut t = enter.max(0.0);
+        // The cone keeps widening past the box exit, so walk until it can't reach back in.
+        while t <= exit + t * tan_radius {
+            if best.is_some_and(|(_, along)| t > along) {
+                break;
+            }
+            let step = (self.cell_size * 0.5).max(t * tan_radius);
+            let reach = step + (t + step) * tan_radius;
+            let centre = ray.origin + direction * t;
+
+            let low = self.cell_coords(centre - Vec3::splat(reach));
+            let high = self.cell_coords(centre + Vec3::splat(reach));
+            for z in low.z..=high.z {
+                for y in low.y..=high.y {
+                    for x in low.x..=high.x {
+                        let cell = ((z * self.dims.y + y) * self.dims.x + x) as usize;
+                        if !visited.insert(cell) {
+                            continue;
+                        }
+                        let run =
+                            self.cell_start[cell] as usize..self.cell_start[cell + 1] as usize;
+                        for &index in &self.indices[run] {
+                            let Some(position) = position_of(index) else {
+                                continue;
+                            };
+                            let offset = position - ray.origin;
+                            let along = offset.dot(direction);
+                            if along <= 0.0 || best.is_some_and(|(_, b)| along >= b) {
+                                continue;
+                            }
+                            let perpendicular = (offset - direction * along).length();
+                            if perpendicular <= along * tan_radius && accept(index) {
+                                best = Some((index, along));
+                            }
+                        }
+                    }
+                }
+            }
+            t += step;
+        }
+        best
+    }
+
+    /// Whether `accept` holds for any point inside the box `low..=high`.
+    pub fn any_in_box(
+        &self,
+        low: Vec3,
+        high: Vec3,
+        position_of: impl Fn(u32) -> Option<Vec3>,
+        mut accept: impl FnMut(u32) -> bool,
+    ) -> bool {
+        let first = self.cell_coords(low);
+        let last = self.cell_coords(high);
+        for z in first.z..=last.z {
+            for y in first.y..=last.y {
+                for x in first.x..=last.x {
+                    let cell = ((z * self.dims.y + y) * self.dims.x + x) as usize;
+                    let run = self.cell_start[cell] as usize..self.cell_start[cell + 1] as usize;
+                    for &index in &self.indices[run] {
+                        let inside = position_of(index)
+                            .is_some_and(|p| p.cmpge(low).all() && p.cmple(high).all());
+                        if inside && accept(index) {
+                            return true;
+                        }
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    fn cell_coords(&self, position: Vec3) -> UVec3 {
+        ((position - self.min) / self.cell_size)
+            .floor()
+            .max(Vec3::ZERO)
+            .as_uvec3()
+            .min(self.dims - UVec3::ONE)
+    }
+
+    fn cell_of(&self, position: Vec3) -> usize {
+        let c = self.cell_coords(position);
+        ((c.z * self.dims.y + c.y) * self.dims.x + c.x) as usize
+    }
+}
+
+fn grid_dims(extent: Vec3, cell_size: f32) -> UVec3 {
+    (extent / cell_size).ceil().max(Vec3::ONE).as_uvec3()
+}
+
+/// Slab test; returns the entry and exit distances along the ray.
+fn ray_box(origin: Vec3, direction: Vec3, min: Vec3, max: Vec3) -> Option<(f32, f32)> {
+    let inverse = direction.recip();
+    let a = (min - origin) * inverse;
+    let b = (max - origin) * inverse;
+    let enter = a.min(b).max_element();
+    let exit = a.max(b).min_element();
+    (exit >= enter.max(0.0)).then_some((enter, exit))
+}
+
+/// CPU spatial index over the position texture for picking rendered points, and the
+/// point currently under the cursor.
+#[derive(Resource, Default)]
+pub struct PointPicker {
+    grid: Option<PointGrid>,
+    reference: Option<ClassificationReference>, // Current polygons; see `update_pick_classification`.
+    pub hovered: Option<PickedPoint>,
+}
+
+impl PointPicker {
+    pub fn is_ready(&self) -> bool {
+        self.grid.is_some()
+    }
+
+    /// Classification of the current polygons, shared with screen-space selection.
+    pub fn reference(&self) -> Option<&ClassificationReference> {
+        self.reference.as_ref()
+    }
+
+    /// Front-most visible point within `PICK_RADIUS_PX` of `cursor` (logical window pixels).
+    /// Hidden points are skipped, so picking sees what is rendered.
+    pub fn pick(
+        &self,
+        cursor: Vec2,
+        camera: &Camera,
+        camera_transform: &GlobalTransform,
+        images: &Assets<Image>,
+        assets: &PointCloudAssets,
+        bounds: &PointCloudBounds,
+    ) -> Option<PickedPoint> {
+        self.pick_with(
+            cursor,
+            camera,
+            camera_transform,
+            images,
+            assets,
+            bounds,
+            PickOptions::default(),
+        )
+    }
+
+    /// As `pick`, with a custom cursor radius and an optional ground class filter.
+    /// Rejected points don't occlude, so a ground pick sees through poles and facades.
+    pub fn pick_with(
+        &self,
+        cursor: Vec2,
+        camera: &Camera,
+        camera_transform: &GlobalTransform,
+        images: &Assets<Image>,
+        assets: &PointCloudAssets,
+        bounds: &PointCloudBounds,
+        options: PickOptions,
+    ) -> Option<PickedPoint> {
+        let position_data = images
+            .get(&assets.position_texture)
+            .and_then(|image| image.data.as_deref())?;
+        let colour_data = images
+            .get(&assets.colour_class_texture)
+            .and_then(|image| image.data.as_deref())?;
+        let ray = camera.viewport_to_world(camera_transform, cursor).ok()?;
+        let viewport = camera.logical_viewport_rect()?;
+
+        // Perspective projection: y_axis.y is 1 / tan(fov / 2).
+        let clip_from_view = camera.clip_from_view();
+        let focal = clip_from_view.y_axis.y;
+        let mut tan_radius =
+            2.0 * options.radius_px / (focal * viewport.height()).max(f32::EPSILON);
+        let mut ray = ray;
+        // Orthographic rays are parallel and y_axis.y is 2 / view height instead. Pick
+        // through a narrow cone whose apex sits far behind the near plane, which is
+        // close to a cylinder of the cursor radius.
+        if clip_from_view.w_axis.w == 1.0 {
+            let apex_distance = bounds.size().length() * ORTHOGRAPHIC_APEX_SCALE;
+            tan_radius /= apex_distance;
+            ray.origin -= *ray.direction * apex_distance;
+        }
+
+        self.pick_in_cone(
+            ray,
+            tan_radius,
+            position_data,
+            colour_data,
+            bounds,
+            options.ground_only,
+        )
+    }
+
+    /// Front-most accepted point in the cone around `ray`, decoded from the texture bytes.
+    fn pick_in_cone(
+        &self,
+        ray: Ray3d,
+        tan_radius: f32,
+        position_data: &[u8],
+        colour_data: &[u8],
+        bounds: &PointCloudBounds,
+        ground_only: bool,
+    ) -> Option<PickedPoint> {
+        let grid = self.grid.as_ref()?;
+        let reference = self.reference.as_ref()?;
+        let min = bounds.min_corner();
+        let extent = bounds.size();
+        let to_world = |texel: [f32; 4]| min + Vec3::new(texel[0], texel[1], texel[2]) * extent;
+        let position_of = |index: u32| read_texel(position_data, index as usize).map(to_world);
+        let class_of = |index: u32| {
+            let position = read_texel(position_data, index as usize)?;
+            let colour = read_texel(colour_data, index as usize)?;
+            Some(reference.classify(index, position, colour))
+        };
+
+        let (index, _) = grid.pick(ray, tan_radius, position_of, |index| {
+            class_of(index).is_some_and(|class| {
+                class != HIDDEN_CLASS
+                    && (!ground_only || ROAD_CLASSIFICATIONS.iter().any(|&c| c as u32 == class))
+            })
+        })?;
+
+        let position = read_texel(position_data, index as usize)?;
+        let colour = read_texel(colour_data, index as usize)?;
+        let to_byte = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Some(PickedPoint {
+            index,
+            position: to_world(position),
+            class_id: reference.classify(index, position, colour),
+            original_class_id: (colour[3] * 255.0) as u32,
+            object_id: object_id_of(position),
+            colour: [to_byte(colour[0]), to_byte(colour[1]), to_byte(colour[2])],
+        })
+    }
+}
+
+impl PointPicker {
+    /// Whether any rendered point inside the box `low..=high` has a current class
+    /// accepted by `accept_class`. Hidden points never match.
+    pub fn any_in_box(
+        &self,
+        low: Vec3,
+        high: Vec3,
+        images: &Assets<Image>,
+        assets: &PointCloudAssets,
+        bounds: &PointCloudBounds,
+        accept_class: impl Fn(u32) -> bool,
+    ) -> bool {
+        let (Some(grid), Some(reference)) = (self.grid.as_ref(), self.reference.as_ref()) else {
+            return false;
+        };
+        let texture_data =
+            |handle: &Handle<Image>| images.get(handle).and_then(|image| image.data.as_deref());
+        let (Some(position_data), Some(colour_data)) = (
+            texture_data(&assets.position_texture),
+            texture_data(&assets.colour_class_texture),
+        ) else {
+            return false;
+        };
+        let min = bounds.min_corner();
+        let extent = bounds.size();
+        let position_of = |index: u32| {
+            read_texel(position_data, index as usize)
+                .map(|texel| min + Vec3::new(texel[0], texel[1], texel[2]) * extent)
+        };
+        grid.any_in_box(low, high, position_of, |index| {
+            let (Some(position), Some(colour)) = (
+                read_texel(position_data, index as usize),
+                read_texel(colour_data, index as usize),
+            ) else {
+                return false;
+            };
+            let class = reference.classify(index, position, colour);
+            class != HIDDEN_CLASS && accept_class(class)
+        })
+    }
+}
+
+pub struct PointPickPlugin;
+impl Plugin for PointPickPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PointPicker>().add_systems(
+            Update,
+            (
+                build_point_pick_index,
+                update_pick_classification,
+                update_point_hover,
+            )
+                .chain()
+                .run_if(in_state(AppState::Running)),
+        );
+    }
+}
+
+/// Builds the pick index once the position texture's CPU copy is available.
+pub fn build_point_pick_index(
+    mut picker: ResMut<PointPicker>,
+    images: Res<Assets<Image>>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+) {
+    if picker.is_ready() {
+        return;
+    }
+    let Some(bounds) = assets.get_bounds(&manifests) else {
+        return;
+    };
+    let Some(position_data) = images
+        .get(&assets.position_texture)
+        .and_then(|image| image.data.as_deref())
+    else {
+        return;
+    };
+
+    let min = bounds.min_corner();
+    let extent = bounds.size();
+    let grid = PointGrid::build(bounds.loaded_points, min, extent, |index| {
+        read_texel(position_data, index as usize)
+            .map(|texel| min + Vec3::new(texel[0], texel[1], texel[2]) * extent)
+    });
+    info!(
+        "Point pick index: {} points in {} cells",
+        grid.point_count(),
+        grid.cell_count()
+    );
+    picker.grid = Some(grid);
+}
+
+/// Repacks the CPU classification reference when the polygons change or the heightmap
+/// arrives, so hover picks and walk collision reuse it between changes.
+pub fn update_pick_classification(
+    mut picker: ResMut<PointPicker>,
+    images: Res<Assets<Image>>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+    classification_data: Res<PolygonClassificationData>,
+) {
+    let heightmap = images
+        .get(&assets.heightmap_texture)
+        .and_then(|image| Some((image.data.as_deref()?, image.width())));
+    let stale = picker.reference.as_ref().is_none_or(|reference| {
+        classification_data.is_changed() || (heightmap.is_some() && !reference.has_heightmap())
+    });
+    if !stale {
+        return;
+    }
+    let Some(bounds) = assets.get_bounds(&manifests) else {
+        return;
+    };
+
+    let reference = ClassificationReference::new(&classification_data.polygons, &bounds.bounds);
+    picker.reference = Some(match heightmap {
+        Some((data, size)) => reference.with_heightmap(data, size),
+        None => reference,
+    });
+}
+
+/// Picks the point under the cursor when the cursor, camera or polygons change, and
+/// highlights its object through `MouseEnterObjectState`.
+///
+/// Skipped while a mouse button is held so camera drags stay cheap. The hover state is
+/// only written when the hovered object changes, leaving frontend-set hovers alone otherwise.
+pub fn update_point_hover(
+    mut picker: ResMut<PointPicker>,
+    mut hover_state: ResMut<MouseEnterObjectState>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&GlobalTransform, &Camera), With<Camera3d>>,
+    images: Res<Assets<Image>>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+    classification_data: Res<PolygonClassificationData>,
+    mut last_query: Local<Option<(Vec2, Mat4)>>,
+) {
+    if !picker.is_ready() || mouse.get_pressed().next().is_some() {
+        return;
+    }
+    let Ok((camera_transform, camera)) = cameras.single() else {
+        return;
+    };
+    let cursor = windows.single().ok().and_then(|w| w.cursor_position());
+
+    let query = cursor.map(|c| (c, camera_transform.compute_matrix()));
+    if query == *last_query && !classification_data.is_changed() {
+        return;
+    }
+    *last_query = query;
+
+    let picked = match cursor {
+        Some(cursor) => {
+            let Some(bounds) = assets.get_bounds(&manifests) else {
+                return;
+            };
+            picker.pick(cursor, camera, camera_transform, &images, &assets, &bounds)
+        }
+        None => None,
+    };
+
+    let previous_object = picker.hovered.map(|p| p.object_id);
+    picker.hovered = picked;
+
+    // Object 0 is unsegmented, so highlighting it would light up everything else.
+    let object_id = picked.map(|p| p.object_id).filter(|&id| id != 0);
+    if object_id != previous_object.filter(|&id| id != 0) {
+        *hover_state = match object_id {
+            Some(id) => MouseEnterObjectState {
+                object_id: Some(id),
+            },
+            None => MouseEnterObjectState::default(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::assets::bounds::BoundsData;
+    use constants::class::encode_object_id;
+
+    fn grid_of(points: &[Vec3]) -> PointGrid {
+        PointGrid::build(points.len(), Vec3::ZERO, Vec3::splat(100.0), |index| {
+            points.get(index as usize).copied()
+        })
+    }
+
+    fn pick(grid: &PointGrid, points: &[Vec3], origin: Vec3, target: Vec3) -> Option<u32> {
+        let ray = Ray3d::new(origin, Dir3::new(target - origin).unwrap());
+        grid.pick(
+            ray,
+            0.01,
+            |index| points.get(index as usize).copied(),
+            |_| true,
+        )
+        .map(|(index, _)| index)
+    }
+
+    #[test]
+    fn picks_front_most_point_on_the_ray() {
+        let points = [
+            Vec3::new(50.0, 10.0, 50.0),
+            Vec3::new(50.0, 20.0, 50.0),
+            Vec3::new(60.0, 20.0, 50.0),
+        ];
+        let grid = grid_of(&points);
+
+        let above = Vec3::new(50.0, 90.0, 50.0);
+        assert_eq!(pick(&grid, &points, above, points[0]), Some(1));
+        let below = Vec3::new(50.0, -10.0, 50.0);
+        assert_eq!(pick(&grid, &points, below, points[1]), Some(0));
+        assert_eq!(
+            pick(&grid, &points, above, Vec3::new(70.0, 0.0, 50.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn cone_widens_with_distance() {
+        let points = [Vec3::new(50.5, 0.0, 50.0)];
+        let grid = grid_of(&points);
+
+        // 0.5 m off the ray is outside a 0.01 cone at 20 m but inside it at 90 m.
+        let near = Vec3::new(50.0, 20.0, 50.0);
+        assert_eq!(pick(&grid, &points, near, Vec3::new(50.0, 0.0, 50.0)), None);
+        let far = Vec3::new(50.0, 90.0, 50.0);
+        assert_eq!(
+            pick(&grid, &points, far, Vec3::new(50.0, 0.0, 50.0)),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn rejected_points_let_later_ones_through() {
+        let points = [Vec3::new(20.0, 5.0, 20.0), Vec3::new(20.0, 1.0, 20.0)];
+        let grid = grid_of(&points);
+        let ray = Ray3d::new(Vec3::new(20.0, 50.0, 20.0), Dir3::NEG_Y);
+
+        let hit = grid.pick(
+            ray,
+            0.01,
+            |index| points.get(index as usize).copied(),
+            |index| index != 0,
+        );
+        assert_eq!(hit.map(|(index, _)| index), Some(1));
+        assert!((hit.unwrap().1 - 49.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn box_query_only_sees_points_inside() {
+        let points = [Vec3::new(10.0, 1.0, 10.0), Vec3::new(10.4, 3.0, 10.0)];
+        let grid = grid_of(&points);
+        let position_of = |index: u32| points.get(index as usize).copied();
+
+        let low = Vec3::new(10.2, 0.5, 9.8);
+        let high = Vec3::new(10.6, 2.0, 10.2);
+        assert!(!grid.any_in_box(low, high, position_of, |_| true));
+        let high = Vec3::new(10.6, 3.5, 10.2);
+        assert!(grid.any_in_box(low, high, position_of, |_| true));
+        assert!(!grid.any_in_box(low, high, position_of, |index| index == 0));
+    }
+
+    #[test]
+    fn every_point_is_bucketed_once() {
+        let points: Vec<Vec3> = (0..500)
+            .map(|i| Vec3::new((i * 7 % 100) as f32, (i % 13) as f32, (i * 3 % 100) as f32))
+            .collect();
+        let grid = grid_of(&points);
+
+        let mut indices = grid.indices.clone();
+        indices.sort_unstable();
+        assert_eq!(indices, (0..500).collect::<Vec<u32>>());
+        for (index, &point) in points.iter().enumerate() {
+            let cell = grid.cell_of(point);
+            let run = grid.cell_start[cell] as usize..grid.cell_start[cell + 1] as usize;
+            assert!(grid.indices[run].contains(&(index as u32)));
+        }
+    }
+
+    #[test]
+    fn picked_point_keeps_object_ids_that_truncate() {
+        let bounds = PointCloudBounds {
+            bounds: BoundsData {
+                origin: (0.0, 0.0, 0.0),
+                min_x: 0.0,
+                max_x: 100.0,
+                min_y: 0.0,
+                max_y: 100.0,
+                min_z: 0.0,
+                max_z: 100.0,
+            },
+            total_points: 3,
+            loaded_points: 3,
+            texture_size: 2,
+            sampling_ratio: 1.0,
+            utilisation_percent: 0.0,
+            has_colour: false,
+            colour_points: 0,
+            road_points: 0,
+        };
+        // Objects 1, 2 and 61 read back as their predecessor when truncated.
+        let objects = [1.0, 2.0, 61.0];
+        let texel_bytes = |texel: [f32; 4]| texel.into_iter().flat_map(f32::to_le_bytes);
+        let position_data: Vec<u8> = objects
+            .iter()
+            .enumerate()
+            .flat_map(|(i, &id)| {
+                texel_bytes([0.2 + 0.3 * i as f32, 0.1, 0.5, encode_object_id(id)])
+            })
+            .collect();
+        let colour_data: Vec<u8> = objects
+            .iter()
+            .flat_map(|_| texel_bytes([0.5, 0.5, 0.5, 1.0 / 255.0]))
+            .collect();
+        let picker = PointPicker {
+            grid: Some(PointGrid::build(
+                3,
+                Vec3::ZERO,
+                Vec3::splat(100.0),
+                |index| {
+                    read_texel(&position_data, index as usize)
+                        .map(|texel| Vec3::new(texel[0], texel[1], texel[2]) * 100.0)
+                },
+            )),
+            reference: Some(ClassificationReference::new(&[], &bounds.bounds)),
+            hovered: None,
+        };
+
+        for (i, &id) in objects.iter().enumerate() {
+            let target = Vec3::new(20.0 + 30.0 * i as f32, 10.0, 50.0);
+            let ray = Ray3d::new(target + Vec3::Y * 50.0, Dir3::NEG_Y);
+            let picked = picker
+                .pick_in_cone(ray, 0.01, &position_data, &colour_data, &bounds, false)
+                .unwrap();
+            assert_eq!(picked.index, i as u32);
+            assert_eq!(picked.object_id, id as u32);
+        }
+    }
+}
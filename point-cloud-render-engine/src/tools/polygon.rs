@@ -3,15 +3,17 @@ use crate::engine::assets::scene_manifest::SceneManifest;
 use crate::engine::camera::viewport_camera::ViewportCamera;
 use crate::engine::core::app_state::AppState;
 use crate::engine::scene::grid::GroundGrid;
-use crate::engine::systems::render_mode::RenderMode;
-use crate::engine::systems::render_mode::RenderModeState;
+use crate::tools::history::{EditCommand, EditHistory};
+use crate::tools::screen_selection::PointSelection;
 use bevy::asset::RenderAssetUsages;
 use bevy::prelude::*;
 use bevy::render::extract_resource::ExtractResource;
 use bevy::render::mesh::PrimitiveTopology;
 use bevy::render::view::RenderLayers;
 use bevy::window::PrimaryWindow;
-use constants::procedural_shader::MAXIMUM_POLYGONS;
+use constants::procedural_shader::{
+    MAX_IGNORE_MASK_LENGTH, MAXIMUM_POLYGON_POINTS, MAXIMUM_POLYGONS, MAXIMUM_SELECTION_POINTS,
+};
 use constants::render_settings::{
     DRAW_LINE_WIDTH, DRAW_VERTEX_SIZE, MOUSE_RAYCAST_INTERSECTION_SPHERE_SIZE,
 };
@@ -21,12 +23,14 @@ use serde::{Deserialize, Serialize};
 #[derive(Event, Debug, Clone)]
 pub struct PolygonHideRequestEvent {
     pub source_items: Vec<(u32, u32)>, // class_id, object_id
+    pub vertical_range: Option<VerticalRange>,
 }
 
 #[derive(Event, Debug, Clone)]
 pub struct PolygonReclassifyRequestEvent {
     pub source_items: Vec<(u32, u32)>,
     pub target: (u32, u32), // target_class_id, target_object_id
+    pub vertical_range: Option<VerticalRange>,
 }
 
 pub struct PolygonToolPlugin;
@@ -41,7 +45,24 @@ impl Plugin for PolygonToolPlugin {
             .add_systems(
                 Update,
                 process_polygon_reclassify_requests.run_if(in_state(AppState::Running)),
-            );
+            )
+            .add_systems(Update, sync_polygon_height_origin);
+    }
+}
+
+/// Keep `PolygonClassificationData::height_origin` on the loaded manifest's origin.
+fn sync_polygon_height_origin(
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+    mut classification_data: ResMut<PolygonClassificationData>,
+) {
+    let Some(bounds) = assets.get_bounds(&manifests) else {
+        return;
+    };
+    // Only write on change so the compute buffers are not rebuilt every frame.
+    let origin_y = bounds.bounds.origin.1;
+    if classification_data.height_origin != origin_y {
+        classification_data.height_origin = origin_y;
     }
 }
 
@@ -50,20 +71,19 @@ fn process_poloygon_hide_requests(
     mut commands: Commands,
     mut ev: EventReader<PolygonHideRequestEvent>,
     mut polygon_tool: ResMut<PolygonTool>,
+    mut point_selection: ResMut<PointSelection>,
     mut polygon_counter: ResMut<PolygonCounter>,
     mut classification_data: ResMut<PolygonClassificationData>,
+    mut history: ResMut<EditHistory>,
     mut rpc_interface: ResMut<crate::rpc::web_rpc::WebRpcInterface>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     viewport_camera: ResMut<ViewportCamera>,
 ) {
     for e in ev.read() {
-        let should_complete = polygon_tool.current_polygon.len() >= 3;
-        if should_complete {
-            let resampled_points = resample_polygon_uniform(
-                &polygon_tool.current_polygon,
-                polygon_tool.target_point_spacing,
-            );
+        if let Some((vertices, selection)) = pending_footprint(&polygon_tool, &point_selection) {
+            let resampled_points =
+                resample_polygon_uniform(&vertices, polygon_tool.target_point_spacing);
 
             // Generate unique polygon identifier for tracking.
             let polygon_id = polygon_counter.next_id;
@@ -72,21 +92,41 @@ fn process_poloygon_hide_requests(
             // Create classification data structure for compute shader processing.
             let class_polygon = ClassificationPolygon {
                 id: polygon_id,
+                vertices: vertices.clone(),
                 points: resampled_points.clone(),
                 new_class: 0, // not actually used during hide mode
                 mode: PolygonMode::Hide,
                 masks: e.source_items.clone(),
+                vertical_range: e.vertical_range,
+                selection,
             };
 
             // Add to classification data with GPU memory constraint validation.
-            if classification_data.polygons.len() < classification_data.max_polygons {
-                classification_data.polygons.push(class_polygon);
+            let capacity =
+                classification_data.check_limits(std::slice::from_ref(&class_polygon), None);
+            if let Err(error) = capacity {
+                warn!("Warning: {}", error);
+
+                // Notify frontend of error
+                rpc_interface.send_notification(
+                    "polygon_error",
+                    serde_json::json!({
+                        "error": error,
+                        "limits": classification_data.limits_summary()
+                    }),
+                );
+            } else {
+                classification_data.polygons.push(class_polygon.clone());
+                history.record(EditCommand::AddPolygon(class_polygon));
 
                 // Create visual representation using standard material pipeline.
                 create_completed_polygon(
                     &mut commands,
                     &resampled_points,
                     polygon_id,
+                    classification_data
+                        .render_range(e.vertical_range.as_ref())
+                        .as_ref(),
                     viewport_camera.ground_height,
                     &mut meshes,
                     &mut materials,
@@ -97,31 +137,18 @@ fn process_poloygon_hide_requests(
                     "polygon_completed",
                     serde_json::json!({
                         "polygon_id": polygon_id,
-                        "point_count": polygon_tool.current_polygon.len(),
+                        "point_count": vertices.len(),
                         "class": polygon_tool.current_class,
                         "total_polygons": classification_data.polygons.len()
                     }),
                 );
-            } else {
-                warn!(
-                    "Warning: Maximum polygon limit reached ({})",
-                    classification_data.max_polygons
-                );
-
-                // Notify frontend of error
-                rpc_interface.send_notification(
-                    "polygon_error",
-                    serde_json::json!({
-                        "error": "Maximum polygon limit reached",
-                        "max_polygons": classification_data.max_polygons
-                    }),
-                );
             }
 
             // Reset state for next polygon creation.
             polygon_tool.current_polygon.clear();
             polygon_tool.preview_point = None;
             polygon_tool.is_completed = false;
+            point_selection.clear();
         }
     }
 }
@@ -130,8 +157,10 @@ fn process_polygon_reclassify_requests(
     mut commands: Commands,
     mut ev: EventReader<PolygonReclassifyRequestEvent>,
     mut polygon_tool: ResMut<PolygonTool>,
+    mut point_selection: ResMut<PointSelection>,
     mut polygon_counter: ResMut<PolygonCounter>,
     mut classification_data: ResMut<PolygonClassificationData>,
+    mut history: ResMut<EditHistory>,
     mut rpc_interface: ResMut<crate::rpc::web_rpc::WebRpcInterface>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
@@ -145,18 +174,17 @@ fn process_polygon_reclassify_requests(
             e.target.0,
             e.target.1
         );
-        let should_complete = polygon_tool.current_polygon.len() >= 3;
+        let footprint = pending_footprint(&polygon_tool, &point_selection);
 
         info!(
             "[POLY] Reclassify Complete: {:?}, with target: {:?}",
-            should_complete, polygon_tool.current_class
+            footprint.is_some(),
+            polygon_tool.current_class
         );
 
-        if should_complete {
-            let resampled_points = resample_polygon_uniform(
-                &polygon_tool.current_polygon,
-                polygon_tool.target_point_spacing,
-            );
+        if let Some((vertices, selection)) = footprint {
+            let resampled_points =
+                resample_polygon_uniform(&vertices, polygon_tool.target_point_spacing);
 
             // Generate unique polygon identifier for tracking.
             let polygon_id = polygon_counter.next_id;
@@ -165,17 +193,34 @@ fn process_polygon_reclassify_requests(
             // Create classification data structure for compute shader processing.
             let class_polygon = ClassificationPolygon {
                 id: polygon_id,
+                vertices: vertices.clone(),
                 points: resampled_points.clone(),
                 new_class: polygon_tool.current_class,
                 mode: PolygonMode::Reclassify,
                 masks: e.source_items.clone(),
+                vertical_range: e.vertical_range,
+                selection,
             };
 
             info!("Reclassify Polygon Data: {:?}", class_polygon);
 
             // Add to classification data with GPU memory constraint validation.
-            if classification_data.polygons.len() < classification_data.max_polygons {
-                classification_data.polygons.push(class_polygon);
+            let capacity =
+                classification_data.check_limits(std::slice::from_ref(&class_polygon), None);
+            if let Err(error) = capacity {
+                println!("Warning: {}", error);
+
+                // Notify frontend of error
+                rpc_interface.send_notification(
+                    "polygon_error",
+                    serde_json::json!({
+                        "error": error,
+                        "limits": classification_data.limits_summary()
+                    }),
+                );
+            } else {
+                classification_data.polygons.push(class_polygon.clone());
+                history.record(EditCommand::AddPolygon(class_polygon));
                 println!(
                     "Added classification polygon {} with class {}",
                     polygon_id, polygon_tool.current_class
@@ -186,6 +231,9 @@ fn process_polygon_reclassify_requests(
                     &mut commands,
                     &resampled_points,
                     polygon_id,
+                    classification_data
+                        .render_range(e.vertical_range.as_ref())
+                        .as_ref(),
                     viewport_camera.ground_height,
                     &mut meshes,
                     &mut materials,
@@ -194,7 +242,7 @@ fn process_polygon_reclassify_requests(
                 println!(
                     "Polygon {} completed with {} points",
                     polygon_id,
-                    polygon_tool.current_polygon.len()
+                    vertices.len()
                 );
 
                 // Notify frontend of successful completion
@@ -202,35 +250,37 @@ fn process_polygon_reclassify_requests(
                     "polygon_completed",
                     serde_json::json!({
                         "polygon_id": polygon_id,
-                        "point_count": polygon_tool.current_polygon.len(),
+                        "point_count": vertices.len(),
                         "class": polygon_tool.current_class,
                         "total_polygons": classification_data.polygons.len()
                     }),
                 );
-            } else {
-                println!(
-                    "Warning: Maximum polygon limit reached ({})",
-                    classification_data.max_polygons
-                );
-
-                // Notify frontend of error
-                rpc_interface.send_notification(
-                    "polygon_error",
-                    serde_json::json!({
-                        "error": "Maximum polygon limit reached",
-                        "max_polygons": classification_data.max_polygons
-                    }),
-                );
             }
 
             // Reset state for next polygon creation.
             polygon_tool.current_polygon.clear();
             polygon_tool.preview_point = None;
             polygon_tool.is_completed = false;
+            point_selection.clear();
         }
     }
 }
 
+/// Footprint for a hide/reclassify request: the polygon being drawn, otherwise the
+/// screen-space point selection. Returns the vertices and the selected point indices.
+fn pending_footprint(
+    polygon_tool: &PolygonTool,
+    point_selection: &PointSelection,
+) -> Option<(Vec<Vec3>, Vec<u32>)> {
+    if polygon_tool.current_polygon.len() >= 3 {
+        return Some((polygon_tool.current_polygon.clone(), Vec::new()));
+    }
+    if point_selection.is_empty() {
+        return None;
+    }
+    Some((point_selection.footprint.clone(), point_selection.indices()))
+}
+
 #[repr(u32)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// Polygon definition for point cloud classification operations.
@@ -240,32 +290,188 @@ pub enum PolygonMode {
     Reclassify,
 }
 
+impl PolygonMode {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "hide" => Some(Self::Hide),
+            "reclassify" => Some(Self::Reclassify),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hide => "hide",
+            Self::Reclassify => "reclassify",
+        }
+    }
+}
+
+/// What a polygon's vertical range is measured from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeightReference {
+    Absolute, // World height in the source datum, before the manifest origin is removed.
+    Ground,   // Height above the ground heightmap under each point.
+}
+
+/// Optional height band that turns a polygon footprint into a prism.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VerticalRange {
+    pub reference: HeightReference,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl VerticalRange {
+    pub fn is_valid(&self) -> bool {
+        self.min.is_finite() && self.max.is_finite() && self.min <= self.max
+    }
+
+    /// Shift an absolute range by `offset`; ground-relative ranges are unchanged.
+    pub fn offset(&self, offset: f64) -> Self {
+        match self.reference {
+            HeightReference::Absolute => Self {
+                min: (self.min as f64 + offset) as f32,
+                max: (self.max as f64 + offset) as f32,
+                ..*self
+            },
+            HeightReference::Ground => *self,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClassificationPolygon {
     pub id: u32,
-    pub points: Vec<Vec3>, // XZ plane coordinates for heightmap intersection.
-    pub new_class: u32,    // Target classification ID for enclosed points.
+    pub vertices: Vec<Vec3>, // User-placed corners; classification tests against these.
+    pub points: Vec<Vec3>,   // Outline resampled from `vertices` for display.
+    pub new_class: u32,      // Target classification ID for enclosed points.
     pub masks: Vec<(u32, u32)>,
     pub mode: PolygonMode,
+    pub vertical_range: Option<VerticalRange>, // `None` selects the full column.
+    /// Sorted point texel indices from a screen-space selection. When non-empty they
+    /// replace the footprint test and `vertices` is only the selection's XZ bounds.
+    #[serde(default)]
+    pub selection: Vec<u32>,
 }
 
 /// Resource containing active polygon classification data.
 /// Extracted to render world for compute shader access.
-#[derive(Resource, ExtractResource, Clone)]
+#[derive(Resource, ExtractResource, Clone, Default)]
 pub struct PolygonClassificationData {
     pub polygons: Vec<ClassificationPolygon>,
-    pub max_polygons: usize, // GPU uniform buffer size constraint.
+    pub limits: PolygonLimits, // GPU storage buffer size constraints.
+    pub height_origin: f64,    // World Y of render-space zero, from the manifest origin.
+}
+
+impl PolygonClassificationData {
+    /// A polygon's vertical range in render space, for drawing its prism.
+    pub fn render_range(&self, range: Option<&VerticalRange>) -> Option<VerticalRange> {
+        range.map(|range| range.offset(-self.height_origin))
+    }
+
+    /// Check the polygon list with `added` appended and the polygon `replaced_id` left out.
+    pub fn check_limits(
+        &self,
+        added: &[ClassificationPolygon],
+        replaced_id: Option<u32>,
+    ) -> Result<(), String> {
+        let kept = self.polygons.iter().filter(|p| Some(p.id) != replaced_id);
+        let (polygons, points, masks, selected) = polygon_totals(kept.chain(added));
+        self.limits.check(polygons, points, masks, selected)
+    }
+
+    /// Current usage against the limits, reported to the frontend.
+    pub fn limits_summary(&self) -> serde_json::Value {
+        let (polygons, points, masks, selected) = polygon_totals(&self.polygons);
+        serde_json::json!({
+            "max_polygons": self.limits.max_polygons,
+            "max_points": self.limits.max_points,
+            "max_masks": self.limits.max_masks,
+            "max_selected_points": self.limits.max_selected_points,
+            "polygons": polygons,
+            "points": points,
+            "masks": masks,
+            "selected_points": selected,
+        })
+    }
+}
+
+/// Capacity of the classification compute storage buffers.
+/// Starts at the `procedural_shader` constants and is clamped to the device's
+/// storage buffer binding size once the render device is available.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PolygonLimits {
+    pub max_polygons: usize,
+    pub max_points: usize,          // Vertices across all polygons.
+    pub max_masks: usize,           // Class/object mask entries across all polygons.
+    pub max_selected_points: usize, // Point indices across all screen-space selections.
 }
 
-impl Default for PolygonClassificationData {
+impl Default for PolygonLimits {
     fn default() -> Self {
         Self {
-            polygons: Vec::new(),
             max_polygons: MAXIMUM_POLYGONS,
+            max_points: MAXIMUM_POLYGON_POINTS,
+            max_masks: MAX_IGNORE_MASK_LENGTH,
+            max_selected_points: MAXIMUM_SELECTION_POINTS,
         }
     }
 }
 
+impl PolygonLimits {
+    pub fn check(
+        &self,
+        polygons: usize,
+        points: usize,
+        masks: usize,
+        selected: usize,
+    ) -> Result<(), String> {
+        if polygons > self.max_polygons {
+            return Err(format!(
+                "Maximum polygon limit reached ({})",
+                self.max_polygons
+            ));
+        }
+        if points > self.max_points {
+            return Err(format!(
+                "Maximum polygon point limit reached ({} vertices)",
+                self.max_points
+            ));
+        }
+        if masks > self.max_masks {
+            return Err(format!(
+                "Maximum polygon mask limit reached ({})",
+                self.max_masks
+            ));
+        }
+        if selected > self.max_selected_points {
+            return Err(format!(
+                "Maximum selected point limit reached ({})",
+                self.max_selected_points
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Polygon, vertex, mask and selected point counts for a polygon list.
+fn polygon_totals<'a>(
+    polygons: impl IntoIterator<Item = &'a ClassificationPolygon>,
+) -> (usize, usize, usize, usize) {
+    polygons
+        .into_iter()
+        .fold((0, 0, 0, 0), |(count, points, masks, selected), p| {
+            (
+                count + 1,
+                points + p.vertices.len(),
+                masks + p.masks.len(),
+                selected + p.selection.len(),
+            )
+        })
+}
+
 impl PolygonTool {
     /// Set the active state of the polygon tool.
     /// Called by the tool manager during activation and deactivation.
@@ -275,6 +481,7 @@ impl PolygonTool {
         if !active {
             // Clear any in-progress polygon when tool is deactivated.
             self.current_polygon.clear();
+            self.edit_mode = false;
         }
     }
 
@@ -303,33 +510,6 @@ pub struct CompletedPolygon {
     pub id: u32,
 }
 
-/// Uniform data structure for polygon classification compute shaders.
-/// Maintains compatibility with existing compute pipeline while removing
-/// dependency on Bevy's Material trait system.
-#[derive(Debug, Clone, Copy, bevy::render::render_resource::ShaderType)]
-#[repr(C)]
-pub struct PolygonClassificationUniform {
-    pub polygon_count: u32,
-    pub total_points: u32,
-    pub render_mode: u32,
-    pub _padding: u32,
-    pub point_data: [Vec4; 512],
-    pub polygon_info: [Vec4; 64],
-}
-
-impl Default for PolygonClassificationUniform {
-    fn default() -> Self {
-        Self {
-            polygon_count: 0,
-            total_points: 0,
-            render_mode: RenderMode::RgbColour as u32,
-            _padding: 0,
-            point_data: [Vec4::ZERO; 512],
-            polygon_info: [Vec4::ZERO; 64],
-        }
-    }
-}
-
 /// Interactive polygon creation tool state.
 /// Manages user input, preview visualization, and completion logic.
 #[derive(Resource)]
@@ -340,6 +520,7 @@ pub struct PolygonTool {
     pub is_completed: bool,
     pub current_class: u32,
     pub target_point_spacing: f32, // Uniform resampling distance.
+    pub edit_mode: bool,           // Drag vertices of completed polygons instead of drawing.
 }
 
 impl Default for PolygonTool {
@@ -351,14 +532,15 @@ impl Default for PolygonTool {
             is_completed: false,
             current_class: 1,
             target_point_spacing: 1.0,
+            edit_mode: false,
         }
     }
 }
 
 /// Resamples polygon edges to ensure uniform point distribution.
-/// Prevents GPU compute shader performance issues from irregular spacing. specifically for our z-order morton codes we need we're looking up near points for each point of the polygons.
-/// there for large polygons or triangles with no internal points will miss 'close' regions in the intersection collision checks
-fn resample_polygon_uniform(points: &[Vec3], target_spacing: f32) -> Vec<Vec3> {
+/// Used for the completed polygon outline; classification tests the original vertices,
+/// so the spacing has no effect on which points are selected.
+pub(crate) fn resample_polygon_uniform(points: &[Vec3], target_spacing: f32) -> Vec<Vec3> {
     if points.len() < 3 {
         return points.to_vec();
     }
@@ -411,6 +593,7 @@ pub fn polygon_tool_system(
     mut polygon_tool: ResMut<PolygonTool>,
     mut polygon_counter: ResMut<PolygonCounter>,
     mut classification_data: ResMut<PolygonClassificationData>,
+    mut history: ResMut<EditHistory>,
     mouse_button: Res<ButtonInput<MouseButton>>,
     keyboard: Res<ButtonInput<KeyCode>>,
     camera_query: Query<(&GlobalTransform, &Camera), With<Camera3d>>,
@@ -482,7 +665,10 @@ pub fn polygon_tool_system(
             polygon_tool.current_polygon.clear();
             polygon_tool.preview_point = None;
             polygon_tool.is_completed = false;
-            classification_data.polygons.clear();
+            let cleared = std::mem::take(&mut classification_data.polygons);
+            if !cleared.is_empty() {
+                history.record(EditCommand::ClearPolygons(cleared));
+            }
             println!("All polygons cleared.");
 
             rpc_interface.send_notification(
@@ -494,6 +680,11 @@ pub fn polygon_tool_system(
         }
     }
 
+    // Vertex dragging is handled by the polygon edit system while edit mode is on.
+    if polygon_tool.edit_mode {
+        return;
+    }
+
     // Update preview point for real-time cursor tracking.
     if !polygon_tool.is_completed {
         if let (Ok((camera_global_transform, camera)), Ok(window)) =
@@ -555,15 +746,32 @@ pub fn polygon_tool_system(
                 // Create classification data structure for compute shader processing.
                 let class_polygon = ClassificationPolygon {
                     id: polygon_id,
+                    vertices: polygon_tool.current_polygon.clone(),
                     points: resampled_points.clone(),
                     new_class: polygon_tool.current_class,
                     mode: PolygonMode::Reclassify,
                     masks: Vec::new(), // empty masks if we're running natively
+                    vertical_range: None,
+                    selection: Vec::new(),
                 };
 
                 // Add to classification data with GPU memory constraint validation.
-                if classification_data.polygons.len() < classification_data.max_polygons {
-                    classification_data.polygons.push(class_polygon);
+                let capacity =
+                    classification_data.check_limits(std::slice::from_ref(&class_polygon), None);
+                if let Err(error) = capacity {
+                    println!("Warning: {}", error);
+
+                    // Notify frontend of error
+                    rpc_interface.send_notification(
+                        "polygon_error",
+                        serde_json::json!({
+                            "error": error,
+                            "limits": classification_data.limits_summary()
+                        }),
+                    );
+                } else {
+                    classification_data.polygons.push(class_polygon.clone());
+                    history.record(EditCommand::AddPolygon(class_polygon));
                     println!(
                         "Added classification polygon {} with class {}",
                         polygon_id, polygon_tool.current_class
@@ -574,6 +782,7 @@ pub fn polygon_tool_system(
                         &mut commands,
                         &resampled_points,
                         polygon_id,
+                        None,
                         viewport_camera.ground_height,
                         &mut meshes,
                         &mut materials,
@@ -595,20 +804,6 @@ pub fn polygon_tool_system(
                             "total_polygons": classification_data.polygons.len()
                         }),
                     );
-                } else {
-                    println!(
-                        "Warning: Maximum polygon limit reached ({})",
-                        classification_data.max_polygons
-                    );
-
-                    // Notify frontend of error
-                    rpc_interface.send_notification(
-                        "polygon_error",
-                        serde_json::json!({
-                            "error": "Maximum polygon limit reached",
-                            "max_polygons": classification_data.max_polygons
-                        }),
-                    );
                 }
 
                 // Reset state for next polygon creation.
@@ -619,55 +814,14 @@ pub fn polygon_tool_system(
         }
     }
 }
-/// Updates polygon classification data for compute shader consumption.
-/// Removed material system dependencies in favor of resource extraction.
-pub fn update_polygon_classification_shader(
-    classification_data: Res<PolygonClassificationData>,
-    render_state: Res<RenderModeState>,
-) {
-    // Early exit if no polygon data changes detected.
-    if !classification_data.is_changed() && !render_state.is_changed() {
-        return;
-    }
-
-    // Create uniform data structure for GPU compute shader compatibility.
-    // Data will be extracted to render world through resource system.
-    let mut uniform = PolygonClassificationUniform::default();
-    uniform.polygon_count = classification_data.polygons.len().min(64) as u32;
-    uniform.render_mode = render_state.current_mode as u32;
-
-    let mut point_offset = 0;
-    for (i, polygon) in classification_data.polygons.iter().take(64).enumerate() {
-        // Store polygon metadata: point offset, count, and classification ID.
-        uniform.polygon_info[i] = Vec4::new(
-            point_offset as f32,
-            polygon.points.len() as f32,
-            polygon.new_class as f32,
-            0.0,
-        );
-
-        // Flatten polygon vertices into linear array for GPU processing.
-        for point in &polygon.points {
-            if point_offset < 512 {
-                uniform.point_data[point_offset] = Vec4::new(point.x, point.z, 0.0, 0.0);
-                point_offset += 1;
-            }
-        }
-    }
-
-    uniform.total_points = point_offset as u32;
-
-    // Note: Uniform data stored in classification_data resource.
-    // Compute classification system extracts this through resource system
-    // rather than through deprecated material uniform updates.
-}
-
 /// Creates persistent visualization entities for completed polygons.
 /// Uses standard material pipeline for UI elements separate from point cloud.
-fn create_completed_polygon(
+/// `vertical_range` is in render space, see `PolygonClassificationData::render_range`.
+pub(crate) fn create_completed_polygon(
     commands: &mut Commands,
     points: &[Vec3],
     polygon_id: u32,
+    vertical_range: Option<&VerticalRange>,
     ground_height: f32,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
@@ -724,6 +878,23 @@ fn create_completed_polygon(
             }
         }
 
+        // Translucent walls for height-bounded polygons.
+        if let Some(range) = vertical_range {
+            commands.spawn((
+                Mesh3d(meshes.add(create_prism_mesh(points, range))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: Color::hsva(0., 1., 1., 0.2),
+                    alpha_mode: AlphaMode::Blend,
+                    cull_mode: None,
+                    double_sided: true,
+                    unlit: true,
+                    ..default()
+                })),
+                CompletedPolygon { id: polygon_id },
+                RenderLayers::layer(1),
+            ));
+        }
+
         // Create polygon fill mesh for visual feedback (optional).
         let _fill_mesh = create_polygon_mesh(points, ground_height);
 
@@ -929,3 +1100,41 @@ fn create_polygon_mesh(points: &[Vec3], ground_height: f32) -> Mesh {
 
     mesh
 }
+
+/// Side walls of a height-bounded polygon, one quad per outline edge.
+/// Absolute ranges must already be in render space. Ground-relative ranges are offset from each outline point's own height.
+fn create_prism_mesh(points: &[Vec3], range: &VerticalRange) -> Mesh {
+    let band = |point: Vec3| match range.reference {
+        HeightReference::Absolute => (range.min, range.max),
+        HeightReference::Ground => (point.y + range.min, point.y + range.max),
+    };
+
+    let mut positions = Vec::with_capacity(points.len() * 4);
+    let mut normals = Vec::with_capacity(points.len() * 4);
+    let mut indices = Vec::with_capacity(points.len() * 6);
+    for (i, &start) in points.iter().enumerate() {
+        let end = points[(i + 1) % points.len()];
+        let (start_bottom, start_top) = band(start);
+        let (end_bottom, end_top) = band(end);
+        let normal = Vec3::new(end.z - start.z, 0.0, start.x - end.x).normalize_or_zero();
+
+        let base = positions.len() as u32;
+        positions.extend_from_slice(&[
+            [start.x, start_bottom, start.z],
+            [end.x, end_bottom, end.z],
+            [end.x, end_top, end.z],
+            [start.x, start_top, start.z],
+        ]);
+        normals.extend_from_slice(&[normal.to_array(); 4]);
+        indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_indices(bevy::render::mesh::Indices::U32(indices));
+    mesh
+}
@@ -0,0 +1,537 @@
+use crate::engine::assets::point_cloud_assets::PointCloudAssets;
+use crate::engine::assets::scene_manifest::SceneManifest;
+use crate::engine::camera::viewport_camera::ViewportCamera;
+use crate::engine::core::app_state::AppState;
+use crate::rpc::web_rpc::WebRpcInterface;
+use crate::tools::history::{EditCommand, EditHistory};
+use crate::tools::polygon::{
+    ClassificationPolygon, CompletedPolygon, PolygonClassificationData, PolygonMode, PolygonTool,
+    VerticalRange, create_completed_polygon, resample_polygon_uniform,
+};
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use bevy::window::PrimaryWindow;
+use constants::render_settings::{DRAW_LINE_WIDTH, MOUSE_RAYCAST_INTERSECTION_SPHERE_SIZE};
+
+/// Vertex pick radius as a fraction of camera height, so picking stays usable when zoomed out.
+const VERTEX_PICK_HEIGHT_FRACTION: f32 = 0.015;
+/// Lower bound on the pick radius in metres when zoomed right in.
+const MIN_VERTEX_PICK_RADIUS: f32 = 0.5;
+
+/// Polygon management requests from RPC or keyboard.
+#[derive(Event, Debug, Clone)]
+pub struct PolygonEditEvent {
+    pub action: PolygonEditAction,
+}
+
+#[derive(Debug, Clone)]
+pub enum PolygonEditAction {
+    Delete {
+        id: u32,
+    },
+    /// Fields left as `None` keep their current value.
+    Update {
+        id: u32,
+        mode: Option<PolygonMode>,
+        new_class: Option<u32>,
+        masks: Option<Vec<(u32, u32)>>,
+        vertices: Option<Vec<Vec3>>,
+        /// `Some(None)` removes the range.
+        vertical_range: Option<Option<VerticalRange>>,
+    },
+    /// Move a polygon to `index` in the evaluation order; later polygons override earlier ones.
+    Reorder {
+        id: u32,
+        index: usize,
+    },
+    SetEditMode(bool),
+}
+
+/// In-progress vertex drag; the polygon data is only updated on release.
+#[derive(Debug, Clone)]
+pub struct VertexDrag {
+    pub polygon_id: u32,
+    pub vertex_index: usize,
+    pub before: ClassificationPolygon,
+    pub vertices: Vec<Vec3>,
+}
+
+#[derive(Resource, Default)]
+pub struct PolygonVertexDrag {
+    pub active: Option<VertexDrag>,
+}
+
+/// Vertex handles and drag preview shown while edit mode is on.
+#[derive(Component)]
+pub struct PolygonEditHandle;
+
+/// Mesh and materials shared by every edit handle, created once so redraws add no assets.
+#[derive(Resource)]
+pub struct PolygonEditHandleAssets {
+    vertex_mesh: Handle<Mesh>,
+    edge_mesh: Handle<Mesh>, // Unit length along X, scaled to each edge.
+    handle_material: Handle<StandardMaterial>,
+    drag_material: Handle<StandardMaterial>,
+}
+
+pub struct PolygonEditPlugin;
+impl Plugin for PolygonEditPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PolygonVertexDrag>()
+            .add_event::<PolygonEditEvent>()
+            .add_systems(Startup, setup_polygon_edit_handle_assets)
+            .add_systems(
+                Update,
+                (
+                    polygon_edit_keyboard_shortcuts,
+                    process_polygon_edit_requests,
+                    polygon_vertex_edit_system,
+                    update_polygon_edit_handles,
+                    notify_polygon_changes,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Running)),
+            );
+    }
+}
+
+/// Native Tab toggles vertex editing while the polygon tool is active.
+pub fn polygon_edit_keyboard_shortcuts(
+    #[cfg(not(target_arch = "wasm32"))] keyboard: Res<ButtonInput<KeyCode>>,
+    #[cfg(not(target_arch = "wasm32"))] polygon_tool: Res<PolygonTool>,
+    #[cfg(not(target_arch = "wasm32"))] mut events: EventWriter<PolygonEditEvent>,
+) {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if polygon_tool.is_active && keyboard.just_pressed(KeyCode::Tab) {
+            events.write(PolygonEditEvent {
+                action: PolygonEditAction::SetEditMode(!polygon_tool.edit_mode),
+            });
+        }
+    }
+}
+
+/// Applies delete, update, reorder and edit mode requests, recording each change in history.
+pub fn process_polygon_edit_requests(
+    mut events: EventReader<PolygonEditEvent>,
+    mut polygon_tool: ResMut<PolygonTool>,
+    mut drag: ResMut<PolygonVertexDrag>,
+    mut classification_data: ResMut<PolygonClassificationData>,
+    mut history: ResMut<EditHistory>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    completed_polygons: Query<(Entity, &CompletedPolygon)>,
+    viewport_camera: Res<ViewportCamera>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    for event in events.read() {
+        let result = match &event.action {
+            PolygonEditAction::Delete { id } => match polygon_index(&classification_data, *id) {
+                Some(index) => {
+                    let polygon = classification_data.polygons.remove(index);
+                    despawn_polygon_meshes(polygon.id, &completed_polygons, &mut commands);
+                    history.record(EditCommand::DeletePolygon { polygon, index });
+                    Ok(())
+                }
+                None => Err(format!("Polygon {} not found", id)),
+            },
+            PolygonEditAction::Update {
+                id,
+                mode,
+                new_class,
+                masks,
+                vertices,
+                vertical_range,
+            } => match polygon_index(&classification_data, *id) {
+                Some(_) if vertices.as_ref().is_some_and(|v| v.len() < 3) => {
+                    Err("A polygon needs at least 3 vertices".to_string())
+                }
+                Some(index) => {
+                    let before = classification_data.polygons[index].clone();
+                    let mut after = before.clone();
+                    if let Some(mode) = mode {
+                        after.mode = mode.clone();
+                    }
+                    if let Some(new_class) = new_class {
+                        after.new_class = *new_class;
+                    }
+                    if let Some(masks) = masks {
+                        after.masks = masks.clone();
+                    }
+                    if let Some(vertices) = vertices {
+                        after.points =
+                            resample_polygon_uniform(vertices, polygon_tool.target_point_spacing);
+                        after.vertices = vertices.clone();
+                    }
+                    if let Some(vertical_range) = vertical_range {
+                        after.vertical_range = *vertical_range;
+                    }
+
+                    // Resampled vertices or added masks can push the totals over the limits.
+                    classification_data
+                        .check_limits(std::slice::from_ref(&after), Some(after.id))
+                        .map(|()| {
+                            classification_data.polygons[index] = after.clone();
+                            rebuild_polygon_meshes(
+                                &after,
+                                &completed_polygons,
+                                &mut commands,
+                                &mut meshes,
+                                &mut materials,
+                                viewport_camera.ground_height,
+                                classification_data.height_origin,
+                            );
+                            history.record(EditCommand::UpdatePolygon { before, after });
+                        })
+                }
+                None => Err(format!("Polygon {} not found", id)),
+            },
+            PolygonEditAction::Reorder { id, index } => {
+                match move_polygon(&mut classification_data, *id, *index) {
+                    Some(from) => {
+                        let to = polygon_index(&classification_data, *id).unwrap_or(from);
+                        if from != to {
+                            history.record(EditCommand::ReorderPolygon { id: *id, from, to });
+                        }
+                        Ok(())
+                    }
+                    None => Err(format!("Polygon {} not found", id)),
+                }
+            }
+            PolygonEditAction::SetEditMode(enabled) => {
+                if !polygon_tool.is_active {
+                    Err("Polygon tool is not active".to_string())
+                } else {
+                    polygon_tool.edit_mode = *enabled;
+                    polygon_tool.preview_point = None;
+                    drag.active = None;
+                    Ok(())
+                }
+            }
+        };
+
+        if let Err(e) = result {
+            warn!("Polygon edit failed: {}", e);
+            rpc_interface.send_notification("polygon_error", serde_json::json!({ "error": e }));
+        }
+    }
+}
+
+/// Picks, drags and commits completed polygon vertices while edit mode is on.
+pub fn polygon_vertex_edit_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    polygon_tool: Res<PolygonTool>,
+    mut drag: ResMut<PolygonVertexDrag>,
+    mut classification_data: ResMut<PolygonClassificationData>,
+    mut history: ResMut<EditHistory>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&GlobalTransform, &Camera), With<Camera3d>>,
+    mut viewport_camera: ResMut<ViewportCamera>,
+    images: Res<Assets<Image>>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+    completed_polygons: Query<(Entity, &CompletedPolygon)>,
+) {
+    if !polygon_tool.is_active || !polygon_tool.edit_mode {
+        // Only touch the drag when there is one, so handles are not redrawn every frame.
+        if drag.active.is_some() {
+            drag.active = None;
+        }
+        return;
+    }
+
+    let Some(bounds) = assets.get_bounds(&manifests) else {
+        return;
+    };
+
+    let cursor_point = match (camera_query.single(), windows.single()) {
+        (Ok((camera_transform, camera)), Ok(window)) => {
+            window.cursor_position().and_then(|cursor_pos| {
+                viewport_camera.mouse_to_ground_plane(
+                    cursor_pos,
+                    camera,
+                    camera_transform,
+                    images.get(&assets.heightmap_texture),
+                    &bounds,
+                )
+            })
+        }
+        _ => None,
+    };
+
+    // Start a drag on the nearest vertex; later polygons win ties since they draw on top.
+    if let Some(point) = cursor_point.filter(|_| mouse_button.just_pressed(MouseButton::Left)) {
+        let radius =
+            (viewport_camera.height * VERTEX_PICK_HEIGHT_FRACTION).max(MIN_VERTEX_PICK_RADIUS);
+        let mut nearest: Option<(f32, &ClassificationPolygon, usize)> = None;
+        for polygon in classification_data.polygons.iter().rev() {
+            for (i, vertex) in polygon.vertices.iter().enumerate() {
+                let distance = vertex.xz().distance(point.xz());
+                if distance <= radius && nearest.is_none_or(|(d, _, _)| distance < d) {
+                    nearest = Some((distance, polygon, i));
+                }
+            }
+        }
+
+        drag.active = nearest.map(|(_, polygon, vertex_index)| VertexDrag {
+            polygon_id: polygon.id,
+            vertex_index,
+            before: polygon.clone(),
+            vertices: polygon.vertices.clone(),
+        });
+    }
+
+    let held_point = cursor_point.filter(|_| mouse_button.pressed(MouseButton::Left));
+    if let Some(point) = held_point
+        && let Some(active) = drag.active.as_mut()
+    {
+        active.vertices[active.vertex_index] = point;
+    }
+
+    if !mouse_button.just_released(MouseButton::Left) || drag.active.is_none() {
+        return;
+    }
+    let Some(finished) = drag.active.take() else {
+        return;
+    };
+    if finished.vertices == finished.before.vertices {
+        return;
+    }
+    // The polygon may have been deleted through RPC mid-drag.
+    let Some(index) = polygon_index(&classification_data, finished.polygon_id) else {
+        return;
+    };
+
+    let mut after = classification_data.polygons[index].clone();
+    after.points = resample_polygon_uniform(&finished.vertices, polygon_tool.target_point_spacing);
+    after.vertices = finished.vertices;
+    if let Err(e) = classification_data.check_limits(std::slice::from_ref(&after), Some(after.id)) {
+        warn!("Vertex edit discarded: {}", e);
+        return;
+    }
+    classification_data.polygons[index] = after.clone();
+    rebuild_polygon_meshes(
+        &after,
+        &completed_polygons,
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        viewport_camera.ground_height,
+        classification_data.height_origin,
+    );
+    history.record(EditCommand::UpdatePolygon {
+        before: finished.before,
+        after,
+    });
+}
+
+fn setup_polygon_edit_handle_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(PolygonEditHandleAssets {
+        vertex_mesh: meshes.add(Sphere::new(MOUSE_RAYCAST_INTERSECTION_SPHERE_SIZE)),
+        edge_mesh: meshes.add(Cuboid::new(1.0, DRAW_LINE_WIDTH, DRAW_LINE_WIDTH)),
+        handle_material: materials.add(StandardMaterial {
+            base_color: Color::hsv(50., 1., 1.),
+            emissive: LinearRgba::new(1., 1., 1., 1.),
+            depth_bias: 0.0,
+            unlit: true,
+            ..default()
+        }),
+        drag_material: materials.add(StandardMaterial {
+            base_color: Color::hsv(0., 0., 1.),
+            emissive: LinearRgba::new(1., 1., 1., 1.),
+            depth_bias: 0.0,
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+/// Draws a handle on every polygon vertex plus the outline of the polygon being dragged.
+/// Handles are only respawned when the polygons, the tool state or the drag change.
+pub fn update_polygon_edit_handles(
+    mut commands: Commands,
+    handle_assets: Res<PolygonEditHandleAssets>,
+    polygon_tool: Res<PolygonTool>,
+    drag: Res<PolygonVertexDrag>,
+    classification_data: Res<PolygonClassificationData>,
+    existing_handles: Query<Entity, With<PolygonEditHandle>>,
+) {
+    if !polygon_tool.is_changed() && !drag.is_changed() && !classification_data.is_changed() {
+        return;
+    }
+
+    for entity in existing_handles.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    if !polygon_tool.is_active || !polygon_tool.edit_mode {
+        return;
+    }
+
+    for polygon in &classification_data.polygons {
+        let dragged = drag.active.as_ref().filter(|d| d.polygon_id == polygon.id);
+        let vertices = dragged.map_or(&polygon.vertices, |d| &d.vertices);
+        let material = if dragged.is_some() {
+            &handle_assets.drag_material
+        } else {
+            &handle_assets.handle_material
+        };
+
+        for vertex in vertices {
+            commands.spawn((
+                Mesh3d(handle_assets.vertex_mesh.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_translation(*vertex),
+                PolygonEditHandle,
+                RenderLayers::layer(1),
+            ));
+        }
+
+        if dragged.is_none() {
+            continue;
+        }
+
+        // Live outline for the dragged polygon; its completed meshes update on release.
+        for i in 0..vertices.len() {
+            let start = vertices[i];
+            let end = vertices[(i + 1) % vertices.len()];
+            let direction = end - start;
+            let distance = direction.length();
+
+            if distance > 0.1 {
+                let rotation = Quat::from_rotation_arc(Vec3::X, direction.normalize());
+                commands.spawn((
+                    Mesh3d(handle_assets.edge_mesh.clone()),
+                    MeshMaterial3d(handle_assets.drag_material.clone()),
+                    Transform::from_translation((start + end) * 0.5)
+                        .with_rotation(rotation)
+                        .with_scale(Vec3::new(distance, 1.0, 1.0)),
+                    PolygonEditHandle,
+                    RenderLayers::layer(1),
+                ));
+            }
+        }
+    }
+}
+
+/// Sends the polygon list whenever classification data changes, and edit mode transitions.
+pub fn notify_polygon_changes(
+    classification_data: Res<PolygonClassificationData>,
+    polygon_tool: Res<PolygonTool>,
+    mut last_edit_mode: Local<bool>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    if classification_data.is_changed() {
+        rpc_interface.send_notification(
+            "polygons_changed",
+            serde_json::json!({
+                "polygons": polygon_summaries(&classification_data),
+                "limits": classification_data.limits_summary(),
+            }),
+        );
+    }
+
+    if polygon_tool.edit_mode != *last_edit_mode {
+        *last_edit_mode = polygon_tool.edit_mode;
+        rpc_interface.send_notification(
+            "polygon_edit_mode_changed",
+            serde_json::json!({ "enabled": polygon_tool.edit_mode }),
+        );
+    }
+}
+
+/// Polygon list in evaluation order with render-space vertices, for `list_polygons`.
+pub fn polygon_summaries(classification_data: &PolygonClassificationData) -> serde_json::Value {
+    classification_data
+        .polygons
+        .iter()
+        .enumerate()
+        .map(|(index, p)| {
+            serde_json::json!({
+                "id": p.id,
+                "index": index,
+                "mode": p.mode.as_str(),
+                "target_class_id": p.new_class,
+                "masked_classes": p
+                    .masks
+                    .iter()
+                    .map(|(class_id, object_id)| {
+                        serde_json::json!({ "class_id": class_id, "object_id": object_id })
+                    })
+                    .collect::<Vec<_>>(),
+                "vertices": p
+                    .vertices
+                    .iter()
+                    .map(|v| [v.x, v.y, v.z])
+                    .collect::<Vec<_>>(),
+                "vertical_range": p.vertical_range,
+                "point_count": p.points.len(),
+            })
+        })
+        .collect()
+}
+
+pub(crate) fn polygon_index(
+    classification_data: &PolygonClassificationData,
+    id: u32,
+) -> Option<usize> {
+    classification_data.polygons.iter().position(|p| p.id == id)
+}
+
+/// Move polygon `id` to `index` (clamped to the list), returning its previous index.
+pub(crate) fn move_polygon(
+    classification_data: &mut PolygonClassificationData,
+    id: u32,
+    index: usize,
+) -> Option<usize> {
+    let from = polygon_index(classification_data, id)?;
+    let polygon = classification_data.polygons.remove(from);
+    let to = index.min(classification_data.polygons.len());
+    classification_data.polygons.insert(to, polygon);
+    Some(from)
+}
+
+pub(crate) fn despawn_polygon_meshes(
+    id: u32,
+    completed_polygons: &Query<(Entity, &CompletedPolygon)>,
+    commands: &mut Commands,
+) {
+    for (entity, completed) in completed_polygons {
+        if completed.id == id {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Replace a polygon's outline meshes with ones built from its current points.
+pub(crate) fn rebuild_polygon_meshes(
+    polygon: &ClassificationPolygon,
+    completed_polygons: &Query<(Entity, &CompletedPolygon)>,
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+    ground_height: f32,
+    height_origin: f64,
+) {
+    despawn_polygon_meshes(polygon.id, completed_polygons, commands);
+    let vertical_range = polygon
+        .vertical_range
+        .map(|range| range.offset(-height_origin));
+    create_completed_polygon(
+        commands,
+        &polygon.points,
+        polygon.id,
+        vertical_range.as_ref(),
+        ground_height,
+        meshes,
+        materials,
+    );
+}
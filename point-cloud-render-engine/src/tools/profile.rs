@@ -0,0 +1,677 @@
+use crate::engine::assets::bounds::PointCloudBounds;
+use crate::engine::assets::point_cloud_assets::{PointCloudAssets, read_texel};
+use crate::engine::assets::scene_manifest::SceneManifest;
+use crate::engine::camera::viewport_camera::ViewportCamera;
+use bevy::prelude::*;
+use bevy::render::view::RenderLayers;
+use bevy::window::PrimaryWindow;
+use constants::class::{ROAD_CLASSIFICATIONS, get_class_name};
+use constants::render_settings::{
+    DRAW_LINE_WIDTH, DRAW_VERTEX_SIZE, MOUSE_RAYCAST_INTERSECTION_SPHERE_SIZE,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// Default corridor half-width either side of the profile line, in metres.
+const DEFAULT_HALF_WIDTH: f32 = 1.0;
+
+/// Upper bound on samples pushed to the frontend chart; exports keep every point.
+const MAX_CHART_SAMPLES: usize = 4000;
+
+/// Upper bound on circles written to an SVG export to keep files openable.
+const MAX_SVG_SAMPLES: usize = 20000;
+
+const SVG_WIDTH: f32 = 1200.0;
+const SVG_HEIGHT: f32 = 400.0;
+const SVG_MARGIN: f32 = 60.0;
+
+/// One corridor point projected onto the profile line.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ProfileSample {
+    /// Distance along the polyline from its first vertex, in metres.
+    pub chainage: f32,
+    /// Absolute elevation in the source height datum.
+    pub elevation: f32,
+    /// Signed perpendicular distance from the line, positive to the right of the drawing direction.
+    pub offset: f32,
+    pub class_id: u8,
+}
+
+/// Completed profile with the settings it was extracted with.
+#[derive(Debug, Clone)]
+pub struct ProfileResult {
+    pub id: u32,
+    pub polyline: Vec<Vec3>,
+    pub half_width: f32,
+    pub ground_only: bool,
+    pub length: f32,
+    pub samples: Vec<ProfileSample>,
+}
+
+/// Export formats offered for a completed profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileExportFormat {
+    Csv,
+    Svg,
+}
+
+impl ProfileExportFormat {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "svg" => Some(Self::Svg),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct ProfileTool {
+    pub is_active: bool,
+    pub polyline: Vec<Vec3>,
+    pub preview_point: Option<Vec3>,
+    pub half_width: f32,
+    pub ground_only: bool,
+    /// Polyline is finished and no longer follows the cursor.
+    pub is_completed: bool,
+    /// Set by completion or corridor changes; the tool system runs the extraction.
+    pub extract_requested: bool,
+    pub next_id: u32,
+    pub result: Option<ProfileResult>,
+}
+
+impl Default for ProfileTool {
+    fn default() -> Self {
+        Self {
+            is_active: false,
+            polyline: Vec::new(),
+            preview_point: None,
+            half_width: DEFAULT_HALF_WIDTH,
+            ground_only: false,
+            is_completed: false,
+            extract_requested: false,
+            next_id: 0,
+            result: None,
+        }
+    }
+}
+
+impl ProfileTool {
+    pub fn set_active(&mut self, active: bool) {
+        self.is_active = active;
+        if !active {
+            self.clear();
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    /// Drop the polyline and any extracted profile.
+    pub fn clear(&mut self) {
+        self.polyline.clear();
+        self.preview_point = None;
+        self.is_completed = false;
+        self.extract_requested = false;
+        self.result = None;
+    }
+
+    /// Finish the polyline and queue extraction. Returns false with fewer than two vertices.
+    pub fn request_completion(&mut self) -> bool {
+        if self.polyline.len() < 2 {
+            return false;
+        }
+        self.is_completed = true;
+        self.extract_requested = true;
+        true
+    }
+
+    /// Update corridor settings, re-extracting a finished profile with the new values.
+    pub fn set_corridor(&mut self, half_width: f32, ground_only: bool) {
+        self.half_width = half_width;
+        self.ground_only = ground_only;
+        if self.is_completed {
+            self.extract_requested = true;
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct ProfilePreview;
+
+#[derive(Component)]
+pub struct CompletedProfileTag;
+
+// Input/logic: click to add vertices, Enter or RPC to finish, click again to start over
+pub fn profile_tool_system(
+    mut profile_tool: ResMut<ProfileTool>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    #[cfg(not(target_arch = "wasm32"))] keyboard: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&GlobalTransform, &Camera), With<Camera3d>>,
+    viewport_camera: Option<ResMut<ViewportCamera>>,
+    images: Res<Assets<Image>>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+    mut rpc_interface: ResMut<crate::rpc::web_rpc::WebRpcInterface>,
+) {
+    if !profile_tool.is_active() {
+        return;
+    }
+
+    let Some(bounds) = assets.get_bounds(&manifests) else {
+        return;
+    };
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if keyboard.just_pressed(KeyCode::Enter) && !profile_tool.request_completion() {
+            println!("Profile needs at least 2 vertices");
+        }
+        if keyboard.just_pressed(KeyCode::Backspace) {
+            profile_tool.clear();
+            rpc_interface.send_notification("profile_cleared", serde_json::json!({}));
+        }
+    }
+
+    if profile_tool.extract_requested {
+        profile_tool.extract_requested = false;
+        run_extraction(
+            &mut profile_tool,
+            &images,
+            &assets,
+            &bounds,
+            &mut rpc_interface,
+        );
+    }
+
+    let Some(mut viewport_camera) = viewport_camera else {
+        return;
+    };
+
+    // Update cursor projection each frame for live preview
+    if let (Ok((cam_tf, cam)), Ok(window)) = (cameras.single(), windows.single()) {
+        profile_tool.preview_point = window.cursor_position().and_then(|cursor| {
+            viewport_camera.mouse_to_ground_plane(
+                cursor,
+                cam,
+                cam_tf,
+                images.get(&assets.heightmap_texture),
+                &bounds,
+            )
+        });
+    }
+
+    if !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(point) = profile_tool.preview_point else {
+        return;
+    };
+
+    // Clicking after completion starts a new profile
+    if profile_tool.is_completed {
+        profile_tool.clear();
+        profile_tool.preview_point = Some(point);
+        rpc_interface.send_notification("profile_cleared", serde_json::json!({}));
+    }
+
+    profile_tool.polyline.push(point);
+    rpc_interface.send_notification(
+        "profile_vertex_added",
+        serde_json::json!({
+            "vertex_count": profile_tool.polyline.len(),
+            "position": [point.x, point.y, point.z],
+            "length": polyline_length(&profile_tool.polyline),
+        }),
+    );
+}
+
+/// Extract the corridor from the CPU copies of the point textures and notify the frontend.
+fn run_extraction(
+    profile_tool: &mut ProfileTool,
+    images: &Assets<Image>,
+    assets: &PointCloudAssets,
+    bounds: &PointCloudBounds,
+    rpc_interface: &mut crate::rpc::web_rpc::WebRpcInterface,
+) {
+    let position_data = images
+        .get(&assets.position_texture)
+        .and_then(|image| image.data.as_deref());
+    let colour_data = images
+        .get(&assets.colour_class_texture)
+        .and_then(|image| image.data.as_deref());
+    let (Some(position_data), Some(colour_data)) = (position_data, colour_data) else {
+        warn!("Profile extraction skipped: point textures have no CPU data");
+        rpc_interface.send_notification(
+            "profile_error",
+            serde_json::json!({ "error": "Point data not available" }),
+        );
+        return;
+    };
+
+    let samples = extract_profile(
+        &profile_tool.polyline,
+        profile_tool.half_width,
+        profile_tool.ground_only,
+        position_data,
+        colour_data,
+        bounds,
+    );
+
+    // Re-extraction keeps the id so the frontend replaces rather than appends
+    let id = match &profile_tool.result {
+        Some(previous) => previous.id,
+        None => {
+            profile_tool.next_id += 1;
+            profile_tool.next_id
+        }
+    };
+
+    let result = ProfileResult {
+        id,
+        polyline: profile_tool.polyline.clone(),
+        half_width: profile_tool.half_width,
+        ground_only: profile_tool.ground_only,
+        length: polyline_length(&profile_tool.polyline),
+        samples,
+    };
+
+    println!(
+        "Profile {} extracted: {:.2} m long, {} points within {:.2} m",
+        result.id,
+        result.length,
+        result.samples.len(),
+        result.half_width
+    );
+
+    rpc_interface.send_notification("profile_completed", profile_payload(&result));
+    profile_tool.result = Some(result);
+}
+
+/// Project every point inside the corridor onto the polyline's chainage axis.
+/// Points beyond either end of the line are excluded; samples are sorted by chainage.
+pub fn extract_profile(
+    polyline: &[Vec3],
+    half_width: f32,
+    ground_only: bool,
+    position_data: &[u8],
+    colour_data: &[u8],
+    bounds: &PointCloudBounds,
+) -> Vec<ProfileSample> {
+    if polyline.len() < 2 {
+        return Vec::new();
+    }
+
+    // Segments in plan view with their starting chainage
+    let mut segments = Vec::with_capacity(polyline.len() - 1);
+    let mut chainage = 0.0;
+    for pair in polyline.windows(2) {
+        let start = pair[0].xz();
+        let direction = pair[1].xz() - start;
+        let length = direction.length();
+        if length > f32::EPSILON {
+            segments.push((start, direction, length, chainage));
+            chainage += length;
+        }
+    }
+    let Some(last_segment) = segments.len().checked_sub(1) else {
+        return Vec::new();
+    };
+
+    // Cheap rejection before testing segments
+    let (corridor_min, corridor_max) = polyline.iter().fold(
+        (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+        |(min, max), vertex| (min.min(vertex.xz()), max.max(vertex.xz())),
+    );
+    let corridor_min = corridor_min - Vec2::splat(half_width);
+    let corridor_max = corridor_max + Vec2::splat(half_width);
+
+    let min = Vec3::new(
+        bounds.min_x() as f32,
+        bounds.min_y() as f32,
+        bounds.min_z() as f32,
+    );
+    let extent = bounds.size();
+    let datum = bounds.bounds.origin.1 as f32;
+
+    let mut samples = Vec::new();
+    for index in 0..bounds.loaded_points {
+        let Some(colour) = read_texel(colour_data, index) else {
+            break;
+        };
+        let Some(position) = read_texel(position_data, index) else {
+            break;
+        };
+
+        let class_id = (colour[3] * 255.0).round() as u8;
+        if ground_only && !ROAD_CLASSIFICATIONS.contains(&class_id) {
+            continue;
+        }
+
+        let world = min + Vec3::new(position[0], position[1], position[2]) * extent;
+        let plan = world.xz();
+        if plan.cmplt(corridor_min).any() || plan.cmpgt(corridor_max).any() {
+            continue;
+        }
+
+        let mut best: Option<(f32, f32, f32)> = None;
+        for (segment_index, &(start, direction, length, start_chainage)) in
+            segments.iter().enumerate()
+        {
+            let relative = plan - start;
+            let t = relative.dot(direction) / (length * length);
+            if (segment_index == 0 && t < 0.0) || (segment_index == last_segment && t > 1.0) {
+                continue;
+            }
+            let t = t.clamp(0.0, 1.0);
+            let distance = (relative - direction * t).length();
+            if distance > half_width || best.is_some_and(|(d, _, _)| d <= distance) {
+                continue;
+            }
+            let side = direction.x * relative.y - direction.y * relative.x;
+            best = Some((
+                distance,
+                start_chainage + t * length,
+                distance.copysign(side),
+            ));
+        }
+
+        if let Some((_, chainage, offset)) = best {
+            samples.push(ProfileSample {
+                chainage,
+                elevation: world.y + datum,
+                offset,
+                class_id,
+            });
+        }
+    }
+
+    samples.sort_by(|a, b| a.chainage.total_cmp(&b.chainage));
+    samples
+}
+
+fn polyline_length(polyline: &[Vec3]) -> f32 {
+    polyline
+        .windows(2)
+        .map(|pair| pair[0].xz().distance(pair[1].xz()))
+        .sum()
+}
+
+/// Evenly thin samples so at most `max` remain, always keeping the first.
+fn thinned(samples: &[ProfileSample], max: usize) -> impl Iterator<Item = &ProfileSample> {
+    let stride = samples.len().div_ceil(max).max(1);
+    samples.iter().step_by(stride)
+}
+
+fn elevation_range(samples: &[ProfileSample]) -> Option<(f32, f32)> {
+    samples.iter().fold(None, |range, sample| {
+        let (low, high) = range.unwrap_or((sample.elevation, sample.elevation));
+        Some((low.min(sample.elevation), high.max(sample.elevation)))
+    })
+}
+
+/// Chart payload for the `profile_completed` notification.
+fn profile_payload(result: &ProfileResult) -> serde_json::Value {
+    let samples: Vec<[f32; 4]> = thinned(&result.samples, MAX_CHART_SAMPLES)
+        .map(|s| [s.chainage, s.elevation, s.offset, s.class_id as f32])
+        .collect();
+    let vertices: Vec<[f32; 3]> = result.polyline.iter().map(|v| v.to_array()).collect();
+
+    serde_json::json!({
+        "id": result.id,
+        "length": result.length,
+        "half_width": result.half_width,
+        "ground_only": result.ground_only,
+        "point_count": result.samples.len(),
+        "elevation_range": elevation_range(&result.samples).map(|(low, high)| [low, high]),
+        "vertices": vertices,
+        "samples": samples,
+    })
+}
+
+/// Serialise a profile in the requested export format.
+pub fn export_profile(result: &ProfileResult, format: ProfileExportFormat) -> String {
+    match format {
+        ProfileExportFormat::Csv => profile_to_csv(result),
+        ProfileExportFormat::Svg => profile_to_svg(result),
+    }
+}
+
+fn profile_to_csv(result: &ProfileResult) -> String {
+    let mut csv = String::from("chainage_m,elevation_m,offset_m,class_id,class_name\n");
+    for sample in &result.samples {
+        let _ = writeln!(
+            csv,
+            "{:.3},{:.3},{:.3},{},\"{}\"",
+            sample.chainage,
+            sample.elevation,
+            sample.offset,
+            sample.class_id,
+            get_class_name(sample.class_id)
+        );
+    }
+    csv
+}
+
+/// Scatter chart of elevation against chainage with metre-labelled axes.
+fn profile_to_svg(result: &ProfileResult) -> String {
+    let (low, high) = elevation_range(&result.samples).unwrap_or((0.0, 1.0));
+    let padding = ((high - low) * 0.05).max(0.1);
+    let (low, high) = (low - padding, high + padding);
+    let length = result.length.max(f32::EPSILON);
+
+    let plot_width = SVG_WIDTH - 2.0 * SVG_MARGIN;
+    let plot_height = SVG_HEIGHT - 2.0 * SVG_MARGIN;
+    let x = |chainage: f32| SVG_MARGIN + chainage / length * plot_width;
+    let y = |elevation: f32| SVG_MARGIN + (high - elevation) / (high - low) * plot_height;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{SVG_WIDTH}" height="{SVG_HEIGHT}" viewBox="0 0 {SVG_WIDTH} {SVG_HEIGHT}" font-family="monospace" font-size="11">"#
+    );
+    let _ = writeln!(
+        svg,
+        r##"<rect width="100%" height="100%" fill="#ffffff"/>"##
+    );
+    let _ = writeln!(
+        svg,
+        r##"<rect x="{SVG_MARGIN}" y="{SVG_MARGIN}" width="{plot_width}" height="{plot_height}" fill="none" stroke="#444444"/>"##
+    );
+
+    // Five ticks per axis is enough to read levels off a printed profile
+    for tick in 0..=4 {
+        let fraction = tick as f32 / 4.0;
+        let chainage = fraction * result.length;
+        let elevation = low + fraction * (high - low);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{:.1}</text>"#,
+            x(chainage),
+            SVG_HEIGHT - SVG_MARGIN + 16.0,
+            chainage
+        );
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="end">{:.2}</text>"#,
+            SVG_MARGIN - 6.0,
+            y(elevation) + 4.0,
+            elevation
+        );
+    }
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">Chainage (m)</text>"#,
+        SVG_WIDTH / 2.0,
+        SVG_HEIGHT - 16.0
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="16" y="{:.1}" text-anchor="middle" transform="rotate(-90 16 {:.1})">Elevation (m)</text>"#,
+        SVG_HEIGHT / 2.0,
+        SVG_HEIGHT / 2.0
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{SVG_MARGIN}" y="24">Profile {}: {:.2} m, corridor ±{:.2} m, {} points</text>"#,
+        result.id,
+        result.length,
+        result.half_width,
+        result.samples.len()
+    );
+
+    for sample in thinned(&result.samples, MAX_SVG_SAMPLES) {
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{:.1}" cy="{:.1}" r="1.5" fill="{}"/>"#,
+            x(sample.chainage),
+            y(sample.elevation),
+            class_colour(sample.class_id)
+        );
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Broad colour groups so ground, vegetation and structures separate on the chart.
+fn class_colour(class_id: u8) -> &'static str {
+    match class_id {
+        id if ROAD_CLASSIFICATIONS.contains(&id) => "#8d6e63",
+        3..=5 => "#43a047",
+        6 => "#757575",
+        _ => "#1e88e5",
+    }
+}
+
+// Renderer: clears previous meshes each frame and rebuilds from state
+pub fn update_profile_render(
+    mut commands: Commands,
+    profile_tool: Res<ProfileTool>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    existing_preview: Query<Entity, With<ProfilePreview>>,
+    existing_completed: Query<Entity, With<CompletedProfileTag>>,
+) {
+    for e in &existing_preview {
+        commands.entity(e).despawn();
+    }
+    for e in &existing_completed {
+        commands.entity(e).despawn();
+    }
+
+    if !profile_tool.is_active() {
+        return;
+    }
+
+    let line_colour = if profile_tool.is_completed {
+        Color::srgb(0.0, 0.8, 1.0)
+    } else {
+        Color::srgb(1.0, 1.0, 0.2)
+    };
+    let line_material = materials.add(StandardMaterial {
+        base_color: line_colour,
+        emissive: line_colour.into(),
+        unlit: true,
+        ..default()
+    });
+    let corridor_material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.0, 0.5, 0.7),
+        emissive: LinearRgba::new(0.0, 0.5, 0.7, 1.0),
+        unlit: true,
+        ..default()
+    });
+
+    for vertex in &profile_tool.polyline {
+        commands.spawn((
+            Mesh3d(meshes.add(Sphere::new(DRAW_VERTEX_SIZE))),
+            MeshMaterial3d(line_material.clone()),
+            Transform::from_translation(*vertex),
+            CompletedProfileTag,
+            RenderLayers::layer(1),
+        ));
+    }
+
+    let mut segments: Vec<(Vec3, Vec3)> = profile_tool
+        .polyline
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .collect();
+
+    // Rubber-band segment to the cursor while drawing
+    if !profile_tool.is_completed {
+        if let (Some(last), Some(preview)) =
+            (profile_tool.polyline.last(), profile_tool.preview_point)
+        {
+            segments.push((*last, preview));
+        }
+        if let Some(preview) = profile_tool.preview_point {
+            commands.spawn((
+                Mesh3d(meshes.add(Sphere::new(MOUSE_RAYCAST_INTERSECTION_SPHERE_SIZE))),
+                MeshMaterial3d(line_material.clone()),
+                Transform::from_translation(preview),
+                ProfilePreview,
+                RenderLayers::layer(1),
+            ));
+        }
+    }
+
+    for (start, end) in segments {
+        spawn_segment(
+            &mut commands,
+            &mut meshes,
+            &line_material,
+            start,
+            end,
+            DRAW_LINE_WIDTH,
+        );
+
+        // Corridor edges either side of the centreline
+        let plan = (end - start).with_y(0.0);
+        if plan.length() > 0.02 {
+            let side = plan.normalize().cross(Vec3::Y) * profile_tool.half_width;
+            for edge in [side, -side] {
+                spawn_segment(
+                    &mut commands,
+                    &mut meshes,
+                    &corridor_material,
+                    start + edge,
+                    end + edge,
+                    DRAW_LINE_WIDTH * 0.5,
+                );
+            }
+        }
+    }
+}
+
+fn spawn_segment(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    material: &Handle<StandardMaterial>,
+    start: Vec3,
+    end: Vec3,
+    width: f32,
+) {
+    let dir = end - start;
+    let dist = dir.length();
+    if dist <= 0.02 {
+        return;
+    }
+    let rot = Quat::from_rotation_arc(Vec3::X, dir.normalize());
+    commands.spawn((
+        Mesh3d(meshes.add(Cuboid::new(dist, width, width))),
+        MeshMaterial3d(material.clone()),
+        Transform::from_translation((start + end) * 0.5).with_rotation(rot),
+        CompletedProfileTag,
+        RenderLayers::layer(1),
+    ));
+}
@@ -0,0 +1,817 @@
+use crate::engine::assets::bounds::PointCloudBounds;
+use crate::engine::assets::point_cloud_assets::{PointCloudAssets, read_texel};
+use crate::engine::assets::scene_manifest::SceneManifest;
+use crate::engine::compute::classification_reference::{ClassificationReference, HIDDEN_CLASS};
+use crate::engine::core::app_state::AppState;
+use crate::rpc::web_rpc::WebRpcInterface;
+use crate::tools::point_pick::{PointPicker, update_pick_classification};
+use bevy::prelude::*;
+use bevy::render::RenderApp;
+use bevy::render::extract_resource::{ExtractResource, ExtractResourcePlugin};
+use bevy::window::PrimaryWindow;
+
+/// Default brush radius in logical window pixels.
+const DEFAULT_BRUSH_RADIUS: f32 = 24.0;
+const MIN_BRUSH_RADIUS: f32 = 2.0;
+const MAX_BRUSH_RADIUS: f32 = 400.0;
+/// Native `[` / `]` brush radius step in pixels.
+const BRUSH_RADIUS_STEP: f32 = 4.0;
+
+/// Minimum cursor travel in pixels before another lasso vertex is recorded.
+const LASSO_MIN_SPACING: f32 = 2.0;
+
+/// Screen cell size in pixels for the front-most depth test.
+const DEPTH_CELL_SIZE: f32 = 4.0;
+/// A point is front-most when it lies within this fraction of the nearest depth in its cell...
+const FRONT_DEPTH_TOLERANCE: f32 = 0.02;
+/// ...or within this many metres of it, whichever is larger.
+const MIN_FRONT_DEPTH_TOLERANCE: f32 = 0.1;
+
+/// Padding around the selected points' XZ bounds so a single point still has an area.
+const FOOTPRINT_MARGIN: f32 = 0.1;
+
+/// Distance in front of the camera at which the selection outline is drawn.
+const OVERLAY_DISTANCE: f32 = 1.0;
+const BRUSH_OVERLAY_SEGMENTS: usize = 48;
+
+/// Screen-space shape drawn by the selection tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionShape {
+    Rectangle,
+    Lasso,
+    Brush,
+}
+
+impl SelectionShape {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "rectangle" => Some(Self::Rectangle),
+            "lasso" => Some(Self::Lasso),
+            "brush" => Some(Self::Brush),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rectangle => "rectangle",
+            Self::Lasso => "lasso",
+            Self::Brush => "brush",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            Self::Rectangle => Self::Lasso,
+            Self::Lasso => Self::Brush,
+            Self::Brush => Self::Rectangle,
+        }
+    }
+}
+
+/// Which points under the shape are picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionDepth {
+    Through,   // Every point inside the shape, through the full view depth.
+    FrontMost, // Only the nearest visible surface under each part of the shape.
+}
+
+impl SelectionDepth {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "through" => Some(Self::Through),
+            "front" => Some(Self::FrontMost),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Through => "through",
+            Self::FrontMost => "front",
+        }
+    }
+}
+
+/// How a gesture combines with the existing selection. The brush never replaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionOperation {
+    Replace,
+    Add,
+    Subtract,
+}
+
+impl SelectionOperation {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "replace" => Some(Self::Replace),
+            "add" => Some(Self::Add),
+            "subtract" => Some(Self::Subtract),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Replace => "replace",
+            Self::Add => "add",
+            Self::Subtract => "subtract",
+        }
+    }
+}
+
+/// Selection tool requests from RPC or keyboard.
+#[derive(Event, Debug, Clone)]
+pub struct SelectionActionEvent {
+    pub action: SelectionAction,
+}
+
+#[derive(Debug, Clone)]
+pub enum SelectionAction {
+    /// Fields left as `None` keep their current value.
+    SetMode {
+        shape: Option<SelectionShape>,
+        depth: Option<SelectionDepth>,
+        operation: Option<SelectionOperation>,
+        brush_radius: Option<f32>,
+    },
+    Clear,
+}
+
+/// Points picked by the screen-space selection tool, waiting for a hide or reclassify.
+///
+/// Indices are point texels in the position texture, so they stay valid for as long
+/// as the same dataset is loaded. Extracted to the render world for the highlight.
+#[derive(Resource, ExtractResource, Clone, Default)]
+pub struct PointSelection {
+    /// One bit per point texel.
+    pub mask: Vec<u32>,
+    pub count: usize,
+    /// XZ bounds of the selected points as four corners, used as the polygon outline.
+    pub footprint: Vec<Vec3>,
+}
+
+impl PointSelection {
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn contains(&self, index: u32) -> bool {
+        self.mask
+            .get((index / 32) as usize)
+            .is_some_and(|word| word & (1 << (index % 32)) != 0)
+    }
+
+    /// Select or deselect one texel, returning whether it changed.
+    pub fn set(&mut self, index: u32, selected: bool) -> bool {
+        let word = (index / 32) as usize;
+        let bit = 1 << (index % 32);
+        if word >= self.mask.len() {
+            if !selected {
+                return false;
+            }
+            self.mask.resize(word + 1, 0);
+        }
+        if (self.mask[word] & bit != 0) == selected {
+            return false;
+        }
+        if selected {
+            self.mask[word] |= bit;
+            self.count += 1;
+        } else {
+            self.mask[word] &= !bit;
+            self.count -= 1;
+        }
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.mask.clear();
+        self.count = 0;
+        self.footprint.clear();
+    }
+
+    /// Selected texel indices in ascending order.
+    pub fn indices(&self) -> Vec<u32> {
+        let mut indices = Vec::with_capacity(self.count);
+        for (word_index, &word) in self.mask.iter().enumerate() {
+            let mut bits = word;
+            while bits != 0 {
+                indices.push(word_index as u32 * 32 + bits.trailing_zeros());
+                bits &= bits - 1;
+            }
+        }
+        indices
+    }
+
+    /// Recompute `footprint` from the selected points' positions.
+    fn update_footprint(&mut self, position_data: &[u8], bounds: &PointCloudBounds) {
+        let min = bounds.min_corner();
+        let extent = bounds.size();
+        let corners = self
+            .indices()
+            .into_iter()
+            .filter_map(|index| read_texel(position_data, index as usize))
+            .map(|position| min + Vec3::new(position[0], position[1], position[2]) * extent)
+            .fold(None, |corners: Option<(Vec3, Vec3)>, world| {
+                Some(corners.map_or((world, world), |(low, high)| {
+                    (low.min(world), high.max(world))
+                }))
+            });
+
+        self.footprint = match corners {
+            Some((low, high)) => {
+                let low = low - Vec3::new(FOOTPRINT_MARGIN, 0.0, FOOTPRINT_MARGIN);
+                let high = high + Vec3::new(FOOTPRINT_MARGIN, 0.0, FOOTPRINT_MARGIN);
+                vec![
+                    Vec3::new(low.x, low.y, low.z),
+                    Vec3::new(high.x, low.y, low.z),
+                    Vec3::new(high.x, low.y, high.z),
+                    Vec3::new(low.x, low.y, high.z),
+                ]
+            }
+            None => Vec::new(),
+        };
+    }
+}
+
+/// Visible point projected into the window when a gesture starts.
+#[derive(Debug, Clone, Copy)]
+struct ProjectedPoint {
+    index: u32,
+    screen: Vec2, // Logical window pixels, origin top left.
+    depth: f32,   // View-space distance along the camera's forward axis.
+}
+
+/// Drag in progress with the points it can pick.
+struct SelectionGesture {
+    operation: SelectionOperation,
+    /// Cursor positions; the rectangle uses the first and last.
+    stroke: Vec<Vec2>,
+    candidates: Vec<ProjectedPoint>,
+}
+
+#[derive(Resource)]
+pub struct ScreenSelectionTool {
+    pub is_active: bool,
+    pub shape: SelectionShape,
+    pub depth: SelectionDepth,
+    pub operation: SelectionOperation,
+    /// Brush radius in logical window pixels.
+    pub brush_radius: f32,
+    pub cursor: Option<Vec2>,
+    gesture: Option<SelectionGesture>,
+}
+
+impl Default for ScreenSelectionTool {
+    fn default() -> Self {
+        Self {
+            is_active: false,
+            shape: SelectionShape::Rectangle,
+            depth: SelectionDepth::Through,
+            operation: SelectionOperation::Replace,
+            brush_radius: DEFAULT_BRUSH_RADIUS,
+            cursor: None,
+            gesture: None,
+        }
+    }
+}
+
+impl ScreenSelectionTool {
+    /// Deactivating drops any gesture in progress; the selection itself is cleared by
+    /// `screen_selection_system` on the next frame.
+    pub fn set_active(&mut self, active: bool) {
+        self.is_active = active;
+        if !active {
+            self.gesture = None;
+            self.cursor = None;
+        }
+    }
+
+    pub fn set_brush_radius(&mut self, radius: f32) {
+        self.brush_radius = radius.clamp(MIN_BRUSH_RADIUS, MAX_BRUSH_RADIUS);
+    }
+
+    /// Tool settings reported to the frontend.
+    pub fn state_summary(&self, selection: &PointSelection) -> serde_json::Value {
+        serde_json::json!({
+            "shape": self.shape.as_str(),
+            "depth": self.depth.as_str(),
+            "operation": self.operation.as_str(),
+            "brush_radius": self.brush_radius,
+            "count": selection.count,
+        })
+    }
+}
+
+pub struct ScreenSelectionPlugin;
+impl Plugin for ScreenSelectionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenSelectionTool>()
+            .init_resource::<PointSelection>()
+            .add_event::<SelectionActionEvent>()
+            .add_plugins(ExtractResourcePlugin::<PointSelection>::default())
+            .add_systems(
+                Update,
+                (
+                    selection_keyboard_shortcuts,
+                    process_selection_actions,
+                    screen_selection_system.after(update_pick_classification),
+                    draw_selection_overlay,
+                    notify_selection_changes,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Running)),
+            );
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<PointSelection>();
+        }
+    }
+}
+
+/// Native Tab cycles the shape, Shift+Tab toggles front-most picking, `[` and `]`
+/// resize the brush and Backspace clears the selection.
+pub fn selection_keyboard_shortcuts(
+    #[cfg(not(target_arch = "wasm32"))] keyboard: Res<ButtonInput<KeyCode>>,
+    #[cfg(not(target_arch = "wasm32"))] tool: Res<ScreenSelectionTool>,
+    #[cfg(not(target_arch = "wasm32"))] mut events: EventWriter<SelectionActionEvent>,
+) {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if !tool.is_active {
+            return;
+        }
+        let shift = keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        let mut mode = |shape, depth, brush_radius| {
+            events.write(SelectionActionEvent {
+                action: SelectionAction::SetMode {
+                    shape,
+                    depth,
+                    operation: None,
+                    brush_radius,
+                },
+            });
+        };
+
+        if keyboard.just_pressed(KeyCode::Tab) {
+            if shift {
+                let depth = match tool.depth {
+                    SelectionDepth::Through => SelectionDepth::FrontMost,
+                    SelectionDepth::FrontMost => SelectionDepth::Through,
+                };
+                mode(None, Some(depth), None);
+            } else {
+                mode(Some(tool.shape.next()), None, None);
+            }
+        }
+        if keyboard.just_pressed(KeyCode::BracketLeft) {
+            mode(None, None, Some(tool.brush_radius - BRUSH_RADIUS_STEP));
+        }
+        if keyboard.just_pressed(KeyCode::BracketRight) {
+            mode(None, None, Some(tool.brush_radius + BRUSH_RADIUS_STEP));
+        }
+        if keyboard.just_pressed(KeyCode::Backspace) {
+            events.write(SelectionActionEvent {
+                action: SelectionAction::Clear,
+            });
+        }
+    }
+}
+
+/// Applies mode changes and clears; settings persist between activations.
+pub fn process_selection_actions(
+    mut events: EventReader<SelectionActionEvent>,
+    mut tool: ResMut<ScreenSelectionTool>,
+    mut selection: ResMut<PointSelection>,
+) {
+    for event in events.read() {
+        match &event.action {
+            SelectionAction::SetMode {
+                shape,
+                depth,
+                operation,
+                brush_radius,
+            } => {
+                if let Some(shape) = shape {
+                    tool.shape = *shape;
+                }
+                if let Some(depth) = depth {
+                    tool.depth = *depth;
+                }
+                if let Some(operation) = operation {
+                    tool.operation = *operation;
+                }
+                if let Some(radius) = brush_radius {
+                    tool.set_brush_radius(*radius);
+                }
+                // A shape change mid-drag would apply the stroke as the wrong shape.
+                tool.gesture = None;
+            }
+            SelectionAction::Clear => {
+                if !selection.is_empty() {
+                    selection.clear();
+                }
+            }
+        }
+    }
+}
+
+/// Starts, extends and applies rectangle, lasso and brush gestures with the left button.
+///
+/// Visible points are projected once when a gesture starts; the camera is not moved by
+/// the left button, so the projection stays valid for the whole drag.
+pub fn screen_selection_system(
+    mut tool: ResMut<ScreenSelectionTool>,
+    mut selection: ResMut<PointSelection>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    #[cfg(not(target_arch = "wasm32"))] keyboard: Res<ButtonInput<KeyCode>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&GlobalTransform, &Camera), With<Camera3d>>,
+    images: Res<Assets<Image>>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+    picker: Res<PointPicker>,
+) {
+    if !tool.is_active {
+        if !selection.is_empty() {
+            selection.clear();
+        }
+        return;
+    }
+
+    tool.cursor = windows.single().ok().and_then(|w| w.cursor_position());
+    let Some(cursor) = tool.cursor else {
+        return;
+    };
+
+    if mouse.just_pressed(MouseButton::Left) {
+        let mut operation = tool.operation;
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+                operation = SelectionOperation::Add;
+            }
+            if keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+                operation = SelectionOperation::Subtract;
+            }
+        }
+        if tool.shape == SelectionShape::Brush && operation == SelectionOperation::Replace {
+            operation = SelectionOperation::Add;
+        }
+
+        let Some(bounds) = assets.get_bounds(&manifests) else {
+            return;
+        };
+        let Ok((camera_transform, camera)) = cameras.single() else {
+            return;
+        };
+        let Some(reference) = picker.reference() else {
+            return;
+        };
+        let Some(candidates) = project_visible_points(
+            camera,
+            camera_transform,
+            tool.depth,
+            &images,
+            &assets,
+            &bounds,
+            reference,
+        ) else {
+            warn!("Selection skipped: point textures have no CPU data");
+            return;
+        };
+
+        tool.gesture = Some(SelectionGesture {
+            operation,
+            stroke: vec![cursor],
+            candidates,
+        });
+        if tool.shape == SelectionShape::Brush {
+            paint_brush(&tool, &mut selection, cursor, cursor);
+        }
+    }
+
+    if tool.gesture.is_none() {
+        return;
+    }
+
+    if mouse.pressed(MouseButton::Left) {
+        let shape = tool.shape;
+        let last = tool.gesture.as_ref().and_then(|g| g.stroke.last().copied());
+        let Some(last) = last else {
+            return;
+        };
+        match shape {
+            SelectionShape::Rectangle => {
+                if let Some(gesture) = tool.gesture.as_mut() {
+                    gesture.stroke.truncate(1);
+                    gesture.stroke.push(cursor);
+                }
+            }
+            SelectionShape::Lasso | SelectionShape::Brush => {
+                if last.distance(cursor) >= LASSO_MIN_SPACING {
+                    if shape == SelectionShape::Brush {
+                        paint_brush(&tool, &mut selection, last, cursor);
+                    }
+                    if let Some(gesture) = tool.gesture.as_mut() {
+                        gesture.stroke.push(cursor);
+                    }
+                }
+            }
+        }
+    }
+
+    if !mouse.just_released(MouseButton::Left) {
+        return;
+    }
+    let Some(gesture) = tool.gesture.take() else {
+        return;
+    };
+
+    match tool.shape {
+        SelectionShape::Rectangle => {
+            let start = gesture.stroke[0];
+            let end = gesture.stroke.last().copied().unwrap_or(start);
+            let rect = Rect::from_corners(start, end);
+            apply_region(&mut selection, &gesture, |p| rect.contains(p));
+        }
+        SelectionShape::Lasso => {
+            if gesture.stroke.len() >= 3 {
+                apply_region(&mut selection, &gesture, |p| {
+                    point_in_lasso(p, &gesture.stroke)
+                });
+            } else if gesture.operation == SelectionOperation::Replace {
+                selection.clear();
+            }
+        }
+        SelectionShape::Brush => {}
+    }
+
+    let Some(bounds) = assets.get_bounds(&manifests) else {
+        return;
+    };
+    let position_data = images
+        .get(&assets.position_texture)
+        .and_then(|image| image.data.as_deref());
+    if let Some(position_data) = position_data {
+        selection.update_footprint(position_data, &bounds);
+    }
+}
+
+/// Select, add or remove every candidate inside a closed shape.
+fn apply_region(
+    selection: &mut ResMut<PointSelection>,
+    gesture: &SelectionGesture,
+    inside: impl Fn(Vec2) -> bool,
+) {
+    let hits: Vec<u32> = gesture
+        .candidates
+        .iter()
+        .filter(|p| inside(p.screen))
+        .map(|p| p.index)
+        .collect();
+
+    match gesture.operation {
+        SelectionOperation::Replace => {
+            selection.clear();
+            for index in hits {
+                selection.set(index, true);
+            }
+        }
+        SelectionOperation::Add | SelectionOperation::Subtract => {
+            let selected = gesture.operation == SelectionOperation::Add;
+            // Only touch the resource when something changes, to avoid needless recomputes.
+            let changed: Vec<u32> = hits
+                .into_iter()
+                .filter(|&index| selection.contains(index) != selected)
+                .collect();
+            for index in changed {
+                selection.set(index, selected);
+            }
+        }
+    }
+}
+
+/// Add or remove candidates within the brush radius of the segment `from`..`to`.
+fn paint_brush(
+    tool: &ScreenSelectionTool,
+    selection: &mut ResMut<PointSelection>,
+    from: Vec2,
+    to: Vec2,
+) {
+    let Some(gesture) = &tool.gesture else {
+        return;
+    };
+    let selected = gesture.operation != SelectionOperation::Subtract;
+    let radius = tool.brush_radius;
+
+    let changed: Vec<u32> = gesture
+        .candidates
+        .iter()
+        .filter(|p| distance_to_segment(p.screen, from, to) <= radius)
+        .filter(|p| selection.contains(p.index) != selected)
+        .map(|p| p.index)
+        .collect();
+    for index in changed {
+        selection.set(index, selected);
+    }
+}
+
+/// Project every loaded, non-hidden point inside the viewport into window pixels, using
+/// the pick classification to skip hidden points. Returns `None` when the point textures
+/// have no CPU copy.
+fn project_visible_points(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    depth: SelectionDepth,
+    images: &Assets<Image>,
+    assets: &PointCloudAssets,
+    bounds: &PointCloudBounds,
+    reference: &ClassificationReference,
+) -> Option<Vec<ProjectedPoint>> {
+    let position_data = images
+        .get(&assets.position_texture)
+        .and_then(|image| image.data.as_deref())?;
+    let colour_data = images
+        .get(&assets.colour_class_texture)
+        .and_then(|image| image.data.as_deref())?;
+    let viewport = camera.logical_viewport_rect()?;
+
+    // Hidden points are not visible, so they are neither picked nor occlude others.
+    let classes = reference.classify_texels(position_data, colour_data, bounds.loaded_points);
+
+    let view_from_world = camera_transform.compute_matrix().inverse();
+    let clip_from_view = camera.clip_from_view();
+    let min = bounds.min_corner();
+    let extent = bounds.size();
+
+    let mut points = Vec::new();
+    for (index, class) in classes.into_iter().enumerate() {
+        if class == HIDDEN_CLASS {
+            continue;
+        }
+        let Some(position) = read_texel(position_data, index) else {
+            break;
+        };
+        let world = min + Vec3::new(position[0], position[1], position[2]) * extent;
+        let view = view_from_world.transform_point3(world);
+        if view.z >= 0.0 {
+            continue; // Behind the camera.
+        }
+        let clip = clip_from_view * view.extend(1.0);
+        let ndc = clip.truncate() / clip.w;
+        if ndc.x.abs() > 1.0 || ndc.y.abs() > 1.0 {
+            continue;
+        }
+
+        points.push(ProjectedPoint {
+            index: index as u32,
+            screen: viewport.min + Vec2::new(ndc.x + 1.0, 1.0 - ndc.y) * 0.5 * viewport.size(),
+            depth: -view.z,
+        });
+    }
+
+    if depth == SelectionDepth::FrontMost {
+        points = front_most(points, viewport);
+    }
+    Some(points)
+}
+
+/// Keep the points close to the nearest depth in their screen cell.
+fn front_most(points: Vec<ProjectedPoint>, viewport: Rect) -> Vec<ProjectedPoint> {
+    let columns = (viewport.width() / DEPTH_CELL_SIZE).ceil().max(1.0) as usize;
+    let rows = (viewport.height() / DEPTH_CELL_SIZE).ceil().max(1.0) as usize;
+    let cell = |screen: Vec2| {
+        let local = ((screen - viewport.min) / DEPTH_CELL_SIZE).max(Vec2::ZERO);
+        let column = (local.x as usize).min(columns - 1);
+        let row = (local.y as usize).min(rows - 1);
+        row * columns + column
+    };
+
+    let mut nearest = vec![f32::MAX; columns * rows];
+    for point in &points {
+        let slot = &mut nearest[cell(point.screen)];
+        *slot = slot.min(point.depth);
+    }
+
+    points
+        .into_iter()
+        .filter(|point| {
+            let near = nearest[cell(point.screen)];
+            point.depth <= near + (near * FRONT_DEPTH_TOLERANCE).max(MIN_FRONT_DEPTH_TOLERANCE)
+        })
+        .collect()
+}
+
+/// Crossing test against the lasso outline, closed back to its first vertex.
+fn point_in_lasso(point: Vec2, outline: &[Vec2]) -> bool {
+    let mut inside = false;
+    for (i, &current) in outline.iter().enumerate() {
+        let previous = outline[(i + outline.len() - 1) % outline.len()];
+        if ((current.y > point.y) != (previous.y > point.y))
+            && (point.x
+                < (previous.x - current.x) * (point.y - current.y) / (previous.y - current.y)
+                    + current.x)
+        {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let direction = end - start;
+    let length_squared = direction.length_squared();
+    if length_squared <= f32::EPSILON {
+        return point.distance(start);
+    }
+    let t = ((point - start).dot(direction) / length_squared).clamp(0.0, 1.0);
+    point.distance(start + direction * t)
+}
+
+/// Outline of the rectangle or lasso being dragged, and the brush circle at the cursor,
+/// drawn just in front of the camera.
+pub fn draw_selection_overlay(
+    tool: Res<ScreenSelectionTool>,
+    cameras: Query<(&GlobalTransform, &Camera), With<Camera3d>>,
+    mut gizmos: Gizmos,
+) {
+    if !tool.is_active {
+        return;
+    }
+    let Ok((camera_transform, camera)) = cameras.single() else {
+        return;
+    };
+    let Some(viewport) = camera.logical_viewport_rect() else {
+        return;
+    };
+
+    let operation = tool
+        .gesture
+        .as_ref()
+        .map_or(tool.operation, |g| g.operation);
+    let colour = match operation {
+        SelectionOperation::Subtract => Color::srgb(1.0, 0.3, 0.2),
+        _ => Color::srgb(1.0, 0.85, 0.0),
+    };
+    let to_world = |screen: Vec2| {
+        camera
+            .viewport_to_world(camera_transform, screen - viewport.min)
+            .ok()
+            .map(|ray| ray.get_point(OVERLAY_DISTANCE))
+    };
+
+    let outline: Vec<Vec2> = match (tool.shape, &tool.gesture) {
+        (SelectionShape::Rectangle, Some(gesture)) if gesture.stroke.len() >= 2 => {
+            let rect = Rect::from_corners(gesture.stroke[0], gesture.stroke[1]);
+            vec![
+                rect.min,
+                Vec2::new(rect.max.x, rect.min.y),
+                rect.max,
+                Vec2::new(rect.min.x, rect.max.y),
+                rect.min,
+            ]
+        }
+        (SelectionShape::Lasso, Some(gesture)) => {
+            let mut outline = gesture.stroke.clone();
+            outline.extend(gesture.stroke.first());
+            outline
+        }
+        (SelectionShape::Brush, _) => match tool.cursor {
+            Some(cursor) => (0..=BRUSH_OVERLAY_SEGMENTS)
+                .map(|i| {
+                    let angle = i as f32 / BRUSH_OVERLAY_SEGMENTS as f32 * std::f32::consts::TAU;
+                    cursor + Vec2::from_angle(angle) * tool.brush_radius
+                })
+                .collect(),
+            None => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+
+    let points: Vec<Vec3> = outline.into_iter().filter_map(to_world).collect();
+    if points.len() >= 2 {
+        gizmos.linestrip(points, colour);
+    }
+}
+
+/// Sends the tool settings and selection size after each change, once a drag has finished.
+pub fn notify_selection_changes(
+    tool: Res<ScreenSelectionTool>,
+    selection: Res<PointSelection>,
+    mut pending: Local<bool>,
+    mut last_settings: Local<Option<(SelectionShape, SelectionDepth, SelectionOperation, f32)>>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    let settings = Some((tool.shape, tool.depth, tool.operation, tool.brush_radius));
+    if selection.is_changed() || *last_settings != settings {
+        *pending = true;
+        *last_settings = settings;
+    }
+
+    if *pending && tool.gesture.is_none() {
+        *pending = false;
+        rpc_interface.send_notification("selection_changed", tool.state_summary(&selection));
+    }
+}
@@ -0,0 +1,647 @@
+use crate::engine::assets::bounds::PointCloudBounds;
+use crate::engine::assets::point_cloud_assets::PointCloudAssets;
+use crate::engine::assets::scene_manifest::SceneManifest;
+use crate::engine::camera::bookmarks::CameraBookmarks;
+use crate::engine::camera::viewport_camera::{CameraView, ViewportCamera};
+use crate::engine::core::app_state::AppState;
+use crate::engine::render::instanced_render_plugin::InstancedAssetData;
+use crate::rpc::web_rpc::WebRpcInterface;
+use crate::tools::asset_manager::manipulation::{
+    calculate_asset_size, create_new_instanced_renderer, spawn_placed_bounds,
+};
+use crate::tools::asset_manager::state::{PlacedAssetInstance, PlacedAssetInstances, PlacedBounds};
+use crate::tools::history::EditHistory;
+use crate::tools::measure::{MeasureMode, MeasureTool, Measurement, Measurements};
+use crate::tools::polygon::{
+    ClassificationPolygon, CompletedPolygon, PolygonClassificationData, PolygonCounter,
+    PolygonLimits, PolygonMode, VerticalRange, create_completed_polygon,
+};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Project file format written by `save_session`. Bump when the layout changes.
+/// Version 2 stores measurements as a mode plus points instead of `start`/`end`.
+pub const SESSION_VERSION: u32 = 2;
+
+/// Oldest project file format `load_session` still reads.
+pub const MIN_SESSION_VERSION: u32 = 1;
+
+/// Project file used by the native F5/F9 shortcuts.
+pub const DEFAULT_SESSION_FILE: &str = "session.json";
+
+/// Origins further apart than this are treated as a different dataset.
+const ORIGIN_TOLERANCE: f64 = 1e-3;
+
+/// Event fired when a session save or load is requested via RPC or keyboard.
+#[derive(Event, Debug, Clone)]
+pub struct SessionEvent {
+    pub action: SessionAction,
+}
+
+/// Session operations; paths are only honoured in native builds.
+#[derive(Debug, Clone)]
+pub enum SessionAction {
+    Save { path: Option<PathBuf> }, // Without a path the JSON is returned to the frontend.
+    LoadContent(String),
+    LoadPath(PathBuf),
+}
+
+/// Versioned project file. All coordinates are absolute world positions
+/// (render space plus manifest origin) so files survive re-centring.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionFile {
+    pub version: u32,
+    pub manifest: SessionManifest,
+    #[serde(default)]
+    pub camera: Option<SessionCamera>,
+    #[serde(default)]
+    pub polygons: Vec<SessionPolygon>,
+    #[serde(default)]
+    pub assets: Vec<SessionAsset>,
+    #[serde(default)]
+    pub measurements: Vec<SessionMeasurement>,
+    #[serde(default)]
+    pub bookmarks: Vec<SessionBookmark>,
+}
+
+/// Identifies the dataset a session was drawn against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionManifest {
+    pub origin: [f64; 3],
+    pub total_points: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCamera {
+    pub focus_point: [f64; 3],
+    pub height: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+    #[serde(default)]
+    pub top_view: bool,
+}
+
+impl SessionCamera {
+    fn from_view(view: &CameraView, origin: DVec3) -> Self {
+        Self {
+            focus_point: to_world(view.focus_point, origin),
+            height: view.height,
+            yaw: view.yaw,
+            pitch: view.pitch,
+            top_view: view.top_down,
+        }
+    }
+
+    fn to_view(&self, origin: DVec3) -> CameraView {
+        CameraView {
+            focus_point: to_render(self.focus_point, origin),
+            height: self.height,
+            yaw: self.yaw,
+            pitch: self.pitch,
+            top_down: self.top_view,
+        }
+    }
+}
+
+/// Named camera view, restored by `go_to_camera_bookmark`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionBookmark {
+    pub name: String,
+    #[serde(flatten)]
+    pub camera: SessionCamera,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionPolygon {
+    pub id: u32,
+    pub mode: PolygonMode,
+    pub new_class: u32,
+    #[serde(default)]
+    pub masks: Vec<(u32, u32)>,
+    /// User-placed corners; older sessions omit these and fall back to `points`.
+    #[serde(default)]
+    pub vertices: Vec<[f64; 3]>,
+    pub points: Vec<[f64; 3]>,
+    /// Absolute ranges are saved in the source height datum, like the points.
+    #[serde(default)]
+    pub vertical_range: Option<VerticalRange>,
+    /// Point texel indices of a screen-space selection; they only match the dataset the
+    /// session was saved against, so a `total_points` mismatch is reported on load.
+    #[serde(default)]
+    pub selection: Vec<u32>,
+}
+
+/// Placed asset; UV bounds and size are looked up from the manifest atlas on load.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionAsset {
+    pub asset_name: String,
+    pub translation: [f64; 3],
+    pub rotation: [f32; 4],
+}
+
+/// Sessions saved before measurement modes only hold a distance's `start` and `end`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMeasurement {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub mode: MeasureMode,
+    #[serde(default)]
+    pub points: Vec<[f64; 3]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start: Option<[f64; 3]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<[f64; 3]>,
+}
+
+pub struct SessionPlugin;
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SessionEvent>().add_systems(
+            Update,
+            handle_session_events.run_if(in_state(AppState::Running)),
+        );
+    }
+}
+
+/// Applies queued session actions. Native builds also save with F5 and load with F9.
+pub fn handle_session_events(
+    mut events: EventReader<SessionEvent>,
+    #[cfg(not(target_arch = "wasm32"))] keyboard: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut classification_data: ResMut<PolygonClassificationData>,
+    mut polygon_counter: ResMut<PolygonCounter>,
+    (mut placed_assets, mut history): (ResMut<PlacedAssetInstances>, ResMut<EditHistory>),
+    (mut measure_tool, mut measurements): (ResMut<MeasureTool>, ResMut<Measurements>),
+    (mut viewport_camera, mut bookmarks): (Option<ResMut<ViewportCamera>>, ResMut<CameraBookmarks>),
+    completed_polygons: Query<Entity, With<CompletedPolygon>>,
+    placed_bounds: Query<(Entity, &PlacedAssetInstance), With<PlacedBounds>>,
+    instanced_renderers: Query<Entity, With<InstancedAssetData>>,
+    assets: Res<PointCloudAssets>,
+    manifests: Res<Assets<SceneManifest>>,
+    mut rpc_interface: ResMut<WebRpcInterface>,
+) {
+    let mut actions: Vec<SessionAction> = events.read().map(|e| e.action.clone()).collect();
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        if keyboard.just_pressed(KeyCode::F5) {
+            actions.push(SessionAction::Save {
+                path: Some(PathBuf::from(DEFAULT_SESSION_FILE)),
+            });
+        }
+        if keyboard.just_pressed(KeyCode::F9) {
+            actions.push(SessionAction::LoadPath(PathBuf::from(DEFAULT_SESSION_FILE)));
+        }
+    }
+
+    if actions.is_empty() {
+        return;
+    }
+
+    let Some(manifest) = assets.manifest.as_ref().and_then(|h| manifests.get(h)) else {
+        return;
+    };
+    let bounds = manifest.to_point_cloud_bounds();
+
+    for action in actions {
+        let content = match action {
+            SessionAction::Save { path } => {
+                // Live components carry moved/rotated transforms; the resource only tracks placement.
+                let session = capture_session(
+                    &bounds,
+                    viewport_camera.as_deref(),
+                    &classification_data,
+                    placed_bounds.iter().map(|(_, placed)| placed),
+                    &measurements,
+                    &bookmarks,
+                );
+                let content = match serde_json::to_string_pretty(&session) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        send_session_error(&mut rpc_interface, &format!("Serialise failed: {}", e));
+                        continue;
+                    }
+                };
+
+                let summary = serde_json::json!({
+                    "version": SESSION_VERSION,
+                    "polygons": session.polygons.len(),
+                    "assets": session.assets.len(),
+                    "measurements": session.measurements.len(),
+                    "bookmarks": session.bookmarks.len(),
+                });
+
+                match path {
+                    Some(path) => match write_session_file(&path, &content) {
+                        Ok(()) => {
+                            info!("Session saved to {}", path.display());
+                            rpc_interface.send_notification(
+                                "session_saved",
+                                serde_json::json!({
+                                    "path": path.display().to_string(),
+                                    "summary": summary,
+                                }),
+                            );
+                        }
+                        Err(e) => send_session_error(&mut rpc_interface, &e),
+                    },
+                    None => {
+                        rpc_interface.send_notification(
+                            "session_saved",
+                            serde_json::json!({
+                                "filename": DEFAULT_SESSION_FILE,
+                                "content": content,
+                                "summary": summary,
+                            }),
+                        );
+                    }
+                }
+                continue;
+            }
+            SessionAction::LoadContent(content) => content,
+            SessionAction::LoadPath(path) => match read_session_file(&path) {
+                Ok(content) => content,
+                Err(e) => {
+                    send_session_error(&mut rpc_interface, &e);
+                    continue;
+                }
+            },
+        };
+
+        let session = match parse_session(&content, &classification_data.limits) {
+            Ok(session) => session,
+            Err(e) => {
+                send_session_error(&mut rpc_interface, &e);
+                continue;
+            }
+        };
+
+        let mut warnings = manifest_warnings(&session.manifest, &bounds);
+        let origin = origin_of(&bounds);
+
+        // Remove everything the session replaces before rebuilding it.
+        for entity in completed_polygons
+            .iter()
+            .chain(placed_bounds.iter().map(|(e, _)| e))
+            .chain(instanced_renderers.iter())
+        {
+            commands.entity(entity).despawn();
+        }
+
+        // Polygons: compute state follows from the resource change detection.
+        let ground_height = viewport_camera
+            .as_ref()
+            .map(|c| c.ground_height)
+            .unwrap_or_else(|| bounds.ground_height());
+        classification_data.polygons = session
+            .polygons
+            .iter()
+            .map(|p| ClassificationPolygon {
+                id: p.id,
+                vertices: if p.vertices.len() >= 3 {
+                    &p.vertices
+                } else {
+                    &p.points
+                }
+                .iter()
+                .map(|&pt| to_render(pt, origin))
+                .collect(),
+                points: p.points.iter().map(|&pt| to_render(pt, origin)).collect(),
+                new_class: p.new_class,
+                masks: p.masks.clone(),
+                mode: p.mode.clone(),
+                vertical_range: p.vertical_range,
+                selection: p.selection.clone(),
+            })
+            .collect();
+        for polygon in &classification_data.polygons {
+            create_completed_polygon(
+                &mut commands,
+                &polygon.points,
+                polygon.id,
+                polygon
+                    .vertical_range
+                    .map(|range| range.offset(-origin.y))
+                    .as_ref(),
+                ground_height,
+                &mut meshes,
+                &mut materials,
+            );
+        }
+        if let Some(max_id) = classification_data.polygons.iter().map(|p| p.id).max() {
+            polygon_counter.next_id = polygon_counter.next_id.max(max_id + 1);
+        }
+
+        // Assets: bounds entities plus a single instanced renderer.
+        let atlas = manifest.asset_atlas.as_ref();
+        let mut instances = Vec::with_capacity(session.assets.len());
+        for saved in &session.assets {
+            let Some(asset_meta) =
+                atlas.and_then(|aa| aa.assets.iter().find(|a| a.name == saved.asset_name))
+            else {
+                warnings.push(format!(
+                    "Asset '{}' is not in the current atlas",
+                    saved.asset_name
+                ));
+                continue;
+            };
+
+            let size = calculate_asset_size(asset_meta);
+            let transform = Transform::from_translation(to_render(saved.translation, origin))
+                .with_rotation(Quat::from_array(saved.rotation).normalize());
+            let placed_instance = PlacedAssetInstance {
+                id: placed_assets.next_id,
+                asset_name: asset_meta.name.clone(),
+                transform,
+                uv_bounds: Vec4::new(
+                    asset_meta.uv_bounds.uv_min[0],
+                    asset_meta.uv_bounds.uv_min[1],
+                    asset_meta.uv_bounds.uv_max[0],
+                    asset_meta.uv_bounds.uv_max[1],
+                ),
+            };
+
+            placed_assets.next_id += 1;
+
+            spawn_placed_bounds(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                placed_instance.clone(),
+                size,
+            );
+            instances.push(placed_instance);
+        }
+        if let Some(asset_meta) = atlas.and_then(|aa| aa.assets.first()) {
+            create_new_instanced_renderer(&mut commands, &mut meshes, &instances, asset_meta);
+        }
+        placed_assets.instances = instances;
+
+        // Measurements keep their saved names but take fresh ids.
+        measure_tool.pending.clear();
+        measure_tool.preview_point = None;
+        measure_tool.live = None;
+        measurements.items.clear();
+        for saved in &session.measurements {
+            let points: Vec<Vec3> = match (&saved.points[..], saved.start, saved.end) {
+                ([], Some(start), Some(end)) => vec![start, end],
+                (points, ..) => points.to_vec(),
+            }
+            .into_iter()
+            .map(|p| to_render(p, origin))
+            .collect();
+            if points.len() < saved.mode.min_points() {
+                warnings.push(format!(
+                    "Skipped {} measurement with {} points",
+                    saved.mode.as_str(),
+                    points.len()
+                ));
+                continue;
+            }
+            if Measurement::new(0, saved.mode, points.clone()).is_degenerate() {
+                warnings.push(format!(
+                    "Skipped degenerate {} measurement",
+                    saved.mode.as_str()
+                ));
+                continue;
+            }
+            measurements.add(saved.mode, points);
+            if let (Some(name), Some(added)) = (&saved.name, measurements.items.last_mut()) {
+                added.name = name.clone();
+            }
+        }
+
+        if let (Some(camera), Some(saved)) = (viewport_camera.as_mut(), &session.camera) {
+            camera.set_view(&saved.to_view(origin));
+            camera.set_top_down(saved.top_view);
+        }
+        bookmarks.items.clear();
+        for saved in &session.bookmarks {
+            bookmarks.save(&saved.name, saved.camera.to_view(origin));
+        }
+
+        // Earlier edits refer to entities that no longer exist.
+        history.clear();
+
+        for warning in &warnings {
+            warn!("Session: {}", warning);
+        }
+        info!(
+            "Session loaded: {} polygons, {} assets, {} measurements",
+            classification_data.polygons.len(),
+            placed_assets.instances.len(),
+            measurements.items.len()
+        );
+        rpc_interface.send_notification(
+            "session_loaded",
+            serde_json::json!({
+                "version": session.version,
+                "polygons": classification_data.polygons.len(),
+                "assets": placed_assets.instances.len(),
+                "measurements": measurements.items.len(),
+                "bookmarks": bookmarks.items.len(),
+                "warnings": warnings,
+            }),
+        );
+    }
+}
+
+/// Snapshot the current editing state into a project file.
+pub fn capture_session<'a>(
+    bounds: &PointCloudBounds,
+    camera: Option<&ViewportCamera>,
+    classification_data: &PolygonClassificationData,
+    placed: impl Iterator<Item = &'a PlacedAssetInstance>,
+    measurements: &Measurements,
+    bookmarks: &CameraBookmarks,
+) -> SessionFile {
+    let origin = origin_of(bounds);
+
+    SessionFile {
+        version: SESSION_VERSION,
+        manifest: SessionManifest {
+            origin: origin.to_array(),
+            total_points: bounds.total_points,
+        },
+        camera: camera.map(|c| SessionCamera::from_view(&c.view(), origin)),
+        polygons: classification_data
+            .polygons
+            .iter()
+            .map(|p| SessionPolygon {
+                id: p.id,
+                mode: p.mode.clone(),
+                new_class: p.new_class,
+                masks: p.masks.clone(),
+                vertices: p.vertices.iter().map(|&pt| to_world(pt, origin)).collect(),
+                points: p.points.iter().map(|&pt| to_world(pt, origin)).collect(),
+                vertical_range: p.vertical_range,
+                selection: p.selection.clone(),
+            })
+            .collect(),
+        assets: placed
+            .map(|a| SessionAsset {
+                asset_name: a.asset_name.clone(),
+                translation: to_world(a.transform.translation, origin),
+                rotation: a.transform.rotation.to_array(),
+            })
+            .collect(),
+        measurements: measurements
+            .items
+            .iter()
+            .map(|m| SessionMeasurement {
+                name: Some(m.name.clone()),
+                mode: m.mode,
+                points: m.points.iter().map(|&p| to_world(p, origin)).collect(),
+                start: None,
+                end: None,
+            })
+            .collect(),
+        bookmarks: bookmarks
+            .items
+            .iter()
+            .map(|b| SessionBookmark {
+                name: b.name.clone(),
+                camera: SessionCamera::from_view(&b.view, origin),
+            })
+            .collect(),
+    }
+}
+
+/// Parse and validate a project file before any scene state is touched.
+pub fn parse_session(content: &str, limits: &PolygonLimits) -> Result<SessionFile, String> {
+    let session: SessionFile =
+        serde_json::from_str(content).map_err(|e| format!("Invalid session file: {}", e))?;
+
+    if !(MIN_SESSION_VERSION..=SESSION_VERSION).contains(&session.version) {
+        return Err(format!(
+            "Unsupported session version {} (expected {}-{})",
+            session.version, MIN_SESSION_VERSION, SESSION_VERSION
+        ));
+    }
+    // Loading falls back to the outline when a polygon has no saved vertices.
+    let points = session
+        .polygons
+        .iter()
+        .map(|p| {
+            if p.vertices.len() >= 3 {
+                p.vertices.len()
+            } else {
+                p.points.len()
+            }
+        })
+        .sum();
+    let masks = session.polygons.iter().map(|p| p.masks.len()).sum();
+    let selected = session.polygons.iter().map(|p| p.selection.len()).sum();
+    limits
+        .check(session.polygons.len(), points, masks, selected)
+        .map_err(|e| format!("Session exceeds polygon limits: {}", e))?;
+    if let Some(p) = session.polygons.iter().find(|p| p.points.len() < 3) {
+        return Err(format!("Polygon {} has fewer than 3 points", p.id));
+    }
+
+    Ok(session)
+}
+
+/// Differences between the dataset a session was saved against and the loaded one.
+fn manifest_warnings(saved: &SessionManifest, bounds: &PointCloudBounds) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let origin = origin_of(bounds);
+
+    if DVec3::from_array(saved.origin).distance(origin) > ORIGIN_TOLERANCE {
+        warnings.push(format!(
+            "Session origin {:?} differs from manifest origin {:?}; positions were re-based",
+            saved.origin,
+            origin.to_array()
+        ));
+    }
+    if saved.total_points != bounds.total_points {
+        warnings.push(format!(
+            "Session was saved against {} points, manifest has {}",
+            saved.total_points, bounds.total_points
+        ));
+    }
+
+    warnings
+}
+
+fn origin_of(bounds: &PointCloudBounds) -> DVec3 {
+    let (x, y, z) = bounds.bounds.origin;
+    DVec3::new(x, y, z)
+}
+
+fn to_world(point: Vec3, origin: DVec3) -> [f64; 3] {
+    (point.as_dvec3() + origin).to_array()
+}
+
+fn to_render(point: [f64; 3], origin: DVec3) -> Vec3 {
+    (DVec3::from_array(point) - origin).as_vec3()
+}
+
+fn send_session_error(rpc_interface: &mut WebRpcInterface, error: &str) {
+    warn!("Session error: {}", error);
+    rpc_interface.send_notification("session_error", serde_json::json!({ "error": error }));
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_session_file(path: &Path, content: &str) -> Result<(), String> {
+    std::fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_session_file(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_session_file(path: &Path, _content: &str) -> Result<(), String> {
+    Err(format!(
+        "Cannot write {} in the browser; save without a path",
+        path.display()
+    ))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_session_file(path: &Path) -> Result<String, String> {
+    Err(format!(
+        "Cannot read {} in the browser; load with file content",
+        path.display()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1_SESSION: &str = r#"{
+        "version": 1,
+        "manifest": { "origin": [0.0, 0.0, 0.0], "total_points": 10 },
+        "camera": null,
+        "polygons": [],
+        "assets": [],
+        "measurements": [{ "start": [1.0, 2.0, 3.0], "end": [4.0, 6.0, 3.0] }]
+    }"#;
+
+    #[test]
+    fn version_1_sessions_still_parse() {
+        let session = parse_session(V1_SESSION, &PolygonLimits::default()).unwrap();
+        let measurement = &session.measurements[0];
+        assert_eq!(measurement.mode, MeasureMode::Distance);
+        assert!(measurement.points.is_empty());
+        assert_eq!(measurement.start, Some([1.0, 2.0, 3.0]));
+        assert_eq!(measurement.end, Some([4.0, 6.0, 3.0]));
+    }
+
+    #[test]
+    fn unknown_versions_are_rejected() {
+        for version in [0, SESSION_VERSION + 1] {
+            let content = V1_SESSION.replace("\"version\": 1", &format!("\"version\": {version}"));
+            let err = parse_session(&content, &PolygonLimits::default()).unwrap_err();
+            assert!(err.contains("Unsupported session version"), "{err}");
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use crate::engine::assets::point_cloud_assets::PointCloudAssets;
+use crate::engine::assets::scene_manifest::SceneManifest;
+use crate::tools::point_pick::{PickOptions, PointPicker};
+use crate::tools::polygon::{ClassificationPolygon, PolygonClassificationData};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+/// Vertices and midpoints within this many logical pixels of the cursor are snapped to.
+const SNAP_RADIUS_PX: f32 = 12.0;
+
+/// What the cursor snaps to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapMode {
+    Point,    // Front-most rendered point under the cursor.
+    Ground,   // Front-most ground class point, seen through poles and facades.
+    Vertex,   // Polygon or measurement vertex, else the nearest point.
+    Midpoint, // Polygon or measurement edge midpoint, else the nearest point.
+}
+
+impl SnapMode {
+    pub fn from_string(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "point" => Some(Self::Point),
+            "ground" => Some(Self::Ground),
+            "vertex" => Some(Self::Vertex),
+            "midpoint" => Some(Self::Midpoint),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Point => "point",
+            Self::Ground => "ground",
+            Self::Vertex => "vertex",
+            Self::Midpoint => "midpoint",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Point => Self::Ground,
+            Self::Ground => Self::Vertex,
+            Self::Vertex => Self::Midpoint,
+            Self::Midpoint => Self::Point,
+        }
+    }
+
+    /// Preview marker colour for targets of this kind.
+    pub fn colour(&self) -> Color {
+        match self {
+            Self::Point => Color::srgb(0.0, 0.9, 1.0),
+            Self::Ground => Color::srgb(0.2, 1.0, 0.3),
+            Self::Vertex => Color::srgb(1.0, 0.2, 1.0),
+            Self::Midpoint => Color::srgb(1.0, 0.6, 0.0),
+        }
+    }
+}
+
+/// Snapping settings; holding Alt inverts `enabled` while it is down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapSettings {
+    pub enabled: bool,
+    pub mode: SnapMode,
+}
+
+impl Default for SnapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            mode: SnapMode::Point,
+        }
+    }
+}
+
+impl SnapSettings {
+    pub fn summary(&self) -> serde_json::Value {
+        serde_json::json!({
+            "enabled": self.enabled,
+            "mode": self.mode.as_str(),
+        })
+    }
+}
+
+/// Snapped cursor position and the kind of feature it snapped to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapTarget {
+    pub position: Vec3,
+    pub kind: SnapMode,
+}
+
+/// Resources needed to resolve snap targets, grouped to stay within Bevy's system
+/// parameter limit.
+#[derive(SystemParam)]
+pub struct SnapParams<'w> {
+    pub keyboard: Res<'w, ButtonInput<KeyCode>>,
+    pub picker: Res<'w, PointPicker>,
+    pub classification_data: Res<'w, PolygonClassificationData>,
+    pub images: Res<'w, Assets<Image>>,
+    pub assets: Res<'w, PointCloudAssets>,
+    pub manifests: Res<'w, Assets<SceneManifest>>,
+}
+
+impl SnapParams<'_> {
+    /// Mode in effect this frame, or `None` when snapping is off.
+    pub fn active_mode(&self, settings: &SnapSettings) -> Option<SnapMode> {
+        let inverted = self
+            .keyboard
+            .any_pressed([KeyCode::AltLeft, KeyCode::AltRight]);
+        (settings.enabled != inverted).then_some(settings.mode)
+    }
+
+    /// Snap target under `cursor` (logical window pixels). `lines` are open polylines
+    /// such as measurements, snapped to alongside completed polygon outlines. Vertex and
+    /// midpoint modes fall back to the nearest point when no feature is in reach.
+    pub fn resolve(
+        &self,
+        mode: SnapMode,
+        cursor: Vec2,
+        camera: &Camera,
+        camera_transform: &GlobalTransform,
+        lines: &[&[Vec3]],
+    ) -> Option<SnapTarget> {
+        let polygons = &self.classification_data.polygons;
+        let feature = match mode {
+            SnapMode::Vertex => snap_vertices(polygons, lines),
+            SnapMode::Midpoint => snap_midpoints(polygons, lines),
+            SnapMode::Point | SnapMode::Ground => Vec::new(),
+        };
+        if let Some(position) = nearest_on_screen(cursor, camera, camera_transform, feature) {
+            return Some(SnapTarget {
+                position,
+                kind: mode,
+            });
+        }
+
+        let ground_only = mode == SnapMode::Ground;
+        let bounds = self.assets.get_bounds(&self.manifests)?;
+        let picked = self.picker.pick_with(
+            cursor,
+            camera,
+            camera_transform,
+            &self.images,
+            &self.assets,
+            &bounds,
+            PickOptions {
+                ground_only,
+                ..default()
+            },
+        )?;
+        Some(SnapTarget {
+            position: picked.position,
+            kind: if ground_only {
+                SnapMode::Ground
+            } else {
+                SnapMode::Point
+            },
+        })
+    }
+}
+
+/// Drawn vertices of completed polygons and the given polylines. Selection polygons are
+/// skipped because their vertices are only the selection's bounds.
+pub fn snap_vertices(polygons: &[ClassificationPolygon], lines: &[&[Vec3]]) -> Vec<Vec3> {
+    polygons
+        .iter()
+        .filter(|polygon| polygon.selection.is_empty())
+        .flat_map(|polygon| polygon.vertices.iter().copied())
+        .chain(lines.iter().flat_map(|line| line.iter().copied()))
+        .collect()
+}
+
+/// Edge midpoints of completed polygon outlines, including the closing edge, and of the
+/// given polylines.
+pub fn snap_midpoints(polygons: &[ClassificationPolygon], lines: &[&[Vec3]]) -> Vec<Vec3> {
+    let closed = polygons
+        .iter()
+        .filter(|polygon| polygon.selection.is_empty() && polygon.vertices.len() >= 2)
+        .flat_map(|polygon| {
+            let vertices = &polygon.vertices;
+            (0..vertices.len())
+                .map(move |i| vertices[i].midpoint(vertices[(i + 1) % vertices.len()]))
+        });
+    let open = lines
+        .iter()
+        .flat_map(|line| line.windows(2).map(|pair| pair[0].midpoint(pair[1])));
+    closed.chain(open).collect()
+}
+
+/// Candidate whose projection lies closest to `cursor`, within `SNAP_RADIUS_PX`.
+fn nearest_on_screen(
+    cursor: Vec2,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    candidates: Vec<Vec3>,
+) -> Option<Vec3> {
+    candidates
+        .into_iter()
+        .filter_map(|position| {
+            let screen = camera.world_to_viewport(camera_transform, position).ok()?;
+            let distance = screen.distance(cursor);
+            (distance <= SNAP_RADIUS_PX).then_some((position, distance))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(position, _)| position)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::polygon::PolygonMode;
+
+    fn polygon(vertices: Vec<Vec3>, selection: Vec<u32>) -> ClassificationPolygon {
+        ClassificationPolygon {
+            id: 1,
+            points: vertices.clone(),
+            vertices,
+            new_class: 0,
+            masks: Vec::new(),
+            mode: PolygonMode::Hide,
+            vertical_range: None,
+            selection,
+        }
+    }
+
+    #[test]
+    fn midpoints_close_polygons_but_not_lines() {
+        let square = vec![
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 2.0),
+            Vec3::new(0.0, 0.0, 2.0),
+        ];
+        let line = [Vec3::new(10.0, 0.0, 0.0), Vec3::new(10.0, 4.0, 0.0)];
+        let selected = polygon(
+            square.iter().map(|v| *v + Vec3::X * 50.0).collect(),
+            vec![3],
+        );
+
+        let midpoints = snap_midpoints(&[polygon(square, Vec::new()), selected], &[&line]);
+        assert_eq!(
+            midpoints,
+            vec![
+                Vec3::new(1.0, 0.0, 0.0),
+                Vec3::new(2.0, 0.0, 1.0),
+                Vec3::new(1.0, 0.0, 2.0),
+                Vec3::new(0.0, 0.0, 1.0),
+                Vec3::new(10.0, 2.0, 0.0),
+            ]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::tools::asset_manager::state::PlacedBounds;
+use crate::tools::profile::{ProfileExportFormat, export_profile};
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +10,8 @@ pub enum ToolType {
     Polygon,
     AssetPlacement,
     Measure,
+    Profile,
+    Selection,
 }
 
 impl ToolType {
@@ -18,6 +21,8 @@ impl ToolType {
             "polygon" => Some(Self::Polygon),
             "assets" => Some(Self::AssetPlacement),
             "measure" => Some(Self::Measure),
+            "profile" => Some(Self::Profile),
+            "selection" => Some(Self::Selection),
             _ => None,
         }
     }
@@ -28,6 +33,8 @@ impl ToolType {
             Self::Polygon => "polygon",
             Self::AssetPlacement => "assets",
             Self::Measure => "measure",
+            Self::Profile => "profile",
+            Self::Selection => "selection",
         }
     }
 }
@@ -99,6 +106,12 @@ pub struct AssetPlacementEvent {
     pub position: Option<Vec3>,
 }
 
+/// Event fired when profile actions are requested via RPC.
+#[derive(Event)]
+pub struct ProfileActionEvent {
+    pub action: ProfileAction,
+}
+
 /// Available polygon actions that can be triggered remotely.
 #[derive(Debug, Clone, Copy)]
 pub enum PolygonAction {
@@ -107,6 +120,15 @@ pub enum PolygonAction {
     ClearAll, // Clear all polygons (equivalent to 'I' key).
 }
 
+/// Available profile actions that can be triggered remotely.
+#[derive(Debug, Clone, Copy)]
+pub enum ProfileAction {
+    Complete, // Finish the polyline and extract (equivalent to Enter key).
+    Clear,    // Discard the polyline and profile (equivalent to Backspace key).
+    SetCorridor { half_width: f32, ground_only: bool },
+    Export(ProfileExportFormat),
+}
+
 /// Available asset placement actions that can be triggered remotely.
 #[derive(Debug, Clone)]
 pub enum AssetPlacementAction {
@@ -130,6 +152,8 @@ pub fn handle_tool_selection_events(
     mut polygon_tool: ResMut<crate::tools::polygon::PolygonTool>,
     mut place_asset_state: ResMut<crate::tools::asset_manager::PlaceAssetBoundState>,
     mut measure_tool: ResMut<crate::tools::measure::MeasureTool>,
+    mut profile_tool: ResMut<crate::tools::profile::ProfileTool>,
+    mut selection_tool: ResMut<crate::tools::screen_selection::ScreenSelectionTool>,
     mut rpc_interface: ResMut<crate::rpc::web_rpc::WebRpcInterface>,
 ) {
     for event in events.read() {
@@ -144,6 +168,8 @@ pub fn handle_tool_selection_events(
         polygon_tool.set_active(false);
         place_asset_state.active = false;
         measure_tool.set_active(false);
+        profile_tool.set_active(false);
+        selection_tool.set_active(false);
 
         // Activate the requested tool.
         match event.tool_type {
@@ -193,6 +219,46 @@ pub fn handle_tool_selection_events(
                     serde_json::json!({
                         "tool": "measure",
                         "active": true,
+                        "mode": measure_tool.mode.as_str(),
+                        "snap": measure_tool.snap.summary(),
+                    }),
+                );
+            }
+            ToolType::Profile => {
+                profile_tool.set_active(true);
+
+                info!("Profile tool activated via {:?}", event.source);
+                println!(
+                    "Left click to add profile vertices, Enter to extract, Backspace to clear"
+                );
+
+                rpc_interface.send_notification(
+                    "tool_state_changed",
+                    serde_json::json!({
+                        "tool": "profile",
+                        "active": true,
+                        "half_width": profile_tool.half_width,
+                        "ground_only": profile_tool.ground_only,
+                    }),
+                );
+            }
+            ToolType::Selection => {
+                selection_tool.set_active(true);
+
+                info!("Selection tool activated via {:?}", event.source);
+                println!(
+                    "Drag to select, Shift adds, Ctrl removes; Tab cycles rectangle/lasso/brush"
+                );
+
+                rpc_interface.send_notification(
+                    "tool_state_changed",
+                    serde_json::json!({
+                        "tool": "selection",
+                        "active": true,
+                        "shape": selection_tool.shape.as_str(),
+                        "depth": selection_tool.depth.as_str(),
+                        "operation": selection_tool.operation.as_str(),
+                        "brush_radius": selection_tool.brush_radius,
                     }),
                 );
             }
@@ -276,6 +342,73 @@ pub fn handle_polygon_action_events(
     }
 }
 
+/// System handling profile actions triggered via RPC.
+pub fn handle_profile_action_events(
+    mut events: EventReader<ProfileActionEvent>,
+    mut profile_tool: ResMut<crate::tools::profile::ProfileTool>,
+    tool_manager: Res<ToolManager>,
+    mut rpc_interface: ResMut<crate::rpc::web_rpc::WebRpcInterface>,
+) {
+    for event in events.read() {
+        match event.action {
+            ProfileAction::Complete => {
+                if !tool_manager.is_tool_active(ToolType::Profile) {
+                    continue;
+                }
+                let success = profile_tool.request_completion();
+                if !success {
+                    warn!("Cannot complete profile: need at least 2 points");
+                }
+
+                rpc_interface.send_notification(
+                    "profile_action_completed",
+                    serde_json::json!({
+                        "action": "complete",
+                        "success": success
+                    }),
+                );
+            }
+            ProfileAction::Clear => {
+                profile_tool.clear();
+
+                info!("Profile cleared via RPC");
+
+                rpc_interface.send_notification("profile_cleared", serde_json::json!({}));
+            }
+            ProfileAction::SetCorridor {
+                half_width,
+                ground_only,
+            } => {
+                // Corridor settings persist between activations, so apply them regardless of tool state
+                profile_tool.set_corridor(half_width, ground_only);
+
+                info!(
+                    "Profile corridor set to {:.2} m, ground only: {}",
+                    half_width, ground_only
+                );
+            }
+            ProfileAction::Export(format) => {
+                let Some(result) = &profile_tool.result else {
+                    rpc_interface.send_notification(
+                        "profile_error",
+                        serde_json::json!({ "error": "No profile to export" }),
+                    );
+                    continue;
+                };
+
+                rpc_interface.send_notification(
+                    "profile_exported",
+                    serde_json::json!({
+                        "format": format.extension(),
+                        "filename": format!("profile_{}.{}", result.id, format.extension()),
+                        "content": export_profile(result, format),
+                    }),
+                );
+            }
+        }
+    }
+}
+
 /// System handling asset placement actions triggered via RPC.
 pub fn handle_asset_placement_events(
     mut events: EventReader<AssetPlacementEvent>,
@@ -403,6 +536,20 @@ pub fn handle_tool_keyboard_shortcuts(
             source: ToolSelectionSource::Keyboard,
         });
     }
+
+    if keyboard.just_pressed(KeyCode::KeyT) {
+        tool_events.write(ToolSelectionEvent {
+            tool_type: ToolType::Profile,
+            source: ToolSelectionSource::Keyboard,
+        });
+    }
+
+    if keyboard.just_pressed(KeyCode::KeyU) {
+        tool_events.write(ToolSelectionEvent {
+            tool_type: ToolType::Selection,
+            source: ToolSelectionSource::Keyboard,
+        });
+    }
 }
 
 /// Placeholder system for WASM builds where keyboard shortcuts are disabled.
@@ -423,6 +570,8 @@ pub fn handle_clear_tool_events(
     mut polygon_tool: ResMut<crate::tools::polygon::PolygonTool>,
     mut place_asset_state: ResMut<crate::tools::asset_manager::PlaceAssetBoundState>,
     mut measure_tool: ResMut<crate::tools::measure::MeasureTool>,
+    mut profile_tool: ResMut<crate::tools::profile::ProfileTool>,
+    mut selection_tool: ResMut<crate::tools::screen_selection::ScreenSelectionTool>,
     mut rpc_interface: ResMut<crate::rpc::web_rpc::WebRpcInterface>,
 ) {
     for event in events.read() {
@@ -432,6 +581,8 @@ pub fn handle_clear_tool_events(
         polygon_tool.set_active(false);
         place_asset_state.active = false;
         measure_tool.set_active(false);
+        profile_tool.set_active(false);
+        selection_tool.set_active(false);
 
         info!("Cleared active tool via {:?}", event.source);
 